@@ -4,6 +4,22 @@ pub use sea_orm_migration::MigratorTrait;
 
 mod m20240101000001_create_tenants;
 mod m20240101000002_create_users;
+mod m20240101000003_add_locales;
+mod m20240101000004_create_api_keys;
+mod m20240101000005_create_oidc;
+mod m20240101000006_create_memberships;
+mod m20240101000007_create_tenant_settings;
+mod m20240101000008_create_password_history;
+mod m20240101000009_add_user_deletion;
+mod m20240101000010_create_tenant_exports;
+mod m20240101000011_create_jobs;
+mod m20240101000012_create_task_runs;
+mod m20240101000013_create_webhooks;
+mod m20240101000014_create_outbox_events;
+mod m20240101000015_create_users_indexes;
+mod m20240101000016_add_webhook_delivery_uniqueness;
+mod m20240101000017_add_outbox_event_positions;
+mod m20240101000018_add_outbox_event_dead_at;
 
 pub struct Migrator;
 
@@ -12,6 +28,22 @@ impl MigratorTrait for Migrator {
         vec![
             Box::new(m20240101000001_create_tenants::Migration),
             Box::new(m20240101000002_create_users::Migration),
+            Box::new(m20240101000003_add_locales::Migration),
+            Box::new(m20240101000004_create_api_keys::Migration),
+            Box::new(m20240101000005_create_oidc::Migration),
+            Box::new(m20240101000006_create_memberships::Migration),
+            Box::new(m20240101000007_create_tenant_settings::Migration),
+            Box::new(m20240101000008_create_password_history::Migration),
+            Box::new(m20240101000009_add_user_deletion::Migration),
+            Box::new(m20240101000010_create_tenant_exports::Migration),
+            Box::new(m20240101000011_create_jobs::Migration),
+            Box::new(m20240101000012_create_task_runs::Migration),
+            Box::new(m20240101000013_create_webhooks::Migration),
+            Box::new(m20240101000014_create_outbox_events::Migration),
+            Box::new(m20240101000015_create_users_indexes::Migration),
+            Box::new(m20240101000016_add_webhook_delivery_uniqueness::Migration),
+            Box::new(m20240101000017_add_outbox_event_positions::Migration),
+            Box::new(m20240101000018_add_outbox_event_dead_at::Migration),
         ]
     }
 }
@@ -11,12 +11,7 @@ impl MigrationTrait for Migration {
                 Table::create()
                     .table(Users::Table)
                     .if_not_exists()
-                    .col(
-                        ColumnDef::new(Users::Id)
-                            .uuid()
-                            .not_null()
-                            .primary_key(),
-                    )
+                    .col(ColumnDef::new(Users::Id).uuid().not_null().primary_key())
                     .col(ColumnDef::new(Users::TenantId).uuid().not_null())
                     .col(ColumnDef::new(Users::Email).string().not_null())
                     .col(ColumnDef::new(Users::PasswordHash).string().not_null())
@@ -52,19 +47,6 @@ impl MigrationTrait for Migration {
                             .on_delete(ForeignKeyAction::Cascade)
                             .on_update(ForeignKeyAction::Cascade),
                     )
-                    .index(
-                        Index::create()
-                            .name("idx_users_email")
-                            .table(Users::Table)
-                            .col(Users::Email)
-                            .unique(),
-                    )
-                    .index(
-                        Index::create()
-                            .name("idx_users_tenant_id")
-                            .table(Users::Table)
-                            .col(Users::TenantId),
-                    )
                     .to_owned(),
             )
             .await
@@ -95,4 +77,3 @@ enum Tenants {
     Table,
     Id,
 }
-
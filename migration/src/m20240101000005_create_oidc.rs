@@ -0,0 +1,251 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(OidcProviders::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(OidcProviders::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(OidcProviders::TenantId).uuid().not_null())
+                    .col(ColumnDef::new(OidcProviders::Name).string().not_null())
+                    .col(ColumnDef::new(OidcProviders::Issuer).string().not_null())
+                    .col(ColumnDef::new(OidcProviders::ClientId).string().not_null())
+                    .col(
+                        ColumnDef::new(OidcProviders::ClientSecret)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OidcProviders::Scopes)
+                            .string()
+                            .not_null()
+                            .default("openid email profile"),
+                    )
+                    .col(
+                        ColumnDef::new(OidcProviders::RedirectUri)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OidcProviders::EmailClaim)
+                            .string()
+                            .not_null()
+                            .default("email"),
+                    )
+                    .col(ColumnDef::new(OidcProviders::RoleClaim).string().null())
+                    .col(
+                        ColumnDef::new(OidcProviders::AdminRoleValue)
+                            .string()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(OidcProviders::DefaultRole)
+                            .string()
+                            .not_null()
+                            .default("regular"),
+                    )
+                    .col(
+                        ColumnDef::new(OidcProviders::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(OidcProviders::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_oidc_providers_tenant_id")
+                            .from(OidcProviders::Table, OidcProviders::TenantId)
+                            .to(Tenants::Table, Tenants::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_oidc_providers_tenant_id")
+                    .table(OidcProviders::Table)
+                    .col(OidcProviders::TenantId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserIdentities::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(UserIdentities::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(UserIdentities::UserId).uuid().not_null())
+                    .col(ColumnDef::new(UserIdentities::ProviderId).uuid().not_null())
+                    .col(ColumnDef::new(UserIdentities::Subject).string().not_null())
+                    .col(
+                        ColumnDef::new(UserIdentities::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_identities_user_id")
+                            .from(UserIdentities::Table, UserIdentities::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_identities_provider_id")
+                            .from(UserIdentities::Table, UserIdentities::ProviderId)
+                            .to(OidcProviders::Table, OidcProviders::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // A subject is unique per issuer, so per provider
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_user_identities_provider_subject")
+                    .table(UserIdentities::Table)
+                    .col(UserIdentities::ProviderId)
+                    .col(UserIdentities::Subject)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(OidcLoginStates::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(OidcLoginStates::State)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(OidcLoginStates::ProviderId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(OidcLoginStates::Nonce).string().not_null())
+                    .col(
+                        ColumnDef::new(OidcLoginStates::CodeVerifier)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OidcLoginStates::ExpiresAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_oidc_login_states_provider_id")
+                            .from(OidcLoginStates::Table, OidcLoginStates::ProviderId)
+                            .to(OidcProviders::Table, OidcProviders::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(OidcLoginStates::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(UserIdentities::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(OidcProviders::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum OidcProviders {
+    Table,
+    Id,
+    TenantId,
+    Name,
+    Issuer,
+    ClientId,
+    ClientSecret,
+    Scopes,
+    RedirectUri,
+    EmailClaim,
+    RoleClaim,
+    AdminRoleValue,
+    DefaultRole,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum UserIdentities {
+    Table,
+    Id,
+    UserId,
+    ProviderId,
+    Subject,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum OidcLoginStates {
+    Table,
+    State,
+    ProviderId,
+    Nonce,
+    CodeVerifier,
+    ExpiresAt,
+}
+
+#[derive(DeriveIden)]
+enum Tenants {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
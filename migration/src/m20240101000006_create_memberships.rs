@@ -0,0 +1,143 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Memberships::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Memberships::UserId).uuid().not_null())
+                    .col(ColumnDef::new(Memberships::TenantId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(Memberships::Role)
+                            .string()
+                            .not_null()
+                            .default("regular"),
+                    )
+                    .col(
+                        ColumnDef::new(Memberships::Status)
+                            .string()
+                            .not_null()
+                            .default("active"),
+                    )
+                    .col(
+                        ColumnDef::new(Memberships::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(Memberships::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(Memberships::UserId)
+                            .col(Memberships::TenantId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_memberships_user_id")
+                            .from(Memberships::Table, Memberships::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_memberships_tenant_id")
+                            .from(Memberships::Table, Memberships::TenantId)
+                            .to(Tenants::Table, Tenants::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_memberships_tenant_id")
+                    .table(Memberships::Table)
+                    .col(Memberships::TenantId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Every existing user becomes a member of the tenant they were created
+        // in. From here on `users.role` and `users.status` are no longer read;
+        // `users.tenant_id` remains the tenant a login starts in.
+        manager
+            .exec_stmt(
+                Query::insert()
+                    .into_table(Memberships::Table)
+                    .columns([
+                        Memberships::UserId,
+                        Memberships::TenantId,
+                        Memberships::Role,
+                        Memberships::Status,
+                        Memberships::CreatedAt,
+                        Memberships::UpdatedAt,
+                    ])
+                    .select_from(
+                        Query::select()
+                            .columns([
+                                Users::Id,
+                                Users::TenantId,
+                                Users::Role,
+                                Users::Status,
+                                Users::CreatedAt,
+                                Users::UpdatedAt,
+                            ])
+                            .from(Users::Table)
+                            .to_owned(),
+                    )
+                    .map_err(|e| DbErr::Migration(e.to_string()))?
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Memberships::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Memberships {
+    Table,
+    UserId,
+    TenantId,
+    Role,
+    Status,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Tenants {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+    TenantId,
+    Role,
+    Status,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,91 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Data export jobs; the archives themselves live on disk
+        manager
+            .create_table(
+                Table::create()
+                    .table(TenantExports::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(TenantExports::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(TenantExports::TenantId).uuid().not_null())
+                    .col(ColumnDef::new(TenantExports::RequestedBy).uuid().not_null())
+                    .col(ColumnDef::new(TenantExports::Format).string().not_null())
+                    .col(ColumnDef::new(TenantExports::Status).string().not_null())
+                    .col(
+                        ColumnDef::new(TenantExports::SizeBytes)
+                            .big_integer()
+                            .null(),
+                    )
+                    .col(ColumnDef::new(TenantExports::Error).string().null())
+                    .col(
+                        ColumnDef::new(TenantExports::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(TenantExports::CompletedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_tenant_exports_tenant_id")
+                            .from(TenantExports::Table, TenantExports::TenantId)
+                            .to(Tenants::Table, Tenants::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_tenant_exports_tenant_id")
+                    .table(TenantExports::Table)
+                    .col(TenantExports::TenantId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TenantExports::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TenantExports {
+    Table,
+    Id,
+    TenantId,
+    RequestedBy,
+    Format,
+    Status,
+    SizeBytes,
+    Error,
+    CreatedAt,
+    CompletedAt,
+}
+
+#[derive(DeriveIden)]
+enum Tenants {
+    Table,
+    Id,
+}
@@ -0,0 +1,115 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // One row per run of a scheduled task
+        manager
+            .create_table(
+                Table::create()
+                    .table(TaskRuns::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(TaskRuns::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(TaskRuns::Task).string().not_null())
+                    .col(
+                        ColumnDef::new(TaskRuns::ScheduledFor)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(TaskRuns::Status).string().not_null())
+                    .col(ColumnDef::new(TaskRuns::Runner).string().not_null())
+                    .col(ColumnDef::new(TaskRuns::Affected).big_integer().null())
+                    .col(ColumnDef::new(TaskRuns::Error).text().null())
+                    .col(
+                        ColumnDef::new(TaskRuns::StartedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TaskRuns::FinishedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // A tick of a schedule is run once, whichever replica gets there first
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_task_runs_task_scheduled_for")
+                    .table(TaskRuns::Table)
+                    .col(TaskRuns::Task)
+                    .col(TaskRuns::ScheduledFor)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_task_runs_started_at")
+                    .table(TaskRuns::Table)
+                    .col(TaskRuns::StartedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        // When the member last obtained a token for the tenant, to find idle
+        // members
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Memberships::Table)
+                    .add_column(
+                        ColumnDef::new(Memberships::LastLoginAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Memberships::Table)
+                    .drop_column(Memberships::LastLoginAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(TaskRuns::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TaskRuns {
+    Table,
+    Id,
+    Task,
+    ScheduledFor,
+    Status,
+    Runner,
+    Affected,
+    Error,
+    StartedAt,
+    FinishedAt,
+}
+
+#[derive(DeriveIden)]
+enum Memberships {
+    Table,
+    LastLoginAt,
+}
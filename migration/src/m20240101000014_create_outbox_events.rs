@@ -0,0 +1,110 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Domain events, written in the transaction of the change they
+        // record and relayed to subscribers afterwards
+        manager
+            .create_table(
+                Table::create()
+                    .table(OutboxEvents::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(OutboxEvents::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(OutboxEvents::AggregateType)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(OutboxEvents::AggregateId).uuid().not_null())
+                    .col(ColumnDef::new(OutboxEvents::TenantId).uuid().not_null())
+                    .col(ColumnDef::new(OutboxEvents::EventType).string().not_null())
+                    .col(
+                        ColumnDef::new(OutboxEvents::Payload)
+                            .json_binary()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OutboxEvents::Attempts)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(OutboxEvents::NextAttemptAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(ColumnDef::new(OutboxEvents::LastError).text().null())
+                    .col(
+                        ColumnDef::new(OutboxEvents::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(OutboxEvents::DispatchedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // The relay looks for pending events, then for earlier pending
+        // events of the same aggregate
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_outbox_events_dispatched_at")
+                    .table(OutboxEvents::Table)
+                    .col(OutboxEvents::DispatchedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_outbox_events_aggregate")
+                    .table(OutboxEvents::Table)
+                    .col(OutboxEvents::AggregateType)
+                    .col(OutboxEvents::AggregateId)
+                    .col(OutboxEvents::Id)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(OutboxEvents::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum OutboxEvents {
+    Table,
+    Id,
+    AggregateType,
+    AggregateId,
+    TenantId,
+    EventType,
+    Payload,
+    Attempts,
+    NextAttemptAt,
+    LastError,
+    CreatedAt,
+    DispatchedAt,
+}
@@ -0,0 +1,79 @@
+use sea_orm_migration::prelude::*;
+
+/// One delivery per event and endpoint, so publishing an event again, as
+/// the outbox relay does after a failure, adds none. Redeliveries send the
+/// same event again on purpose and are told apart by `redelivery_of`.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WebhookDeliveries::Table)
+                    .add_column(
+                        ColumnDef::new(WebhookDeliveries::RedeliveryOf)
+                            .uuid()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Existing redeliveries point at the first delivery of their event
+        let first = "SELECT first.id FROM webhook_deliveries AS first \
+                     WHERE first.endpoint_id = webhook_deliveries.endpoint_id \
+                     AND first.event_id = webhook_deliveries.event_id \
+                     ORDER BY first.created_at, first.id LIMIT 1";
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                "UPDATE webhook_deliveries SET redelivery_of = ({first}) WHERE id <> ({first})"
+            ))
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_webhook_deliveries_endpoint_id_event_id")
+                    .table(WebhookDeliveries::Table)
+                    .col(WebhookDeliveries::EndpointId)
+                    .col(WebhookDeliveries::EventId)
+                    .unique()
+                    .and_where(Expr::col(WebhookDeliveries::RedeliveryOf).is_null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_webhook_deliveries_endpoint_id_event_id")
+                    .table(WebhookDeliveries::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WebhookDeliveries::Table)
+                    .drop_column(WebhookDeliveries::RedeliveryOf)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum WebhookDeliveries {
+    Table,
+    EndpointId,
+    EventId,
+    RedeliveryOf,
+}
@@ -0,0 +1,136 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::DatabaseBackend;
+
+/// Orders the outbox by a position the database assigns on insert, rather
+/// than by ids taken from the clock of each replica.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        if manager.get_database_backend() == DatabaseBackend::Postgres {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(OutboxEvents::Table)
+                        .add_column(
+                            ColumnDef::new(OutboxEvents::Position)
+                                .big_integer()
+                                .not_null()
+                                .auto_increment(),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+            // Rows already there are numbered in no particular order; the
+            // sequence has moved past them, so renumbering keeps it ahead
+            db.execute_unprepared(
+                "UPDATE outbox_events SET position = ordered.position \
+                 FROM (SELECT id, row_number() OVER (ORDER BY id) AS position \
+                 FROM outbox_events) AS ordered \
+                 WHERE outbox_events.id = ordered.id",
+            )
+            .await?;
+        } else {
+            // SQLite cannot add an auto-increment column. Its writes are
+            // serialized, so the next position is one past the last.
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(OutboxEvents::Table)
+                        .add_column(ColumnDef::new(OutboxEvents::Position).big_integer().null())
+                        .to_owned(),
+                )
+                .await?;
+            db.execute_unprepared(
+                "UPDATE outbox_events SET position = \
+                 (SELECT count(*) FROM outbox_events AS earlier \
+                 WHERE earlier.id <= outbox_events.id)",
+            )
+            .await?;
+            db.execute_unprepared(
+                "CREATE TRIGGER IF NOT EXISTS outbox_events_position \
+                 AFTER INSERT ON outbox_events FOR EACH ROW WHEN NEW.position IS NULL \
+                 BEGIN \
+                 UPDATE outbox_events SET position = \
+                 (SELECT coalesce(max(position), 0) + 1 FROM outbox_events) \
+                 WHERE rowid = NEW.rowid; \
+                 END",
+            )
+            .await?;
+        }
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_outbox_events_aggregate")
+                    .table(OutboxEvents::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_outbox_events_aggregate")
+                    .table(OutboxEvents::Table)
+                    .col(OutboxEvents::AggregateType)
+                    .col(OutboxEvents::AggregateId)
+                    .col(OutboxEvents::Position)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_outbox_events_aggregate")
+                    .table(OutboxEvents::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        if manager.get_database_backend() != DatabaseBackend::Postgres {
+            manager
+                .get_connection()
+                .execute_unprepared("DROP TRIGGER IF EXISTS outbox_events_position")
+                .await?;
+        }
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OutboxEvents::Table)
+                    .drop_column(OutboxEvents::Position)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_outbox_events_aggregate")
+                    .table(OutboxEvents::Table)
+                    .col(OutboxEvents::AggregateType)
+                    .col(OutboxEvents::AggregateId)
+                    .col(OutboxEvents::Id)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum OutboxEvents {
+    Table,
+    Id,
+    AggregateType,
+    AggregateId,
+    Position,
+}
@@ -1,41 +1,165 @@
-use utoipa::OpenApi;
+use utoipa::{
+    Modify, OpenApi,
+    openapi::{Content, Ref, RefOr, Schema},
+};
 
 use crate::{
-    handlers::health,
+    enums::{
+        ApiScope, ExportFormat, ExportStatus, Locale, TaskRunStatus, WebhookDeliveryStatus,
+        WebhookEvent,
+    },
+    handlers::{
+        api_keys::{
+            create_api_key::CreateApiKeyRequest,
+            create_service_account::CreateServiceAccountRequest,
+        },
+        auth::oidc_callback::OidcCallbackRequest,
+        health,
+        tenants::request_export::RequestExportRequest,
+        users::{
+            add_member::AddMemberRequest, change_password::ChangePasswordRequest,
+            set_locale::SetLocaleRequest,
+        },
+    },
+    middleware::error_format::PROBLEM_JSON,
     models,
-    services::auth_service::{AuthResponse, LoginRequest, RegisterRequest},
-    utils::error::ErrorResponse,
+    services::api_keys_service::IssuedApiKey,
+    services::auth_service::{AuthResponse, LoginRequest, RegisterRequest, SwitchTenantRequest},
+    services::exports_service::ExportResponse,
+    services::health_service::{CheckResult, CheckStatus, PoolStats},
+    services::oidc_service::{AuthorizationRequest, CreateOidcProviderRequest},
+    services::scim_service::{
+        ScimMeta, ScimMultiValue, ScimPatchOperation, ScimPatchRequest, ScimUser, ScimUserList,
+        ScimUserRequest,
+    },
+    services::tenant_settings_service::{TenantSettingsResponse, UpdateTenantSettingsRequest},
+    services::webhooks_service::{CreateWebhookRequest, CreatedWebhook},
+    utils::error::{ErrorCode, ErrorResponse, FieldError, ProblemDetails, ScimError},
 };
 
 #[derive(OpenApi)]
 #[openapi(
     paths(
-        crate::handlers::health::health_check,
+        crate::handlers::health::health_check::health_check,
+        crate::handlers::health::live::liveness,
+        crate::handlers::health::ready::readiness,
+        crate::handlers::health::details::health_details,
         crate::handlers::auth::register::register,
         crate::handlers::auth::login::login,
         crate::handlers::auth::refresh::refresh,
+        crate::handlers::auth::switch_tenant::switch_tenant,
+        crate::handlers::auth::oidc_authorize::oidc_authorize,
+        crate::handlers::auth::oidc_callback::oidc_callback,
         crate::handlers::users::me::me,
+        crate::handlers::users::set_locale::set_locale,
+        crate::handlers::users::change_password::change_password,
         crate::handlers::users::get_user::get_user,
         crate::handlers::users::get_users::get_users,
+        crate::handlers::users::add_member::add_member,
+        crate::handlers::users::delete_user::delete_user,
+        crate::handlers::users::restore_user::restore_user,
         crate::handlers::tenants::get_tenants::list_tenants,
-        crate::handlers::tenants::get_tenant::get_tenant
+        crate::handlers::tenants::get_tenant::get_tenant,
+        crate::handlers::tenants::get_settings::get_settings,
+        crate::handlers::tenants::update_settings::update_settings,
+        crate::handlers::tenants::request_export::request_export,
+        crate::handlers::tenants::get_export::get_export,
+        crate::handlers::tenants::download_export::download_export,
+        crate::handlers::api_keys::create_service_account::create_service_account,
+        crate::handlers::api_keys::list_service_accounts::list_service_accounts,
+        crate::handlers::api_keys::create_api_key::create_api_key,
+        crate::handlers::api_keys::list_api_keys::list_api_keys,
+        crate::handlers::api_keys::rotate_api_key::rotate_api_key,
+        crate::handlers::api_keys::revoke_api_key::revoke_api_key,
+        crate::handlers::oidc_providers::create_oidc_provider::create_oidc_provider,
+        crate::handlers::oidc_providers::list_oidc_providers::list_oidc_providers,
+        crate::handlers::oidc_providers::delete_oidc_provider::delete_oidc_provider,
+        crate::handlers::webhooks::create_webhook::create_webhook,
+        crate::handlers::webhooks::list_webhooks::list_webhooks,
+        crate::handlers::webhooks::delete_webhook::delete_webhook,
+        crate::handlers::webhooks::ping_webhook::ping_webhook,
+        crate::handlers::webhooks::list_webhook_deliveries::list_webhook_deliveries,
+        crate::handlers::webhooks::redeliver_webhook::redeliver_webhook,
+        crate::handlers::tasks::list_task_runs::list_task_runs,
+        crate::handlers::scim::service_provider_config::service_provider_config,
+        crate::handlers::scim::list_users::list_users,
+        crate::handlers::scim::get_user::get_user,
+        crate::handlers::scim::create_user::create_user,
+        crate::handlers::scim::replace_user::replace_user,
+        crate::handlers::scim::patch_user::patch_user,
+        crate::handlers::scim::delete_user::delete_user
     ),
     components(
         schemas(
             health::HealthResponse,
+            health::HealthDetailsResponse,
+            health::ReadinessResponse,
+            health::ReadinessCheck,
+            CheckResult,
+            CheckStatus,
+            PoolStats,
             RegisterRequest,
             LoginRequest,
+            SwitchTenantRequest,
+            SetLocaleRequest,
+            ChangePasswordRequest,
+            AddMemberRequest,
+            Locale,
             AuthResponse,
             models::users::Model,
+            models::memberships::Model,
             models::tenants::Model,
+            models::tenant_settings::TenantSettings,
+            TenantSettingsResponse,
+            UpdateTenantSettingsRequest,
+            models::tenant_exports::Model,
+            RequestExportRequest,
+            ExportResponse,
+            ExportFormat,
+            ExportStatus,
+            models::service_accounts::Model,
+            models::api_keys::Model,
+            CreateServiceAccountRequest,
+            CreateApiKeyRequest,
+            IssuedApiKey,
+            ApiScope,
+            models::oidc_providers::Model,
+            models::task_runs::Model,
+            TaskRunStatus,
+            CreateOidcProviderRequest,
+            models::webhook_endpoints::Model,
+            models::webhook_deliveries::Model,
+            CreateWebhookRequest,
+            CreatedWebhook,
+            WebhookEvent,
+            WebhookDeliveryStatus,
+            AuthorizationRequest,
+            OidcCallbackRequest,
+            ScimUser,
+            ScimMeta,
+            ScimMultiValue,
+            ScimUserRequest,
+            ScimPatchRequest,
+            ScimPatchOperation,
+            ScimUserList,
+            ScimError,
             ErrorResponse,
+            ErrorCode,
+            ProblemDetails,
+            FieldError,
         )
     ),
+    modifiers(&ErrorCatalog, &ProblemResponses),
     tags(
         (name = "Health", description = "Health check endpoints"),
         (name = "Authentication", description = "User authentication endpoints"),
         (name = "Users", description = "User management endpoints"),
         (name = "Tenants", description = "Tenant management endpoints"),
+        (name = "API Keys", description = "Service accounts and API keys for machine-to-machine access"),
+        (name = "Identity Providers", description = "Per-tenant OpenID Connect providers for single sign-on"),
+        (name = "Webhooks", description = "Signed notifications of tenant events sent to the tenant's endpoints"),
+        (name = "SCIM", description = "SCIM 2.0 user provisioning from a tenant's directory"),
+        (name = "Scheduled Tasks", description = "Runs of the scheduled maintenance tasks"),
     ),
     info(
         title = "Rust Backend Template API",
@@ -47,3 +171,65 @@ use crate::{
     )
 )]
 pub struct ApiDoc;
+
+/// Documents every [`ErrorCode`] with its HTTP status on the `ErrorCode`
+/// schema, so the catalog lives next to the responses that reference it.
+struct ErrorCatalog;
+
+impl Modify for ErrorCatalog {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let Some(components) = openapi.components.as_mut() else {
+            return;
+        };
+        let Some(RefOr::T(Schema::Object(schema))) = components.schemas.get_mut("ErrorCode") else {
+            return;
+        };
+
+        let mut catalog = String::from(
+            "Stable error codes returned in the `error` field.\n\n| Code | Status | Meaning |\n| --- | --- | --- |\n",
+        );
+        for code in ErrorCode::ALL {
+            catalog.push_str(&format!(
+                "| `{}` | {} | {} |\n",
+                code,
+                code.status().as_u16(),
+                code.description()
+            ));
+        }
+        schema.description = Some(catalog);
+    }
+}
+
+/// Every response documented with `ErrorResponse` can also be served as
+/// RFC 9457 problem details; advertise that alternative representation.
+struct ProblemResponses;
+
+impl Modify for ProblemResponses {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        for item in openapi.paths.paths.values_mut() {
+            let operations = [
+                &mut item.get,
+                &mut item.put,
+                &mut item.post,
+                &mut item.delete,
+                &mut item.patch,
+            ];
+            for operation in operations.into_iter().flatten() {
+                for response in operation.responses.responses.values_mut() {
+                    let RefOr::T(response) = response else {
+                        continue;
+                    };
+                    let is_error = response.content.values().any(|content| {
+                        matches!(&content.schema, Some(RefOr::Ref(r)) if r.ref_location.ends_with("/ErrorResponse"))
+                    });
+                    if is_error {
+                        response.content.insert(
+                            PROBLEM_JSON.to_string(),
+                            Content::new(Some(Ref::from_schema_name("ProblemDetails"))),
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
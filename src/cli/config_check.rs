@@ -0,0 +1,48 @@
+use crate::config::{ConfigError, Settings};
+use clap::Subcommand;
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// Load and validate configuration from files and the environment
+    Check {
+        /// Also try to connect to the database
+        #[arg(long)]
+        connect: bool,
+    },
+    /// Print the effective configuration with secrets redacted
+    Show,
+}
+
+pub async fn run(
+    command: ConfigCommand,
+    settings: Result<Settings, ConfigError>,
+) -> anyhow::Result<()> {
+    let settings = match settings {
+        Ok(settings) => settings,
+        Err(e) => {
+            for error in &e.errors {
+                eprintln!("error: {}", error);
+            }
+            anyhow::bail!("configuration is invalid ({} error(s))", e.errors.len());
+        }
+    };
+
+    match command {
+        ConfigCommand::Check { connect } => {
+            if connect {
+                let db = settings
+                    .database
+                    .connect()
+                    .await
+                    .map_err(|e| anyhow::anyhow!("Database connection failed: {}", e))?;
+                db.close().await?;
+                println!("Database connection: ok");
+            }
+
+            println!("Configuration: ok");
+        }
+        ConfigCommand::Show => println!("{}", settings.redacted_dump()),
+    }
+
+    Ok(())
+}
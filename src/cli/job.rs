@@ -0,0 +1,52 @@
+use crate::config::Settings;
+use crate::repositories::SeaOrmJobRepository;
+use crate::services::jobs_service::{JobQueue, JobsService};
+use clap::Subcommand;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Debug, Subcommand)]
+pub enum JobCommand {
+    /// List jobs that failed on every attempt
+    Dead {
+        /// Maximum number of jobs to list
+        #[arg(long, default_value_t = 50)]
+        limit: u64,
+    },
+    /// Queue a dead job again with a fresh set of attempts
+    Retry {
+        /// Job to retry
+        #[arg(long)]
+        job_id: Uuid,
+    },
+}
+
+pub async fn run(command: JobCommand, settings: Settings) -> anyhow::Result<()> {
+    let db = Arc::new(super::connect(&settings).await?);
+    let jobs = JobsService::new(
+        Arc::new(SeaOrmJobRepository::new(db.clone())),
+        settings.app.jobs.max_attempts,
+    );
+
+    match command {
+        JobCommand::Dead { limit } => {
+            for job in jobs.list_dead(limit).await? {
+                println!(
+                    "{}\t{}\t{} attempt(s)\t{}\t{}",
+                    job.id,
+                    job.kind,
+                    job.attempts,
+                    job.updated_at.to_rfc3339(),
+                    job.last_error.unwrap_or_default()
+                );
+            }
+        }
+        JobCommand::Retry { job_id } => match jobs.retry(job_id).await? {
+            Some(job) => println!("Job {} queued again", job.id),
+            None => anyhow::bail!("No dead job {}", job_id),
+        },
+    }
+
+    db.close_by_ref().await?;
+    Ok(())
+}
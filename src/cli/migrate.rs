@@ -0,0 +1,60 @@
+use crate::config::Settings;
+use clap::Subcommand;
+use migration::{Migrator, MigratorTrait};
+
+#[derive(Debug, Subcommand)]
+pub enum MigrateCommand {
+    /// Apply pending migrations
+    Up {
+        /// Number of migrations to apply (default: all pending)
+        #[arg(long)]
+        steps: Option<u32>,
+    },
+    /// Roll back applied migrations
+    Down {
+        /// Number of migrations to roll back
+        #[arg(long, default_value_t = 1)]
+        steps: u32,
+    },
+    /// List migrations and whether they are applied
+    Status,
+    /// Drop every table and re-apply all migrations
+    Fresh {
+        /// Required to confirm that all data will be lost
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+pub async fn run(command: MigrateCommand, settings: Settings) -> anyhow::Result<()> {
+    let db = super::connect(&settings).await?;
+
+    match command {
+        MigrateCommand::Up { steps } => {
+            tracing::info!("Applying migrations...");
+            Migrator::up(&db, steps).await?;
+            tracing::info!("Migrations completed successfully");
+        }
+        MigrateCommand::Down { steps } => {
+            tracing::info!("Rolling back {} migration(s)...", steps);
+            Migrator::down(&db, Some(steps)).await?;
+            tracing::info!("Rollback completed successfully");
+        }
+        MigrateCommand::Status => {
+            for migration in Migrator::get_migration_with_status(&db).await? {
+                println!("{:<8} {}", migration.status(), migration.name());
+            }
+        }
+        MigrateCommand::Fresh { yes } => {
+            if !yes {
+                anyhow::bail!("`migrate fresh` drops all tables; re-run with --yes to confirm");
+            }
+            tracing::warn!("Dropping all tables and re-applying migrations");
+            Migrator::fresh(&db).await?;
+            tracing::info!("Database recreated successfully");
+        }
+    }
+
+    db.close().await?;
+    Ok(())
+}
@@ -0,0 +1,76 @@
+pub mod config_check;
+pub mod job;
+pub mod migrate;
+pub mod openapi;
+pub mod serve;
+pub mod tenant;
+pub mod user;
+pub mod worker;
+
+use crate::config::{ConfigError, Settings};
+use clap::{Parser, Subcommand};
+use sea_orm::DatabaseConnection;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "template-rust-backend",
+    version,
+    about = "Multi-tenant Rust backend API and administration tool"
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Start the HTTP server (default when no subcommand is given)
+    Serve,
+    /// Run a background job worker without the HTTP server
+    Worker,
+    /// Manage database migrations
+    #[command(subcommand)]
+    Migrate(migrate::MigrateCommand),
+    /// Manage tenants
+    #[command(subcommand)]
+    Tenant(tenant::TenantCommand),
+    /// Manage users
+    #[command(subcommand)]
+    User(user::UserCommand),
+    /// Inspect and retry background jobs
+    #[command(subcommand)]
+    Job(job::JobCommand),
+    /// OpenAPI specification tools
+    #[command(subcommand)]
+    Openapi(openapi::OpenapiCommand),
+    /// Configuration tools
+    #[command(subcommand)]
+    Config(config_check::ConfigCommand),
+    /// Legacy alias for `migrate up`
+    #[command(name = "run_migrations", hide = true)]
+    RunMigrations,
+}
+
+/// Runs a command. `settings` is passed through unchecked so that commands
+/// which don't need configuration (`openapi export`) or which report on it
+/// (`config check`) still work when it is invalid.
+pub async fn run(cli: Cli, settings: Result<Settings, ConfigError>) -> anyhow::Result<()> {
+    match cli.command.unwrap_or(Command::Serve) {
+        Command::Serve => serve::run(settings?).await,
+        Command::Worker => worker::run(settings?).await,
+        Command::Migrate(command) => migrate::run(command, settings?).await,
+        Command::Tenant(command) => tenant::run(command, settings?).await,
+        Command::User(command) => user::run(command, settings?).await,
+        Command::Job(command) => job::run(command, settings?).await,
+        Command::Openapi(command) => openapi::run(command),
+        Command::Config(command) => config_check::run(command, settings).await,
+        Command::RunMigrations => {
+            migrate::run(migrate::MigrateCommand::Up { steps: None }, settings?).await
+        }
+    }
+}
+
+/// Connects using the database URL and pool settings.
+pub async fn connect(settings: &Settings) -> anyhow::Result<DatabaseConnection> {
+    Ok(settings.database.connect().await?)
+}
@@ -0,0 +1,31 @@
+use crate::api_doc::ApiDoc;
+use clap::Subcommand;
+use std::path::PathBuf;
+use utoipa::OpenApi;
+
+#[derive(Debug, Subcommand)]
+pub enum OpenapiCommand {
+    /// Write the OpenAPI specification as JSON
+    Export {
+        /// Output file (default: stdout)
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+}
+
+pub fn run(command: OpenapiCommand) -> anyhow::Result<()> {
+    match command {
+        OpenapiCommand::Export { output } => {
+            let spec = ApiDoc::openapi().to_pretty_json()?;
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, spec)?;
+                    eprintln!("OpenAPI specification written to {}", path.display());
+                }
+                None => println!("{}", spec),
+            }
+        }
+    }
+
+    Ok(())
+}
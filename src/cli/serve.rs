@@ -0,0 +1,118 @@
+use crate::{
+    config::Settings,
+    events,
+    jobs::{self, Worker},
+    repositories::{SeaOrmJobRepository, SeaOrmOutboxRepository, SeaOrmTaskRunRepository},
+    routes, scheduler,
+    services::health_service::HealthRegistry,
+    utils::shutdown::{Shutdown, shutdown_signal},
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+pub async fn run(settings: Settings) -> anyhow::Result<()> {
+    tracing::info!("Effective configuration:\n{}", settings.redacted_dump());
+    if settings.app.jwt_secret == crate::config::settings::DEFAULT_JWT_SECRET {
+        tracing::warn!("Using default BEARER_TOKEN - this should be changed in production!");
+    }
+
+    let db = Arc::new(super::connect(&settings).await?);
+    let config = Arc::new(settings.app);
+
+    let health = Arc::new(HealthRegistry::with_defaults(db.clone()));
+    let state = routes::AppState::new(db.clone(), config.clone(), health.clone());
+    let scheduler = config
+        .scheduler
+        .enabled
+        .then(|| scheduler::scheduler(&state, Arc::new(SeaOrmTaskRunRepository::new(db.clone()))));
+    let worker = config.jobs.worker_in_server.then(|| {
+        Worker::new(
+            Arc::new(SeaOrmJobRepository::new(db.clone())),
+            jobs::registry(&state),
+            config.jobs.clone(),
+        )
+    });
+    let relay = config
+        .outbox
+        .relay_in_server
+        .then(|| events::relay(&state, Arc::new(SeaOrmOutboxRepository::new(db.clone()))));
+    let app = routes::create_router(state);
+
+    let listener =
+        tokio::net::TcpListener::bind(format!("{}:{}", config.server_host, config.server_port))
+            .await?;
+    tracing::info!(
+        "Server listening on http://{}:{}",
+        config.server_host,
+        config.server_port
+    );
+
+    let shutdown = Shutdown::new();
+    let drain_timeout = Duration::from_secs(config.shutdown_drain_timeout_secs);
+    let pre_drain_delay = Duration::from_secs(config.shutdown_pre_drain_delay_secs);
+
+    {
+        let shutdown = shutdown.clone();
+        let health = health.clone();
+        tokio::spawn(async move {
+            shutdown_signal().await;
+            // Fail readiness first and keep serving while load balancers
+            // notice, so they stop routing before connections are refused
+            health.begin_shutdown();
+            if !pre_drain_delay.is_zero() {
+                tracing::info!(
+                    "Not ready, waiting {}s before draining",
+                    pre_drain_delay.as_secs()
+                );
+                tokio::time::sleep(pre_drain_delay).await;
+            }
+            shutdown.trigger();
+        });
+    }
+
+    if let Some(scheduler) = scheduler {
+        shutdown.spawn("scheduler", scheduler.run(shutdown.clone()));
+    }
+
+    if let Some(worker) = worker {
+        shutdown.spawn("job-worker", worker.run(shutdown.clone()));
+    }
+
+    if let Some(relay) = relay {
+        shutdown.spawn("outbox-relay", relay.run(shutdown.clone()));
+    }
+
+    let mut server = {
+        let shutdown = shutdown.clone();
+        tokio::spawn(async move {
+            axum::serve(listener, app)
+                .with_graceful_shutdown(async move { shutdown.wait().await })
+                .await
+        })
+    };
+
+    tokio::select! {
+        result = &mut server => result??,
+        _ = shutdown.wait() => {
+            tracing::info!(
+                "Draining in-flight requests (timeout {}s)",
+                drain_timeout.as_secs()
+            );
+            match tokio::time::timeout(drain_timeout, &mut server).await {
+                Ok(result) => result??,
+                Err(_) => {
+                    tracing::warn!("Drain timeout elapsed, dropping remaining connections");
+                    server.abort();
+                }
+            }
+        }
+    }
+
+    shutdown.join_tasks(drain_timeout).await;
+
+    tracing::info!("Closing database connection pool");
+    db.close_by_ref().await?;
+    tracing::info!("Shutdown complete");
+
+    Ok(())
+}
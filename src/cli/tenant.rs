@@ -0,0 +1,72 @@
+use crate::config::Settings;
+use crate::enums::{Locale, TenantStatus};
+use crate::routes::AppState;
+use crate::services::health_service::HealthRegistry;
+use clap::Subcommand;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Debug, Subcommand)]
+pub enum TenantCommand {
+    /// Create a new active tenant
+    Create {
+        /// Display name of the tenant
+        #[arg(long)]
+        name: String,
+    },
+    /// List all tenants
+    List,
+    /// Mark a tenant as inactive
+    Suspend {
+        /// Tenant identifier
+        #[arg(long)]
+        tenant_id: Uuid,
+    },
+    /// Set the locale used for users without their own preference
+    SetLocale {
+        /// Tenant identifier
+        #[arg(long)]
+        tenant_id: Uuid,
+        /// Locale (en, es, pt); omit to clear
+        #[arg(long)]
+        locale: Option<Locale>,
+    },
+}
+
+pub async fn run(command: TenantCommand, settings: Settings) -> anyhow::Result<()> {
+    let db = Arc::new(super::connect(&settings).await?);
+    let state = AppState::new(
+        db.clone(),
+        Arc::new(settings.app),
+        Arc::new(HealthRegistry::new()),
+    );
+    let tenants = state.tenants;
+
+    match command {
+        TenantCommand::Create { name } => {
+            let tenant = tenants.create(name).await?;
+            println!("Created tenant {} ({})", tenant.id, tenant.name);
+        }
+        TenantCommand::List => {
+            for tenant in tenants.list_all().await? {
+                println!("{}  {:<8?}  {}", tenant.id, tenant.status, tenant.name);
+            }
+        }
+        TenantCommand::Suspend { tenant_id } => {
+            let tenant = tenants
+                .set_status(tenant_id, TenantStatus::Inactive)
+                .await?;
+            println!("Suspended tenant {} ({})", tenant.id, tenant.name);
+        }
+        TenantCommand::SetLocale { tenant_id, locale } => {
+            let tenant = tenants.set_default_locale(tenant_id, locale).await?;
+            match tenant.default_locale {
+                Some(locale) => println!("Set default locale of {} to {}", tenant.name, locale),
+                None => println!("Cleared default locale of {}", tenant.name),
+            }
+        }
+    }
+
+    db.close_by_ref().await?;
+    Ok(())
+}
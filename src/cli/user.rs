@@ -0,0 +1,114 @@
+use crate::config::Settings;
+use crate::enums::UserRole;
+use crate::middleware::validation::validate_request;
+use crate::routes::AppState;
+use crate::services::auth_service::RegisterRequest;
+use crate::services::health_service::HealthRegistry;
+use clap::Subcommand;
+use std::io::BufRead;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Debug, Subcommand)]
+pub enum UserCommand {
+    /// Create an admin user in a tenant
+    CreateAdmin {
+        /// Tenant the user belongs to
+        #[arg(long)]
+        tenant_id: Uuid,
+        /// Email address of the new admin
+        #[arg(long)]
+        email: String,
+        /// Password; read from stdin when omitted
+        #[arg(long, env = "ADMIN_PASSWORD", hide_env_values = true)]
+        password: Option<String>,
+    },
+    /// Set a new password for an existing user
+    ResetPassword {
+        /// Tenant the user belongs to
+        #[arg(long)]
+        tenant_id: Uuid,
+        /// Email address of the user
+        #[arg(long)]
+        email: String,
+        /// New password; read from stdin when omitted
+        #[arg(long, env = "NEW_PASSWORD", hide_env_values = true)]
+        password: Option<String>,
+    },
+    /// Erase the personal data of users deleted longer than
+    /// USER_DELETION_GRACE_DAYS ago
+    EraseDeleted,
+}
+
+pub async fn run(command: UserCommand, settings: Settings) -> anyhow::Result<()> {
+    let db = Arc::new(super::connect(&settings).await?);
+    let state = AppState::new(
+        db.clone(),
+        Arc::new(settings.app),
+        Arc::new(HealthRegistry::new()),
+    );
+
+    match command {
+        UserCommand::CreateAdmin {
+            tenant_id,
+            email,
+            password,
+        } => {
+            // Same validation rules as POST /api/auth/register
+            let req = validate_request(RegisterRequest {
+                tenant_id,
+                email,
+                password: password_or_stdin(password)?,
+            })?;
+            state.tenants.get_by_id(req.tenant_id).await?;
+            state
+                .passwords
+                .check(&[req.tenant_id], &req.email, &req.password)
+                .await?;
+
+            let user = state
+                .users
+                .create(req.tenant_id, req.email, &req.password, UserRole::Admin)
+                .await?;
+            println!(
+                "Created admin user {} in tenant {}",
+                user.id, user.tenant_id
+            );
+        }
+        UserCommand::ResetPassword {
+            tenant_id,
+            email,
+            password,
+        } => {
+            let req = validate_request(RegisterRequest {
+                tenant_id,
+                email,
+                password: password_or_stdin(password)?,
+            })?;
+
+            let user = state
+                .users
+                .reset_password(req.tenant_id, &req.email, &req.password)
+                .await?;
+            println!("Password reset for user {}", user.id);
+        }
+        UserCommand::EraseDeleted => {
+            let erased = state.users.erase_deleted().await?;
+            println!("Erased {} deleted user(s)", erased);
+        }
+    }
+
+    db.close_by_ref().await?;
+    Ok(())
+}
+
+fn password_or_stdin(password: Option<String>) -> anyhow::Result<String> {
+    if let Some(password) = password {
+        return Ok(password);
+    }
+
+    eprintln!("Enter password:");
+    let mut line = String::new();
+    std::io::stdin().lock().read_line(&mut line)?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
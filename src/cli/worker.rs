@@ -0,0 +1,39 @@
+use crate::{
+    config::Settings,
+    events,
+    jobs::{self, Worker},
+    repositories::{SeaOrmJobRepository, SeaOrmOutboxRepository},
+    routes,
+    services::health_service::HealthRegistry,
+    utils::shutdown::{Shutdown, shutdown_signal},
+};
+use std::sync::Arc;
+
+/// Runs a job worker and an outbox relay without the HTTP server until a
+/// shutdown signal, then lets the jobs in progress finish.
+pub async fn run(settings: Settings) -> anyhow::Result<()> {
+    tracing::info!("Effective configuration:\n{}", settings.redacted_dump());
+
+    let db = Arc::new(super::connect(&settings).await?);
+    let config = Arc::new(settings.app);
+    let state = routes::AppState::new(db.clone(), config.clone(), Arc::new(HealthRegistry::new()));
+    let worker = Worker::new(
+        Arc::new(SeaOrmJobRepository::new(db.clone())),
+        jobs::registry(&state),
+        config.jobs.clone(),
+    );
+    let relay = events::relay(&state, Arc::new(SeaOrmOutboxRepository::new(db.clone())));
+
+    let shutdown = Shutdown::new();
+    {
+        let shutdown = shutdown.clone();
+        tokio::spawn(async move {
+            shutdown_signal().await;
+            shutdown.trigger();
+        });
+    }
+    tokio::join!(worker.run(shutdown.clone()), relay.run(shutdown));
+
+    db.close_by_ref().await?;
+    Ok(())
+}
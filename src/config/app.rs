@@ -1,4 +1,8 @@
-use std::env;
+use crate::config::settings::{DEFAULT_JWT_SECRET, Environment, Reader};
+use crate::config::{
+    ExportConfig, JobsConfig, OutboxConfig, PasswordConfig, SchedulerConfig, WebhooksConfig,
+};
+use axum::http::HeaderValue;
 
 #[derive(Clone, Debug)]
 pub struct Config {
@@ -6,69 +10,141 @@ pub struct Config {
     pub jwt_expiration_minutes: i64,
     pub server_host: String,
     pub server_port: u16,
-    pub environment: String,
+    pub environment: Environment,
     pub frontend_url: Option<String>,
+    /// Browser origins allowed to call the API besides `FRONTEND_URL`, e.g.
+    /// tenants' own frontends.
+    pub cors_allowed_origins: Vec<String>,
+    pub shutdown_drain_timeout_secs: u64,
+    /// Seconds between failing readiness and closing the listener, so load
+    /// balancers stop routing before connections are refused.
+    pub shutdown_pre_drain_delay_secs: u64,
+    pub password: PasswordConfig,
+    /// Days a deleted user can be restored before its personal data is
+    /// erased.
+    pub user_deletion_grace_days: u32,
+    pub export: ExportConfig,
+    pub jobs: JobsConfig,
+    pub scheduler: SchedulerConfig,
+    pub webhooks: WebhooksConfig,
+    pub outbox: OutboxConfig,
+    /// Lets requests to identity providers and webhook receivers reach
+    /// loopback and private addresses; for development only.
+    pub outbound_allow_private_addresses: bool,
 }
 
 impl Config {
-    pub fn from_env() -> Result<Self, String> {
-        let jwt_secret = env::var("BEARER_TOKEN")
-            .unwrap_or_else(|_| "your-secret-key-change-in-production".to_string());
+    pub(crate) fn read(reader: &mut Reader<'_>, environment: Environment) -> Self {
+        let jwt_secret = reader
+            .optional("BEARER_TOKEN")
+            .unwrap_or_else(|| DEFAULT_JWT_SECRET.to_string());
+        let jwt_expiration_minutes = reader.parse_or("JWT_EXPIRATION_MINUTES", 10i64);
+        let server_host = reader
+            .optional("SERVER_HOST")
+            .unwrap_or_else(|| "0.0.0.0".to_string());
+        let server_port = reader.parse_or("SERVER_PORT", 8070u16);
+        let shutdown_drain_timeout_secs = reader.parse_or("SHUTDOWN_DRAIN_TIMEOUT_SECS", 30u64);
+        // Nothing routes through a load balancer in development
+        let pre_drain_delay = if environment.is_development() { 0 } else { 5 };
+        let shutdown_pre_drain_delay_secs =
+            reader.parse_or("SHUTDOWN_PRE_DRAIN_DELAY_SECS", pre_drain_delay);
+        let frontend_url = reader.optional("FRONTEND_URL");
+        let cors_allowed_origins: Vec<String> = reader
+            .optional("CORS_ALLOWED_ORIGINS")
+            .map(|origins| {
+                origins
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|origin| !origin.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let password = PasswordConfig::read(reader);
+        let user_deletion_grace_days = reader.parse_or("USER_DELETION_GRACE_DAYS", 30u32);
+        let export = ExportConfig::read(reader);
+        let jobs = JobsConfig::read(reader);
+        let scheduler = SchedulerConfig::read(reader);
+        let webhooks = WebhooksConfig::read(reader);
+        let outbox = OutboxConfig::read(reader);
+        let outbound_allow_private_addresses =
+            reader.parse_or("OUTBOUND_ALLOW_PRIVATE_ADDRESSES", false);
 
-        tracing::info!("BEARER_TOKEN loaded from environment");
-        tracing::debug!("BEARER_TOKEN length: {}", jwt_secret.len());
-        tracing::debug!(
-            "BEARER_TOKEN (first 10 chars): {}",
-            &jwt_secret.chars().take(10).collect::<String>()
+        reader.check(
+            jwt_expiration_minutes > 0,
+            "JWT_EXPIRATION_MINUTES must be greater than 0",
+        );
+        reader.check(
+            shutdown_pre_drain_delay_secs <= 300,
+            "SHUTDOWN_PRE_DRAIN_DELAY_SECS must be at most 300",
+        );
+        reader.check(
+            user_deletion_grace_days <= 3650,
+            "USER_DELETION_GRACE_DAYS must be at most 3650",
         );
-        if jwt_secret == "your-secret-key-change-in-production" {
-            tracing::warn!("Using default BEARER_TOKEN - this should be changed in production!");
-        }
-
-        let jwt_expiration_minutes = env::var("JWT_EXPIRATION_MINUTES")
-            .unwrap_or_else(|_| "10".to_string())
-            .parse()
-            .map_err(|_| "JWT_EXPIRATION_MINUTES must be a valid number".to_string())?;
-
-        tracing::info!("JWT expiration minutes: {}", jwt_expiration_minutes);
-
-        let server_host = env::var("SERVER_HOST").unwrap_or_else(|_| "0.0.0.0".to_string());
-
-        let server_port = env::var("SERVER_PORT")
-            .unwrap_or_else(|_| "8070".to_string())
-            .parse::<u16>()
-            .map_err(|_| "SERVER_PORT must be a valid number".to_string())?;
-
-        tracing::info!("Server will bind to {}:{}", server_host, server_port);
-
-        let environment = env::var("ENVIRONMENT")
-            .unwrap_or_else(|_| "production".to_string())
-            .to_lowercase();
 
-        let frontend_url = env::var("FRONTEND_URL").ok();
+        if environment.is_production() {
+            reader.check(
+                jwt_secret != DEFAULT_JWT_SECRET,
+                "BEARER_TOKEN must be set in production",
+            );
+            reader.check(
+                jwt_secret.len() >= 32,
+                "BEARER_TOKEN must be at least 32 characters in production",
+            );
+            reader.check(
+                frontend_url.is_some(),
+                "FRONTEND_URL must be set in production",
+            );
+            reader.check(
+                !outbound_allow_private_addresses,
+                "OUTBOUND_ALLOW_PRIVATE_ADDRESSES cannot be enabled in production",
+            );
+        }
 
-        if environment == "production" || environment == "prod" {
-            if frontend_url.is_none() {
-                tracing::warn!(
-                    "ENVIRONMENT is production but FRONTEND_URL is not set - CORS will be restrictive"
-                );
-            } else {
-                tracing::info!(
-                    "CORS configured for production with FRONTEND_URL: {}",
-                    frontend_url.as_ref().unwrap()
-                );
-            }
-        } else {
-            tracing::info!("CORS configured for development - allowing all origins");
+        if let Some(frontend_url) = &frontend_url {
+            reader.check(
+                frontend_url.parse::<HeaderValue>().is_ok(),
+                format!("FRONTEND_URL '{}' is not a valid origin", frontend_url),
+            );
+        }
+        for origin in &cors_allowed_origins {
+            reader.check(
+                is_origin(origin),
+                format!(
+                    "CORS_ALLOWED_ORIGINS entry '{}' is not an origin such as https://app.example.com",
+                    origin
+                ),
+            );
         }
 
-        Ok(Self {
+        Self {
             jwt_secret,
             jwt_expiration_minutes,
             server_host,
             server_port,
             environment,
             frontend_url,
-        })
+            cors_allowed_origins,
+            shutdown_drain_timeout_secs,
+            shutdown_pre_drain_delay_secs,
+            password,
+            user_deletion_grace_days,
+            export,
+            jobs,
+            scheduler,
+            webhooks,
+            outbox,
+            outbound_allow_private_addresses,
+        }
     }
 }
+
+/// A bare `scheme://host[:port]`, as browsers send it in `Origin`.
+fn is_origin(origin: &str) -> bool {
+    origin
+        .strip_prefix("https://")
+        .or_else(|| origin.strip_prefix("http://"))
+        .is_some_and(|host| !host.is_empty() && !host.contains('/'))
+        && origin.parse::<HeaderValue>().is_ok()
+}
@@ -2,27 +2,42 @@ use crate::config::Config;
 use axum::http::HeaderValue;
 use tower_http::cors::{AllowOrigin, CorsLayer};
 
+/// Outside development, allows `FRONTEND_URL` and `CORS_ALLOWED_ORIGINS`.
 pub fn create_cors_layer(config: &Config) -> CorsLayer {
-    let cors_origin = if config.environment == "dev" || config.environment == "development" {
+    let cors_origin = if config.environment.is_development() {
         AllowOrigin::predicate(
             |_origin: &HeaderValue, _request_head: &axum::http::request::Parts| true,
         )
     } else {
-        if let Some(frontend_url) = &config.frontend_url {
+        let frontend_origin = if let Some(frontend_url) = &config.frontend_url {
             match frontend_url.parse::<HeaderValue>() {
-                Ok(header_value) => AllowOrigin::exact(header_value),
+                Ok(header_value) => Some(header_value),
                 Err(_) => {
                     tracing::warn!(
-                        "FRONTEND_URL '{}' is invalid, defaulting to no CORS",
+                        "FRONTEND_URL '{}' is invalid, only CORS_ALLOWED_ORIGINS get CORS",
                         frontend_url
                     );
-                    AllowOrigin::list(vec![])
+                    None
                 }
             }
         } else {
-            tracing::warn!("FRONTEND_URL not set in production, defaulting to no CORS");
-            AllowOrigin::list(vec![])
-        }
+            tracing::warn!(
+                "FRONTEND_URL not set in production, only CORS_ALLOWED_ORIGINS get CORS"
+            );
+            None
+        };
+
+        // Validated when the configuration is read
+        let origins: Vec<HeaderValue> = frontend_origin
+            .into_iter()
+            .chain(
+                config
+                    .cors_allowed_origins
+                    .iter()
+                    .filter_map(|origin| origin.parse().ok()),
+            )
+            .collect();
+        AllowOrigin::list(origins)
     };
 
     CorsLayer::new()
@@ -1,12 +1,12 @@
+use crate::config::settings::{Environment, Reader};
 use sea_orm::{Database, DatabaseConnection};
 use serde::{Deserialize, Serialize};
-use std::env;
 use std::time::Duration;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DatabaseConfig {
     pub url: String,
-    pub environment: String,
+    pub environment: Environment,
     pub max_connections: u32,
     pub min_connections: u32,
     pub connect_timeout_secs: u64,
@@ -15,85 +15,54 @@ pub struct DatabaseConfig {
 }
 
 impl DatabaseConfig {
-    pub fn from_env() -> Result<Self, String> {
-        let url = env::var("DATABASE_URL")
-            .map_err(|_| "DATABASE_URL environment variable is not set".to_string())?;
+    pub(crate) fn read(reader: &mut Reader<'_>, environment: Environment) -> Self {
+        let url = reader.required("DATABASE_URL");
+        if !url.is_empty() {
+            reader.check(
+                Self::is_supported_url(&url),
+                if cfg!(feature = "sqlite") {
+                    "DATABASE_URL must start with postgres://, postgresql:// or sqlite:"
+                } else {
+                    "DATABASE_URL must start with postgres:// or postgresql:// (build with the `sqlite` feature for SQLite)"
+                },
+            );
+        }
 
-        let environment = env::var("ENVIRONMENT")
-            .unwrap_or_else(|_| "production".to_string())
-            .to_lowercase();
+        // Environment-based pool defaults: development is more permissive,
+        // everything else gets stricter, production-ready settings
+        let (max, min, connect, idle, lifetime) = if environment.is_development() {
+            (10, 2, 10, 600, 1800)
+        } else {
+            (20, 5, 5, 300, 1800)
+        };
 
-        // Environment-based pool configuration
-        let (max_connections, min_connections, connect_timeout, idle_timeout, max_lifetime) =
-            if environment == "dev" || environment == "development" {
-                // Development: More permissive settings
-                (
-                    env::var("DB_MAX_CONNECTIONS")
-                        .unwrap_or_else(|_| "10".to_string())
-                        .parse::<u32>()
-                        .unwrap_or(10),
-                    env::var("DB_MIN_CONNECTIONS")
-                        .unwrap_or_else(|_| "2".to_string())
-                        .parse::<u32>()
-                        .unwrap_or(2),
-                    env::var("DB_CONNECT_TIMEOUT_SECS")
-                        .unwrap_or_else(|_| "10".to_string())
-                        .parse::<u64>()
-                        .unwrap_or(10),
-                    env::var("DB_IDLE_TIMEOUT_SECS")
-                        .unwrap_or_else(|_| "600".to_string())
-                        .parse::<u64>()
-                        .unwrap_or(600),
-                    env::var("DB_MAX_LIFETIME_SECS")
-                        .unwrap_or_else(|_| "1800".to_string())
-                        .parse::<u64>()
-                        .unwrap_or(1800),
-                )
-            } else {
-                // Production: Stricter, optimized settings
-                (
-                    env::var("DB_MAX_CONNECTIONS")
-                        .unwrap_or_else(|_| "20".to_string())
-                        .parse::<u32>()
-                        .unwrap_or(20),
-                    env::var("DB_MIN_CONNECTIONS")
-                        .unwrap_or_else(|_| "5".to_string())
-                        .parse::<u32>()
-                        .unwrap_or(5),
-                    env::var("DB_CONNECT_TIMEOUT_SECS")
-                        .unwrap_or_else(|_| "5".to_string())
-                        .parse::<u64>()
-                        .unwrap_or(5),
-                    env::var("DB_IDLE_TIMEOUT_SECS")
-                        .unwrap_or_else(|_| "300".to_string())
-                        .parse::<u64>()
-                        .unwrap_or(300),
-                    env::var("DB_MAX_LIFETIME_SECS")
-                        .unwrap_or_else(|_| "1800".to_string())
-                        .parse::<u64>()
-                        .unwrap_or(1800),
-                )
-            };
+        let max_connections = reader.parse_or("DB_MAX_CONNECTIONS", max);
+        let min_connections = reader.parse_or("DB_MIN_CONNECTIONS", min);
+        let connect_timeout_secs = reader.parse_or("DB_CONNECT_TIMEOUT_SECS", connect);
+        let idle_timeout_secs = reader.parse_or("DB_IDLE_TIMEOUT_SECS", idle);
+        let max_lifetime_secs = reader.parse_or("DB_MAX_LIFETIME_SECS", lifetime);
 
-        tracing::info!(
-            "Database pool configuration (environment: {}): max={}, min={}, connect_timeout={}s, idle_timeout={}s, max_lifetime={}s",
-            environment,
-            max_connections,
-            min_connections,
-            connect_timeout,
-            idle_timeout,
-            max_lifetime
+        reader.check(
+            max_connections > 0,
+            "DB_MAX_CONNECTIONS must be greater than 0",
+        );
+        reader.check(
+            min_connections <= max_connections,
+            format!(
+                "DB_MIN_CONNECTIONS ({}) must not exceed DB_MAX_CONNECTIONS ({})",
+                min_connections, max_connections
+            ),
         );
 
-        Ok(Self {
+        Self {
             url,
             environment,
             max_connections,
             min_connections,
-            connect_timeout_secs: connect_timeout,
-            idle_timeout_secs: idle_timeout,
-            max_lifetime_secs: max_lifetime,
-        })
+            connect_timeout_secs,
+            idle_timeout_secs,
+            max_lifetime_secs,
+        }
     }
 
     pub async fn connect(&self) -> Result<DatabaseConnection, sea_orm::DbErr> {
@@ -106,8 +75,18 @@ impl DatabaseConfig {
             .idle_timeout(Duration::from_secs(self.idle_timeout_secs))
             .max_lifetime(Duration::from_secs(self.max_lifetime_secs));
 
+        // Every new connection to an in-memory SQLite database opens a fresh,
+        // empty database, so keep exactly one connection alive for the
+        // lifetime of the pool
+        if self.is_in_memory_sqlite() {
+            opt.max_connections(1)
+                .min_connections(1)
+                .idle_timeout(None)
+                .max_lifetime(None);
+        }
+
         // Logging configuration
-        if self.environment == "dev" || self.environment == "development" {
+        if self.environment.is_development() {
             opt.sqlx_logging(true);
             opt.sqlx_logging_level(log::LevelFilter::Debug);
         } else {
@@ -115,11 +94,29 @@ impl DatabaseConfig {
         }
 
         tracing::info!(
-            "Connecting to database with pool: max={}, min={}",
+            "Connecting to database (environment: {}) with pool: max={}, min={}, connect_timeout={}s, idle_timeout={}s, max_lifetime={}s",
+            self.environment,
             self.max_connections,
-            self.min_connections
+            self.min_connections,
+            self.connect_timeout_secs,
+            self.idle_timeout_secs,
+            self.max_lifetime_secs
         );
 
         Database::connect(opt).await
     }
+
+    pub fn is_sqlite(&self) -> bool {
+        self.url.starts_with("sqlite:")
+    }
+
+    pub fn is_in_memory_sqlite(&self) -> bool {
+        self.is_sqlite() && (self.url.contains(":memory:") || self.url.contains("mode=memory"))
+    }
+
+    fn is_supported_url(url: &str) -> bool {
+        url.starts_with("postgres://")
+            || url.starts_with("postgresql://")
+            || (cfg!(feature = "sqlite") && url.starts_with("sqlite:"))
+    }
 }
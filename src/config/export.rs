@@ -0,0 +1,40 @@
+use crate::config::settings::Reader;
+use std::path::PathBuf;
+
+/// Where tenant exports are written and how long their download links last.
+#[derive(Clone, Debug)]
+pub struct ExportConfig {
+    pub dir: PathBuf,
+    pub link_ttl_minutes: i64,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from("exports"),
+            link_ttl_minutes: 15,
+        }
+    }
+}
+
+impl ExportConfig {
+    pub(crate) fn read(reader: &mut Reader<'_>) -> Self {
+        let defaults = Self::default();
+        let dir = reader
+            .optional("EXPORT_DIR")
+            .map(PathBuf::from)
+            .unwrap_or(defaults.dir);
+        let link_ttl_minutes =
+            reader.parse_or("EXPORT_LINK_TTL_MINUTES", defaults.link_ttl_minutes);
+
+        reader.check(
+            (1..=1440).contains(&link_ttl_minutes),
+            "EXPORT_LINK_TTL_MINUTES must be between 1 and 1440",
+        );
+
+        Self {
+            dir,
+            link_ttl_minutes,
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use crate::config::settings::Reader;
+use std::time::Duration;
+
+/// How background job workers poll, run and retry jobs.
+#[derive(Clone, Debug)]
+pub struct JobsConfig {
+    /// Whether `serve` runs a worker next to the HTTP server. Turn off when
+    /// workers run separately with the `worker` command.
+    pub worker_in_server: bool,
+    /// Jobs a worker runs at the same time.
+    pub concurrency: u32,
+    pub poll_interval: Duration,
+    /// How long a job may run before another worker presumes its worker dead
+    /// and claims it again.
+    pub lock_timeout: Duration,
+    /// Attempts a job gets unless it asks for another number.
+    pub max_attempts: i32,
+}
+
+impl Default for JobsConfig {
+    fn default() -> Self {
+        Self {
+            worker_in_server: true,
+            concurrency: 4,
+            poll_interval: Duration::from_secs(1),
+            lock_timeout: Duration::from_secs(300),
+            max_attempts: 5,
+        }
+    }
+}
+
+impl JobsConfig {
+    pub(crate) fn read(reader: &mut Reader<'_>) -> Self {
+        let defaults = Self::default();
+        let worker_in_server = reader.parse_or("JOB_WORKER_IN_SERVER", defaults.worker_in_server);
+        let concurrency = reader.parse_or("JOB_CONCURRENCY", defaults.concurrency);
+        let poll_interval_ms = reader.parse_or(
+            "JOB_POLL_INTERVAL_MS",
+            defaults.poll_interval.as_millis() as u64,
+        );
+        let lock_timeout_secs =
+            reader.parse_or("JOB_LOCK_TIMEOUT_SECS", defaults.lock_timeout.as_secs());
+        let max_attempts = reader.parse_or("JOB_MAX_ATTEMPTS", defaults.max_attempts);
+
+        reader.check(
+            (1..=64).contains(&concurrency),
+            "JOB_CONCURRENCY must be between 1 and 64",
+        );
+        reader.check(
+            (10..=60_000).contains(&poll_interval_ms),
+            "JOB_POLL_INTERVAL_MS must be between 10 and 60000",
+        );
+        reader.check(
+            lock_timeout_secs >= 10,
+            "JOB_LOCK_TIMEOUT_SECS must be at least 10",
+        );
+        reader.check(
+            (1..=25).contains(&max_attempts),
+            "JOB_MAX_ATTEMPTS must be between 1 and 25",
+        );
+
+        Self {
+            worker_in_server,
+            concurrency,
+            poll_interval: Duration::from_millis(poll_interval_ms),
+            lock_timeout: Duration::from_secs(lock_timeout_secs),
+            max_attempts,
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use crate::config::settings::{Environment, Reader};
+use crate::utils::redact::RedactingMakeWriter;
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::fmt::MakeWriter;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogFormat {
+    Pretty,
+    Compact,
+    Json,
+}
+
+impl std::str::FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "pretty" => Ok(LogFormat::Pretty),
+            "compact" => Ok(LogFormat::Compact),
+            "json" => Ok(LogFormat::Json),
+            other => Err(format!(
+                "expected one of pretty, compact, json (got '{}')",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LoggingConfig {
+    pub filter: String,
+    pub format: LogFormat,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            filter: "template_rust_backend=info,tower_http=info".to_string(),
+            format: LogFormat::Compact,
+        }
+    }
+}
+
+impl LoggingConfig {
+    pub(crate) fn read(reader: &mut Reader<'_>, environment: Environment) -> Self {
+        // RUST_LOG takes precedence; otherwise pick a sane default per environment
+        let filter = reader.optional("RUST_LOG").unwrap_or_else(|| {
+            if environment.is_development() {
+                "template_rust_backend=debug,tower_http=debug".to_string()
+            } else {
+                "template_rust_backend=info,tower_http=info".to_string()
+            }
+        });
+        let default_format = if environment.is_development() {
+            LogFormat::Pretty
+        } else {
+            LogFormat::Json
+        };
+        let format = reader.parse_or("LOG_FORMAT", default_format);
+
+        if let Err(e) = EnvFilter::try_new(&filter) {
+            reader.check(false, format!("RUST_LOG '{}' is invalid: {}", filter, e));
+        }
+
+        Self { filter, format }
+    }
+
+    /// Installs the global tracing subscriber writing to stdout.
+    pub fn init(&self) -> Result<(), String> {
+        self.init_with_writer(std::io::stdout)
+    }
+
+    /// Installs the global tracing subscriber. Every format writes through
+    /// `RedactingMakeWriter`, so PII is masked regardless of the output shape.
+    pub fn init_with_writer<W>(&self, writer: W) -> Result<(), String>
+    where
+        W: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+    {
+        let filter = EnvFilter::try_new(&self.filter)
+            .map_err(|e| format!("Invalid RUST_LOG filter '{}': {}", self.filter, e))?;
+
+        let builder = tracing_subscriber::fmt()
+            .with_env_filter(filter)
+            .with_writer(RedactingMakeWriter::new(writer));
+
+        let result = match self.format {
+            LogFormat::Pretty => builder.pretty().try_init(),
+            LogFormat::Compact => builder.compact().try_init(),
+            LogFormat::Json => builder
+                .json()
+                .flatten_event(true)
+                .with_current_span(true)
+                .try_init(),
+        };
+
+        result.map_err(|e| format!("Failed to initialize logging: {}", e))
+    }
+}
@@ -1,7 +1,23 @@
 pub mod app;
 pub mod cors;
 pub mod database;
+pub mod export;
+pub mod jobs;
+pub mod logging;
+pub mod outbox;
+pub mod password;
+pub mod scheduler;
+pub mod settings;
+pub mod webhooks;
 
 pub use app::Config;
 pub use cors::create_cors_layer;
 pub use database::DatabaseConfig;
+pub use export::ExportConfig;
+pub use jobs::JobsConfig;
+pub use logging::{LogFormat, LoggingConfig};
+pub use outbox::OutboxConfig;
+pub use password::PasswordConfig;
+pub use scheduler::SchedulerConfig;
+pub use settings::{ConfigError, Environment, Settings};
+pub use webhooks::WebhooksConfig;
@@ -0,0 +1,61 @@
+use crate::config::settings::Reader;
+use std::time::Duration;
+
+/// Where and how often domain events are relayed from the outbox, how often
+/// a failing event is tried, and how long relayed events are kept.
+#[derive(Clone, Debug)]
+pub struct OutboxConfig {
+    /// Whether `serve` runs a relay next to the HTTP server. `worker`
+    /// processes always run one.
+    pub relay_in_server: bool,
+    pub poll_interval: Duration,
+    /// Attempts an event gets before the relay gives up on it.
+    pub max_attempts: i32,
+    /// Days relayed events are kept before the `purge_outbox` task deletes
+    /// them.
+    pub retention_days: u32,
+}
+
+impl Default for OutboxConfig {
+    fn default() -> Self {
+        Self {
+            relay_in_server: true,
+            poll_interval: Duration::from_millis(500),
+            max_attempts: 20,
+            retention_days: 7,
+        }
+    }
+}
+
+impl OutboxConfig {
+    pub(crate) fn read(reader: &mut Reader<'_>) -> Self {
+        let defaults = Self::default();
+        let relay_in_server = reader.parse_or("OUTBOX_RELAY_IN_SERVER", defaults.relay_in_server);
+        let poll_interval_ms = reader.parse_or(
+            "OUTBOX_POLL_INTERVAL_MS",
+            defaults.poll_interval.as_millis() as u64,
+        );
+        let max_attempts = reader.parse_or("OUTBOX_MAX_ATTEMPTS", defaults.max_attempts);
+        let retention_days = reader.parse_or("OUTBOX_RETENTION_DAYS", defaults.retention_days);
+
+        reader.check(
+            (10..=60_000).contains(&poll_interval_ms),
+            "OUTBOX_POLL_INTERVAL_MS must be between 10 and 60000",
+        );
+        reader.check(
+            (1..=100).contains(&max_attempts),
+            "OUTBOX_MAX_ATTEMPTS must be between 1 and 100",
+        );
+        reader.check(
+            (1..=365).contains(&retention_days),
+            "OUTBOX_RETENTION_DAYS must be between 1 and 365",
+        );
+
+        Self {
+            relay_in_server,
+            poll_interval: Duration::from_millis(poll_interval_ms),
+            max_attempts,
+            retention_days,
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use crate::config::settings::Reader;
+use crate::utils::password_policy::{
+    BreachedPasswords, MAX_PASSWORD_HISTORY, MAX_PASSWORD_LENGTH, PasswordPolicy,
+};
+use argon2::Params;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Global password policy, which tenants can override, the breached
+/// password list checked for every tenant, and how passwords are hashed.
+#[derive(Clone, Debug, Default)]
+pub struct PasswordConfig {
+    pub policy: PasswordPolicy,
+    pub breached_passwords_dir: Option<PathBuf>,
+    /// Argon2id cost of new hashes; hashes made with other parameters are
+    /// replaced at the user's next login.
+    pub argon2: Params,
+    /// Server-side secret mixed into every hash and kept out of the database.
+    pub pepper: Option<String>,
+}
+
+impl PasswordConfig {
+    pub(crate) fn read(reader: &mut Reader<'_>) -> Self {
+        let defaults = PasswordPolicy::default();
+        let policy = PasswordPolicy {
+            min_length: reader.parse_or("PASSWORD_MIN_LENGTH", defaults.min_length),
+            min_character_classes: reader.parse_or(
+                "PASSWORD_MIN_CHARACTER_CLASSES",
+                defaults.min_character_classes,
+            ),
+            min_strength: reader.parse_or("PASSWORD_MIN_STRENGTH", defaults.min_strength),
+            reject_personal_info: reader.parse_or(
+                "PASSWORD_REJECT_PERSONAL_INFO",
+                defaults.reject_personal_info,
+            ),
+            history: reader.parse_or("PASSWORD_HISTORY", defaults.history),
+        };
+        let breached_passwords_dir = reader.optional("BREACHED_PASSWORDS_DIR").map(PathBuf::from);
+        let memory_kib = reader.parse_or("ARGON2_MEMORY_KIB", Params::DEFAULT_M_COST);
+        let iterations = reader.parse_or("ARGON2_ITERATIONS", Params::DEFAULT_T_COST);
+        let parallelism = reader.parse_or("ARGON2_PARALLELISM", Params::DEFAULT_P_COST);
+        let pepper = reader.optional("PASSWORD_PEPPER");
+
+        reader.check(
+            (8..=MAX_PASSWORD_LENGTH).contains(&policy.min_length),
+            format!(
+                "PASSWORD_MIN_LENGTH must be between 8 and {}",
+                MAX_PASSWORD_LENGTH
+            ),
+        );
+        reader.check(
+            (1..=4).contains(&policy.min_character_classes),
+            "PASSWORD_MIN_CHARACTER_CLASSES must be between 1 and 4",
+        );
+        reader.check(
+            policy.min_strength <= 4,
+            "PASSWORD_MIN_STRENGTH must be between 0 and 4",
+        );
+        reader.check(
+            policy.history <= MAX_PASSWORD_HISTORY,
+            format!("PASSWORD_HISTORY must be at most {}", MAX_PASSWORD_HISTORY),
+        );
+        let argon2 = Params::new(memory_kib, iterations, parallelism, None);
+        reader.check(
+            argon2.is_ok(),
+            format!(
+                "ARGON2_* parameters are invalid: {}",
+                argon2
+                    .as_ref()
+                    .err()
+                    .map(ToString::to_string)
+                    .unwrap_or_default()
+            ),
+        );
+        reader.check(
+            pepper.as_ref().is_none_or(|pepper| pepper.len() >= 32),
+            "PASSWORD_PEPPER must be at least 32 characters",
+        );
+        if let Some(path) = &breached_passwords_dir {
+            reader.check(
+                path.is_dir(),
+                format!("BREACHED_PASSWORDS_DIR '{}' does not exist", path.display()),
+            );
+        }
+
+        Self {
+            policy,
+            breached_passwords_dir,
+            argon2: argon2.unwrap_or_default(),
+            pepper,
+        }
+    }
+
+    /// The breached password list in `BREACHED_PASSWORDS_DIR`, if set.
+    pub fn breached_passwords(&self) -> Option<Arc<BreachedPasswords>> {
+        let dir = self.breached_passwords_dir.as_ref()?;
+        Some(Arc::new(BreachedPasswords::new(dir)))
+    }
+}
@@ -0,0 +1,45 @@
+use crate::config::settings::Reader;
+use crate::utils::cron::Schedule;
+
+/// Whether `serve` runs the maintenance scheduler, and the cron schedule of
+/// each built-in task, in UTC.
+#[derive(Clone, Debug)]
+pub struct SchedulerConfig {
+    pub enabled: bool,
+    pub erase_deleted_users: Schedule,
+    pub purge_auth_artifacts: Schedule,
+    pub deactivate_idle_members: Schedule,
+    pub purge_outbox: Schedule,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            erase_deleted_users: "0 * * * *".parse().expect("valid schedule"),
+            purge_auth_artifacts: "*/15 * * * *".parse().expect("valid schedule"),
+            deactivate_idle_members: "30 3 * * *".parse().expect("valid schedule"),
+            purge_outbox: "15 4 * * *".parse().expect("valid schedule"),
+        }
+    }
+}
+
+impl SchedulerConfig {
+    pub(crate) fn read(reader: &mut Reader<'_>) -> Self {
+        let defaults = Self::default();
+        Self {
+            enabled: reader.parse_or("SCHEDULER_ENABLED", defaults.enabled),
+            erase_deleted_users: reader
+                .parse_or("SCHEDULE_ERASE_DELETED_USERS", defaults.erase_deleted_users),
+            purge_auth_artifacts: reader.parse_or(
+                "SCHEDULE_PURGE_AUTH_ARTIFACTS",
+                defaults.purge_auth_artifacts,
+            ),
+            deactivate_idle_members: reader.parse_or(
+                "SCHEDULE_DEACTIVATE_IDLE_MEMBERS",
+                defaults.deactivate_idle_members,
+            ),
+            purge_outbox: reader.parse_or("SCHEDULE_PURGE_OUTBOX", defaults.purge_outbox),
+        }
+    }
+}
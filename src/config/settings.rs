@@ -0,0 +1,589 @@
+use crate::config::{Config, DatabaseConfig, LoggingConfig};
+use crate::utils::redact::REDACTED;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+pub const DEFAULT_JWT_SECRET: &str = "your-secret-key-change-in-production";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Environment {
+    Development,
+    Test,
+    Production,
+}
+
+impl Environment {
+    pub fn is_development(&self) -> bool {
+        *self == Environment::Development
+    }
+
+    pub fn is_production(&self) -> bool {
+        *self == Environment::Production
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Environment::Development => "development",
+            Environment::Test => "test",
+            Environment::Production => "production",
+        }
+    }
+}
+
+impl FromStr for Environment {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "dev" | "development" => Ok(Environment::Development),
+            "test" => Ok(Environment::Test),
+            "prod" | "production" => Ok(Environment::Production),
+            other => Err(format!(
+                "ENVIRONMENT must be one of dev, development, test, prod, production (got '{}')",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Environment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A configuration key, addressable both as an environment variable and as a
+/// dotted path in the TOML file.
+pub struct Key {
+    pub env: &'static str,
+    pub path: &'static str,
+    pub secret: bool,
+    /// The effective value, as [`Settings::redacted_dump`] shows it before
+    /// masking secrets; `None` when unset.
+    value: fn(&Settings) -> Option<String>,
+}
+
+const fn key(env: &'static str, path: &'static str, value: fn(&Settings) -> Option<String>) -> Key {
+    Key {
+        env,
+        path,
+        secret: false,
+        value,
+    }
+}
+
+const fn secret(
+    env: &'static str,
+    path: &'static str,
+    value: fn(&Settings) -> Option<String>,
+) -> Key {
+    Key {
+        env,
+        path,
+        secret: true,
+        value,
+    }
+}
+
+pub const KEYS: &[Key] = &[
+    key("SERVER_HOST", "server.host", |s| {
+        Some(s.app.server_host.clone())
+    }),
+    key("SERVER_PORT", "server.port", |s| {
+        Some(s.app.server_port.to_string())
+    }),
+    key(
+        "SHUTDOWN_DRAIN_TIMEOUT_SECS",
+        "server.shutdown_drain_timeout_secs",
+        |s| Some(s.app.shutdown_drain_timeout_secs.to_string()),
+    ),
+    key(
+        "SHUTDOWN_PRE_DRAIN_DELAY_SECS",
+        "server.shutdown_pre_drain_delay_secs",
+        |s| Some(s.app.shutdown_pre_drain_delay_secs.to_string()),
+    ),
+    key("FRONTEND_URL", "server.frontend_url", |s| {
+        s.app.frontend_url.clone()
+    }),
+    key("CORS_ALLOWED_ORIGINS", "server.cors_allowed_origins", |s| {
+        (!s.app.cors_allowed_origins.is_empty()).then(|| s.app.cors_allowed_origins.join(","))
+    }),
+    secret("BEARER_TOKEN", "auth.jwt_secret", |s| {
+        Some(s.app.jwt_secret.clone())
+    }),
+    key(
+        "JWT_EXPIRATION_MINUTES",
+        "auth.jwt_expiration_minutes",
+        |s| Some(s.app.jwt_expiration_minutes.to_string()),
+    ),
+    key("PASSWORD_MIN_LENGTH", "password.min_length", |s| {
+        Some(s.app.password.policy.min_length.to_string())
+    }),
+    key(
+        "PASSWORD_MIN_CHARACTER_CLASSES",
+        "password.min_character_classes",
+        |s| Some(s.app.password.policy.min_character_classes.to_string()),
+    ),
+    key("PASSWORD_MIN_STRENGTH", "password.min_strength", |s| {
+        Some(s.app.password.policy.min_strength.to_string())
+    }),
+    key(
+        "PASSWORD_REJECT_PERSONAL_INFO",
+        "password.reject_personal_info",
+        |s| Some(s.app.password.policy.reject_personal_info.to_string()),
+    ),
+    key("PASSWORD_HISTORY", "password.history", |s| {
+        Some(s.app.password.policy.history.to_string())
+    }),
+    key(
+        "BREACHED_PASSWORDS_DIR",
+        "password.breached_passwords_dir",
+        |s| {
+            s.app
+                .password
+                .breached_passwords_dir
+                .as_ref()
+                .map(|path| path.display().to_string())
+        },
+    ),
+    key("ARGON2_MEMORY_KIB", "password.argon2_memory_kib", |s| {
+        Some(s.app.password.argon2.m_cost().to_string())
+    }),
+    key("ARGON2_ITERATIONS", "password.argon2_iterations", |s| {
+        Some(s.app.password.argon2.t_cost().to_string())
+    }),
+    key("ARGON2_PARALLELISM", "password.argon2_parallelism", |s| {
+        Some(s.app.password.argon2.p_cost().to_string())
+    }),
+    secret("PASSWORD_PEPPER", "password.pepper", |s| {
+        s.app.password.pepper.clone()
+    }),
+    key(
+        "USER_DELETION_GRACE_DAYS",
+        "users.deletion_grace_days",
+        |s| Some(s.app.user_deletion_grace_days.to_string()),
+    ),
+    key("EXPORT_DIR", "export.dir", |s| {
+        Some(s.app.export.dir.display().to_string())
+    }),
+    key("EXPORT_LINK_TTL_MINUTES", "export.link_ttl_minutes", |s| {
+        Some(s.app.export.link_ttl_minutes.to_string())
+    }),
+    key("JOB_WORKER_IN_SERVER", "jobs.worker_in_server", |s| {
+        Some(s.app.jobs.worker_in_server.to_string())
+    }),
+    key("JOB_CONCURRENCY", "jobs.concurrency", |s| {
+        Some(s.app.jobs.concurrency.to_string())
+    }),
+    key("JOB_POLL_INTERVAL_MS", "jobs.poll_interval_ms", |s| {
+        Some(s.app.jobs.poll_interval.as_millis().to_string())
+    }),
+    key("JOB_LOCK_TIMEOUT_SECS", "jobs.lock_timeout_secs", |s| {
+        Some(s.app.jobs.lock_timeout.as_secs().to_string())
+    }),
+    key("JOB_MAX_ATTEMPTS", "jobs.max_attempts", |s| {
+        Some(s.app.jobs.max_attempts.to_string())
+    }),
+    key("SCHEDULER_ENABLED", "scheduler.enabled", |s| {
+        Some(s.app.scheduler.enabled.to_string())
+    }),
+    key(
+        "SCHEDULE_ERASE_DELETED_USERS",
+        "scheduler.erase_deleted_users",
+        |s| Some(s.app.scheduler.erase_deleted_users.to_string()),
+    ),
+    key(
+        "SCHEDULE_PURGE_AUTH_ARTIFACTS",
+        "scheduler.purge_auth_artifacts",
+        |s| Some(s.app.scheduler.purge_auth_artifacts.to_string()),
+    ),
+    key(
+        "SCHEDULE_DEACTIVATE_IDLE_MEMBERS",
+        "scheduler.deactivate_idle_members",
+        |s| Some(s.app.scheduler.deactivate_idle_members.to_string()),
+    ),
+    key("SCHEDULE_PURGE_OUTBOX", "scheduler.purge_outbox", |s| {
+        Some(s.app.scheduler.purge_outbox.to_string())
+    }),
+    key("WEBHOOK_MAX_ATTEMPTS", "webhooks.max_attempts", |s| {
+        Some(s.app.webhooks.max_attempts.to_string())
+    }),
+    key("WEBHOOK_TIMEOUT_SECS", "webhooks.timeout_secs", |s| {
+        Some(s.app.webhooks.timeout.as_secs().to_string())
+    }),
+    key(
+        "OUTBOUND_ALLOW_PRIVATE_ADDRESSES",
+        "outbound.allow_private_addresses",
+        |s| Some(s.app.outbound_allow_private_addresses.to_string()),
+    ),
+    key("OUTBOX_RELAY_IN_SERVER", "outbox.relay_in_server", |s| {
+        Some(s.app.outbox.relay_in_server.to_string())
+    }),
+    key("OUTBOX_POLL_INTERVAL_MS", "outbox.poll_interval_ms", |s| {
+        Some(s.app.outbox.poll_interval.as_millis().to_string())
+    }),
+    key("OUTBOX_MAX_ATTEMPTS", "outbox.max_attempts", |s| {
+        Some(s.app.outbox.max_attempts.to_string())
+    }),
+    key("OUTBOX_RETENTION_DAYS", "outbox.retention_days", |s| {
+        Some(s.app.outbox.retention_days.to_string())
+    }),
+    secret("DATABASE_URL", "database.url", |s| {
+        Some(s.database.url.clone())
+    }),
+    key("DB_MAX_CONNECTIONS", "database.max_connections", |s| {
+        Some(s.database.max_connections.to_string())
+    }),
+    key("DB_MIN_CONNECTIONS", "database.min_connections", |s| {
+        Some(s.database.min_connections.to_string())
+    }),
+    key(
+        "DB_CONNECT_TIMEOUT_SECS",
+        "database.connect_timeout_secs",
+        |s| Some(s.database.connect_timeout_secs.to_string()),
+    ),
+    key("DB_IDLE_TIMEOUT_SECS", "database.idle_timeout_secs", |s| {
+        Some(s.database.idle_timeout_secs.to_string())
+    }),
+    key("DB_MAX_LIFETIME_SECS", "database.max_lifetime_secs", |s| {
+        Some(s.database.max_lifetime_secs.to_string())
+    }),
+    key("RUST_LOG", "logging.filter", |s| {
+        Some(s.logging.filter.clone())
+    }),
+    key("LOG_FORMAT", "logging.format", |s| {
+        Some(format!("{:?}", s.logging.format))
+    }),
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Origin {
+    Default,
+    File(PathBuf),
+    Env,
+    SecretFile(PathBuf),
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::Default => f.write_str("default"),
+            Origin::File(path) => write!(f, "file {}", path.display()),
+            Origin::Env => f.write_str("env"),
+            Origin::SecretFile(path) => write!(f, "secret file {}", path.display()),
+        }
+    }
+}
+
+/// All errors found while loading configuration, reported together.
+#[derive(Debug)]
+pub struct ConfigError {
+    pub errors: Vec<String>,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid configuration ({} error(s))", self.errors.len())?;
+        for error in &self.errors {
+            write!(f, "\n  - {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Raw configuration values merged from the TOML file and the environment,
+/// before any typing or validation.
+pub struct ConfigSource {
+    pub environment: Environment,
+    values: HashMap<&'static str, (String, Origin)>,
+}
+
+impl ConfigSource {
+    /// Reads `ENVIRONMENT`, then `$CONFIG_DIR/<environment>.toml` (if present),
+    /// then environment variables, then `<KEY>_FILE` secret files.
+    pub fn load() -> Result<Self, ConfigError> {
+        Self::load_with(|name| std::env::var(name).ok())
+    }
+
+    pub fn load_with<F>(env: F) -> Result<Self, ConfigError>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let mut errors = Vec::new();
+
+        let environment = match env("ENVIRONMENT") {
+            Some(value) => value.parse().unwrap_or_else(|e| {
+                errors.push(e);
+                Environment::Production
+            }),
+            None => Environment::Production,
+        };
+
+        let mut source = Self {
+            environment,
+            values: HashMap::new(),
+        };
+
+        let config_dir = env("CONFIG_DIR").unwrap_or_else(|| "config".to_string());
+        let path = Path::new(&config_dir).join(format!("{}.toml", environment.as_str()));
+        if path.exists()
+            && let Err(e) = source.merge_file(&path)
+        {
+            errors.extend(e);
+        }
+
+        for key in KEYS {
+            if let Some(value) = env(key.env) {
+                source.values.insert(key.env, (value, Origin::Env));
+            }
+
+            let file_var = format!("{}_FILE", key.env);
+            if let Some(file) = env(&file_var) {
+                let file = PathBuf::from(file);
+                match std::fs::read_to_string(&file) {
+                    Ok(value) => {
+                        let value = value.trim_end_matches(['\r', '\n']).to_string();
+                        source
+                            .values
+                            .insert(key.env, (value, Origin::SecretFile(file)));
+                    }
+                    Err(e) => errors.push(format!(
+                        "{}: cannot read {}: {}",
+                        file_var,
+                        file.display(),
+                        e
+                    )),
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(source)
+        } else {
+            Err(ConfigError { errors })
+        }
+    }
+
+    /// Builds a source from explicit values, keyed by environment variable name.
+    pub fn from_pairs(environment: Environment, pairs: &[(&str, &str)]) -> Self {
+        let mut values = HashMap::new();
+        for (name, value) in pairs {
+            if let Some(key) = KEYS.iter().find(|k| k.env == *name) {
+                values.insert(key.env, (value.to_string(), Origin::Env));
+            }
+        }
+        Self {
+            environment,
+            values,
+        }
+    }
+
+    fn merge_file(&mut self, path: &Path) -> Result<(), Vec<String>> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| vec![format!("cannot read {}: {}", path.display(), e)])?;
+        let table: toml::Table = toml::from_str(&content)
+            .map_err(|e| vec![format!("cannot parse {}: {}", path.display(), e)])?;
+
+        let mut flat = Vec::new();
+        flatten_toml("", &toml::Value::Table(table), &mut flat);
+
+        let mut errors = Vec::new();
+        for (path_key, value) in flat {
+            match KEYS.iter().find(|k| k.path == path_key) {
+                Some(key) => {
+                    self.values
+                        .insert(key.env, (value, Origin::File(path.to_path_buf())));
+                }
+                None => errors.push(format!(
+                    "{}: unknown configuration key '{}'",
+                    path.display(),
+                    path_key
+                )),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(|(value, _)| value.as_str())
+    }
+
+    pub fn origin(&self, name: &str) -> Origin {
+        self.values
+            .get(name)
+            .map(|(_, origin)| origin.clone())
+            .unwrap_or(Origin::Default)
+    }
+}
+
+fn flatten_toml(prefix: &str, value: &toml::Value, out: &mut Vec<(String, String)>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (name, value) in table {
+                let path = if prefix.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{}.{}", prefix, name)
+                };
+                flatten_toml(&path, value, out);
+            }
+        }
+        toml::Value::String(s) => out.push((prefix.to_string(), s.clone())),
+        other => out.push((prefix.to_string(), other.to_string())),
+    }
+}
+
+/// Typed accessor over a [`ConfigSource`] that collects every error instead of
+/// stopping at the first one.
+pub struct Reader<'a> {
+    source: &'a ConfigSource,
+    pub errors: Vec<String>,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(source: &'a ConfigSource) -> Self {
+        Self {
+            source,
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn optional(&self, name: &str) -> Option<String> {
+        self.source.get(name).map(str::to_string)
+    }
+
+    pub fn required(&mut self, name: &str) -> String {
+        self.optional(name).unwrap_or_else(|| {
+            self.errors.push(format!("{} is required", name));
+            String::new()
+        })
+    }
+
+    pub fn parse_or<T>(&mut self, name: &str, default: T) -> T
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        match self.source.get(name) {
+            None => default,
+            Some(raw) => raw.trim().parse().unwrap_or_else(|e| {
+                self.errors
+                    .push(format!("{} has invalid value '{}': {}", name, raw, e));
+                default
+            }),
+        }
+    }
+
+    pub fn check(&mut self, condition: bool, message: impl Into<String>) {
+        if !condition {
+            self.errors.push(message.into());
+        }
+    }
+}
+
+/// Root of the typed configuration tree.
+#[derive(Clone, Debug)]
+pub struct Settings {
+    pub environment: Environment,
+    pub app: Config,
+    pub database: DatabaseConfig,
+    pub logging: LoggingConfig,
+    source_dump: Vec<(String, String, Origin)>,
+}
+
+impl Settings {
+    pub fn load() -> Result<Self, ConfigError> {
+        Self::from_source(&ConfigSource::load()?)
+    }
+
+    pub fn from_source(source: &ConfigSource) -> Result<Self, ConfigError> {
+        let environment = source.environment;
+        let mut reader = Reader::new(source);
+
+        let app = Config::read(&mut reader, environment);
+        let database = DatabaseConfig::read(&mut reader, environment);
+        let logging = LoggingConfig::read(&mut reader, environment);
+
+        if !reader.errors.is_empty() {
+            return Err(ConfigError {
+                errors: reader.errors,
+            });
+        }
+
+        let source_dump = KEYS
+            .iter()
+            .filter_map(|key| {
+                source.get(key.env).map(|value| {
+                    (
+                        key.env.to_string(),
+                        redact_value(key, value),
+                        source.origin(key.env),
+                    )
+                })
+            })
+            .collect();
+
+        Ok(Self {
+            environment,
+            app,
+            database,
+            logging,
+            source_dump,
+        })
+    }
+
+    /// Effective configuration with secrets masked, suitable for logs and
+    /// `config show`.
+    pub fn redacted_dump(&self) -> String {
+        let mut lines = vec![format!("environment = {}", self.environment)];
+        for key in KEYS {
+            let value = match (key.value)(self) {
+                Some(value) => redact_value(key, &value),
+                None => "(unset)".to_string(),
+            };
+            lines.push(format!("{} = {}", key.path, value));
+        }
+
+        if !self.source_dump.is_empty() {
+            lines.push(String::new());
+            lines.push("# overridden values".to_string());
+            for (name, value, origin) in &self.source_dump {
+                lines.push(format!("{} = {} ({})", name, value, origin));
+            }
+        }
+
+        lines.join("\n")
+    }
+}
+
+static URL_PASSWORD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(://[^:/@]*:)[^@]*@").expect("valid url password regex"));
+
+fn redact_url(url: &str) -> String {
+    URL_PASSWORD
+        .replace(url, format!("${{1}}{}@", REDACTED))
+        .into_owned()
+}
+
+fn redact_value(key: &Key, value: &str) -> String {
+    if key.env == "DATABASE_URL" {
+        redact_url(value)
+    } else if key.secret {
+        REDACTED.to_string()
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,42 @@
+use crate::config::settings::Reader;
+use std::time::Duration;
+
+/// How outgoing webhooks are delivered and retried.
+#[derive(Clone, Debug)]
+pub struct WebhooksConfig {
+    /// Attempts a delivery gets before it is failed for good.
+    pub max_attempts: i32,
+    /// Deadline for a whole delivery attempt.
+    pub timeout: Duration,
+}
+
+impl Default for WebhooksConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 8,
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl WebhooksConfig {
+    pub(crate) fn read(reader: &mut Reader<'_>) -> Self {
+        let defaults = Self::default();
+        let max_attempts = reader.parse_or("WEBHOOK_MAX_ATTEMPTS", defaults.max_attempts);
+        let timeout_secs = reader.parse_or("WEBHOOK_TIMEOUT_SECS", defaults.timeout.as_secs());
+
+        reader.check(
+            (1..=25).contains(&max_attempts),
+            "WEBHOOK_MAX_ATTEMPTS must be between 1 and 25",
+        );
+        reader.check(
+            (1..=60).contains(&timeout_secs),
+            "WEBHOOK_TIMEOUT_SECS must be between 1 and 60",
+        );
+
+        Self {
+            max_attempts,
+            timeout: Duration::from_secs(timeout_secs),
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Permissions an API key can be granted. Users authenticated with a JWT are
+/// not restricted by scopes; only their role applies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+pub enum ApiScope {
+    #[serde(rename = "users:read")]
+    UsersRead,
+    #[serde(rename = "users:write")]
+    UsersWrite,
+    #[serde(rename = "tenants:read")]
+    TenantsRead,
+    #[serde(rename = "api_keys:manage")]
+    ApiKeysManage,
+    #[serde(rename = "oidc:manage")]
+    OidcManage,
+    #[serde(rename = "settings:manage")]
+    SettingsManage,
+    #[serde(rename = "scim:provision")]
+    ScimProvision,
+    #[serde(rename = "tenants:export")]
+    TenantsExport,
+    #[serde(rename = "webhooks:manage")]
+    WebhooksManage,
+}
+
+impl ApiScope {
+    pub const ALL: &'static [ApiScope] = &[
+        ApiScope::UsersRead,
+        ApiScope::UsersWrite,
+        ApiScope::TenantsRead,
+        ApiScope::ApiKeysManage,
+        ApiScope::OidcManage,
+        ApiScope::SettingsManage,
+        ApiScope::ScimProvision,
+        ApiScope::TenantsExport,
+        ApiScope::WebhooksManage,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ApiScope::UsersRead => "users:read",
+            ApiScope::UsersWrite => "users:write",
+            ApiScope::TenantsRead => "tenants:read",
+            ApiScope::ApiKeysManage => "api_keys:manage",
+            ApiScope::OidcManage => "oidc:manage",
+            ApiScope::SettingsManage => "settings:manage",
+            ApiScope::ScimProvision => "scim:provision",
+            ApiScope::TenantsExport => "tenants:export",
+            ApiScope::WebhooksManage => "webhooks:manage",
+        }
+    }
+
+    /// Parses the space-separated form stored with a key, skipping scopes
+    /// this build does not know.
+    pub fn parse_list(scopes: &str) -> Vec<ApiScope> {
+        scopes
+            .split_whitespace()
+            .filter_map(|s| s.parse().ok())
+            .collect()
+    }
+
+    pub fn join(scopes: &[ApiScope]) -> String {
+        scopes
+            .iter()
+            .map(|s| s.as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl std::fmt::Display for ApiScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ApiScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ApiScope::ALL
+            .iter()
+            .copied()
+            .find(|scope| scope.as_str() == s)
+            .ok_or_else(|| format!("unknown scope '{}'", s))
+    }
+}
@@ -1,7 +1,19 @@
+pub mod api_scope;
+pub mod export;
+pub mod job_status;
+pub mod locale;
+pub mod task_run_status;
 pub mod tenant_status;
 pub mod user_role;
 pub mod user_status;
+pub mod webhook;
 
+pub use api_scope::*;
+pub use export::*;
+pub use job_status::*;
+pub use locale::*;
+pub use task_run_status::*;
 pub use tenant_status::*;
 pub use user_role::*;
 pub use user_status::*;
+pub use webhook::*;
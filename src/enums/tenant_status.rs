@@ -5,7 +5,11 @@ use utoipa::ToSchema;
 #[derive(
     Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, Copy, ToSchema,
 )]
-#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "tenant_status")]
+#[sea_orm(
+    rs_type = "String",
+    db_type = "String(StringLen::None)",
+    enum_name = "tenant_status"
+)]
 pub enum TenantStatus {
     #[sea_orm(string_value = "active")]
     Active,
@@ -5,10 +5,21 @@ use utoipa::ToSchema;
 #[derive(
     Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, Copy, ToSchema,
 )]
-#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "user_role")]
+#[sea_orm(
+    rs_type = "String",
+    db_type = "String(StringLen::None)",
+    enum_name = "user_role"
+)]
 pub enum UserRole {
     #[sea_orm(string_value = "admin")]
     Admin,
     #[sea_orm(string_value = "regular")]
     Regular,
 }
+
+impl UserRole {
+    /// Whether this role allows at least what `other` does.
+    pub fn includes(self, other: UserRole) -> bool {
+        self == UserRole::Admin || self == other
+    }
+}
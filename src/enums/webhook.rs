@@ -0,0 +1,90 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Events a webhook endpoint can subscribe to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+pub enum WebhookEvent {
+    #[serde(rename = "user.created")]
+    UserCreated,
+    #[serde(rename = "user.deactivated")]
+    UserDeactivated,
+    #[serde(rename = "user.role_changed")]
+    UserRoleChanged,
+    /// Sent on demand to check an endpoint; every endpoint receives it.
+    #[serde(rename = "ping")]
+    Ping,
+}
+
+impl WebhookEvent {
+    pub const ALL: &'static [WebhookEvent] = &[
+        WebhookEvent::UserCreated,
+        WebhookEvent::UserDeactivated,
+        WebhookEvent::UserRoleChanged,
+        WebhookEvent::Ping,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WebhookEvent::UserCreated => "user.created",
+            WebhookEvent::UserDeactivated => "user.deactivated",
+            WebhookEvent::UserRoleChanged => "user.role_changed",
+            WebhookEvent::Ping => "ping",
+        }
+    }
+
+    /// Parses the space-separated form stored with an endpoint, skipping
+    /// events this build does not know.
+    pub fn parse_list(events: &str) -> Vec<WebhookEvent> {
+        events
+            .split_whitespace()
+            .filter_map(|s| s.parse().ok())
+            .collect()
+    }
+
+    pub fn join(events: &[WebhookEvent]) -> String {
+        events
+            .iter()
+            .map(|e| e.as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl std::fmt::Display for WebhookEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for WebhookEvent {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        WebhookEvent::ALL
+            .iter()
+            .copied()
+            .find(|event| event.as_str() == s)
+            .ok_or_else(|| format!("unknown event '{}'", s))
+    }
+}
+
+#[derive(
+    Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, Copy, ToSchema,
+)]
+#[sea_orm(
+    rs_type = "String",
+    db_type = "String(StringLen::None)",
+    enum_name = "webhook_delivery_status"
+)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookDeliveryStatus {
+    /// Not delivered yet; another attempt is scheduled.
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    #[sea_orm(string_value = "succeeded")]
+    Succeeded,
+    /// Every attempt failed.
+    #[sea_orm(string_value = "failed")]
+    Failed,
+}
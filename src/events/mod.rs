@@ -0,0 +1,149 @@
+//! Domain events. Each is written to the outbox in the transaction of the
+//! change it records, so it is neither lost when the process dies after the
+//! change nor recorded for a change that was rolled back. The
+//! [`Relay`] then hands it to the in-process [`Subscriber`]s.
+
+pub mod relay;
+pub mod webhooks;
+
+pub use relay::Relay;
+
+use crate::enums::{TenantStatus, UserRole, UserStatus};
+use crate::models::users;
+use crate::repositories::OutboxRepository;
+use crate::routes::AppState;
+use crate::utils::error::AppError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// A member of a tenant as it was right after the event.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemberSnapshot {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub email: String,
+    pub role: UserRole,
+    pub status: UserStatus,
+}
+
+impl From<&users::Model> for MemberSnapshot {
+    fn from(user: &users::Model) -> Self {
+        Self {
+            id: user.id,
+            tenant_id: user.tenant_id,
+            email: user.email.clone(),
+            role: user.role,
+            status: user.status,
+        }
+    }
+}
+
+/// Something that happened to a user or a tenant. Stored as its JSON
+/// representation, so variants and fields must only ever be added.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+pub enum DomainEvent {
+    /// A new user, with their first membership.
+    UserRegistered { user: MemberSnapshot },
+    /// An existing user joined another tenant.
+    MemberAdded { user: MemberSnapshot },
+    UserRoleChanged {
+        user: MemberSnapshot,
+        previous_role: UserRole,
+    },
+    UserStatusChanged {
+        user: MemberSnapshot,
+        previous_status: UserStatus,
+    },
+    TenantStatusChanged {
+        tenant_id: Uuid,
+        status: TenantStatus,
+        previous_status: TenantStatus,
+    },
+}
+
+impl DomainEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::UserRegistered { .. } => "UserRegistered",
+            Self::MemberAdded { .. } => "MemberAdded",
+            Self::UserRoleChanged { .. } => "UserRoleChanged",
+            Self::UserStatusChanged { .. } => "UserStatusChanged",
+            Self::TenantStatusChanged { .. } => "TenantStatusChanged",
+        }
+    }
+
+    /// Type and id of what the event happened to. Events of one aggregate
+    /// are relayed in the order they were recorded.
+    pub fn aggregate(&self) -> (&'static str, Uuid) {
+        match self {
+            Self::UserRegistered { user }
+            | Self::MemberAdded { user }
+            | Self::UserRoleChanged { user, .. }
+            | Self::UserStatusChanged { user, .. } => ("user", user.id),
+            Self::TenantStatusChanged { tenant_id, .. } => ("tenant", *tenant_id),
+        }
+    }
+
+    pub fn tenant_id(&self) -> Uuid {
+        match self {
+            Self::UserRegistered { user }
+            | Self::MemberAdded { user }
+            | Self::UserRoleChanged { user, .. }
+            | Self::UserStatusChanged { user, .. } => user.tenant_id,
+            Self::TenantStatusChanged { tenant_id, .. } => *tenant_id,
+        }
+    }
+
+    /// The events recording how the member `before` became `after`.
+    pub fn member_changes(before: &users::Model, after: &users::Model) -> Vec<Self> {
+        let mut events = Vec::new();
+        if after.role != before.role {
+            events.push(Self::UserRoleChanged {
+                user: after.into(),
+                previous_role: before.role,
+            });
+        }
+        if after.status != before.status {
+            events.push(Self::UserStatusChanged {
+                user: after.into(),
+                previous_status: before.status,
+            });
+        }
+        events
+    }
+}
+
+/// An event read back from the outbox.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedEvent {
+    /// The same every time the event is relayed, so subscribers can tell
+    /// repeats.
+    pub id: Uuid,
+    pub recorded_at: DateTime<Utc>,
+    pub event: DomainEvent,
+}
+
+/// Reacts to domain events. Delivery is at least once: an event is handed
+/// to every subscriber again when any of them fails, so handlers must
+/// tolerate repeats.
+#[async_trait]
+pub trait Subscriber: Send + Sync + 'static {
+    /// Shown in the error recorded when the subscriber fails.
+    fn name(&self) -> &'static str;
+
+    async fn handle(&self, event: &RecordedEvent) -> Result<(), AppError>;
+}
+
+/// The relay with every subscriber of the application.
+pub fn relay(state: &AppState, outbox: Arc<dyn OutboxRepository>) -> Relay {
+    Relay::new(
+        outbox,
+        state.config.outbox.poll_interval,
+        state.config.outbox.max_attempts,
+    )
+    .subscribe(webhooks::WebhookSubscriber::new(state.webhooks.clone()))
+}
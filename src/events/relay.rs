@@ -0,0 +1,186 @@
+use crate::events::{DomainEvent, RecordedEvent, Subscriber};
+use crate::jobs::worker::retry_delay;
+use crate::models::outbox_events;
+use crate::repositories::OutboxRepository;
+use crate::utils::error::AppError;
+use crate::utils::shutdown::Shutdown;
+use chrono::Utc;
+use sea_orm::Set;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Events relayed in one pass at most.
+const BATCH_SIZE: u64 = 100;
+
+/// Hands the events in the outbox to the subscribers. Only the relay holding
+/// the outbox lock works, so replicas can all run one. An event that fails
+/// is retried with the backoff of jobs and holds back the later events of
+/// its aggregate until it succeeds, or is dead after `max_attempts`.
+pub struct Relay {
+    outbox: Arc<dyn OutboxRepository>,
+    subscribers: Vec<Arc<dyn Subscriber>>,
+    poll_interval: Duration,
+    max_attempts: i32,
+    id: String,
+}
+
+impl Relay {
+    pub fn new(
+        outbox: Arc<dyn OutboxRepository>,
+        poll_interval: Duration,
+        max_attempts: i32,
+    ) -> Self {
+        Self {
+            outbox,
+            subscribers: Vec::new(),
+            poll_interval,
+            max_attempts,
+            id: format!("relay-{}", Uuid::now_v7()),
+        }
+    }
+
+    /// Subscribers see each event in the order they were added. Panics if a
+    /// subscriber with the same name is already added.
+    pub fn subscribe<S: Subscriber>(mut self, subscriber: S) -> Self {
+        assert!(
+            self.subscribers
+                .iter()
+                .all(|added| added.name() != subscriber.name()),
+            "subscriber '{}' added twice",
+            subscriber.name()
+        );
+        self.subscribers.push(Arc::new(subscriber));
+        self
+    }
+
+    pub fn subscribers(&self) -> Vec<&'static str> {
+        self.subscribers
+            .iter()
+            .map(|subscriber| subscriber.name())
+            .collect()
+    }
+
+    /// Relays events until shutdown. The pass in progress is finished
+    /// before returning.
+    pub async fn run(self, shutdown: Shutdown) {
+        tracing::info!(
+            relay = %self.id,
+            subscribers = ?self.subscribers(),
+            "Outbox relay started"
+        );
+        while !shutdown.is_triggered() {
+            let relayed = match self.run_once().await {
+                Ok(relayed) => relayed,
+                Err(e) => {
+                    tracing::warn!(relay = %self.id, "Failed to relay events: {}", e);
+                    0
+                }
+            };
+            if relayed == 0 {
+                tokio::select! {
+                    _ = tokio::time::sleep(self.poll_interval) => {}
+                    _ = shutdown.wait() => {}
+                }
+            }
+        }
+        tracing::info!(relay = %self.id, "Outbox relay stopped");
+    }
+
+    /// Relays one batch of due events, the oldest pending event of each
+    /// aggregate, unless another relay holds the lock. Returns how many
+    /// events were handed to the subscribers, successfully or not.
+    pub async fn run_once(&self) -> Result<usize, AppError> {
+        let Some(lock) = self.outbox.try_lock().await? else {
+            tracing::debug!(relay = %self.id, "Outbox is relayed by another replica");
+            return Ok(0);
+        };
+        let result = self.relay_due().await;
+        lock.release().await?;
+        result
+    }
+
+    async fn relay_due(&self) -> Result<usize, AppError> {
+        let due = self
+            .outbox
+            .due(Utc::now().fixed_offset(), BATCH_SIZE)
+            .await?;
+        let count = due.len();
+        for event in due {
+            self.dispatch(event).await?;
+        }
+        Ok(count)
+    }
+
+    /// Hands `record` to every subscriber and records the outcome. An event
+    /// failing its last attempt is dead: it is never relayed again, and the
+    /// later events of its aggregate go on without it.
+    pub async fn dispatch(
+        &self,
+        record: outbox_events::Model,
+    ) -> Result<outbox_events::Model, AppError> {
+        let outcome = match serde_json::from_value::<DomainEvent>(record.payload.clone()) {
+            Ok(event) => {
+                let event = RecordedEvent {
+                    id: record.id,
+                    recorded_at: record.created_at.to_utc(),
+                    event,
+                };
+                self.deliver(&event).await
+            }
+            // Possibly an event added by a newer release, relayed by an
+            // older one
+            Err(e) => Err(format!("Invalid payload: {}", e)),
+        };
+
+        let now = Utc::now();
+        let (event_id, event_type) = (record.id, record.event_type.clone());
+        let attempts = record.attempts + 1;
+        let mut update: outbox_events::ActiveModel = record.into();
+        update.attempts = Set(attempts);
+        match outcome {
+            Ok(()) => {
+                tracing::debug!(%event_id, %event_type, "Event relayed");
+                update.dispatched_at = Set(Some(now.fixed_offset()));
+                update.next_attempt_at = Set(None);
+                update.last_error = Set(None);
+            }
+            Err(error) if attempts >= self.max_attempts => {
+                tracing::error!(
+                    %event_id,
+                    %event_type,
+                    attempts,
+                    "Event is dead, no longer relayed: {}",
+                    error
+                );
+                update.dead_at = Set(Some(now.fixed_offset()));
+                update.next_attempt_at = Set(None);
+                update.last_error = Set(Some(error));
+            }
+            Err(error) => {
+                let delay = retry_delay(attempts);
+                tracing::warn!(
+                    %event_id,
+                    %event_type,
+                    attempts,
+                    retry_in_secs = delay.num_seconds(),
+                    "Event not relayed, will retry: {}",
+                    error
+                );
+                update.next_attempt_at = Set(Some((now + delay).fixed_offset()));
+                update.last_error = Set(Some(error));
+            }
+        }
+        Ok(self.outbox.update(update).await?)
+    }
+
+    async fn deliver(&self, event: &RecordedEvent) -> Result<(), String> {
+        for subscriber in &self.subscribers {
+            subscriber
+                .handle(event)
+                .await
+                .map_err(|e| format!("{}: {}", subscriber.name(), e))?;
+        }
+        Ok(())
+    }
+}
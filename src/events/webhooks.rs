@@ -0,0 +1,60 @@
+use crate::enums::{UserStatus, WebhookEvent};
+use crate::events::{DomainEvent, RecordedEvent, Subscriber};
+use crate::services::webhooks_service::WebhookService;
+use crate::utils::error::AppError;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Sends the events tenants can subscribe to to their webhooks. The
+/// webhook event id is the outbox event id, so receivers can drop the
+/// deliveries of an event relayed twice.
+pub struct WebhookSubscriber {
+    webhooks: Arc<dyn WebhookService>,
+}
+
+impl WebhookSubscriber {
+    pub fn new(webhooks: Arc<dyn WebhookService>) -> Self {
+        Self { webhooks }
+    }
+}
+
+/// The webhook event for `event` and its `data`, if tenants can subscribe
+/// to it.
+fn webhook_event(event: &DomainEvent) -> Option<(WebhookEvent, serde_json::Value)> {
+    match event {
+        DomainEvent::UserRegistered { user } | DomainEvent::MemberAdded { user } => Some((
+            WebhookEvent::UserCreated,
+            serde_json::json!({ "user": user }),
+        )),
+        DomainEvent::UserRoleChanged {
+            user,
+            previous_role,
+        } => Some((
+            WebhookEvent::UserRoleChanged,
+            serde_json::json!({ "user": user, "previous_role": previous_role }),
+        )),
+        DomainEvent::UserStatusChanged { user, .. } if user.status == UserStatus::Inactive => {
+            Some((
+                WebhookEvent::UserDeactivated,
+                serde_json::json!({ "user": user }),
+            ))
+        }
+        DomainEvent::UserStatusChanged { .. } | DomainEvent::TenantStatusChanged { .. } => None,
+    }
+}
+
+#[async_trait]
+impl Subscriber for WebhookSubscriber {
+    fn name(&self) -> &'static str {
+        "webhooks"
+    }
+
+    async fn handle(&self, event: &RecordedEvent) -> Result<(), AppError> {
+        let Some((webhook_event, data)) = webhook_event(&event.event) else {
+            return Ok(());
+        };
+        self.webhooks
+            .publish(event.event.tenant_id(), event.id, webhook_event, data)
+            .await
+    }
+}
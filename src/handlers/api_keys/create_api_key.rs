@@ -0,0 +1,69 @@
+use crate::enums::ApiScope;
+use crate::middleware::validation::{ValidatedJson, ValidatedPath};
+use crate::services::api_keys_service::{ApiKeyService, IssuedApiKey};
+use crate::utils::{
+    AdminRoleWithTenant,
+    error::{AppError, ErrorResponse},
+};
+use axum::{extract::State, http::StatusCode, response::Json};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::sync::Arc;
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
+pub struct CreateApiKeyRequest {
+    #[validate(length(min = 1, max = 100, message = "name_length"))]
+    pub name: String,
+    #[validate(length(min = 1, message = "scopes_required"))]
+    pub scopes: Vec<ApiScope>,
+    /// No expiry when omitted.
+    #[schema(value_type = Option<String>)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/tenants/{tenant_id}/service-accounts/{service_account_id}/api-keys",
+    tag = "API Keys",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID"),
+        ("service_account_id" = String, Path, description = "Service account ID")
+    ),
+    request_body = CreateApiKeyRequest,
+    responses(
+        (status = 201, description = "API key created; `secret` is only returned here", body = IssuedApiKey),
+        (status = 400, description = "Validation error", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Admin access required, or a requested scope or the account's role is not held by the caller", body = ErrorResponse),
+        (status = 404, description = "Service account not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn create_api_key(
+    State(api_keys): State<Arc<dyn ApiKeyService>>,
+    AdminRoleWithTenant {
+        principal,
+        tenant_id,
+    }: AdminRoleWithTenant,
+    ValidatedPath((_tenant_id, service_account_id)): ValidatedPath<(Uuid, Uuid)>,
+    ValidatedJson(payload): ValidatedJson<CreateApiKeyRequest>,
+) -> Result<(StatusCode, Json<IssuedApiKey>), AppError> {
+    principal.require_scope(ApiScope::ApiKeysManage)?;
+
+    let issued = api_keys
+        .create_key(
+            &principal,
+            tenant_id,
+            service_account_id,
+            payload.name,
+            payload.scopes,
+            payload.expires_at.map(|at| at.fixed_offset()),
+        )
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(issued)))
+}
@@ -0,0 +1,60 @@
+use crate::enums::{ApiScope, UserRole};
+use crate::middleware::validation::ValidatedJson;
+use crate::models::service_accounts;
+use crate::services::api_keys_service::ApiKeyService;
+use crate::utils::{
+    AdminRoleWithTenant,
+    error::{AppError, ErrorResponse},
+};
+use axum::{extract::State, http::StatusCode, response::Json};
+use serde::Deserialize;
+use std::sync::Arc;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
+pub struct CreateServiceAccountRequest {
+    #[validate(length(min = 1, max = 100, message = "name_length"))]
+    pub name: String,
+    /// Defaults to `Regular`.
+    #[serde(default = "default_role")]
+    pub role: UserRole,
+}
+
+fn default_role() -> UserRole {
+    UserRole::Regular
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/tenants/{tenant_id}/service-accounts",
+    tag = "API Keys",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID")
+    ),
+    request_body = CreateServiceAccountRequest,
+    responses(
+        (status = 201, description = "Service account created", body = service_accounts::Model),
+        (status = 400, description = "Validation error", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Admin access required, or the role is not held by the caller", body = ErrorResponse)
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn create_service_account(
+    State(api_keys): State<Arc<dyn ApiKeyService>>,
+    AdminRoleWithTenant {
+        principal,
+        tenant_id,
+    }: AdminRoleWithTenant,
+    ValidatedJson(payload): ValidatedJson<CreateServiceAccountRequest>,
+) -> Result<(StatusCode, Json<service_accounts::Model>), AppError> {
+    principal.require_scope(ApiScope::ApiKeysManage)?;
+
+    let account = api_keys
+        .create_service_account(&principal, tenant_id, payload.name, payload.role)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(account)))
+}
@@ -0,0 +1,37 @@
+use crate::enums::ApiScope;
+use crate::models::api_keys;
+use crate::services::api_keys_service::ApiKeyService;
+use crate::utils::{
+    AdminRoleWithTenant,
+    error::{AppError, ErrorResponse},
+};
+use axum::{extract::State, response::Json};
+use std::sync::Arc;
+
+#[utoipa::path(
+    get,
+    path = "/api/tenants/{tenant_id}/api-keys",
+    tag = "API Keys",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID")
+    ),
+    responses(
+        (status = 200, description = "API keys of the tenant, without secrets", body = Vec<api_keys::Model>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Admin access required", body = ErrorResponse)
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn list_api_keys(
+    State(api_keys): State<Arc<dyn ApiKeyService>>,
+    AdminRoleWithTenant {
+        principal,
+        tenant_id,
+    }: AdminRoleWithTenant,
+) -> Result<Json<Vec<api_keys::Model>>, AppError> {
+    principal.require_scope(ApiScope::ApiKeysManage)?;
+
+    Ok(Json(api_keys.list_keys(tenant_id).await?))
+}
@@ -0,0 +1,37 @@
+use crate::enums::ApiScope;
+use crate::models::service_accounts;
+use crate::services::api_keys_service::ApiKeyService;
+use crate::utils::{
+    AdminRoleWithTenant,
+    error::{AppError, ErrorResponse},
+};
+use axum::{extract::State, response::Json};
+use std::sync::Arc;
+
+#[utoipa::path(
+    get,
+    path = "/api/tenants/{tenant_id}/service-accounts",
+    tag = "API Keys",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID")
+    ),
+    responses(
+        (status = 200, description = "Service accounts of the tenant", body = Vec<service_accounts::Model>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Admin access required", body = ErrorResponse)
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn list_service_accounts(
+    State(api_keys): State<Arc<dyn ApiKeyService>>,
+    AdminRoleWithTenant {
+        principal,
+        tenant_id,
+    }: AdminRoleWithTenant,
+) -> Result<Json<Vec<service_accounts::Model>>, AppError> {
+    principal.require_scope(ApiScope::ApiKeysManage)?;
+
+    Ok(Json(api_keys.list_service_accounts(tenant_id).await?))
+}
@@ -0,0 +1,13 @@
+pub mod create_api_key;
+pub mod create_service_account;
+pub mod list_api_keys;
+pub mod list_service_accounts;
+pub mod revoke_api_key;
+pub mod rotate_api_key;
+
+pub use create_api_key::create_api_key;
+pub use create_service_account::create_service_account;
+pub use list_api_keys::list_api_keys;
+pub use list_service_accounts::list_service_accounts;
+pub use revoke_api_key::revoke_api_key;
+pub use rotate_api_key::rotate_api_key;
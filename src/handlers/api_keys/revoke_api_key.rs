@@ -0,0 +1,42 @@
+use crate::enums::ApiScope;
+use crate::middleware::validation::ValidatedPath;
+use crate::models::api_keys;
+use crate::services::api_keys_service::ApiKeyService;
+use crate::utils::{
+    AdminRoleWithTenant,
+    error::{AppError, ErrorResponse},
+};
+use axum::{extract::State, response::Json};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[utoipa::path(
+    delete,
+    path = "/api/tenants/{tenant_id}/api-keys/{key_id}",
+    tag = "API Keys",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID"),
+        ("key_id" = String, Path, description = "API key ID")
+    ),
+    responses(
+        (status = 200, description = "Key revoked", body = api_keys::Model),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Admin access required", body = ErrorResponse),
+        (status = 404, description = "API key not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn revoke_api_key(
+    State(api_keys): State<Arc<dyn ApiKeyService>>,
+    AdminRoleWithTenant {
+        principal,
+        tenant_id,
+    }: AdminRoleWithTenant,
+    ValidatedPath((_tenant_id, key_id)): ValidatedPath<(Uuid, Uuid)>,
+) -> Result<Json<api_keys::Model>, AppError> {
+    principal.require_scope(ApiScope::ApiKeysManage)?;
+
+    Ok(Json(api_keys.revoke_key(tenant_id, key_id).await?))
+}
@@ -0,0 +1,44 @@
+use crate::enums::ApiScope;
+use crate::middleware::validation::ValidatedPath;
+use crate::services::api_keys_service::{ApiKeyService, IssuedApiKey};
+use crate::utils::{
+    AdminRoleWithTenant,
+    error::{AppError, ErrorResponse},
+};
+use axum::{extract::State, http::StatusCode, response::Json};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[utoipa::path(
+    post,
+    path = "/api/tenants/{tenant_id}/api-keys/{key_id}/rotate",
+    tag = "API Keys",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID"),
+        ("key_id" = String, Path, description = "API key ID")
+    ),
+    responses(
+        (status = 201, description = "Replacement key; the old key is revoked", body = IssuedApiKey),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Admin access required", body = ErrorResponse),
+        (status = 404, description = "API key not found", body = ErrorResponse),
+        (status = 409, description = "API key is revoked or expired", body = ErrorResponse)
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn rotate_api_key(
+    State(api_keys): State<Arc<dyn ApiKeyService>>,
+    AdminRoleWithTenant {
+        principal,
+        tenant_id,
+    }: AdminRoleWithTenant,
+    ValidatedPath((_tenant_id, key_id)): ValidatedPath<(Uuid, Uuid)>,
+) -> Result<(StatusCode, Json<IssuedApiKey>), AppError> {
+    principal.require_scope(ApiScope::ApiKeysManage)?;
+
+    let issued = api_keys.rotate_key(&principal, tenant_id, key_id).await?;
+
+    Ok((StatusCode::CREATED, Json(issued)))
+}
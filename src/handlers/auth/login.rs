@@ -1,11 +1,9 @@
 use crate::{
-    config::Config,
-    middleware::{auth::BearerToken, validation::validate_request},
-    services::auth_service::{AuthResponse, AuthService, LoginRequest},
-    utils::error::AppError,
+    middleware::{auth::BearerToken, validation::ValidatedJson},
+    services::auth_service::{AuthResponse, AuthenticationService, LoginRequest},
+    utils::error::{AppError, ErrorResponse},
 };
 use axum::{extract::State, response::Json};
-use sea_orm::DatabaseConnection;
 use serde_json::Value;
 use std::sync::Arc;
 
@@ -16,29 +14,20 @@ use std::sync::Arc;
     request_body = LoginRequest,
     responses(
         (status = 200, description = "Login successful", body = AuthResponse),
-        (status = 400, description = "Validation error"),
-        (status = 401, description = "Invalid credentials"),
-        (status = 403, description = "User not validated"),
-        (status = 500, description = "Internal server error")
+        (status = 400, description = "Validation error", body = ErrorResponse),
+        (status = 401, description = "Invalid credentials", body = ErrorResponse),
+        (status = 403, description = "User not validated", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
     )
 )]
 pub async fn login(
-    State(db): State<Arc<DatabaseConnection>>,
-    State(config): State<Arc<Config>>,
+    State(auth): State<Arc<dyn AuthenticationService>>,
     _bearer_token: BearerToken,
-    Json(payload): Json<LoginRequest>,
+    ValidatedJson(payload): ValidatedJson<LoginRequest>,
 ) -> Result<Json<Value>, AppError> {
-    let payload = validate_request(payload)?;
-    let email = payload.email.clone();
-    tracing::info!("Login attempt for email: {}", email);
+    tracing::info!("Login attempt");
 
-    let response = AuthService::login(
-        &db,
-        payload,
-        &config.jwt_secret,
-        config.jwt_expiration_minutes,
-    )
-    .await?;
+    let response = auth.login(payload).await?;
 
     tracing::info!(
         "Login successful: user_id={}, tenant_id={}",
@@ -49,5 +38,6 @@ pub async fn login(
     Ok(Json(serde_json::json!({
         "token": response.token,
         "user": response.user,
+        "memberships": response.memberships,
     })))
 }
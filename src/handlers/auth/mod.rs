@@ -1,7 +1,13 @@
 pub mod login;
+pub mod oidc_authorize;
+pub mod oidc_callback;
 pub mod refresh;
 pub mod register;
+pub mod switch_tenant;
 
 pub use login::login;
+pub use oidc_authorize::oidc_authorize;
+pub use oidc_callback::oidc_callback;
 pub use refresh::refresh;
 pub use register::register;
+pub use switch_tenant::switch_tenant;
@@ -0,0 +1,32 @@
+use crate::{
+    middleware::{auth::BearerToken, validation::ValidatedPath},
+    services::oidc_service::{AuthorizationRequest, OidcService},
+    utils::error::{AppError, ErrorResponse},
+};
+use axum::{extract::State, response::Json};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/oidc/{provider_id}/authorize",
+    tag = "Authentication",
+    params(
+        ("provider_id" = String, Path, description = "Identity provider ID")
+    ),
+    responses(
+        (status = 200, description = "URL to send the browser to", body = AuthorizationRequest),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Identity provider not found", body = ErrorResponse),
+        (status = 502, description = "Identity provider unavailable", body = ErrorResponse)
+    )
+)]
+pub async fn oidc_authorize(
+    State(oidc): State<Arc<dyn OidcService>>,
+    _bearer_token: BearerToken,
+    ValidatedPath(provider_id): ValidatedPath<Uuid>,
+) -> Result<Json<AuthorizationRequest>, AppError> {
+    tracing::info!("OIDC login started: provider_id={}", provider_id);
+
+    Ok(Json(oidc.authorize(provider_id).await?))
+}
@@ -0,0 +1,53 @@
+use crate::{
+    middleware::{auth::BearerToken, validation::ValidatedJson},
+    services::{auth_service::AuthResponse, oidc_service::OidcService},
+    utils::error::{AppError, ErrorResponse},
+};
+use axum::{extract::State, response::Json};
+use serde::Deserialize;
+use serde_json::Value;
+use std::sync::Arc;
+use validator::Validate;
+
+/// The query parameters the provider appended to the redirect URI.
+#[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
+pub struct OidcCallbackRequest {
+    #[validate(length(min = 1, message = "field_required"))]
+    pub state: String,
+    #[validate(length(min = 1, message = "field_required"))]
+    pub code: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/oidc/callback",
+    tag = "Authentication",
+    request_body = OidcCallbackRequest,
+    responses(
+        (status = 200, description = "Login successful", body = AuthResponse),
+        (status = 400, description = "Invalid or expired state", body = ErrorResponse),
+        (status = 401, description = "Identity provider login failed", body = ErrorResponse),
+        (status = 403, description = "User not validated", body = ErrorResponse),
+        (status = 409, description = "Email matches an account but is not verified", body = ErrorResponse),
+        (status = 502, description = "Identity provider unavailable", body = ErrorResponse)
+    )
+)]
+pub async fn oidc_callback(
+    State(oidc): State<Arc<dyn OidcService>>,
+    _bearer_token: BearerToken,
+    ValidatedJson(payload): ValidatedJson<OidcCallbackRequest>,
+) -> Result<Json<Value>, AppError> {
+    let response = oidc.callback(&payload.state, &payload.code).await?;
+
+    tracing::info!(
+        "OIDC login successful: user_id={}, tenant_id={}",
+        response.user.id,
+        response.user.tenant_id
+    );
+
+    Ok(Json(serde_json::json!({
+        "token": response.token,
+        "user": response.user,
+        "memberships": response.memberships,
+    })))
+}
@@ -1,11 +1,9 @@
 use crate::{
-    config::Config,
     middleware::auth::Claims,
-    services::auth_service::{AuthResponse, AuthService},
-    utils::error::AppError,
+    services::auth_service::{AuthResponse, AuthenticationService},
+    utils::error::{AppError, ErrorResponse},
 };
 use axum::{extract::State, response::Json};
-use sea_orm::DatabaseConnection;
 use serde_json::Value;
 use std::sync::Arc;
 
@@ -15,27 +13,20 @@ use std::sync::Arc;
     tag = "Authentication",
     responses(
         (status = 200, description = "Token refreshed successfully", body = AuthResponse),
-        (status = 401, description = "Token expired or invalid"),
-        (status = 404, description = "User not found"),
-        (status = 500, description = "Internal server error")
+        (status = 401, description = "Token expired or invalid", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     security(
         ("bearer" = [])
     )
 )]
 pub async fn refresh(
-    State(db): State<Arc<DatabaseConnection>>,
-    State(config): State<Arc<Config>>,
+    State(auth): State<Arc<dyn AuthenticationService>>,
     claims: Claims,
 ) -> Result<Json<Value>, AppError> {
     tracing::info!("Refresh token request for user_id={}", claims.user_id);
-    let response = AuthService::refresh_token(
-        &db,
-        claims,
-        &config.jwt_secret,
-        config.jwt_expiration_minutes,
-    )
-    .await?;
+    let response = auth.refresh_token(claims).await?;
 
     tracing::info!(
         "Token refreshed successfully: user_id={}, tenant_id={}",
@@ -45,5 +36,6 @@ pub async fn refresh(
     Ok(Json(serde_json::json!({
         "token": response.token,
         "user": response.user,
+        "memberships": response.memberships,
     })))
 }
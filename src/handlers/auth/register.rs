@@ -1,11 +1,9 @@
 use crate::{
-    config::Config,
-    middleware::{auth::BearerToken, validation::validate_request},
-    services::auth_service::{AuthResponse, AuthService, RegisterRequest},
-    utils::error::AppError,
+    middleware::{auth::BearerToken, validation::ValidatedJson},
+    services::auth_service::{AuthResponse, AuthenticationService, RegisterRequest},
+    utils::error::{AppError, ErrorResponse},
 };
 use axum::{extract::State, response::Json};
-use sea_orm::DatabaseConnection;
 use serde_json::Value;
 use std::sync::Arc;
 
@@ -16,26 +14,18 @@ use std::sync::Arc;
     request_body = RegisterRequest,
     responses(
         (status = 200, description = "User registered successfully", body = AuthResponse),
-        (status = 400, description = "Validation error"),
-        (status = 409, description = "User already exists"),
-        (status = 500, description = "Internal server error")
+        (status = 400, description = "Validation error", body = ErrorResponse),
+        (status = 409, description = "User already exists", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
     )
 )]
 pub async fn register(
-    State(db): State<Arc<DatabaseConnection>>,
-    State(config): State<Arc<Config>>,
+    State(auth): State<Arc<dyn AuthenticationService>>,
     _bearer_token: BearerToken,
-    Json(payload): Json<RegisterRequest>,
+    ValidatedJson(payload): ValidatedJson<RegisterRequest>,
 ) -> Result<Json<Value>, AppError> {
-    let payload = validate_request(payload)?;
-    tracing::info!("Register request for email: {}", payload.email);
-    let response = AuthService::register(
-        &db,
-        payload,
-        &config.jwt_secret,
-        config.jwt_expiration_minutes,
-    )
-    .await?;
+    tracing::info!("Register request for tenant_id={}", payload.tenant_id);
+    let response = auth.register(payload).await?;
 
     tracing::info!(
         "User registered successfully: user_id={}, tenant_id={}",
@@ -45,5 +35,6 @@ pub async fn register(
     Ok(Json(serde_json::json!({
         "token": response.token,
         "user": response.user,
+        "memberships": response.memberships,
     })))
 }
@@ -0,0 +1,44 @@
+use crate::{
+    middleware::{auth::Claims, validation::ValidatedJson},
+    services::auth_service::{AuthResponse, AuthenticationService, SwitchTenantRequest},
+    utils::error::{AppError, ErrorResponse},
+};
+use axum::{extract::State, response::Json};
+use serde_json::Value;
+use std::sync::Arc;
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/switch-tenant",
+    tag = "Authentication",
+    request_body = SwitchTenantRequest,
+    responses(
+        (status = 200, description = "Token issued for the tenant", body = AuthResponse),
+        (status = 400, description = "Validation error", body = ErrorResponse),
+        (status = 401, description = "Token expired or invalid", body = ErrorResponse),
+        (status = 403, description = "Not an active member of the tenant", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn switch_tenant(
+    State(auth): State<Arc<dyn AuthenticationService>>,
+    claims: Claims,
+    ValidatedJson(payload): ValidatedJson<SwitchTenantRequest>,
+) -> Result<Json<Value>, AppError> {
+    tracing::info!(
+        "Switch tenant request: user_id={}, from tenant_id={} to tenant_id={}",
+        claims.user_id,
+        claims.tenant_id,
+        payload.tenant_id
+    );
+    let response = auth.switch_tenant(claims, payload.tenant_id).await?;
+
+    Ok(Json(serde_json::json!({
+        "token": response.token,
+        "user": response.user,
+        "memberships": response.memberships,
+    })))
+}
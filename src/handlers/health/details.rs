@@ -0,0 +1,55 @@
+use crate::middleware::auth::Claims;
+use crate::services::health_service::{CheckResult, HealthRegistry, HealthService, PoolStats};
+use crate::utils::error::{AppError, ErrorResponse};
+use axum::{extract::State, response::Json};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+pub struct HealthDetailsResponse {
+    pub ready: bool,
+    pub shutting_down: bool,
+    pub version: String,
+    pub uptime_secs: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub database_pool: Option<PoolStats>,
+    pub pending_migrations: Vec<String>,
+    pub checks: Vec<CheckResult>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/health/details",
+    tag = "Health",
+    responses(
+        (status = 200, description = "Detailed health information", body = HealthDetailsResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn health_details(
+    State(db): State<Arc<DatabaseConnection>>,
+    State(health): State<Arc<HealthRegistry>>,
+    claims: Claims,
+) -> Result<Json<HealthDetailsResponse>, AppError> {
+    tracing::debug!("Health details requested by user_id={}", claims.user_id);
+
+    let report = health.run().await;
+    let pending_migrations = HealthService::pending_migrations(&db)
+        .await
+        .unwrap_or_default();
+
+    Ok(Json(HealthDetailsResponse {
+        ready: report.ready,
+        shutting_down: report.shutting_down,
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        uptime_secs: health.uptime().as_secs(),
+        database_pool: HealthService::pool_stats(&db),
+        pending_migrations,
+        checks: report.checks,
+    }))
+}
@@ -1,9 +1,10 @@
+use crate::services::health_service::{CheckStatus, HealthRegistry};
 use crate::utils::error::{AppError, ErrorResponse};
 use axum::{extract::State, response::Json};
-use sea_orm::DatabaseConnection;
 use serde_json::{Value, json};
 use std::sync::Arc;
 use utoipa::ToSchema;
+
 #[derive(ToSchema)]
 pub struct HealthResponse {
     pub status: String,
@@ -20,21 +21,23 @@ pub struct HealthResponse {
     )
 )]
 pub async fn health_check(
-    State(db): State<Arc<DatabaseConnection>>,
+    State(health): State<Arc<HealthRegistry>>,
 ) -> Result<Json<Value>, AppError> {
-    let db_status = sea_orm::sqlx::query("SELECT 1")
-        .execute(db.get_postgres_connection_pool())
-        .await
-        .is_ok();
+    let report = health.run().await;
+
+    let db_status = report
+        .checks
+        .iter()
+        .any(|c| c.name == "database" && c.status == CheckStatus::Up);
 
-    let status = if db_status { "healthy" } else { "unhealthy" };
+    let status = if report.ready { "healthy" } else { "unhealthy" };
 
     let response = json!({
         "status": status,
         "database": if db_status { "connected" } else { "disconnected" }
     });
 
-    if db_status {
+    if report.ready {
         Ok(Json(response))
     } else {
         Err(AppError::ServiceUnavailable)
@@ -0,0 +1,14 @@
+use axum::response::Json;
+use serde_json::{Value, json};
+
+#[utoipa::path(
+    get,
+    path = "/health/live",
+    tag = "Health",
+    responses(
+        (status = 200, description = "Process is alive")
+    )
+)]
+pub async fn liveness() -> Json<Value> {
+    Json(json!({ "status": "alive" }))
+}
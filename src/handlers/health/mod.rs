@@ -0,0 +1,9 @@
+pub mod details;
+pub mod health_check;
+pub mod live;
+pub mod ready;
+
+pub use details::{HealthDetailsResponse, health_details};
+pub use health_check::{HealthResponse, health_check};
+pub use live::liveness;
+pub use ready::{ReadinessCheck, ReadinessResponse, readiness};
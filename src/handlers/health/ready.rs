@@ -0,0 +1,57 @@
+use crate::services::health_service::{CheckStatus, HealthRegistry};
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+use serde::Serialize;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+/// A check as the public readiness probe shows it. Why a check failed is
+/// logged, and shown by `/health/details`, but not here.
+#[derive(Serialize, ToSchema)]
+pub struct ReadinessCheck {
+    pub name: String,
+    pub status: CheckStatus,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ReadinessResponse {
+    pub ready: bool,
+    pub shutting_down: bool,
+    pub checks: Vec<ReadinessCheck>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    tag = "Health",
+    responses(
+        (status = 200, description = "Service is ready to serve traffic", body = ReadinessResponse),
+        (status = 503, description = "A dependency is failing or the service is shutting down", body = ReadinessResponse)
+    )
+)]
+pub async fn readiness(State(health): State<Arc<HealthRegistry>>) -> Response {
+    let report = health.run().await;
+
+    let status = if report.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    let response = ReadinessResponse {
+        ready: report.ready,
+        shutting_down: report.shutting_down,
+        checks: report
+            .checks
+            .into_iter()
+            .map(|check| ReadinessCheck {
+                name: check.name,
+                status: check.status,
+            })
+            .collect(),
+    };
+
+    (status, Json(response)).into_response()
+}
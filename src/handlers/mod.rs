@@ -1,7 +1,12 @@
+pub mod api_keys;
 pub mod auth;
 pub mod health;
+pub mod oidc_providers;
+pub mod scim;
+pub mod tasks;
 pub mod tenants;
 pub mod users;
+pub mod webhooks;
 
 pub use auth::*;
 pub use health::*;
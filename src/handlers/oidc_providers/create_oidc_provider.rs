@@ -0,0 +1,43 @@
+use crate::enums::ApiScope;
+use crate::middleware::validation::ValidatedJson;
+use crate::models::oidc_providers;
+use crate::services::oidc_service::{CreateOidcProviderRequest, OidcService};
+use crate::utils::{
+    AdminRoleWithTenant,
+    error::{AppError, ErrorResponse},
+};
+use axum::{extract::State, http::StatusCode, response::Json};
+use std::sync::Arc;
+
+#[utoipa::path(
+    post,
+    path = "/api/tenants/{tenant_id}/oidc-providers",
+    tag = "Identity Providers",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID")
+    ),
+    request_body = CreateOidcProviderRequest,
+    responses(
+        (status = 201, description = "Identity provider created", body = oidc_providers::Model),
+        (status = 400, description = "Validation error", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Admin access required", body = ErrorResponse)
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn create_oidc_provider(
+    State(oidc): State<Arc<dyn OidcService>>,
+    AdminRoleWithTenant {
+        principal,
+        tenant_id,
+    }: AdminRoleWithTenant,
+    ValidatedJson(payload): ValidatedJson<CreateOidcProviderRequest>,
+) -> Result<(StatusCode, Json<oidc_providers::Model>), AppError> {
+    principal.require_scope(ApiScope::OidcManage)?;
+
+    let provider = oidc.create_provider(tenant_id, payload).await?;
+
+    Ok((StatusCode::CREATED, Json(provider)))
+}
@@ -0,0 +1,43 @@
+use crate::enums::ApiScope;
+use crate::middleware::validation::ValidatedPath;
+use crate::services::oidc_service::OidcService;
+use crate::utils::{
+    AdminRoleWithTenant,
+    error::{AppError, ErrorResponse},
+};
+use axum::{extract::State, http::StatusCode};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[utoipa::path(
+    delete,
+    path = "/api/tenants/{tenant_id}/oidc-providers/{provider_id}",
+    tag = "Identity Providers",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID"),
+        ("provider_id" = String, Path, description = "Identity provider ID")
+    ),
+    responses(
+        (status = 204, description = "Identity provider and its linked identities deleted"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Admin access required", body = ErrorResponse),
+        (status = 404, description = "Identity provider not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn delete_oidc_provider(
+    State(oidc): State<Arc<dyn OidcService>>,
+    AdminRoleWithTenant {
+        principal,
+        tenant_id,
+    }: AdminRoleWithTenant,
+    ValidatedPath((_tenant_id, provider_id)): ValidatedPath<(Uuid, Uuid)>,
+) -> Result<StatusCode, AppError> {
+    principal.require_scope(ApiScope::OidcManage)?;
+
+    oidc.delete_provider(tenant_id, provider_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
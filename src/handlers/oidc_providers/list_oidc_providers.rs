@@ -0,0 +1,37 @@
+use crate::enums::ApiScope;
+use crate::models::oidc_providers;
+use crate::services::oidc_service::OidcService;
+use crate::utils::{
+    AdminRoleWithTenant,
+    error::{AppError, ErrorResponse},
+};
+use axum::{extract::State, response::Json};
+use std::sync::Arc;
+
+#[utoipa::path(
+    get,
+    path = "/api/tenants/{tenant_id}/oidc-providers",
+    tag = "Identity Providers",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID")
+    ),
+    responses(
+        (status = 200, description = "Identity providers of the tenant, without client secrets", body = Vec<oidc_providers::Model>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Admin access required", body = ErrorResponse)
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn list_oidc_providers(
+    State(oidc): State<Arc<dyn OidcService>>,
+    AdminRoleWithTenant {
+        principal,
+        tenant_id,
+    }: AdminRoleWithTenant,
+) -> Result<Json<Vec<oidc_providers::Model>>, AppError> {
+    principal.require_scope(ApiScope::OidcManage)?;
+
+    Ok(Json(oidc.list_providers(tenant_id).await?))
+}
@@ -0,0 +1,7 @@
+pub mod create_oidc_provider;
+pub mod delete_oidc_provider;
+pub mod list_oidc_providers;
+
+pub use create_oidc_provider::create_oidc_provider;
+pub use delete_oidc_provider::delete_oidc_provider;
+pub use list_oidc_providers::list_oidc_providers;
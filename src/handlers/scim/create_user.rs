@@ -0,0 +1,35 @@
+use super::ScimJson;
+use crate::enums::ApiScope;
+use crate::middleware::validation::ValidatedJson;
+use crate::services::scim_service::{ScimService, ScimUser, ScimUserRequest};
+use crate::utils::{AdminPrincipal, error::AppError, error::ScimError};
+use axum::{extract::State, http::StatusCode};
+use std::sync::Arc;
+
+#[utoipa::path(
+    post,
+    path = "/scim/v2/Users",
+    tag = "SCIM",
+    request_body(content = ScimUserRequest, content_type = "application/scim+json"),
+    responses(
+        (status = 201, description = "User provisioned", body = ScimUser, content_type = "application/scim+json"),
+        (status = 400, description = "Invalid userName or role", body = ScimError, content_type = "application/scim+json"),
+        (status = 401, description = "Unauthorized", body = ScimError, content_type = "application/scim+json"),
+        (status = 403, description = "Forbidden - Admin role and scim:provision required", body = ScimError, content_type = "application/scim+json"),
+        (status = 409, description = "A user with this userName exists", body = ScimError, content_type = "application/scim+json")
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn create_user(
+    State(scim): State<Arc<dyn ScimService>>,
+    AdminPrincipal(principal): AdminPrincipal,
+    ValidatedJson(payload): ValidatedJson<ScimUserRequest>,
+) -> Result<(StatusCode, ScimJson<ScimUser>), AppError> {
+    principal.require_scope(ApiScope::ScimProvision)?;
+
+    let user = scim.create_user(principal.tenant_id, payload).await?;
+
+    Ok((StatusCode::CREATED, ScimJson(user)))
+}
@@ -0,0 +1,36 @@
+use crate::enums::ApiScope;
+use crate::middleware::ValidatedPath;
+use crate::services::scim_service::ScimService;
+use crate::utils::{AdminPrincipal, error::AppError, error::ScimError};
+use axum::{extract::State, http::StatusCode};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[utoipa::path(
+    delete,
+    path = "/scim/v2/Users/{user_id}",
+    tag = "SCIM",
+    params(
+        ("user_id" = String, Path, description = "User ID")
+    ),
+    responses(
+        (status = 204, description = "User deleted"),
+        (status = 401, description = "Unauthorized", body = ScimError, content_type = "application/scim+json"),
+        (status = 403, description = "Forbidden - Admin role and scim:provision required", body = ScimError, content_type = "application/scim+json"),
+        (status = 404, description = "User not found in the caller's tenant", body = ScimError, content_type = "application/scim+json")
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn delete_user(
+    State(scim): State<Arc<dyn ScimService>>,
+    AdminPrincipal(principal): AdminPrincipal,
+    ValidatedPath(user_id): ValidatedPath<Uuid>,
+) -> Result<StatusCode, AppError> {
+    principal.require_scope(ApiScope::ScimProvision)?;
+
+    scim.delete_user(principal.tenant_id, user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,32 @@
+use super::ScimJson;
+use crate::enums::ApiScope;
+use crate::middleware::ValidatedQuery;
+use crate::services::scim_service::{ScimListQuery, ScimService, ScimUserList};
+use crate::utils::{AdminPrincipal, error::AppError, error::ScimError};
+use axum::extract::State;
+use std::sync::Arc;
+
+#[utoipa::path(
+    get,
+    path = "/scim/v2/Users",
+    tag = "SCIM",
+    params(ScimListQuery),
+    responses(
+        (status = 200, description = "Users of the caller's tenant matching the filter", body = ScimUserList, content_type = "application/scim+json"),
+        (status = 400, description = "Invalid filter", body = ScimError, content_type = "application/scim+json"),
+        (status = 401, description = "Unauthorized", body = ScimError, content_type = "application/scim+json"),
+        (status = 403, description = "Forbidden - Admin role and scim:provision required", body = ScimError, content_type = "application/scim+json")
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn list_users(
+    State(scim): State<Arc<dyn ScimService>>,
+    AdminPrincipal(principal): AdminPrincipal,
+    ValidatedQuery(query): ValidatedQuery<ScimListQuery>,
+) -> Result<ScimJson<ScimUserList>, AppError> {
+    principal.require_scope(ApiScope::ScimProvision)?;
+
+    Ok(ScimJson(scim.list_users(principal.tenant_id, query).await?))
+}
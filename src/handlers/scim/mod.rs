@@ -0,0 +1,37 @@
+pub mod create_user;
+pub mod delete_user;
+pub mod get_user;
+pub mod list_users;
+pub mod patch_user;
+pub mod replace_user;
+pub mod service_provider_config;
+
+pub use create_user::create_user;
+pub use delete_user::delete_user;
+pub use get_user::get_user;
+pub use list_users::list_users;
+pub use patch_user::patch_user;
+pub use replace_user::replace_user;
+pub use service_provider_config::service_provider_config;
+
+use crate::middleware::error_format::SCIM_JSON;
+use axum::{
+    http::{HeaderValue, header},
+    response::{IntoResponse, Json, Response},
+};
+use serde::Serialize;
+
+/// A JSON body served as `application/scim+json`.
+pub struct ScimJson<T>(pub T);
+
+impl<T: Serialize> IntoResponse for ScimJson<T> {
+    fn into_response(self) -> Response {
+        let mut response = Json(self.0).into_response();
+        if response.status().is_success() {
+            response
+                .headers_mut()
+                .insert(header::CONTENT_TYPE, HeaderValue::from_static(SCIM_JSON));
+        }
+        response
+    }
+}
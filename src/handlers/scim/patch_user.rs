@@ -0,0 +1,42 @@
+use super::ScimJson;
+use crate::enums::ApiScope;
+use crate::middleware::{ValidatedPath, validation::ValidatedJson};
+use crate::services::scim_service::{ScimPatchRequest, ScimService, ScimUser};
+use crate::utils::{AdminPrincipal, error::AppError, error::ScimError};
+use axum::extract::State;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[utoipa::path(
+    patch,
+    path = "/scim/v2/Users/{user_id}",
+    tag = "SCIM",
+    params(
+        ("user_id" = String, Path, description = "User ID")
+    ),
+    request_body(content = ScimPatchRequest, content_type = "application/scim+json"),
+    responses(
+        (status = 200, description = "User updated", body = ScimUser, content_type = "application/scim+json"),
+        (status = 400, description = "Unsupported operation or invalid value", body = ScimError, content_type = "application/scim+json"),
+        (status = 401, description = "Unauthorized", body = ScimError, content_type = "application/scim+json"),
+        (status = 403, description = "Forbidden - Admin role and scim:provision required", body = ScimError, content_type = "application/scim+json"),
+        (status = 404, description = "User not found in the caller's tenant", body = ScimError, content_type = "application/scim+json"),
+        (status = 409, description = "Another user has this userName", body = ScimError, content_type = "application/scim+json")
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn patch_user(
+    State(scim): State<Arc<dyn ScimService>>,
+    AdminPrincipal(principal): AdminPrincipal,
+    ValidatedPath(user_id): ValidatedPath<Uuid>,
+    ValidatedJson(payload): ValidatedJson<ScimPatchRequest>,
+) -> Result<ScimJson<ScimUser>, AppError> {
+    principal.require_scope(ApiScope::ScimProvision)?;
+
+    Ok(ScimJson(
+        scim.patch_user(principal.tenant_id, user_id, payload)
+            .await?,
+    ))
+}
@@ -0,0 +1,31 @@
+use super::ScimJson;
+use crate::services::scim_service::MAX_PAGE_SIZE;
+use serde_json::{Value, json};
+
+/// What this SCIM service supports (RFC 7643 §5). Public, so directories
+/// can probe it before a token is configured.
+#[utoipa::path(
+    get,
+    path = "/scim/v2/ServiceProviderConfig",
+    tag = "SCIM",
+    responses(
+        (status = 200, description = "Supported SCIM features", body = Object, content_type = "application/scim+json")
+    )
+)]
+pub async fn service_provider_config() -> ScimJson<Value> {
+    ScimJson(json!({
+        "schemas": ["urn:ietf:params:scim:schemas:core:2.0:ServiceProviderConfig"],
+        "patch": { "supported": true },
+        "bulk": { "supported": false, "maxOperations": 0, "maxPayloadSize": 0 },
+        "filter": { "supported": true, "maxResults": MAX_PAGE_SIZE },
+        "changePassword": { "supported": false },
+        "sort": { "supported": false },
+        "etag": { "supported": false },
+        "authenticationSchemes": [{
+            "type": "oauthbearertoken",
+            "name": "API key",
+            "description": "A service account API key with the scim:provision scope, sent as a bearer token",
+            "primary": true
+        }]
+    }))
+}
@@ -0,0 +1,39 @@
+use crate::middleware::{ValidatedQuery, auth::BearerToken};
+use crate::models::task_runs;
+use crate::services::maintenance_service::MaintenanceService;
+use crate::utils::error::{AppError, ErrorResponse};
+use axum::{extract::State, response::Json};
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TaskRunsQuery {
+    /// Only runs of this task, e.g. `purge_auth_artifacts`.
+    pub task: Option<String>,
+    /// Runs to return, 1 to 200 (default: 50).
+    pub limit: Option<u64>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/tasks/runs",
+    tag = "Scheduled Tasks",
+    params(TaskRunsQuery),
+    responses(
+        (status = 200, description = "Runs of scheduled tasks, newest first", body = Vec<task_runs::Model>),
+        (status = 401, description = "Unauthorized - the deployment's BEARER_TOKEN is required", body = ErrorResponse)
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+/// Runs span every tenant, so they are for the operator of the deployment
+/// rather than for tenant admins.
+pub async fn list_task_runs(
+    State(maintenance): State<Arc<dyn MaintenanceService>>,
+    _bearer_token: BearerToken,
+    ValidatedQuery(query): ValidatedQuery<TaskRunsQuery>,
+) -> Result<Json<Vec<task_runs::Model>>, AppError> {
+    Ok(Json(maintenance.list_runs(query.task, query.limit).await?))
+}
@@ -0,0 +1,3 @@
+pub mod list_task_runs;
+
+pub use list_task_runs::list_task_runs;
@@ -0,0 +1,55 @@
+use crate::middleware::{ValidatedPath, ValidatedQuery};
+use crate::services::exports_service::ExportService;
+use crate::utils::error::{AppError, ErrorResponse};
+use axum::{
+    extract::State,
+    http::{HeaderValue, header},
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DownloadQuery {
+    /// Signature from the export's `download_url`.
+    pub token: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/exports/{export_id}/download",
+    tag = "Tenants",
+    params(
+        ("export_id" = String, Path, description = "Export ID"),
+        DownloadQuery
+    ),
+    responses(
+        (status = 200, description = "The export archive", content_type = "application/json"),
+        (status = 403, description = "Download link invalid or expired", body = ErrorResponse),
+        (status = 404, description = "Export not found or not completed", body = ErrorResponse)
+    )
+)]
+pub async fn download_export(
+    State(exports): State<Arc<dyn ExportService>>,
+    ValidatedPath(export_id): ValidatedPath<Uuid>,
+    ValidatedQuery(query): ValidatedQuery<DownloadQuery>,
+) -> Result<Response, AppError> {
+    let archive = exports.download(export_id, &query.token).await?;
+    let disposition =
+        HeaderValue::from_str(&format!("attachment; filename=\"{}\"", archive.file_name))
+            .map_err(|_| AppError::Internal)?;
+
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                HeaderValue::from_static(archive.format.content_type()),
+            ),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        archive.content,
+    )
+        .into_response())
+}
@@ -0,0 +1,41 @@
+use crate::enums::ApiScope;
+use crate::middleware::ValidatedPath;
+use crate::services::exports_service::{ExportResponse, ExportService};
+use crate::utils::{
+    AdminRoleWithTenant,
+    error::{AppError, ErrorResponse},
+};
+use axum::{extract::State, response::Json};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[utoipa::path(
+    get,
+    path = "/api/tenants/{tenant_id}/exports/{export_id}",
+    tag = "Tenants",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID"),
+        ("export_id" = String, Path, description = "Export ID")
+    ),
+    responses(
+        (status = 200, description = "Export status, with a download link once completed", body = ExportResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Admin access required", body = ErrorResponse),
+        (status = 404, description = "Export not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn get_export(
+    State(exports): State<Arc<dyn ExportService>>,
+    AdminRoleWithTenant {
+        principal,
+        tenant_id,
+    }: AdminRoleWithTenant,
+    ValidatedPath((_tenant_id, export_id)): ValidatedPath<(Uuid, Uuid)>,
+) -> Result<Json<ExportResponse>, AppError> {
+    principal.require_scope(ApiScope::TenantsExport)?;
+
+    Ok(Json(exports.get(tenant_id, export_id).await?))
+}
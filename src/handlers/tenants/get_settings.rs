@@ -0,0 +1,36 @@
+use crate::enums::ApiScope;
+use crate::services::tenant_settings_service::{TenantSettingsResponse, TenantSettingsService};
+use crate::utils::{
+    AdminRoleWithTenant,
+    error::{AppError, ErrorResponse},
+};
+use axum::{extract::State, response::Json};
+use std::sync::Arc;
+
+#[utoipa::path(
+    get,
+    path = "/api/tenants/{tenant_id}/settings",
+    tag = "Tenants",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID")
+    ),
+    responses(
+        (status = 200, description = "Current settings of the tenant", body = TenantSettingsResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Admin access required", body = ErrorResponse)
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn get_settings(
+    State(settings): State<Arc<dyn TenantSettingsService>>,
+    AdminRoleWithTenant {
+        principal,
+        tenant_id,
+    }: AdminRoleWithTenant,
+) -> Result<Json<TenantSettingsResponse>, AppError> {
+    principal.require_scope(ApiScope::SettingsManage)?;
+
+    Ok(Json(settings.get(tenant_id).await?))
+}
@@ -1,8 +1,11 @@
+use crate::enums::ApiScope;
 use crate::models::tenants;
-use crate::services::tenants_service::TenantsService;
-use crate::utils::{TenantAccess, error::AppError};
+use crate::services::tenants_service::TenantService;
+use crate::utils::{
+    TenantAccess,
+    error::{AppError, ErrorResponse},
+};
 use axum::{extract::State, response::Json};
-use sea_orm::DatabaseConnection;
 use serde_json::Value;
 use std::sync::Arc;
 
@@ -15,18 +18,23 @@ use std::sync::Arc;
     ),
     responses(
         (status = 200, description = "Tenant information", body = tenants::Model),
-        (status = 401, description = "Unauthorized"),
-        (status = 404, description = "Tenant not found")
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden", body = ErrorResponse),
+        (status = 404, description = "Tenant not found", body = ErrorResponse)
     ),
     security(
         ("bearer" = [])
     )
 )]
 pub async fn get_tenant(
-    State(db): State<Arc<DatabaseConnection>>,
-    TenantAccess { tenant_id, .. }: TenantAccess,
+    State(tenants): State<Arc<dyn TenantService>>,
+    TenantAccess {
+        principal,
+        tenant_id,
+    }: TenantAccess,
 ) -> Result<Json<Value>, AppError> {
-    let tenant = TenantsService::get_by_id(&db, tenant_id).await?;
+    principal.require_scope(ApiScope::TenantsRead)?;
+    let tenant = tenants.get_by_id(tenant_id).await?;
 
     Ok(Json(serde_json::json!(tenant)))
 }
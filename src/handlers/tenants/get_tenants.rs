@@ -1,8 +1,7 @@
 use crate::models::tenants;
-use crate::services::tenants_service::TenantsService;
-use crate::utils::error::AppError;
+use crate::services::tenants_service::TenantService;
+use crate::utils::error::{AppError, ErrorResponse};
 use axum::{extract::State, response::Json};
-use sea_orm::DatabaseConnection;
 use serde_json::Value;
 use std::sync::Arc;
 
@@ -12,13 +11,13 @@ use std::sync::Arc;
     tag = "Tenants",
     responses(
         (status = 200, description = "List of all tenants", body = Vec<tenants::Model>),
-        (status = 500, description = "Internal server error")
+        (status = 500, description = "Internal server error", body = ErrorResponse)
     )
 )]
 pub async fn list_tenants(
-    State(db): State<Arc<DatabaseConnection>>,
+    State(tenants): State<Arc<dyn TenantService>>,
 ) -> Result<Json<Value>, AppError> {
-    let tenants = TenantsService::list_all(&db).await?;
+    let tenants = tenants.list_all().await?;
 
     Ok(Json(serde_json::json!(tenants)))
 }
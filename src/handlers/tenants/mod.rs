@@ -1,5 +1,15 @@
+pub mod download_export;
+pub mod get_export;
+pub mod get_settings;
 pub mod get_tenant;
 pub mod get_tenants;
+pub mod request_export;
+pub mod update_settings;
 
+pub use download_export::download_export;
+pub use get_export::get_export;
+pub use get_settings::get_settings;
 pub use get_tenant::get_tenant;
 pub use get_tenants::list_tenants;
+pub use request_export::request_export;
+pub use update_settings::update_settings;
@@ -0,0 +1,52 @@
+use crate::enums::{ApiScope, ExportFormat};
+use crate::middleware::validation::ValidatedJson;
+use crate::services::exports_service::{ExportResponse, ExportService};
+use crate::utils::{
+    AdminRoleWithTenant,
+    error::{AppError, ErrorResponse},
+};
+use axum::{extract::State, http::StatusCode, response::Json};
+use serde::Deserialize;
+use std::sync::Arc;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
+pub struct RequestExportRequest {
+    /// Defaults to `json`.
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/tenants/{tenant_id}/exports",
+    tag = "Tenants",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID")
+    ),
+    request_body = RequestExportRequest,
+    responses(
+        (status = 202, description = "Export queued; poll it until it has completed", body = ExportResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Admin access required", body = ErrorResponse)
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn request_export(
+    State(exports): State<Arc<dyn ExportService>>,
+    AdminRoleWithTenant {
+        principal,
+        tenant_id,
+    }: AdminRoleWithTenant,
+    ValidatedJson(payload): ValidatedJson<RequestExportRequest>,
+) -> Result<(StatusCode, Json<ExportResponse>), AppError> {
+    principal.require_scope(ApiScope::TenantsExport)?;
+
+    let export = exports
+        .request(tenant_id, principal.id(), payload.format)
+        .await?;
+
+    Ok((StatusCode::ACCEPTED, Json(export)))
+}
@@ -0,0 +1,48 @@
+use crate::enums::ApiScope;
+use crate::middleware::validation::ValidatedJson;
+use crate::services::tenant_settings_service::{
+    TenantSettingsResponse, TenantSettingsService, UpdateTenantSettingsRequest,
+};
+use crate::utils::{
+    AdminRoleWithTenant,
+    error::{AppError, ErrorResponse},
+};
+use axum::{extract::State, response::Json};
+use std::sync::Arc;
+
+#[utoipa::path(
+    put,
+    path = "/api/tenants/{tenant_id}/settings",
+    tag = "Tenants",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID")
+    ),
+    request_body = UpdateTenantSettingsRequest,
+    responses(
+        (status = 200, description = "Settings updated", body = TenantSettingsResponse),
+        (status = 400, description = "Validation error", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Admin access required", body = ErrorResponse),
+        (status = 409, description = "Settings changed since `version`", body = ErrorResponse)
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn update_settings(
+    State(settings): State<Arc<dyn TenantSettingsService>>,
+    AdminRoleWithTenant {
+        principal,
+        tenant_id,
+    }: AdminRoleWithTenant,
+    ValidatedJson(payload): ValidatedJson<UpdateTenantSettingsRequest>,
+) -> Result<Json<TenantSettingsResponse>, AppError> {
+    principal.require_scope(ApiScope::SettingsManage)?;
+
+    tracing::info!(
+        "Updating settings of tenant {} from version {}",
+        tenant_id,
+        payload.version
+    );
+    Ok(Json(settings.update(tenant_id, payload).await?))
+}
@@ -0,0 +1,63 @@
+use crate::enums::{ApiScope, UserRole};
+use crate::middleware::validation::ValidatedJson;
+use crate::models::users;
+use crate::services::users_service::UserService;
+use crate::utils::{
+    AdminRoleWithTenant,
+    error::{AppError, ErrorResponse},
+};
+use axum::{extract::State, http::StatusCode, response::Json};
+use serde::Deserialize;
+use std::sync::Arc;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
+pub struct AddMemberRequest {
+    /// Email of an existing user, typically of another tenant.
+    #[validate(email(message = "email_invalid"))]
+    pub email: String,
+    /// Defaults to `Regular`.
+    #[serde(default = "default_role")]
+    pub role: UserRole,
+}
+
+fn default_role() -> UserRole {
+    UserRole::Regular
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/tenants/{tenant_id}/members",
+    tag = "Users",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID")
+    ),
+    request_body = AddMemberRequest,
+    responses(
+        (status = 201, description = "User added to the tenant", body = users::Model),
+        (status = 400, description = "Validation error", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Admin access required", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 409, description = "Already a member", body = ErrorResponse)
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn add_member(
+    State(users): State<Arc<dyn UserService>>,
+    AdminRoleWithTenant {
+        principal,
+        tenant_id,
+    }: AdminRoleWithTenant,
+    ValidatedJson(payload): ValidatedJson<AddMemberRequest>,
+) -> Result<(StatusCode, Json<users::Model>), AppError> {
+    principal.require_scope(ApiScope::UsersWrite)?;
+
+    let user = users
+        .add_member(tenant_id, &payload.email, payload.role)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(user)))
+}
@@ -0,0 +1,48 @@
+use crate::middleware::{auth::Claims, validation::ValidatedJson};
+use crate::services::users_service::UserService;
+use crate::utils::error::{AppError, ErrorResponse};
+use axum::{extract::State, http::StatusCode};
+use serde::Deserialize;
+use std::sync::Arc;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
+pub struct ChangePasswordRequest {
+    #[validate(length(min = 1, message = "password_required"))]
+    pub current_password: String,
+    /// Must also satisfy the password policy of every tenant the user
+    /// belongs to.
+    #[validate(length(min = 8, max = 100, message = "password_length"))]
+    pub new_password: String,
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/me/password",
+    tag = "Users",
+    request_body = ChangePasswordRequest,
+    responses(
+        (status = 204, description = "Password changed"),
+        (status = 400, description = "Wrong current password or new password rejected by the password policy", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn change_password(
+    State(users): State<Arc<dyn UserService>>,
+    claims: Claims,
+    ValidatedJson(payload): ValidatedJson<ChangePasswordRequest>,
+) -> Result<StatusCode, AppError> {
+    users
+        .change_password(
+            claims.user_id,
+            &payload.current_password,
+            &payload.new_password,
+        )
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,17 +1,22 @@
-use crate::services::users_service::UsersService;
+use crate::enums::ApiScope;
+use crate::middleware::ValidatedPath;
+use crate::services::users_service::UserService;
 use crate::utils::{AdminRoleWithTenant, error::AppError};
-use axum::{extract::Path, extract::State, response::Json};
-use sea_orm::DatabaseConnection;
+use axum::{extract::State, response::Json};
 use serde_json::Value;
 use std::sync::Arc;
 use uuid::Uuid;
 
 pub async fn change_user_status(
-    State(db): State<Arc<DatabaseConnection>>,
-    AdminRoleWithTenant { tenant_id, .. }: AdminRoleWithTenant,
-    Path((_, user_id)): Path<(Uuid, Uuid)>,
+    State(users): State<Arc<dyn UserService>>,
+    AdminRoleWithTenant {
+        principal,
+        tenant_id,
+    }: AdminRoleWithTenant,
+    ValidatedPath((_, user_id)): ValidatedPath<(Uuid, Uuid)>,
 ) -> Result<Json<Value>, AppError> {
-    let user = UsersService::change_user_status(&db, user_id, tenant_id).await?;
+    principal.require_scope(ApiScope::UsersWrite)?;
+    let user = users.change_user_status(user_id, tenant_id).await?;
 
     Ok(Json(serde_json::json!({
         "id": user.id,
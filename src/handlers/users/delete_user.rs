@@ -0,0 +1,42 @@
+use crate::enums::ApiScope;
+use crate::middleware::ValidatedPath;
+use crate::services::users_service::UserService;
+use crate::utils::{
+    AdminRoleWithTenant,
+    error::{AppError, ErrorResponse},
+};
+use axum::{extract::State, http::StatusCode};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[utoipa::path(
+    delete,
+    path = "/api/tenants/{tenant_id}/users/{user_id}",
+    tag = "Users",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID"),
+        ("user_id" = String, Path, description = "User ID")
+    ),
+    responses(
+        (status = 204, description = "User deleted, or removed from the tenant if they belong to others"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Admin access required", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn delete_user(
+    State(users): State<Arc<dyn UserService>>,
+    AdminRoleWithTenant {
+        principal,
+        tenant_id,
+    }: AdminRoleWithTenant,
+    ValidatedPath((_tenant_id, user_id)): ValidatedPath<(Uuid, Uuid)>,
+) -> Result<StatusCode, AppError> {
+    principal.require_scope(ApiScope::UsersWrite)?;
+    users.delete(tenant_id, user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,7 +1,12 @@
+use crate::enums::ApiScope;
+use crate::middleware::ValidatedPath;
 use crate::models::users;
-use crate::utils::{TenantAccess, error::AppError};
-use axum::{extract::Path, extract::State, response::Json};
-use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use crate::services::users_service::UserService;
+use crate::utils::{
+    TenantAccess,
+    error::{AppError, ErrorResponse},
+};
+use axum::{extract::State, response::Json};
 use serde_json::Value;
 use std::sync::Arc;
 use uuid::Uuid;
@@ -16,25 +21,24 @@ use uuid::Uuid;
     ),
     responses(
         (status = 200, description = "User information", body = users::Model),
-        (status = 401, description = "Unauthorized"),
-        (status = 403, description = "Forbidden"),
-        (status = 404, description = "User not found")
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse)
     ),
     security(
         ("bearer" = [])
     )
 )]
 pub async fn get_user(
-    State(db): State<Arc<DatabaseConnection>>,
-    TenantAccess { tenant_id, .. }: TenantAccess,
-    Path(user_id): Path<Uuid>,
+    State(users): State<Arc<dyn UserService>>,
+    TenantAccess {
+        principal,
+        tenant_id,
+    }: TenantAccess,
+    ValidatedPath((_tenant_id, user_id)): ValidatedPath<(Uuid, Uuid)>,
 ) -> Result<Json<Value>, AppError> {
-    let user = users::Entity::find()
-        .filter(users::Column::Id.eq(user_id))
-        .filter(users::Column::TenantId.eq(tenant_id))
-        .one(db.as_ref())
-        .await?
-        .ok_or(AppError::UserNotFound)?;
+    principal.require_scope(ApiScope::UsersRead)?;
+    let user = users.get_in_tenant(tenant_id, user_id).await?;
 
     Ok(Json(serde_json::json!(user)))
 }
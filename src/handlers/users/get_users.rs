@@ -1,7 +1,11 @@
+use crate::enums::ApiScope;
 use crate::models::users;
-use crate::utils::{AdminRoleWithTenant, error::AppError};
+use crate::services::users_service::UserService;
+use crate::utils::{
+    AdminRoleWithTenant,
+    error::{AppError, ErrorResponse},
+};
 use axum::{extract::State, response::Json};
-use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
 use serde_json::Value;
 use std::sync::Arc;
 
@@ -14,22 +18,22 @@ use std::sync::Arc;
     ),
     responses(
         (status = 200, description = "List of users", body = Vec<users::Model>),
-        (status = 401, description = "Unauthorized"),
-        (status = 403, description = "Forbidden - Admin access required")
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Admin access required", body = ErrorResponse)
     ),
     security(
         ("bearer" = [])
     )
 )]
 pub async fn get_users(
-    State(db): State<Arc<DatabaseConnection>>,
-    AdminRoleWithTenant { tenant_id, .. }: AdminRoleWithTenant,
+    State(users): State<Arc<dyn UserService>>,
+    AdminRoleWithTenant {
+        principal,
+        tenant_id,
+    }: AdminRoleWithTenant,
 ) -> Result<Json<Value>, AppError> {
-    let users_list = users::Entity::find()
-        .filter(users::Column::TenantId.eq(tenant_id))
-        .order_by_desc(users::Column::CreatedAt)
-        .all(db.as_ref())
-        .await?;
+    principal.require_scope(ApiScope::UsersRead)?;
+    let users_list = users.list_by_tenant(tenant_id).await?;
 
     Ok(Json(serde_json::json!(users_list)))
 }
@@ -1,8 +1,8 @@
 use crate::middleware::auth::Claims;
 use crate::models::users;
-use crate::utils::error::AppError;
+use crate::services::users_service::UserService;
+use crate::utils::error::{AppError, ErrorResponse};
 use axum::{extract::State, response::Json};
-use sea_orm::{DatabaseConnection, EntityTrait};
 use serde_json::Value;
 use std::sync::Arc;
 
@@ -12,15 +12,15 @@ use std::sync::Arc;
     tag = "Users",
     responses(
         (status = 200, description = "Current user information", body = users::Model),
-        (status = 401, description = "Unauthorized"),
-        (status = 404, description = "User not found")
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse)
     ),
     security(
         ("bearer" = [])
     )
 )]
 pub async fn me(
-    State(db): State<Arc<DatabaseConnection>>,
+    State(users): State<Arc<dyn UserService>>,
     claims: Claims,
 ) -> Result<Json<Value>, AppError> {
     tracing::info!(
@@ -28,14 +28,14 @@ pub async fn me(
         claims.user_id,
         claims.tenant_id
     );
-    let user = users::Entity::find_by_id(claims.user_id)
-        .one(db.as_ref())
-        .await?
-        .ok_or(AppError::UserNotFound)?;
+    // Role and status are those of the tenant the token is for
+    let user = users
+        .get_in_tenant(claims.tenant_id, claims.user_id)
+        .await?;
 
     tracing::debug!(
-        "User data retrieved: email={}, tenant_id={}",
-        user.email,
+        "User data retrieved: user_id={}, tenant_id={}",
+        user.id,
         user.tenant_id
     );
     Ok(Json(serde_json::json!({
@@ -44,6 +44,7 @@ pub async fn me(
         "tenant_id": user.tenant_id,
         "status": user.status,
         "role": user.role,
+        "locale": user.locale,
         "created_at": user.created_at
     })))
 }
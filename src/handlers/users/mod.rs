@@ -1,11 +1,21 @@
+pub mod add_member;
+pub mod change_password;
 pub mod change_role;
 pub mod change_status;
+pub mod delete_user;
 pub mod get_user;
 pub mod get_users;
 pub mod me;
+pub mod restore_user;
+pub mod set_locale;
 
+pub use add_member::add_member;
+pub use change_password::change_password;
 pub use change_role::change_role;
 pub use change_status::change_user_status;
+pub use delete_user::delete_user;
 pub use get_user::get_user;
 pub use get_users::get_users;
 pub use me::me;
+pub use restore_user::restore_user;
+pub use set_locale::set_locale;
@@ -0,0 +1,44 @@
+use crate::enums::ApiScope;
+use crate::middleware::ValidatedPath;
+use crate::models::users;
+use crate::services::users_service::UserService;
+use crate::utils::{
+    AdminRoleWithTenant,
+    error::{AppError, ErrorResponse},
+};
+use axum::{extract::State, response::Json};
+use serde_json::Value;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[utoipa::path(
+    post,
+    path = "/api/tenants/{tenant_id}/users/{user_id}/restore",
+    tag = "Users",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID"),
+        ("user_id" = String, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "User restored", body = users::Model),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Admin access required", body = ErrorResponse),
+        (status = 404, description = "No deleted user to restore, or the grace period is over", body = ErrorResponse)
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn restore_user(
+    State(users): State<Arc<dyn UserService>>,
+    AdminRoleWithTenant {
+        principal,
+        tenant_id,
+    }: AdminRoleWithTenant,
+    ValidatedPath((_tenant_id, user_id)): ValidatedPath<(Uuid, Uuid)>,
+) -> Result<Json<Value>, AppError> {
+    principal.require_scope(ApiScope::UsersWrite)?;
+    let user = users.restore(tenant_id, user_id).await?;
+
+    Ok(Json(serde_json::json!(user)))
+}
@@ -0,0 +1,43 @@
+use crate::enums::Locale;
+use crate::middleware::{auth::Claims, validation::ValidatedJson};
+use crate::services::users_service::UserService;
+use crate::utils::error::{AppError, ErrorResponse};
+use axum::{extract::State, response::Json};
+use serde::Deserialize;
+use serde_json::Value;
+use std::sync::Arc;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
+pub struct SetLocaleRequest {
+    /// `null` clears the preference so the tenant default applies again.
+    pub locale: Option<Locale>,
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/me/locale",
+    tag = "Users",
+    request_body = SetLocaleRequest,
+    responses(
+        (status = 200, description = "Locale updated; takes effect on the next issued token"),
+        (status = 400, description = "Unsupported locale", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn set_locale(
+    State(users): State<Arc<dyn UserService>>,
+    claims: Claims,
+    ValidatedJson(payload): ValidatedJson<SetLocaleRequest>,
+) -> Result<Json<Value>, AppError> {
+    let user = users.set_locale(claims.user_id, payload.locale).await?;
+
+    Ok(Json(serde_json::json!({
+        "id": user.id,
+        "locale": user.locale,
+    })))
+}
@@ -0,0 +1,42 @@
+use crate::enums::ApiScope;
+use crate::middleware::validation::ValidatedJson;
+use crate::services::webhooks_service::{CreateWebhookRequest, CreatedWebhook, WebhookService};
+use crate::utils::{
+    AdminRoleWithTenant,
+    error::{AppError, ErrorResponse},
+};
+use axum::{extract::State, http::StatusCode, response::Json};
+use std::sync::Arc;
+
+#[utoipa::path(
+    post,
+    path = "/api/tenants/{tenant_id}/webhooks",
+    tag = "Webhooks",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID")
+    ),
+    request_body = CreateWebhookRequest,
+    responses(
+        (status = 201, description = "Webhook created; `secret` is only returned here", body = CreatedWebhook),
+        (status = 400, description = "Validation error", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Admin access required", body = ErrorResponse)
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn create_webhook(
+    State(webhooks): State<Arc<dyn WebhookService>>,
+    AdminRoleWithTenant {
+        principal,
+        tenant_id,
+    }: AdminRoleWithTenant,
+    ValidatedJson(payload): ValidatedJson<CreateWebhookRequest>,
+) -> Result<(StatusCode, Json<CreatedWebhook>), AppError> {
+    principal.require_scope(ApiScope::WebhooksManage)?;
+
+    let created = webhooks.create_endpoint(tenant_id, payload).await?;
+
+    Ok((StatusCode::CREATED, Json(created)))
+}
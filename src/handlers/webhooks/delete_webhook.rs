@@ -0,0 +1,43 @@
+use crate::enums::ApiScope;
+use crate::middleware::validation::ValidatedPath;
+use crate::services::webhooks_service::WebhookService;
+use crate::utils::{
+    AdminRoleWithTenant,
+    error::{AppError, ErrorResponse},
+};
+use axum::{extract::State, http::StatusCode};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[utoipa::path(
+    delete,
+    path = "/api/tenants/{tenant_id}/webhooks/{webhook_id}",
+    tag = "Webhooks",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID"),
+        ("webhook_id" = String, Path, description = "Webhook ID")
+    ),
+    responses(
+        (status = 204, description = "Webhook and its delivery log deleted"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Admin access required", body = ErrorResponse),
+        (status = 404, description = "Webhook not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn delete_webhook(
+    State(webhooks): State<Arc<dyn WebhookService>>,
+    AdminRoleWithTenant {
+        principal,
+        tenant_id,
+    }: AdminRoleWithTenant,
+    ValidatedPath((_tenant_id, webhook_id)): ValidatedPath<(Uuid, Uuid)>,
+) -> Result<StatusCode, AppError> {
+    principal.require_scope(ApiScope::WebhooksManage)?;
+
+    webhooks.delete_endpoint(tenant_id, webhook_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,57 @@
+use crate::enums::ApiScope;
+use crate::middleware::ValidatedQuery;
+use crate::middleware::validation::ValidatedPath;
+use crate::models::webhook_deliveries;
+use crate::services::webhooks_service::WebhookService;
+use crate::utils::{
+    AdminRoleWithTenant,
+    error::{AppError, ErrorResponse},
+};
+use axum::{extract::State, response::Json};
+use serde::Deserialize;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct WebhookDeliveriesQuery {
+    /// Deliveries to return, 1 to 200 (default: 50).
+    pub limit: Option<u64>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/tenants/{tenant_id}/webhooks/{webhook_id}/deliveries",
+    tag = "Webhooks",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID"),
+        ("webhook_id" = String, Path, description = "Webhook ID"),
+        WebhookDeliveriesQuery
+    ),
+    responses(
+        (status = 200, description = "Deliveries to the webhook, newest first", body = Vec<webhook_deliveries::Model>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Admin access required", body = ErrorResponse),
+        (status = 404, description = "Webhook not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn list_webhook_deliveries(
+    State(webhooks): State<Arc<dyn WebhookService>>,
+    AdminRoleWithTenant {
+        principal,
+        tenant_id,
+    }: AdminRoleWithTenant,
+    ValidatedPath((_tenant_id, webhook_id)): ValidatedPath<(Uuid, Uuid)>,
+    ValidatedQuery(query): ValidatedQuery<WebhookDeliveriesQuery>,
+) -> Result<Json<Vec<webhook_deliveries::Model>>, AppError> {
+    principal.require_scope(ApiScope::WebhooksManage)?;
+
+    Ok(Json(
+        webhooks
+            .list_deliveries(tenant_id, webhook_id, query.limit)
+            .await?,
+    ))
+}
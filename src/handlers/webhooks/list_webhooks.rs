@@ -0,0 +1,37 @@
+use crate::enums::ApiScope;
+use crate::models::webhook_endpoints;
+use crate::services::webhooks_service::WebhookService;
+use crate::utils::{
+    AdminRoleWithTenant,
+    error::{AppError, ErrorResponse},
+};
+use axum::{extract::State, response::Json};
+use std::sync::Arc;
+
+#[utoipa::path(
+    get,
+    path = "/api/tenants/{tenant_id}/webhooks",
+    tag = "Webhooks",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID")
+    ),
+    responses(
+        (status = 200, description = "Webhooks of the tenant, without secrets", body = Vec<webhook_endpoints::Model>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Admin access required", body = ErrorResponse)
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn list_webhooks(
+    State(webhooks): State<Arc<dyn WebhookService>>,
+    AdminRoleWithTenant {
+        principal,
+        tenant_id,
+    }: AdminRoleWithTenant,
+) -> Result<Json<Vec<webhook_endpoints::Model>>, AppError> {
+    principal.require_scope(ApiScope::WebhooksManage)?;
+
+    Ok(Json(webhooks.list_endpoints(tenant_id).await?))
+}
@@ -0,0 +1,13 @@
+pub mod create_webhook;
+pub mod delete_webhook;
+pub mod list_webhook_deliveries;
+pub mod list_webhooks;
+pub mod ping_webhook;
+pub mod redeliver_webhook;
+
+pub use create_webhook::create_webhook;
+pub use delete_webhook::delete_webhook;
+pub use list_webhook_deliveries::list_webhook_deliveries;
+pub use list_webhooks::list_webhooks;
+pub use ping_webhook::ping_webhook;
+pub use redeliver_webhook::redeliver_webhook;
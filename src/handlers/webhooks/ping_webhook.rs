@@ -0,0 +1,42 @@
+use crate::enums::ApiScope;
+use crate::middleware::validation::ValidatedPath;
+use crate::models::webhook_deliveries;
+use crate::services::webhooks_service::WebhookService;
+use crate::utils::{
+    AdminRoleWithTenant,
+    error::{AppError, ErrorResponse},
+};
+use axum::{extract::State, response::Json};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[utoipa::path(
+    post,
+    path = "/api/tenants/{tenant_id}/webhooks/{webhook_id}/ping",
+    tag = "Webhooks",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID"),
+        ("webhook_id" = String, Path, description = "Webhook ID")
+    ),
+    responses(
+        (status = 200, description = "Ping sent once, without retries; the delivery records whether the endpoint accepted it", body = webhook_deliveries::Model),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Admin access required", body = ErrorResponse),
+        (status = 404, description = "Webhook not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn ping_webhook(
+    State(webhooks): State<Arc<dyn WebhookService>>,
+    AdminRoleWithTenant {
+        principal,
+        tenant_id,
+    }: AdminRoleWithTenant,
+    ValidatedPath((_tenant_id, webhook_id)): ValidatedPath<(Uuid, Uuid)>,
+) -> Result<Json<webhook_deliveries::Model>, AppError> {
+    principal.require_scope(ApiScope::WebhooksManage)?;
+
+    Ok(Json(webhooks.ping(tenant_id, webhook_id).await?))
+}
@@ -0,0 +1,47 @@
+use crate::enums::ApiScope;
+use crate::middleware::validation::ValidatedPath;
+use crate::models::webhook_deliveries;
+use crate::services::webhooks_service::WebhookService;
+use crate::utils::{
+    AdminRoleWithTenant,
+    error::{AppError, ErrorResponse},
+};
+use axum::{extract::State, http::StatusCode, response::Json};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[utoipa::path(
+    post,
+    path = "/api/tenants/{tenant_id}/webhooks/{webhook_id}/deliveries/{delivery_id}/redeliver",
+    tag = "Webhooks",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID"),
+        ("webhook_id" = String, Path, description = "Webhook ID"),
+        ("delivery_id" = String, Path, description = "Delivery to send again")
+    ),
+    responses(
+        (status = 202, description = "New delivery of the same event queued", body = webhook_deliveries::Model),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Admin access required", body = ErrorResponse),
+        (status = 404, description = "Webhook or delivery not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn redeliver_webhook(
+    State(webhooks): State<Arc<dyn WebhookService>>,
+    AdminRoleWithTenant {
+        principal,
+        tenant_id,
+    }: AdminRoleWithTenant,
+    ValidatedPath((_tenant_id, webhook_id, delivery_id)): ValidatedPath<(Uuid, Uuid, Uuid)>,
+) -> Result<(StatusCode, Json<webhook_deliveries::Model>), AppError> {
+    principal.require_scope(ApiScope::WebhooksManage)?;
+
+    let delivery = webhooks
+        .redeliver(tenant_id, webhook_id, delivery_id)
+        .await?;
+
+    Ok((StatusCode::ACCEPTED, Json(delivery)))
+}
@@ -0,0 +1,105 @@
+//! Message catalogs for user-facing strings.
+//!
+//! Catalogs live in `locales/<locale>.toml` and are compiled into the binary.
+//! Lookups fall back to English, so a missing translation degrades to
+//! English rather than failing the request.
+
+use crate::enums::Locale;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+tokio::task_local! {
+    static CURRENT_LOCALE: Locale;
+}
+
+const SOURCES: &[(Locale, &str)] = &[
+    (Locale::En, include_str!("../../locales/en.toml")),
+    (Locale::Es, include_str!("../../locales/es.toml")),
+    (Locale::Pt, include_str!("../../locales/pt.toml")),
+];
+
+static CATALOGS: LazyLock<HashMap<Locale, HashMap<String, String>>> = LazyLock::new(|| {
+    SOURCES
+        .iter()
+        .map(|(locale, source)| (*locale, parse_catalog(source)))
+        .collect()
+});
+
+/// Flattens `[section] key = "..."` into `section.key` entries.
+fn parse_catalog(source: &str) -> HashMap<String, String> {
+    let table: toml::Table = toml::from_str(source).expect("message catalog is not valid TOML");
+    let mut messages = HashMap::new();
+    for (section, entries) in table {
+        let Some(entries) = entries.as_table() else {
+            continue;
+        };
+        for (key, value) in entries {
+            if let Some(value) = value.as_str() {
+                messages.insert(format!("{}.{}", section, key), value.to_string());
+            }
+        }
+    }
+    messages
+}
+
+/// Keys defined in `locale`'s catalog, for completeness checks.
+pub fn keys(locale: Locale) -> Vec<&'static str> {
+    let mut keys: Vec<&str> = CATALOGS[&locale].keys().map(String::as_str).collect();
+    keys.sort_unstable();
+    keys
+}
+
+/// Looks up `key` in `locale`, falling back to English.
+pub fn lookup(locale: Locale, key: &str) -> Option<&'static str> {
+    CATALOGS[&locale]
+        .get(key)
+        .or_else(|| CATALOGS[&Locale::En].get(key))
+        .map(String::as_str)
+}
+
+/// Like [`lookup`], replacing `{name}` placeholders with `params`.
+pub fn translate(locale: Locale, key: &str, params: &[(&str, String)]) -> Option<String> {
+    let template = lookup(locale, key)?;
+    Some(
+        params
+            .iter()
+            .fold(template.to_string(), |message, (name, value)| {
+                message.replace(&format!("{{{}}}", name), value)
+            }),
+    )
+}
+
+/// Picks the first supported language from an `Accept-Language` value,
+/// honouring `q` weights. Regional tags match their primary language.
+pub fn negotiate(accept_language: &str) -> Option<Locale> {
+    let mut ranges: Vec<(f32, &str)> = accept_language
+        .split(',')
+        .filter_map(|range| {
+            let mut parts = range.split(';').map(str::trim);
+            let tag = parts.next().filter(|tag| !tag.is_empty())?;
+            let q = parts
+                .filter_map(|p| p.strip_prefix("q="))
+                .find_map(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            (q > 0.0).then_some((q, tag))
+        })
+        .collect();
+    // Stable sort keeps header order for equal weights
+    ranges.sort_by(|a, b| b.0.total_cmp(&a.0));
+    ranges
+        .into_iter()
+        .find_map(|(_, tag)| Locale::from_tag(tag))
+}
+
+/// Runs `future` with `locale` available through [`current`].
+pub async fn scope<F: Future>(locale: Locale, future: F) -> F::Output {
+    CURRENT_LOCALE.scope(locale, future).await
+}
+
+/// Locale resolved for the request being handled on this task, or English
+/// outside of a request.
+pub fn current() -> Locale {
+    CURRENT_LOCALE
+        .try_with(|locale| *locale)
+        .unwrap_or_default()
+}
@@ -0,0 +1,36 @@
+use crate::jobs::{Job, JobHandler};
+use crate::services::exports_service::ExportService;
+use crate::utils::error::AppError;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Writes the archive of a requested tenant export.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunExport {
+    pub export_id: Uuid,
+}
+
+impl Job for RunExport {
+    const KIND: &'static str = "tenant_export";
+}
+
+pub struct RunExportHandler {
+    exports: Arc<dyn ExportService>,
+}
+
+impl RunExportHandler {
+    pub fn new(exports: Arc<dyn ExportService>) -> Self {
+        Self { exports }
+    }
+}
+
+#[async_trait]
+impl JobHandler for RunExportHandler {
+    type Job = RunExport;
+
+    async fn handle(&self, job: RunExport) -> Result<(), AppError> {
+        self.exports.run(job.export_id).await
+    }
+}
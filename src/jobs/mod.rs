@@ -0,0 +1,104 @@
+//! Background jobs: typed jobs, the handlers registered for them, and the
+//! worker that claims and runs them. Jobs are enqueued through
+//! [`JobQueue`](crate::services::jobs_service::JobQueue).
+
+pub mod export;
+pub mod webhook;
+pub mod worker;
+
+pub use worker::Worker;
+
+use crate::routes::AppState;
+use crate::utils::error::AppError;
+use async_trait::async_trait;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Input of a job, stored as its JSON payload.
+pub trait Job: Serialize + DeserializeOwned + Send + 'static {
+    /// Stored with each job to find its handler. Must never change once jobs
+    /// of this kind may be queued.
+    const KIND: &'static str;
+}
+
+/// Runs jobs of one kind. Handlers may run more than once for the same job,
+/// after a failure or when a worker dies mid-job, so they must be
+/// idempotent.
+#[async_trait]
+pub trait JobHandler: Send + Sync + 'static {
+    type Job: Job;
+
+    async fn handle(&self, job: Self::Job) -> Result<(), AppError>;
+}
+
+/// Why a job failed, and whether another attempt could succeed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JobFailure {
+    Retryable(String),
+    /// The payload cannot be decoded; retrying would fail the same way.
+    Permanent(String),
+}
+
+#[async_trait]
+trait ErasedHandler: Send + Sync {
+    async fn handle(&self, payload: serde_json::Value) -> Result<(), JobFailure>;
+}
+
+#[async_trait]
+impl<H: JobHandler> ErasedHandler for H {
+    async fn handle(&self, payload: serde_json::Value) -> Result<(), JobFailure> {
+        let job = serde_json::from_value::<H::Job>(payload)
+            .map_err(|e| JobFailure::Permanent(format!("Invalid payload: {}", e)))?;
+        JobHandler::handle(self, job)
+            .await
+            .map_err(|e| JobFailure::Retryable(e.to_string()))
+    }
+}
+
+/// Handlers by job kind, built once at startup.
+#[derive(Clone, Default)]
+pub struct JobRegistry {
+    handlers: HashMap<&'static str, Arc<dyn ErasedHandler>>,
+}
+
+impl JobRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Panics if a handler is already registered for the kind.
+    pub fn register<H: JobHandler>(mut self, handler: H) -> Self {
+        let kind = H::Job::KIND;
+        let previous = self.handlers.insert(kind, Arc::new(handler));
+        assert!(previous.is_none(), "job kind '{}' registered twice", kind);
+        self
+    }
+
+    pub fn kinds(&self) -> Vec<&'static str> {
+        let mut kinds: Vec<_> = self.handlers.keys().copied().collect();
+        kinds.sort_unstable();
+        kinds
+    }
+
+    /// Runs a payload with the handler of its kind.
+    pub async fn run(&self, kind: &str, payload: serde_json::Value) -> Result<(), JobFailure> {
+        match self.handlers.get(kind) {
+            Some(handler) => handler.handle(payload).await,
+            // Possibly a kind added by a newer release whose workers have
+            // not started yet
+            None => Err(JobFailure::Retryable(format!(
+                "No handler for job kind '{}'",
+                kind
+            ))),
+        }
+    }
+}
+
+/// The handlers of every job the application enqueues.
+pub fn registry(state: &AppState) -> JobRegistry {
+    JobRegistry::new()
+        .register(export::RunExportHandler::new(state.exports.clone()))
+        .register(webhook::DeliverWebhookHandler::new(state.webhooks.clone()))
+}
@@ -0,0 +1,38 @@
+use crate::jobs::{Job, JobHandler};
+use crate::services::webhooks_service::WebhookService;
+use crate::utils::error::AppError;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Makes the next attempt of a webhook delivery. Failed attempts are
+/// retried by the delivery itself, with a new job, so the job only fails
+/// when the outcome cannot be recorded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeliverWebhook {
+    pub delivery_id: Uuid,
+}
+
+impl Job for DeliverWebhook {
+    const KIND: &'static str = "webhook_delivery";
+}
+
+pub struct DeliverWebhookHandler {
+    webhooks: Arc<dyn WebhookService>,
+}
+
+impl DeliverWebhookHandler {
+    pub fn new(webhooks: Arc<dyn WebhookService>) -> Self {
+        Self { webhooks }
+    }
+}
+
+#[async_trait]
+impl JobHandler for DeliverWebhookHandler {
+    type Job = DeliverWebhook;
+
+    async fn handle(&self, job: DeliverWebhook) -> Result<(), AppError> {
+        self.webhooks.deliver(job.delivery_id).await
+    }
+}
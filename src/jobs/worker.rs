@@ -0,0 +1,164 @@
+use crate::config::JobsConfig;
+use crate::enums::JobStatus;
+use crate::jobs::{JobFailure, JobRegistry};
+use crate::models::jobs;
+use crate::repositories::JobRepository;
+use crate::utils::error::AppError;
+use crate::utils::shutdown::Shutdown;
+use chrono::{Duration, Utc};
+use sea_orm::Set;
+use std::sync::Arc;
+use tokio::task::JoinSet;
+use uuid::Uuid;
+
+/// Delay before the first retry; each further retry waits twice as long.
+const RETRY_BASE_DELAY: Duration = Duration::seconds(10);
+const RETRY_MAX_DELAY: Duration = Duration::hours(1);
+
+/// How long to wait after `attempts` failed attempts.
+pub fn retry_delay(attempts: i32) -> Duration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 16) as u32;
+    (RETRY_BASE_DELAY * 2i32.pow(exponent)).min(RETRY_MAX_DELAY)
+}
+
+/// Claims due jobs and runs them with the registered handlers.
+pub struct Worker {
+    jobs: Arc<dyn JobRepository>,
+    registry: JobRegistry,
+    config: JobsConfig,
+    id: String,
+}
+
+impl Worker {
+    pub fn new(jobs: Arc<dyn JobRepository>, registry: JobRegistry, config: JobsConfig) -> Self {
+        Self {
+            jobs,
+            registry,
+            config,
+            id: format!("worker-{}", Uuid::now_v7()),
+        }
+    }
+
+    /// Name recorded in `locked_by` on the jobs this worker claims.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Polls for jobs until shutdown. Jobs already started are finished
+    /// before returning.
+    pub async fn run(self, shutdown: Shutdown) {
+        tracing::info!(
+            worker = %self.id,
+            kinds = ?self.registry.kinds(),
+            "Job worker started"
+        );
+        while !shutdown.is_triggered() {
+            let ran = match self.run_once().await {
+                Ok(ran) => ran,
+                Err(e) => {
+                    tracing::warn!(worker = %self.id, "Failed to claim jobs: {}", e);
+                    0
+                }
+            };
+            if ran == 0 {
+                tokio::select! {
+                    _ = tokio::time::sleep(self.config.poll_interval) => {}
+                    _ = shutdown.wait() => {}
+                }
+            }
+        }
+        tracing::info!(worker = %self.id, "Job worker stopped");
+    }
+
+    /// Claims one batch of due jobs, up to `JOB_CONCURRENCY`, and runs them
+    /// concurrently. Returns how many were run.
+    pub async fn run_once(&self) -> Result<usize, AppError> {
+        let lock_timeout = Duration::from_std(self.config.lock_timeout).unwrap_or(Duration::MAX);
+        let claimed = self
+            .jobs
+            .claim(
+                &self.id,
+                self.config.concurrency.into(),
+                (Utc::now() - lock_timeout).fixed_offset(),
+            )
+            .await?;
+        let count = claimed.len();
+
+        let mut running = JoinSet::new();
+        for job in claimed {
+            let registry = self.registry.clone();
+            let (kind, payload) = (job.kind.clone(), job.payload.clone());
+            // Its own task, so a panicking handler fails only its job
+            let handle = tokio::spawn(async move { registry.run(&kind, payload).await });
+            running.spawn(async move {
+                let outcome = handle.await.unwrap_or_else(|e| {
+                    Err(JobFailure::Retryable(format!("Handler panicked: {}", e)))
+                });
+                (job, outcome)
+            });
+        }
+        while let Some(result) = running.join_next().await {
+            let Ok((job, outcome)) = result else {
+                continue;
+            };
+            if let Err(e) = self.finish(job, outcome).await {
+                tracing::error!(worker = %self.id, "Failed to record job outcome: {}", e);
+            }
+        }
+
+        Ok(count)
+    }
+
+    async fn finish(
+        &self,
+        job: jobs::Model,
+        outcome: Result<(), JobFailure>,
+    ) -> Result<Option<jobs::Model>, AppError> {
+        let now = Utc::now().fixed_offset();
+        let (job_id, kind, attempts, max_attempts) =
+            (job.id, job.kind.clone(), job.attempts, job.max_attempts);
+        let mut finished: jobs::ActiveModel = job.into();
+        finished.locked_by = Set(None);
+        finished.locked_at = Set(None);
+        finished.updated_at = Set(now);
+
+        match outcome {
+            Ok(()) => {
+                tracing::info!(%job_id, %kind, attempts, "Job completed");
+                finished.status = Set(JobStatus::Completed);
+                finished.completed_at = Set(Some(now));
+                finished.last_error = Set(None);
+            }
+            Err(JobFailure::Retryable(error)) if attempts < max_attempts => {
+                let delay = retry_delay(attempts);
+                tracing::warn!(
+                    %job_id,
+                    %kind,
+                    attempts,
+                    retry_in_secs = delay.num_seconds(),
+                    "Job failed, will retry: {}",
+                    error
+                );
+                finished.status = Set(JobStatus::Pending);
+                finished.run_at = Set(now + delay);
+                finished.last_error = Set(Some(error));
+            }
+            Err(JobFailure::Retryable(error) | JobFailure::Permanent(error)) => {
+                tracing::error!(%job_id, %kind, attempts, "Job is dead: {}", error);
+                finished.status = Set(JobStatus::Dead);
+                finished.last_error = Set(Some(error));
+            }
+        }
+
+        let finished = self.jobs.finish(finished, &self.id).await?;
+        if finished.is_none() {
+            tracing::warn!(
+                worker = %self.id,
+                %job_id,
+                %kind,
+                "Job was claimed by another worker, outcome discarded"
+            );
+        }
+        Ok(finished)
+    }
+}
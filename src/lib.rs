@@ -1,10 +1,16 @@
 pub mod api_doc;
+pub mod cli;
 pub mod config;
 pub mod db;
 pub mod enums;
+pub mod events;
 pub mod handlers;
+pub mod i18n;
+pub mod jobs;
 pub mod middleware;
 pub mod models;
+pub mod repositories;
 pub mod routes;
+pub mod scheduler;
 pub mod services;
 pub mod utils;
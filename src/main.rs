@@ -1,62 +1,29 @@
+use clap::Parser;
 use dotenv::dotenv;
-use std::sync::Arc;
-use template_rust_backend::{config, routes};
+use template_rust_backend::{
+    cli::{self, Cli, Command},
+    config,
+};
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
+async fn main() -> anyhow::Result<()> {
     dotenv().ok();
 
-    tracing_subscriber::fmt()
-        .with_env_filter("template_rust_backend=debug,tower_http=debug,sqlx=debug")
-        .with_span_events(tracing_subscriber::fmt::format::FmtSpan::FULL)
-        .init();
-
-    let db_config = config::DatabaseConfig::from_env()
-        .map_err(|e| Box::new(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
-    let db = Arc::new(db_config.connect().await?);
-
-    let config = Arc::new(
-        config::Config::from_env()
-            .map_err(|e| Box::new(std::io::Error::new(std::io::ErrorKind::Other, e)))?,
-    );
-
-    let args: Vec<String> = std::env::args().collect();
-    if args.len() > 1 {
-        match args[1].as_str() {
-            "run_migrations" => {
-                tracing::info!("Running migrations...");
-                run_migrations(&db).await?;
-                return Ok(());
-            }
-            _ => {}
-        }
+    let cli = Cli::parse();
+    let settings = config::Settings::load();
+
+    // Keep stdout clean for command output (e.g. `openapi export`); only the
+    // server logs there. Invalid settings are reported by the command itself,
+    // so fall back to default logging until then.
+    let logging = settings
+        .as_ref()
+        .map(|settings| settings.logging.clone())
+        .unwrap_or_default();
+    match cli.command {
+        None | Some(Command::Serve) => logging.init(),
+        Some(_) => logging.init_with_writer(std::io::stderr),
     }
+    .map_err(anyhow::Error::msg)?;
 
-    let app = routes::create_router(db.clone(), config.clone());
-
-    let listener =
-        tokio::net::TcpListener::bind(format!("{}:{}", config.server_host, config.server_port))
-            .await?;
-    tracing::info!(
-        "Server listening on http://{}:{}",
-        config.server_host,
-        config.server_port
-    );
-    axum::serve(listener, app).await?;
-
-    Ok(())
-}
-async fn run_migrations(db: &sea_orm::DatabaseConnection) -> anyhow::Result<()> {
-    use migration::Migrator;
-    use sea_orm_migration::prelude::*;
-
-    tracing::info!("Starting migrations...");
-    let result = Migrator::up(db, None).await;
-    if let Err(e) = &result {
-        tracing::error!("Migration error: {:?}", e);
-        tracing::error!("Full error: {:#?}", e);
-    }
-    result?;
-    tracing::info!("Migrations completed successfully");
-    Ok(())
+    cli::run(cli, settings).await
 }
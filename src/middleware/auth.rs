@@ -1,64 +1,66 @@
 use axum::{
-    extract::FromRequestParts,
+    extract::{FromRef, FromRequestParts},
     http::{header, request::Parts},
-    response::{IntoResponse, Response},
 };
-use jsonwebtoken::{DecodingKey, Validation};
-use serde_json::json;
 use std::sync::Arc;
+use uuid::Uuid;
 
+use crate::enums::{ApiScope, UserRole};
+use crate::services::api_keys_service::{API_KEY_PREFIX, ApiKeyService};
+use crate::services::auth_service::AuthService;
 pub use crate::services::auth_service::Claims;
+use crate::utils::error::{AppError, AuthError};
+
+/// Header carrying an API key, as an alternative to `Authorization: Bearer`.
+pub const API_KEY_HEADER: &str = "x-api-key";
 
 pub struct AuthState {
     pub secret: String,
     pub bearer_token: String,
 }
 
+fn auth_state(parts: &Parts) -> Result<Arc<AuthState>, AppError> {
+    parts
+        .extensions
+        .get::<Arc<AuthState>>()
+        .cloned()
+        .ok_or_else(|| {
+            tracing::error!("AuthState not found in request extensions");
+            AppError::Internal
+        })
+}
+
+fn bearer(parts: &Parts) -> Result<&str, AppError> {
+    let auth_header = parts
+        .headers
+        .get(header::AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .ok_or_else(|| {
+            tracing::warn!("No Authorization header found in request");
+            AuthError::MissingToken
+        })?;
+
+    auth_header.strip_prefix("Bearer ").ok_or_else(|| {
+        tracing::warn!("Authorization header does not start with 'Bearer '");
+        AuthError::InvalidToken.into()
+    })
+}
+
 pub struct BearerToken;
 
 impl<S> FromRequestParts<S> for BearerToken
 where
     S: Send + Sync,
 {
-    type Rejection = Response;
+    type Rejection = AppError;
 
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        let auth_state_arc = parts
-            .extensions
-            .get::<Arc<AuthState>>()
-            .ok_or_else(|| {
-                tracing::error!("AuthState not found in request extensions");
-                let body =
-                    json!({"error": "INTERNAL_ERROR", "message": "Server configuration error"});
-                (
-                    axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                    axum::Json(body),
-                )
-                    .into_response()
-            })?
-            .clone();
-
-        let auth_header = parts
-            .headers
-            .get(header::AUTHORIZATION)
-            .and_then(|h| h.to_str().ok())
-            .ok_or_else(|| {
-                tracing::warn!("No Authorization header found in request");
-                let body =
-                    json!({"error": "MISSING_TOKEN", "message": "Authentication token required"});
-                (axum::http::StatusCode::UNAUTHORIZED, axum::Json(body)).into_response()
-            })?;
-
-        let token = auth_header.strip_prefix("Bearer ").ok_or_else(|| {
-            tracing::warn!("Authorization header does not start with 'Bearer '");
-            let body = json!({"error": "INVALID_TOKEN", "message": "Invalid authentication token"});
-            (axum::http::StatusCode::UNAUTHORIZED, axum::Json(body)).into_response()
-        })?;
+        let auth_state = auth_state(parts)?;
+        let token = bearer(parts)?;
 
-        if token != auth_state_arc.bearer_token {
+        if token != auth_state.bearer_token {
             tracing::warn!("Bearer token mismatch");
-            let body = json!({"error": "INVALID_TOKEN", "message": "Invalid authentication token"});
-            return Err((axum::http::StatusCode::UNAUTHORIZED, axum::Json(body)).into_response());
+            return Err(AuthError::InvalidToken.into());
         }
 
         tracing::info!("Bearer token validated successfully");
@@ -70,77 +72,124 @@ impl<S> FromRequestParts<S> for Claims
 where
     S: Send + Sync,
 {
-    type Rejection = Response;
+    type Rejection = AppError;
 
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        let auth_state_arc = parts
-            .extensions
-            .get::<Arc<AuthState>>()
-            .ok_or_else(|| {
-                tracing::error!("AuthState not found in request extensions");
-                let body =
-                    json!({"error": "INTERNAL_ERROR", "message": "Server configuration error"});
-                (
-                    axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                    axum::Json(body),
-                )
-                    .into_response()
-            })?
-            .clone();
-
-        let auth_header = parts
-            .headers
-            .get(header::AUTHORIZATION)
-            .and_then(|h| h.to_str().ok())
-            .ok_or_else(|| {
-                tracing::warn!("No Authorization header found in request");
-                let body =
-                    json!({"error": "MISSING_TOKEN", "message": "Authentication token required"});
-                (axum::http::StatusCode::UNAUTHORIZED, axum::Json(body)).into_response()
-            })?;
-
-        let token = auth_header.strip_prefix("Bearer ").ok_or_else(|| {
-            tracing::warn!("Authorization header does not start with 'Bearer '");
-            let body = json!({"error": "INVALID_TOKEN", "message": "Invalid authentication token"});
-            (axum::http::StatusCode::UNAUTHORIZED, axum::Json(body)).into_response()
-        })?;
+        let auth_state = auth_state(parts)?;
+        let token = bearer(parts)?;
 
         tracing::debug!("Validating JWT token for protected endpoint");
+        AuthService::verify_token(token, &auth_state.secret)
+    }
+}
+
+/// Who is making the request: a user with a JWT, or a service account with
+/// an API key, acting on `tenant_id` with `role`. A service account belongs
+/// to that one tenant; a user may be a member of others too.
+#[derive(Debug, Clone)]
+pub struct Principal {
+    pub tenant_id: Uuid,
+    pub role: UserRole,
+    pub kind: PrincipalKind,
+}
+
+#[derive(Debug, Clone)]
+pub enum PrincipalKind {
+    User {
+        user_id: Uuid,
+        email: String,
+        /// See [`Claims::tenant_bound`].
+        tenant_bound: bool,
+    },
+    ServiceAccount {
+        service_account_id: Uuid,
+        api_key_id: Uuid,
+        scopes: Vec<ApiScope>,
+    },
+}
+
+impl Principal {
+    /// The user or service account acting.
+    pub fn id(&self) -> Uuid {
+        match &self.kind {
+            PrincipalKind::User { user_id, .. } => *user_id,
+            PrincipalKind::ServiceAccount {
+                service_account_id, ..
+            } => *service_account_id,
+        }
+    }
+
+    /// Users are limited by their role only; API keys also by their scopes.
+    pub fn has_scope(&self, scope: ApiScope) -> bool {
+        match &self.kind {
+            PrincipalKind::User { .. } => true,
+            PrincipalKind::ServiceAccount { scopes, .. } => scopes.contains(&scope),
+        }
+    }
+
+    pub fn require_scope(&self, scope: ApiScope) -> Result<(), AppError> {
+        if !self.has_scope(scope) {
+            return Err(AppError::InsufficientScope(scope));
+        }
+        Ok(())
+    }
+
+    /// Fails unless the principal holds `role` and every one of `scopes`, so
+    /// that nothing it creates can do more than it can.
+    pub fn require_grant(&self, role: UserRole, scopes: &[ApiScope]) -> Result<(), AppError> {
+        if !self.role.includes(role) {
+            return Err(AppError::Forbidden(format!(
+                "cannot grant the {:?} role",
+                role
+            )));
+        }
+        scopes
+            .iter()
+            .try_for_each(|scope| self.require_scope(*scope))
+    }
+}
+
+impl From<Claims> for Principal {
+    fn from(claims: Claims) -> Self {
+        Principal {
+            tenant_id: claims.tenant_id,
+            role: claims.role,
+            kind: PrincipalKind::User {
+                user_id: claims.user_id,
+                email: claims.email,
+                tenant_bound: claims.tenant_bound,
+            },
+        }
+    }
+}
+
+/// An API key from `X-API-Key`, or from `Authorization: Bearer` when it has
+/// the key prefix (JWTs never do).
+fn api_key(parts: &Parts) -> Option<&str> {
+    if let Some(key) = parts.headers.get(API_KEY_HEADER) {
+        return key.to_str().ok();
+    }
+    parts
+        .headers
+        .get(header::AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .filter(|token| token.starts_with(API_KEY_PREFIX))
+}
 
-        let mut validation = Validation::default();
-        validation.validate_exp = true;
-
-        let claims_result = jsonwebtoken::decode::<crate::services::auth_service::Claims>(
-            token,
-            &DecodingKey::from_secret(auth_state_arc.secret.as_ref()),
-            &validation,
-        );
-
-        match claims_result {
-            Ok(token_data) => {
-                tracing::debug!(
-                    "JWT token verified successfully for user_id: {}, tenant_id: {}",
-                    token_data.claims.user_id,
-                    token_data.claims.tenant_id
-                );
-                Ok(token_data.claims)
-            }
-            Err(e) => {
-                tracing::error!("JWT token verification failed: {:?}", e);
-
-                let (error_code, message) = match e.kind() {
-                    jsonwebtoken::errors::ErrorKind::ExpiredSignature => {
-                        ("TOKEN_EXPIRED", "Token has expired")
-                    }
-                    _ => ("INVALID_TOKEN", "Invalid authentication token"),
-                };
-
-                let body = json!({
-                    "error": error_code,
-                    "message": message
-                });
-                Err((axum::http::StatusCode::UNAUTHORIZED, axum::Json(body)).into_response())
-            }
+impl<S> FromRequestParts<S> for Principal
+where
+    S: Send + Sync,
+    Arc<dyn ApiKeyService>: FromRef<S>,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(key) = api_key(parts) {
+            let api_keys = Arc::<dyn ApiKeyService>::from_ref(state);
+            return api_keys.authenticate(key).await;
         }
+
+        Ok(Claims::from_request_parts(parts, state).await?.into())
     }
 }
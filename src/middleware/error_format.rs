@@ -0,0 +1,90 @@
+use axum::{
+    extract::Request,
+    http::{HeaderMap, header},
+    middleware::Next,
+    response::Response,
+};
+
+pub const PROBLEM_JSON: &str = "application/problem+json";
+pub const SCIM_JSON: &str = "application/scim+json";
+pub const SCIM_ERROR_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:Error";
+
+/// Routes under this prefix always answer with SCIM errors, whatever the
+/// client accepts: directory clients parse nothing else.
+pub const SCIM_PATH_PREFIX: &str = "/scim/v2";
+
+tokio::task_local! {
+    static CURRENT_ERROR_FORMAT: ErrorFormat;
+}
+
+/// Shape of error bodies for the current request, chosen from `Accept`.
+///
+/// Clients get the legacy `{error, message}` body unless they explicitly ask
+/// for `application/problem+json` (RFC 9457) at least as strongly as
+/// `application/json`. SCIM endpoints always get SCIM errors (RFC 7644).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ErrorFormat {
+    Legacy,
+    Problem {
+        /// Request path, reported as the problem `instance`.
+        instance: String,
+    },
+    Scim,
+}
+
+impl ErrorFormat {
+    pub fn negotiate(headers: &HeaderMap, path: &str) -> Self {
+        if path == SCIM_PATH_PREFIX || path.starts_with(&format!("{}/", SCIM_PATH_PREFIX)) {
+            return ErrorFormat::Scim;
+        }
+
+        let mut problem_q: Option<f32> = None;
+        let mut json_q: Option<f32> = None;
+
+        for value in headers.get_all(header::ACCEPT) {
+            let Ok(value) = value.to_str() else {
+                continue;
+            };
+            for range in value.split(',') {
+                let mut parts = range.split(';').map(str::trim);
+                let media_type = parts.next().unwrap_or_default().to_ascii_lowercase();
+                let q = parts
+                    .filter_map(|p| p.strip_prefix("q="))
+                    .find_map(|q| q.parse::<f32>().ok())
+                    .unwrap_or(1.0);
+
+                let slot = match media_type.as_str() {
+                    PROBLEM_JSON => &mut problem_q,
+                    "application/json" => &mut json_q,
+                    _ => continue,
+                };
+                *slot = Some(slot.map_or(q, |existing| existing.max(q)));
+            }
+        }
+
+        match problem_q {
+            Some(q) if q > 0.0 && q >= json_q.unwrap_or(0.0) => ErrorFormat::Problem {
+                instance: path.to_string(),
+            },
+            _ => ErrorFormat::Legacy,
+        }
+    }
+
+    /// Runs `future` with this format available through [`ErrorFormat::current`].
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT_ERROR_FORMAT.scope(self, future).await
+    }
+
+    /// Format negotiated for the request being handled on this task, or
+    /// [`ErrorFormat::Legacy`] outside of a request.
+    pub fn current() -> Self {
+        CURRENT_ERROR_FORMAT
+            .try_with(|format| format.clone())
+            .unwrap_or(ErrorFormat::Legacy)
+    }
+}
+
+pub async fn error_format_middleware(request: Request, next: Next) -> Response {
+    let format = ErrorFormat::negotiate(request.headers(), request.uri().path());
+    format.scope(next.run(request)).await
+}
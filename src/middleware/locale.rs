@@ -0,0 +1,54 @@
+use crate::enums::Locale;
+use crate::i18n;
+use crate::middleware::auth::AuthState;
+use crate::services::auth_service::Claims;
+use axum::{
+    extract::Request,
+    http::{HeaderMap, header},
+    middleware::Next,
+    response::Response,
+};
+use jsonwebtoken::{DecodingKey, Validation};
+use std::sync::Arc;
+
+/// Resolves the locale for user-facing messages and makes it available
+/// through [`i18n::current`] for the rest of the request.
+///
+/// Resolution order: a supported language in `Accept-Language`, then the
+/// locale carried by a valid JWT (the user's profile locale, or their
+/// tenant's default when they have none), then English.
+pub async fn locale_middleware(request: Request, next: Next) -> Response {
+    let locale = resolve(
+        request.headers(),
+        request.extensions().get::<Arc<AuthState>>(),
+    );
+    i18n::scope(locale, next.run(request)).await
+}
+
+pub fn resolve(headers: &HeaderMap, auth_state: Option<&Arc<AuthState>>) -> Locale {
+    headers
+        .get_all(header::ACCEPT_LANGUAGE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .find_map(i18n::negotiate)
+        .or_else(|| auth_state.and_then(|state| token_locale(headers, &state.secret)))
+        .unwrap_or_default()
+}
+
+/// The `locale` claim of a valid bearer JWT. Failures are silent here; the
+/// `Claims` extractor reports them on routes that require authentication.
+fn token_locale(headers: &HeaderMap, secret: &str) -> Option<Locale> {
+    let token = headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")?;
+    jsonwebtoken::decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(secret.as_ref()),
+        &Validation::default(),
+    )
+    .ok()?
+    .claims
+    .locale
+}
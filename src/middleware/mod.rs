@@ -1,7 +1,13 @@
 pub mod auth;
+pub mod error_format;
+pub mod locale;
+pub mod request_id;
 mod tracing_middleware;
 pub mod validation;
 
 pub use auth::*;
+pub use error_format::{ErrorFormat, error_format_middleware};
+pub use locale::locale_middleware;
+pub use request_id::RequestId;
 pub use tracing_middleware::tracing_middleware;
-pub use validation::validate_request;
+pub use validation::{ValidatedJson, ValidatedPath, ValidatedQuery, validate_request};
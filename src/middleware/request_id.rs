@@ -0,0 +1,70 @@
+use axum::{extract::FromRequestParts, http::request::Parts};
+use std::convert::Infallible;
+use std::fmt;
+use uuid::Uuid;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static CURRENT_REQUEST_ID: RequestId;
+}
+
+/// Correlation id of the current request, either accepted from the inbound
+/// `X-Request-Id` header or generated by `tracing_middleware`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    pub fn generate() -> Self {
+        RequestId(Uuid::now_v7().to_string())
+    }
+
+    /// Accepts an inbound id only if it is 1-128 visible ASCII characters
+    /// from `[A-Za-z0-9._:-]`, so it is safe to echo in headers and logs.
+    pub fn parse(value: &str) -> Option<Self> {
+        let valid = !value.is_empty()
+            && value.len() <= MAX_REQUEST_ID_LEN
+            && value
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b':'));
+        valid.then(|| RequestId(value.to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Runs `future` with this id available through [`RequestId::current`].
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT_REQUEST_ID.scope(self, future).await
+    }
+
+    /// Returns the id of the request being handled on this task, if any.
+    pub fn current() -> Option<Self> {
+        CURRENT_REQUEST_ID.try_with(|id| id.clone()).ok()
+    }
+}
+
+impl fmt::Display for RequestId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl<S> FromRequestParts<S> for RequestId
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts
+            .extensions
+            .get::<RequestId>()
+            .cloned()
+            .or_else(RequestId::current)
+            .unwrap_or_else(RequestId::generate))
+    }
+}
@@ -1,10 +1,22 @@
-use axum::{extract::Request, middleware::Next, response::Response};
+use crate::middleware::request_id::{REQUEST_ID_HEADER, RequestId};
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
 use std::time::Instant;
 use tracing::{Instrument, info_span};
-use uuid::Uuid;
 
-pub async fn tracing_middleware(request: Request, next: Next) -> Response {
-    let request_id = Uuid::now_v7();
+pub async fn tracing_middleware(mut request: Request, next: Next) -> Response {
+    let request_id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(RequestId::parse)
+        .unwrap_or_else(RequestId::generate);
+    request.extensions_mut().insert(request_id.clone());
+
     let method = request.method().clone();
     let uri = request.uri().clone();
     let start = Instant::now();
@@ -16,7 +28,10 @@ pub async fn tracing_middleware(request: Request, next: Next) -> Response {
         uri = %uri
     );
 
-    let response = next.run(request).instrument(span).await;
+    let mut response = request_id
+        .clone()
+        .scope(next.run(request).instrument(span))
+        .await;
 
     let duration = start.elapsed();
     let status = response.status();
@@ -30,5 +45,11 @@ pub async fn tracing_middleware(request: Request, next: Next) -> Response {
         "Request completed"
     );
 
+    if let Ok(value) = HeaderValue::from_str(request_id.as_str()) {
+        response
+            .headers_mut()
+            .insert(HeaderName::from_static(REQUEST_ID_HEADER), value);
+    }
+
     response
 }
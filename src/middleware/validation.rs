@@ -1,27 +1,126 @@
-use crate::utils::error::AppError;
-use validator::Validate;
+use crate::i18n;
+use crate::utils::error::{AppError, FieldError};
+use axum::{
+    extract::{FromRequest, FromRequestParts, Path, Query, Request},
+    http::request::Parts,
+    response::Json,
+};
+use serde::de::DeserializeOwned;
+use std::collections::BTreeMap;
+use validator::{Validate, ValidationError, ValidationErrors, ValidationErrorsKind};
 
 pub fn validate_request<T: Validate>(payload: T) -> Result<T, AppError> {
     payload.validate().map_err(|errors| {
-        let error_messages: Vec<String> = errors
-            .field_errors()
-            .iter()
-            .flat_map(|(field, errors)| {
-                errors.iter().map(move |e| {
-                    format!(
-                        "{}: {}",
-                        field,
-                        e.message
-                            .as_ref()
-                            .map(|m| m.to_string())
-                            .unwrap_or_else(|| format!("{:?}", e.code))
-                    )
-                })
-            })
-            .collect();
-
-        AppError::Validation(error_messages.join(", "))
+        let mut field_errors = Vec::new();
+        collect_field_errors("", &errors, &mut field_errors);
+        field_errors.sort_by(|a, b| a.field.cmp(&b.field));
+        AppError::Validation(field_errors)
     })?;
 
     Ok(payload)
 }
+
+/// Flattens nested validator errors into one entry per failed rule, with
+/// `field` as a dotted path (`address.lines[0]`). The rejected `value` is
+/// dropped from `params` so passwords are never echoed back.
+fn collect_field_errors(prefix: &str, errors: &ValidationErrors, out: &mut Vec<FieldError>) {
+    for (field, kind) in errors.errors() {
+        let path = if prefix.is_empty() {
+            field.to_string()
+        } else {
+            format!("{}.{}", prefix, field)
+        };
+        match kind {
+            ValidationErrorsKind::Field(errors) => {
+                out.extend(errors.iter().map(|e| {
+                    let params: BTreeMap<String, serde_json::Value> = e
+                        .params
+                        .iter()
+                        .filter(|(key, _)| *key != "value")
+                        .map(|(key, value)| (key.to_string(), value.clone()))
+                        .collect();
+                    FieldError {
+                        field: path.clone(),
+                        code: e.code.to_string(),
+                        message: localize(e, &params),
+                        params,
+                    }
+                }));
+            }
+            ValidationErrorsKind::Struct(nested) => collect_field_errors(&path, nested, out),
+            ValidationErrorsKind::List(items) => {
+                for (index, nested) in items {
+                    collect_field_errors(&format!("{}[{}]", path, index), nested, out);
+                }
+            }
+        }
+    }
+}
+
+/// `message` in `#[validate]` attributes is a key into the `validation`
+/// section of the message catalogs; free-form messages are passed through.
+fn localize(error: &ValidationError, params: &BTreeMap<String, serde_json::Value>) -> String {
+    let Some(key) = error.message.as_deref() else {
+        return error.code.to_string();
+    };
+    let params: Vec<(&str, String)> = params
+        .iter()
+        .map(|(name, value)| match value {
+            serde_json::Value::String(s) => (name.as_str(), s.clone()),
+            other => (name.as_str(), other.to_string()),
+        })
+        .collect();
+    i18n::translate(i18n::current(), &format!("validation.{}", key), &params)
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// JSON body extractor that rejects malformed bodies with
+/// `INVALID_REQUEST_BODY` and failed validation with `VALIDATION_ERROR`.
+pub struct ValidatedJson<T>(pub T);
+
+impl<S, T> FromRequest<S> for ValidatedJson<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned + Validate,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(payload) = Json::<T>::from_request(req, state).await?;
+        validate_request(payload).map(ValidatedJson)
+    }
+}
+
+/// `Path` extractor that rejects malformed segments with
+/// `INVALID_PATH_PARAMETER` instead of axum's plain-text response.
+pub struct ValidatedPath<T>(pub T);
+
+impl<S, T> FromRequestParts<S> for ValidatedPath<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned + Send,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Path(value) = Path::<T>::from_request_parts(parts, state).await?;
+        Ok(ValidatedPath(value))
+    }
+}
+
+/// `Query` extractor that rejects malformed query strings with
+/// `INVALID_QUERY_PARAMETER` instead of axum's plain-text response.
+pub struct ValidatedQuery<T>(pub T);
+
+impl<S, T> FromRequestParts<S> for ValidatedQuery<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(value) = Query::<T>::from_request_parts(parts, state).await?;
+        Ok(ValidatedQuery(value))
+    }
+}
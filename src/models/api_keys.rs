@@ -0,0 +1,47 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// An API key of a service account. Only the SHA-256 of the secret is
+/// stored; `prefix` is the public part used to find the key.
+#[derive(
+    Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, utoipa::ToSchema,
+)]
+#[sea_orm(table_name = "api_keys")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub service_account_id: Uuid,
+    pub name: String,
+    pub prefix: String,
+    #[serde(skip_serializing)]
+    pub key_hash: String,
+    /// Space-separated scopes, e.g. `users:read tenants:read`.
+    pub scopes: String,
+    #[schema(value_type = Option<String>)]
+    pub expires_at: Option<DateTimeWithTimeZone>,
+    #[schema(value_type = Option<String>)]
+    pub last_used_at: Option<DateTimeWithTimeZone>,
+    #[schema(value_type = Option<String>)]
+    pub revoked_at: Option<DateTimeWithTimeZone>,
+    #[schema(value_type = String)]
+    pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::service_accounts::Entity",
+        from = "Column::ServiceAccountId",
+        to = "super::service_accounts::Column::Id"
+    )]
+    ServiceAccount,
+}
+
+impl Related<super::service_accounts::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ServiceAccount.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,33 @@
+use crate::enums::JobStatus;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A unit of background work: `kind` selects the handler and `payload` is
+/// its input.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "jobs")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub kind: String,
+    #[sea_orm(column_type = "JsonBinary")]
+    pub payload: Json,
+    pub status: JobStatus,
+    /// Attempts started so far, the current one included.
+    pub attempts: i32,
+    pub max_attempts: i32,
+    /// When the job becomes due, first or after a failed attempt.
+    pub run_at: DateTimeWithTimeZone,
+    /// Worker holding the job while it runs.
+    pub locked_by: Option<String>,
+    pub locked_at: Option<DateTimeWithTimeZone>,
+    pub last_error: Option<String>,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+    pub completed_at: Option<DateTimeWithTimeZone>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
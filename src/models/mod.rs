@@ -1,3 +1,17 @@
+pub mod api_keys;
 pub mod common;
+pub mod jobs;
+pub mod memberships;
+pub mod oidc_login_states;
+pub mod oidc_providers;
+pub mod outbox_events;
+pub mod password_history;
+pub mod service_accounts;
+pub mod task_runs;
+pub mod tenant_exports;
+pub mod tenant_settings;
 pub mod tenants;
+pub mod user_identities;
 pub mod users;
+pub mod webhook_deliveries;
+pub mod webhook_endpoints;
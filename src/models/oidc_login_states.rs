@@ -0,0 +1,19 @@
+use sea_orm::entity::prelude::*;
+
+/// A pending authorization request, keyed by its `state` parameter. Consumed
+/// by the callback, so each state can be redeemed once.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "oidc_login_states")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub state: String,
+    pub provider_id: Uuid,
+    pub nonce: String,
+    pub code_verifier: String,
+    pub expires_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,54 @@
+use crate::enums::UserRole;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// An OpenID Connect identity provider users of a tenant can sign in with.
+#[derive(
+    Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, utoipa::ToSchema,
+)]
+#[sea_orm(table_name = "oidc_providers")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub name: String,
+    /// Issuer URL; discovery is read from `<issuer>/.well-known/openid-configuration`.
+    pub issuer: String,
+    pub client_id: String,
+    #[serde(skip_serializing)]
+    pub client_secret: String,
+    /// Space-separated scopes requested at the provider.
+    pub scopes: String,
+    /// Where the provider sends the browser back to with `code` and `state`.
+    pub redirect_uri: String,
+    /// ID token claim holding the user's email.
+    pub email_claim: String,
+    /// ID token claim (string or array) checked for `admin_role_value` when
+    /// a user is provisioned.
+    pub role_claim: Option<String>,
+    pub admin_role_value: Option<String>,
+    /// Role of provisioned users when the role claim does not grant admin.
+    pub default_role: UserRole,
+    #[schema(value_type = String)]
+    pub created_at: DateTimeWithTimeZone,
+    #[schema(value_type = String)]
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::tenants::Entity",
+        from = "Column::TenantId",
+        to = "super::tenants::Column::Id"
+    )]
+    Tenant,
+}
+
+impl Related<super::tenants::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Tenant.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,35 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A domain event waiting in the outbox, or relayed already. `payload` is
+/// the serialized [`DomainEvent`](crate::events::DomainEvent).
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "outbox_events")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    /// Assigned by the database on insert; events of an aggregate are
+    /// relayed in this order.
+    pub position: i64,
+    pub aggregate_type: String,
+    pub aggregate_id: Uuid,
+    pub tenant_id: Uuid,
+    pub event_type: String,
+    #[sea_orm(column_type = "JsonBinary")]
+    pub payload: Json,
+    /// Relay attempts so far.
+    pub attempts: i32,
+    /// When a failed event is tried again.
+    pub next_attempt_at: Option<DateTimeWithTimeZone>,
+    pub last_error: Option<String>,
+    pub created_at: DateTimeWithTimeZone,
+    /// Set once every subscriber has handled the event.
+    pub dispatched_at: Option<DateTimeWithTimeZone>,
+    /// Set when the relay gives up on the event after its last attempt.
+    pub dead_at: Option<DateTimeWithTimeZone>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
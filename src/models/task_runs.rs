@@ -0,0 +1,33 @@
+use crate::enums::TaskRunStatus;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A run of a scheduled task, for the tick of its schedule at
+/// `scheduled_for`.
+#[derive(
+    Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, utoipa::ToSchema,
+)]
+#[sea_orm(table_name = "task_runs")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub task: String,
+    #[schema(value_type = String)]
+    pub scheduled_for: DateTimeWithTimeZone,
+    pub status: TaskRunStatus,
+    /// Scheduler instance that ran the task.
+    pub runner: String,
+    /// Rows the task changed, once succeeded.
+    pub affected: Option<i64>,
+    /// Why the run failed.
+    pub error: Option<String>,
+    #[schema(value_type = String)]
+    pub started_at: DateTimeWithTimeZone,
+    #[schema(value_type = Option<String>)]
+    pub finished_at: Option<DateTimeWithTimeZone>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,45 @@
+use crate::enums::{ExportFormat, ExportStatus};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A data export of a tenant. The archive is written to `EXPORT_DIR` as
+/// `<id>.<format>` once the export has completed.
+#[derive(
+    Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, utoipa::ToSchema,
+)]
+#[sea_orm(table_name = "tenant_exports")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    /// The user or service account that asked for the export.
+    pub requested_by: Uuid,
+    pub format: ExportFormat,
+    pub status: ExportStatus,
+    /// Size of the archive, once completed.
+    pub size_bytes: Option<i64>,
+    /// Why the export failed.
+    pub error: Option<String>,
+    #[schema(value_type = String)]
+    pub created_at: DateTimeWithTimeZone,
+    #[schema(value_type = Option<String>)]
+    pub completed_at: Option<DateTimeWithTimeZone>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::tenants::Entity",
+        from = "Column::TenantId",
+        to = "super::tenants::Column::Id"
+    )]
+    Tenant,
+}
+
+impl Related<super::tenants::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Tenant.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,78 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Stored settings of a tenant. `version` starts at 1 and is bumped on every
+/// update so concurrent edits can be detected.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "tenant_settings")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub tenant_id: Uuid,
+    pub version: i32,
+    /// A serialized [`TenantSettings`].
+    #[sea_orm(column_type = "JsonBinary")]
+    pub settings: Json,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::tenants::Entity",
+        from = "Column::TenantId",
+        to = "super::tenants::Column::Id"
+    )]
+    Tenant,
+}
+
+impl Related<super::tenants::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Tenant.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// Per-tenant behavior that overrides the global configuration. Omitted
+/// fields take their defaults; unknown fields are rejected.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Validate, utoipa::ToSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct TenantSettings {
+    /// Whether anyone may register in the tenant through `/api/auth/register`.
+    pub registration_open: bool,
+    /// Lifetime of tokens issued for the tenant; `JWT_EXPIRATION_MINUTES`
+    /// when unset.
+    #[validate(range(min = 1, max = 43200, message = "token_lifetime_range"))]
+    pub jwt_expiration_minutes: Option<i64>,
+    /// Password policy overrides; each falls back to the `PASSWORD_*`
+    /// configuration when unset.
+    #[validate(range(min = 8, max = 100, message = "length_range"))]
+    pub password_min_length: Option<u64>,
+    #[validate(range(min = 1, max = 4, message = "range"))]
+    pub password_min_character_classes: Option<u8>,
+    #[validate(range(min = 0, max = 4, message = "range"))]
+    pub password_min_strength: Option<u8>,
+    pub password_reject_personal_info: Option<bool>,
+    #[validate(range(min = 0, max = 24, message = "range"))]
+    pub password_history: Option<u32>,
+    /// Members other than admins are deactivated once they have not logged
+    /// in to the tenant for this many days; never when unset.
+    #[validate(range(min = 1, max = 3650, message = "range"))]
+    pub idle_deactivation_days: Option<u32>,
+}
+
+impl Default for TenantSettings {
+    fn default() -> Self {
+        Self {
+            registration_open: true,
+            jwt_expiration_minutes: None,
+            password_min_length: None,
+            password_min_character_classes: None,
+            password_min_strength: None,
+            password_reject_personal_info: None,
+            password_history: None,
+            idle_deactivation_days: None,
+        }
+    }
+}
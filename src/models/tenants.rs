@@ -1,4 +1,4 @@
-use crate::enums::TenantStatus;
+use crate::enums::{Locale, TenantStatus};
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -11,6 +11,7 @@ pub struct Model {
     pub id: Uuid,
     pub name: String,
     pub status: TenantStatus,
+    pub default_locale: Option<Locale>,
     #[schema(value_type = String)]
     pub created_at: DateTimeWithTimeZone,
     #[schema(value_type = String)]
@@ -1,7 +1,10 @@
-use crate::enums::{UserRole, UserStatus};
+use crate::enums::{Locale, UserRole, UserStatus};
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
+/// A user identity, as seen from one of its memberships: `tenant_id`, `role`
+/// and `status` are those of the tenant it was read for, or of the tenant it
+/// was created in (see [`Model::as_member`]).
 #[derive(
     Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, utoipa::ToSchema,
 )]
@@ -11,13 +14,22 @@ pub struct Model {
     pub id: Uuid,
     pub tenant_id: Uuid,
     pub email: String,
+    #[serde(skip_serializing)]
     pub password_hash: String,
     pub role: UserRole,
     pub status: UserStatus,
+    pub locale: Option<Locale>,
     #[schema(value_type = String)]
     pub created_at: DateTimeWithTimeZone,
     #[schema(value_type = String)]
     pub updated_at: DateTimeWithTimeZone,
+    /// Set when the user is deleted. Deleted users are hidden from every
+    /// lookup and can be restored until they are erased.
+    #[schema(value_type = Option<String>)]
+    pub deleted_at: Option<DateTimeWithTimeZone>,
+    /// Set once the personal data of a deleted user has been anonymized.
+    #[schema(value_type = Option<String>)]
+    pub erased_at: Option<DateTimeWithTimeZone>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -36,4 +48,22 @@ impl Related<super::tenants::Entity> for Entity {
     }
 }
 
+impl Related<super::memberships::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::memberships::Relation::User.def().rev()
+    }
+}
+
+impl Model {
+    /// The user as a member of `membership`'s tenant.
+    pub fn as_member(self, membership: &super::memberships::Model) -> Self {
+        Self {
+            tenant_id: membership.tenant_id,
+            role: membership.role,
+            status: membership.status,
+            ..self
+        }
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
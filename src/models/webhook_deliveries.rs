@@ -0,0 +1,62 @@
+use crate::enums::WebhookDeliveryStatus;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// An event sent to a webhook endpoint, with the outcome of its latest
+/// attempt.
+#[derive(
+    Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, utoipa::ToSchema,
+)]
+#[sea_orm(table_name = "webhook_deliveries")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub endpoint_id: Uuid,
+    /// Shared by redeliveries of the same event, so receivers can
+    /// deduplicate.
+    pub event_id: Uuid,
+    pub event: String,
+    /// The delivery this one sends again, for redeliveries. Every other
+    /// delivery is the only one of its event to its endpoint.
+    pub redelivery_of: Option<Uuid>,
+    #[sea_orm(column_type = "JsonBinary")]
+    #[schema(value_type = Object)]
+    pub payload: Json,
+    pub status: WebhookDeliveryStatus,
+    /// Attempts made so far.
+    pub attempts: i32,
+    /// When the next attempt is due, while pending.
+    #[schema(value_type = Option<String>)]
+    pub next_attempt_at: Option<DateTimeWithTimeZone>,
+    /// HTTP status the endpoint answered the latest attempt with.
+    pub response_status: Option<i32>,
+    /// Start of the body of that answer.
+    pub response_body: Option<String>,
+    /// Why the latest attempt failed.
+    pub error: Option<String>,
+    #[schema(value_type = String)]
+    pub created_at: DateTimeWithTimeZone,
+    #[schema(value_type = Option<String>)]
+    pub last_attempt_at: Option<DateTimeWithTimeZone>,
+    #[schema(value_type = Option<String>)]
+    pub delivered_at: Option<DateTimeWithTimeZone>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::webhook_endpoints::Entity",
+        from = "Column::EndpointId",
+        to = "super::webhook_endpoints::Column::Id"
+    )]
+    Endpoint,
+}
+
+impl Related<super::webhook_endpoints::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Endpoint.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
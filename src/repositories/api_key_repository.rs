@@ -0,0 +1,179 @@
+use crate::models::{api_keys, service_accounts};
+use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, QueryFilter,
+    QueryOrder, TransactionTrait, sea_query::Expr,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Persistence operations on service accounts and their API keys.
+#[async_trait]
+pub trait ApiKeyRepository: Send + Sync {
+    async fn insert_service_account(
+        &self,
+        account: service_accounts::ActiveModel,
+    ) -> Result<service_accounts::Model, DbErr>;
+
+    async fn find_service_account(
+        &self,
+        tenant_id: Uuid,
+        service_account_id: Uuid,
+    ) -> Result<Option<service_accounts::Model>, DbErr>;
+
+    /// Service accounts of a tenant, oldest first.
+    async fn list_service_accounts(
+        &self,
+        tenant_id: Uuid,
+    ) -> Result<Vec<service_accounts::Model>, DbErr>;
+
+    async fn find_key(
+        &self,
+        tenant_id: Uuid,
+        key_id: Uuid,
+    ) -> Result<Option<api_keys::Model>, DbErr>;
+
+    /// The key with this public prefix, together with its service account.
+    async fn find_key_by_prefix(
+        &self,
+        prefix: &str,
+    ) -> Result<Option<(api_keys::Model, service_accounts::Model)>, DbErr>;
+
+    /// Keys of a tenant, newest first, including revoked and expired ones.
+    async fn list_keys(&self, tenant_id: Uuid) -> Result<Vec<api_keys::Model>, DbErr>;
+
+    async fn insert_key(&self, key: api_keys::ActiveModel) -> Result<api_keys::Model, DbErr>;
+
+    async fn update_key(&self, key: api_keys::ActiveModel) -> Result<api_keys::Model, DbErr>;
+
+    /// Saves `revoked` and inserts its replacement `key` in one transaction.
+    /// Returns the replacement.
+    async fn rotate_key(
+        &self,
+        revoked: api_keys::ActiveModel,
+        key: api_keys::ActiveModel,
+    ) -> Result<api_keys::Model, DbErr>;
+
+    async fn touch_key(&self, key_id: Uuid, at: DateTime<FixedOffset>) -> Result<(), DbErr>;
+
+    /// Deletes keys revoked or expired before `before`. Returns how many were
+    /// deleted.
+    async fn delete_keys_ended_before(&self, before: DateTime<FixedOffset>) -> Result<u64, DbErr>;
+}
+
+pub struct SeaOrmApiKeyRepository {
+    db: Arc<DatabaseConnection>,
+}
+
+impl SeaOrmApiKeyRepository {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for SeaOrmApiKeyRepository {
+    async fn insert_service_account(
+        &self,
+        account: service_accounts::ActiveModel,
+    ) -> Result<service_accounts::Model, DbErr> {
+        account.insert(self.db.as_ref()).await
+    }
+
+    async fn find_service_account(
+        &self,
+        tenant_id: Uuid,
+        service_account_id: Uuid,
+    ) -> Result<Option<service_accounts::Model>, DbErr> {
+        service_accounts::Entity::find()
+            .filter(service_accounts::Column::Id.eq(service_account_id))
+            .filter(service_accounts::Column::TenantId.eq(tenant_id))
+            .one(self.db.as_ref())
+            .await
+    }
+
+    async fn list_service_accounts(
+        &self,
+        tenant_id: Uuid,
+    ) -> Result<Vec<service_accounts::Model>, DbErr> {
+        service_accounts::Entity::find()
+            .filter(service_accounts::Column::TenantId.eq(tenant_id))
+            .order_by_asc(service_accounts::Column::CreatedAt)
+            .all(self.db.as_ref())
+            .await
+    }
+
+    async fn find_key(
+        &self,
+        tenant_id: Uuid,
+        key_id: Uuid,
+    ) -> Result<Option<api_keys::Model>, DbErr> {
+        api_keys::Entity::find()
+            .filter(api_keys::Column::Id.eq(key_id))
+            .filter(api_keys::Column::TenantId.eq(tenant_id))
+            .one(self.db.as_ref())
+            .await
+    }
+
+    async fn find_key_by_prefix(
+        &self,
+        prefix: &str,
+    ) -> Result<Option<(api_keys::Model, service_accounts::Model)>, DbErr> {
+        let found = api_keys::Entity::find()
+            .filter(api_keys::Column::Prefix.eq(prefix))
+            .find_also_related(service_accounts::Entity)
+            .one(self.db.as_ref())
+            .await?;
+        Ok(found.and_then(|(key, account)| account.map(|account| (key, account))))
+    }
+
+    async fn list_keys(&self, tenant_id: Uuid) -> Result<Vec<api_keys::Model>, DbErr> {
+        api_keys::Entity::find()
+            .filter(api_keys::Column::TenantId.eq(tenant_id))
+            .order_by_desc(api_keys::Column::CreatedAt)
+            .all(self.db.as_ref())
+            .await
+    }
+
+    async fn insert_key(&self, key: api_keys::ActiveModel) -> Result<api_keys::Model, DbErr> {
+        key.insert(self.db.as_ref()).await
+    }
+
+    async fn update_key(&self, key: api_keys::ActiveModel) -> Result<api_keys::Model, DbErr> {
+        key.update(self.db.as_ref()).await
+    }
+
+    async fn rotate_key(
+        &self,
+        revoked: api_keys::ActiveModel,
+        key: api_keys::ActiveModel,
+    ) -> Result<api_keys::Model, DbErr> {
+        let txn = self.db.begin().await?;
+        revoked.update(&txn).await?;
+        let key = key.insert(&txn).await?;
+        txn.commit().await?;
+        Ok(key)
+    }
+
+    async fn touch_key(&self, key_id: Uuid, at: DateTime<FixedOffset>) -> Result<(), DbErr> {
+        api_keys::Entity::update_many()
+            .col_expr(api_keys::Column::LastUsedAt, Expr::value(at))
+            .filter(api_keys::Column::Id.eq(key_id))
+            .exec(self.db.as_ref())
+            .await?;
+        Ok(())
+    }
+
+    async fn delete_keys_ended_before(&self, before: DateTime<FixedOffset>) -> Result<u64, DbErr> {
+        let deleted = api_keys::Entity::delete_many()
+            .filter(
+                Condition::any()
+                    .add(api_keys::Column::RevokedAt.lt(before))
+                    .add(api_keys::Column::ExpiresAt.lt(before)),
+            )
+            .exec(self.db.as_ref())
+            .await?;
+        Ok(deleted.rows_affected)
+    }
+}
@@ -0,0 +1,161 @@
+use crate::enums::JobStatus;
+use crate::models::jobs;
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::sea_query::{Expr, ExprTrait, LockBehavior, LockType};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect, TransactionTrait, prelude::DateTimeWithTimeZone,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Persistence operations on background jobs.
+#[async_trait]
+pub trait JobRepository: Send + Sync {
+    async fn find_by_id(&self, job_id: Uuid) -> Result<Option<jobs::Model>, DbErr>;
+
+    /// Jobs in `status`, oldest first.
+    async fn list_by_status(
+        &self,
+        status: JobStatus,
+        limit: u64,
+    ) -> Result<Vec<jobs::Model>, DbErr>;
+
+    async fn insert(&self, job: jobs::ActiveModel) -> Result<jobs::Model, DbErr>;
+
+    async fn update(&self, job: jobs::ActiveModel) -> Result<jobs::Model, DbErr>;
+
+    /// Saves the outcome of a run by `worker`, unless the job is no longer
+    /// locked by it, e.g. because it ran past the stale timeout and another
+    /// worker claimed it. Returns `None` then, leaving the row as it is.
+    async fn finish(
+        &self,
+        job: jobs::ActiveModel,
+        worker: &str,
+    ) -> Result<Option<jobs::Model>, DbErr>;
+
+    /// Marks up to `limit` due jobs as running under `worker` and returns
+    /// them. Due jobs are pending ones whose `run_at` has passed, and running
+    /// ones locked before `stale_before`, whose worker is presumed dead. Rows
+    /// claimed concurrently by another worker are skipped, not waited for.
+    async fn claim(
+        &self,
+        worker: &str,
+        limit: u64,
+        stale_before: DateTimeWithTimeZone,
+    ) -> Result<Vec<jobs::Model>, DbErr>;
+}
+
+pub struct SeaOrmJobRepository {
+    db: Arc<DatabaseConnection>,
+}
+
+impl SeaOrmJobRepository {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl JobRepository for SeaOrmJobRepository {
+    async fn find_by_id(&self, job_id: Uuid) -> Result<Option<jobs::Model>, DbErr> {
+        jobs::Entity::find_by_id(job_id).one(self.db.as_ref()).await
+    }
+
+    async fn list_by_status(
+        &self,
+        status: JobStatus,
+        limit: u64,
+    ) -> Result<Vec<jobs::Model>, DbErr> {
+        jobs::Entity::find()
+            .filter(jobs::Column::Status.eq(status))
+            .order_by_asc(jobs::Column::UpdatedAt)
+            .limit(limit)
+            .all(self.db.as_ref())
+            .await
+    }
+
+    async fn insert(&self, job: jobs::ActiveModel) -> Result<jobs::Model, DbErr> {
+        job.insert(self.db.as_ref()).await
+    }
+
+    async fn update(&self, job: jobs::ActiveModel) -> Result<jobs::Model, DbErr> {
+        job.update(self.db.as_ref()).await
+    }
+
+    async fn finish(
+        &self,
+        job: jobs::ActiveModel,
+        worker: &str,
+    ) -> Result<Option<jobs::Model>, DbErr> {
+        match jobs::Entity::update(job)
+            .validate()?
+            .filter(jobs::Column::LockedBy.eq(worker))
+            .exec(self.db.as_ref())
+            .await
+        {
+            Ok(job) => Ok(Some(job)),
+            Err(DbErr::RecordNotUpdated) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn claim(
+        &self,
+        worker: &str,
+        limit: u64,
+        stale_before: DateTimeWithTimeZone,
+    ) -> Result<Vec<jobs::Model>, DbErr> {
+        let now = Utc::now().fixed_offset();
+        let txn = self.db.begin().await?;
+        // SQLite has no row locks and ignores the clause; its writers are
+        // serialized anyway
+        let due: Vec<Uuid> = jobs::Entity::find()
+            .select_only()
+            .column(jobs::Column::Id)
+            .filter(
+                Condition::any()
+                    .add(
+                        Condition::all()
+                            .add(jobs::Column::Status.eq(JobStatus::Pending))
+                            .add(jobs::Column::RunAt.lte(now)),
+                    )
+                    .add(
+                        Condition::all()
+                            .add(jobs::Column::Status.eq(JobStatus::Running))
+                            .add(jobs::Column::LockedAt.lt(stale_before)),
+                    ),
+            )
+            .order_by_asc(jobs::Column::RunAt)
+            .limit(limit)
+            .lock_with_behavior(LockType::Update, LockBehavior::SkipLocked)
+            .into_tuple()
+            .all(&txn)
+            .await?;
+        if due.is_empty() {
+            txn.commit().await?;
+            return Ok(Vec::new());
+        }
+
+        jobs::Entity::update_many()
+            .col_expr(jobs::Column::Status, Expr::value(JobStatus::Running))
+            .col_expr(jobs::Column::LockedBy, Expr::value(worker))
+            .col_expr(jobs::Column::LockedAt, Expr::value(now))
+            .col_expr(
+                jobs::Column::Attempts,
+                Expr::col(jobs::Column::Attempts).add(1),
+            )
+            .col_expr(jobs::Column::UpdatedAt, Expr::value(now))
+            .filter(jobs::Column::Id.is_in(due.clone()))
+            .exec(&txn)
+            .await?;
+        let claimed = jobs::Entity::find()
+            .filter(jobs::Column::Id.is_in(due))
+            .order_by_asc(jobs::Column::RunAt)
+            .all(&txn)
+            .await?;
+        txn.commit().await?;
+        Ok(claimed)
+    }
+}
@@ -0,0 +1,251 @@
+use crate::enums::{UserRole, UserStatus};
+use crate::events::DomainEvent;
+use crate::models::{memberships, users};
+use crate::repositories::outbox_repository;
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect, QueryTrait, TransactionTrait, prelude::DateTimeWithTimeZone,
+    sea_query::Expr,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Persistence operations on tenant memberships.
+#[async_trait]
+pub trait MembershipRepository: Send + Sync {
+    /// The membership, unless the user has been deleted.
+    async fn find(
+        &self,
+        user_id: Uuid,
+        tenant_id: Uuid,
+    ) -> Result<Option<memberships::Model>, DbErr>;
+
+    /// Memberships of a user, oldest first.
+    async fn list_by_user(&self, user_id: Uuid) -> Result<Vec<memberships::Model>, DbErr>;
+
+    /// Memberships in a tenant, oldest first.
+    async fn list_by_tenant(&self, tenant_id: Uuid) -> Result<Vec<memberships::Model>, DbErr>;
+
+    /// Inserts the membership and adds `events` to the outbox, in one
+    /// transaction.
+    async fn insert(
+        &self,
+        membership: memberships::ActiveModel,
+        events: Vec<DomainEvent>,
+    ) -> Result<memberships::Model, DbErr>;
+
+    /// Updates the membership and adds `events` to the outbox, in one
+    /// transaction.
+    async fn update(
+        &self,
+        membership: memberships::ActiveModel,
+        events: Vec<DomainEvent>,
+    ) -> Result<memberships::Model, DbErr>;
+
+    /// Deletes the membership and, with `home_tenant_id`, makes that the
+    /// tenant the user logs in to, in one transaction. Returns whether a
+    /// membership was deleted.
+    async fn delete(
+        &self,
+        user_id: Uuid,
+        tenant_id: Uuid,
+        home_tenant_id: Option<Uuid>,
+    ) -> Result<bool, DbErr>;
+
+    /// Records that the member obtained a token for the tenant at `at`.
+    async fn touch(
+        &self,
+        user_id: Uuid,
+        tenant_id: Uuid,
+        at: DateTimeWithTimeZone,
+    ) -> Result<(), DbErr>;
+
+    /// Deactivates the active, non-admin members of a tenant who have not
+    /// logged in since `idle_since`, or never did and joined before it, and
+    /// records a `UserStatusChanged` event for each. Returns how many were
+    /// deactivated.
+    async fn deactivate_idle(
+        &self,
+        tenant_id: Uuid,
+        idle_since: DateTimeWithTimeZone,
+    ) -> Result<u64, DbErr>;
+}
+
+pub struct SeaOrmMembershipRepository {
+    db: Arc<DatabaseConnection>,
+}
+
+impl SeaOrmMembershipRepository {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl MembershipRepository for SeaOrmMembershipRepository {
+    async fn find(
+        &self,
+        user_id: Uuid,
+        tenant_id: Uuid,
+    ) -> Result<Option<memberships::Model>, DbErr> {
+        memberships::Entity::find_by_id((user_id, tenant_id))
+            .inner_join(users::Entity)
+            .filter(users::Column::DeletedAt.is_null())
+            .one(self.db.as_ref())
+            .await
+    }
+
+    async fn list_by_user(&self, user_id: Uuid) -> Result<Vec<memberships::Model>, DbErr> {
+        memberships::Entity::find()
+            .filter(memberships::Column::UserId.eq(user_id))
+            .order_by_asc(memberships::Column::CreatedAt)
+            .all(self.db.as_ref())
+            .await
+    }
+
+    async fn list_by_tenant(&self, tenant_id: Uuid) -> Result<Vec<memberships::Model>, DbErr> {
+        memberships::Entity::find()
+            .filter(memberships::Column::TenantId.eq(tenant_id))
+            .order_by_asc(memberships::Column::CreatedAt)
+            .all(self.db.as_ref())
+            .await
+    }
+
+    async fn insert(
+        &self,
+        membership: memberships::ActiveModel,
+        events: Vec<DomainEvent>,
+    ) -> Result<memberships::Model, DbErr> {
+        let txn = self.db.begin().await?;
+        let membership = membership.insert(&txn).await?;
+        outbox_repository::append(&txn, events).await?;
+        txn.commit().await?;
+        Ok(membership)
+    }
+
+    async fn update(
+        &self,
+        membership: memberships::ActiveModel,
+        events: Vec<DomainEvent>,
+    ) -> Result<memberships::Model, DbErr> {
+        let txn = self.db.begin().await?;
+        let membership = membership.update(&txn).await?;
+        outbox_repository::append(&txn, events).await?;
+        txn.commit().await?;
+        Ok(membership)
+    }
+
+    async fn delete(
+        &self,
+        user_id: Uuid,
+        tenant_id: Uuid,
+        home_tenant_id: Option<Uuid>,
+    ) -> Result<bool, DbErr> {
+        let txn = self.db.begin().await?;
+        let result = memberships::Entity::delete_by_id((user_id, tenant_id))
+            .exec(&txn)
+            .await?;
+        if result.rows_affected != 1 {
+            return Ok(false);
+        }
+        if let Some(home_tenant_id) = home_tenant_id {
+            users::Entity::update_many()
+                .col_expr(users::Column::TenantId, Expr::value(home_tenant_id))
+                .col_expr(
+                    users::Column::UpdatedAt,
+                    Expr::value(Utc::now().fixed_offset()),
+                )
+                .filter(users::Column::Id.eq(user_id))
+                .exec(&txn)
+                .await?;
+        }
+        txn.commit().await?;
+        Ok(true)
+    }
+
+    async fn touch(
+        &self,
+        user_id: Uuid,
+        tenant_id: Uuid,
+        at: DateTimeWithTimeZone,
+    ) -> Result<(), DbErr> {
+        memberships::Entity::update_many()
+            .col_expr(memberships::Column::LastLoginAt, Expr::value(at))
+            .filter(memberships::Column::UserId.eq(user_id))
+            .filter(memberships::Column::TenantId.eq(tenant_id))
+            .exec(self.db.as_ref())
+            .await?;
+        Ok(())
+    }
+
+    async fn deactivate_idle(
+        &self,
+        tenant_id: Uuid,
+        idle_since: DateTimeWithTimeZone,
+    ) -> Result<u64, DbErr> {
+        let txn = self.db.begin().await?;
+        // One statement, so a member who logs in or is changed meanwhile is
+        // either deactivated with an event or left alone
+        let deactivated = memberships::Entity::update_many()
+            .col_expr(
+                memberships::Column::Status,
+                Expr::value(UserStatus::Inactive),
+            )
+            .col_expr(
+                memberships::Column::UpdatedAt,
+                Expr::value(Utc::now().fixed_offset()),
+            )
+            .filter(memberships::Column::TenantId.eq(tenant_id))
+            .filter(memberships::Column::Status.eq(UserStatus::Active))
+            .filter(memberships::Column::Role.ne(UserRole::Admin))
+            .filter(
+                Condition::any()
+                    .add(memberships::Column::LastLoginAt.lt(idle_since))
+                    .add(
+                        Condition::all()
+                            .add(memberships::Column::LastLoginAt.is_null())
+                            .add(memberships::Column::CreatedAt.lt(idle_since)),
+                    ),
+            )
+            .filter(
+                memberships::Column::UserId.in_subquery(
+                    users::Entity::find()
+                        .select_only()
+                        .column(users::Column::Id)
+                        .filter(users::Column::DeletedAt.is_null())
+                        .into_query(),
+                ),
+            )
+            .exec_with_returning(&txn)
+            .await?;
+        if deactivated.is_empty() {
+            txn.commit().await?;
+            return Ok(0);
+        }
+
+        let users = users::Entity::find()
+            .filter(users::Column::Id.is_in(deactivated.iter().map(|m| m.user_id)))
+            .all(&txn)
+            .await?;
+        let events = deactivated
+            .iter()
+            .filter_map(|membership| {
+                let user = users.iter().find(|user| user.id == membership.user_id)?;
+                let active = memberships::Model {
+                    status: UserStatus::Active,
+                    ..membership.clone()
+                };
+                Some(DomainEvent::member_changes(
+                    &user.clone().as_member(&active),
+                    &user.clone().as_member(membership),
+                ))
+            })
+            .flatten()
+            .collect();
+        outbox_repository::append(&txn, events).await?;
+        txn.commit().await?;
+        Ok(deactivated.len() as u64)
+    }
+}
@@ -0,0 +1,25 @@
+pub mod api_key_repository;
+pub mod job_repository;
+pub mod membership_repository;
+pub mod oidc_repository;
+pub mod outbox_repository;
+pub mod password_history_repository;
+pub mod task_run_repository;
+pub mod tenant_export_repository;
+pub mod tenant_repository;
+pub mod tenant_settings_repository;
+pub mod user_repository;
+pub mod webhook_repository;
+
+pub use api_key_repository::{ApiKeyRepository, SeaOrmApiKeyRepository};
+pub use job_repository::{JobRepository, SeaOrmJobRepository};
+pub use membership_repository::{MembershipRepository, SeaOrmMembershipRepository};
+pub use oidc_repository::{OidcRepository, SeaOrmOidcRepository};
+pub use outbox_repository::{OutboxRepository, SeaOrmOutboxRepository};
+pub use password_history_repository::{PasswordHistoryRepository, SeaOrmPasswordHistoryRepository};
+pub use task_run_repository::{SeaOrmTaskRunRepository, TaskLock, TaskRunRepository};
+pub use tenant_export_repository::{SeaOrmTenantExportRepository, TenantExportRepository};
+pub use tenant_repository::{SeaOrmTenantRepository, TenantRepository};
+pub use tenant_settings_repository::{SeaOrmTenantSettingsRepository, TenantSettingsRepository};
+pub use user_repository::{SeaOrmUserRepository, UserRepository};
+pub use webhook_repository::{SeaOrmWebhookRepository, WebhookRepository};
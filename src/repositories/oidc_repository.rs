@@ -0,0 +1,158 @@
+use crate::models::{oidc_login_states, oidc_providers, user_identities};
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    prelude::DateTimeWithTimeZone,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Persistence operations on identity providers, linked identities and
+/// pending logins.
+#[async_trait]
+pub trait OidcRepository: Send + Sync {
+    async fn insert_provider(
+        &self,
+        provider: oidc_providers::ActiveModel,
+    ) -> Result<oidc_providers::Model, DbErr>;
+
+    /// Looks a provider up by id alone, for the unauthenticated login flow.
+    async fn find_provider(
+        &self,
+        provider_id: Uuid,
+    ) -> Result<Option<oidc_providers::Model>, DbErr>;
+
+    async fn list_providers(&self, tenant_id: Uuid) -> Result<Vec<oidc_providers::Model>, DbErr>;
+
+    /// Returns whether a provider of this tenant was deleted.
+    async fn delete_provider(&self, tenant_id: Uuid, provider_id: Uuid) -> Result<bool, DbErr>;
+
+    async fn find_identity(
+        &self,
+        provider_id: Uuid,
+        subject: &str,
+    ) -> Result<Option<user_identities::Model>, DbErr>;
+
+    async fn insert_identity(
+        &self,
+        identity: user_identities::ActiveModel,
+    ) -> Result<user_identities::Model, DbErr>;
+
+    async fn insert_login_state(
+        &self,
+        state: oidc_login_states::ActiveModel,
+    ) -> Result<oidc_login_states::Model, DbErr>;
+
+    /// Deletes and returns a pending login. Only one caller can take a given
+    /// state; concurrent callers get `None`.
+    async fn take_login_state(
+        &self,
+        state: &str,
+    ) -> Result<Option<oidc_login_states::Model>, DbErr>;
+
+    /// Deletes pending logins that expired before `before`. Returns how many
+    /// were deleted.
+    async fn delete_expired_login_states(&self, before: DateTimeWithTimeZone)
+    -> Result<u64, DbErr>;
+}
+
+pub struct SeaOrmOidcRepository {
+    db: Arc<DatabaseConnection>,
+}
+
+impl SeaOrmOidcRepository {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl OidcRepository for SeaOrmOidcRepository {
+    async fn insert_provider(
+        &self,
+        provider: oidc_providers::ActiveModel,
+    ) -> Result<oidc_providers::Model, DbErr> {
+        provider.insert(self.db.as_ref()).await
+    }
+
+    async fn find_provider(
+        &self,
+        provider_id: Uuid,
+    ) -> Result<Option<oidc_providers::Model>, DbErr> {
+        oidc_providers::Entity::find_by_id(provider_id)
+            .one(self.db.as_ref())
+            .await
+    }
+
+    async fn list_providers(&self, tenant_id: Uuid) -> Result<Vec<oidc_providers::Model>, DbErr> {
+        oidc_providers::Entity::find()
+            .filter(oidc_providers::Column::TenantId.eq(tenant_id))
+            .order_by_asc(oidc_providers::Column::CreatedAt)
+            .all(self.db.as_ref())
+            .await
+    }
+
+    async fn delete_provider(&self, tenant_id: Uuid, provider_id: Uuid) -> Result<bool, DbErr> {
+        let result = oidc_providers::Entity::delete_many()
+            .filter(oidc_providers::Column::Id.eq(provider_id))
+            .filter(oidc_providers::Column::TenantId.eq(tenant_id))
+            .exec(self.db.as_ref())
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn find_identity(
+        &self,
+        provider_id: Uuid,
+        subject: &str,
+    ) -> Result<Option<user_identities::Model>, DbErr> {
+        user_identities::Entity::find()
+            .filter(user_identities::Column::ProviderId.eq(provider_id))
+            .filter(user_identities::Column::Subject.eq(subject))
+            .one(self.db.as_ref())
+            .await
+    }
+
+    async fn insert_identity(
+        &self,
+        identity: user_identities::ActiveModel,
+    ) -> Result<user_identities::Model, DbErr> {
+        identity.insert(self.db.as_ref()).await
+    }
+
+    async fn insert_login_state(
+        &self,
+        state: oidc_login_states::ActiveModel,
+    ) -> Result<oidc_login_states::Model, DbErr> {
+        state.insert(self.db.as_ref()).await
+    }
+
+    async fn take_login_state(
+        &self,
+        state: &str,
+    ) -> Result<Option<oidc_login_states::Model>, DbErr> {
+        let Some(found) = oidc_login_states::Entity::find_by_id(state.to_string())
+            .one(self.db.as_ref())
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        // Whoever deletes the row owns it
+        let deleted = oidc_login_states::Entity::delete_by_id(state.to_string())
+            .exec(self.db.as_ref())
+            .await?;
+        Ok((deleted.rows_affected == 1).then_some(found))
+    }
+
+    async fn delete_expired_login_states(
+        &self,
+        before: DateTimeWithTimeZone,
+    ) -> Result<u64, DbErr> {
+        let deleted = oidc_login_states::Entity::delete_many()
+            .filter(oidc_login_states::Column::ExpiresAt.lt(before))
+            .exec(self.db.as_ref())
+            .await?;
+        Ok(deleted.rows_affected)
+    }
+}
@@ -0,0 +1,146 @@
+use crate::events::DomainEvent;
+use crate::models::outbox_events;
+use crate::repositories::TaskLock;
+use crate::repositories::task_run_repository::try_advisory_lock;
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::sea_query::{Alias, Expr, ExprTrait, Query};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, DbErr,
+    EntityTrait, NotSet, QueryFilter, QueryOrder, QuerySelect, Set, prelude::DateTimeWithTimeZone,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// First key of the advisory lock held by the relay at work.
+const RELAY_LOCK_CLASS: i32 = 0x6f75_7462;
+
+/// Adds `events` to the outbox on `db`, which must be the transaction of the
+/// change they record. Positions are taken after that change is written: a
+/// concurrent change of the same rows waits for this transaction to end, so
+/// its events get later positions.
+pub async fn append<C: ConnectionTrait>(db: &C, events: Vec<DomainEvent>) -> Result<(), DbErr> {
+    for event in events {
+        let (aggregate_type, aggregate_id) = event.aggregate();
+        let event = outbox_events::ActiveModel {
+            id: Set(Uuid::now_v7()),
+            position: NotSet,
+            aggregate_type: Set(aggregate_type.to_string()),
+            aggregate_id: Set(aggregate_id),
+            tenant_id: Set(event.tenant_id()),
+            event_type: Set(event.name().to_string()),
+            payload: Set(serde_json::to_value(&event).map_err(|e| DbErr::Json(e.to_string()))?),
+            attempts: Set(0),
+            next_attempt_at: Set(None),
+            last_error: Set(None),
+            created_at: Set(Utc::now().fixed_offset()),
+            dispatched_at: Set(None),
+            dead_at: Set(None),
+        };
+        // Without RETURNING, which on SQLite would read the position before
+        // its trigger sets it
+        outbox_events::Entity::insert(event)
+            .exec_without_returning(db)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Persistence operations on the outbox, for the relay. Events are added
+/// with [`append`] by the repositories writing the changes they record.
+#[async_trait]
+pub trait OutboxRepository: Send + Sync {
+    /// Takes the relay lock unless another replica holds it.
+    async fn try_lock(&self) -> Result<Option<TaskLock>, DbErr>;
+
+    /// Up to `limit` events to relay, by position: of each aggregate, the
+    /// oldest event neither relayed nor dead yet, if it is not waiting for a
+    /// retry after `now`.
+    async fn due(
+        &self,
+        now: DateTimeWithTimeZone,
+        limit: u64,
+    ) -> Result<Vec<outbox_events::Model>, DbErr>;
+
+    async fn update(
+        &self,
+        event: outbox_events::ActiveModel,
+    ) -> Result<outbox_events::Model, DbErr>;
+
+    /// Deletes the events relayed before `before` and returns how many.
+    async fn purge_dispatched(&self, before: DateTimeWithTimeZone) -> Result<u64, DbErr>;
+}
+
+pub struct SeaOrmOutboxRepository {
+    db: Arc<DatabaseConnection>,
+}
+
+impl SeaOrmOutboxRepository {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl OutboxRepository for SeaOrmOutboxRepository {
+    async fn try_lock(&self) -> Result<Option<TaskLock>, DbErr> {
+        try_advisory_lock(&self.db, RELAY_LOCK_CLASS, "outbox_relay").await
+    }
+
+    async fn due(
+        &self,
+        now: DateTimeWithTimeZone,
+        limit: u64,
+    ) -> Result<Vec<outbox_events::Model>, DbErr> {
+        let earlier = Alias::new("earlier");
+        let earlier_pending = Query::select()
+            .expr(Expr::val(1))
+            .from_as(outbox_events::Entity, earlier.clone())
+            .and_where(
+                Expr::col((earlier.clone(), outbox_events::Column::AggregateType))
+                    .equals((outbox_events::Entity, outbox_events::Column::AggregateType)),
+            )
+            .and_where(
+                Expr::col((earlier.clone(), outbox_events::Column::AggregateId))
+                    .equals((outbox_events::Entity, outbox_events::Column::AggregateId)),
+            )
+            .and_where(
+                Expr::col((earlier.clone(), outbox_events::Column::Position)).lt(Expr::col((
+                    outbox_events::Entity,
+                    outbox_events::Column::Position,
+                ))),
+            )
+            .and_where(Expr::col((earlier.clone(), outbox_events::Column::DispatchedAt)).is_null())
+            .and_where(Expr::col((earlier, outbox_events::Column::DeadAt)).is_null())
+            .to_owned();
+
+        outbox_events::Entity::find()
+            .filter(outbox_events::Column::DispatchedAt.is_null())
+            .filter(outbox_events::Column::DeadAt.is_null())
+            .filter(
+                Condition::any()
+                    .add(outbox_events::Column::NextAttemptAt.is_null())
+                    .add(outbox_events::Column::NextAttemptAt.lte(now)),
+            )
+            .filter(Expr::exists(earlier_pending).not())
+            .order_by_asc(outbox_events::Column::Position)
+            .limit(limit)
+            .all(self.db.as_ref())
+            .await
+    }
+
+    async fn update(
+        &self,
+        event: outbox_events::ActiveModel,
+    ) -> Result<outbox_events::Model, DbErr> {
+        event.update(self.db.as_ref()).await
+    }
+
+    async fn purge_dispatched(&self, before: DateTimeWithTimeZone) -> Result<u64, DbErr> {
+        Ok(outbox_events::Entity::delete_many()
+            .filter(outbox_events::Column::DispatchedAt.lt(before))
+            .exec(self.db.as_ref())
+            .await?
+            .rows_affected)
+    }
+}
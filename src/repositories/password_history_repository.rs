@@ -0,0 +1,77 @@
+use crate::models::password_history;
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Persistence operations on users' previous password hashes.
+#[async_trait]
+pub trait PasswordHistoryRepository: Send + Sync {
+    /// The `limit` most recent entries of a user, newest first.
+    async fn list_recent(
+        &self,
+        user_id: Uuid,
+        limit: u64,
+    ) -> Result<Vec<password_history::Model>, DbErr>;
+
+    async fn insert(
+        &self,
+        entry: password_history::ActiveModel,
+    ) -> Result<password_history::Model, DbErr>;
+
+    /// Deletes all but the `keep` most recent entries of a user.
+    async fn prune(&self, user_id: Uuid, keep: u64) -> Result<(), DbErr>;
+}
+
+pub struct SeaOrmPasswordHistoryRepository {
+    db: Arc<DatabaseConnection>,
+}
+
+impl SeaOrmPasswordHistoryRepository {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl PasswordHistoryRepository for SeaOrmPasswordHistoryRepository {
+    async fn list_recent(
+        &self,
+        user_id: Uuid,
+        limit: u64,
+    ) -> Result<Vec<password_history::Model>, DbErr> {
+        password_history::Entity::find()
+            .filter(password_history::Column::UserId.eq(user_id))
+            .order_by_desc(password_history::Column::CreatedAt)
+            .order_by_desc(password_history::Column::Id)
+            .limit(limit)
+            .all(self.db.as_ref())
+            .await
+    }
+
+    async fn insert(
+        &self,
+        entry: password_history::ActiveModel,
+    ) -> Result<password_history::Model, DbErr> {
+        entry.insert(self.db.as_ref()).await
+    }
+
+    async fn prune(&self, user_id: Uuid, keep: u64) -> Result<(), DbErr> {
+        let kept: Vec<Uuid> = self
+            .list_recent(user_id, keep)
+            .await?
+            .into_iter()
+            .map(|entry| entry.id)
+            .collect();
+
+        password_history::Entity::delete_many()
+            .filter(password_history::Column::UserId.eq(user_id))
+            .filter(password_history::Column::Id.is_not_in(kept))
+            .exec(self.db.as_ref())
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+use crate::models::task_runs;
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseBackend, DatabaseConnection,
+    DatabaseTransaction, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Statement,
+    TransactionTrait, prelude::DateTimeWithTimeZone,
+};
+use std::sync::Arc;
+
+/// First key of the advisory locks taken for scheduled tasks; the second is
+/// a hash of the task name.
+const TASK_LOCK_CLASS: i32 = 0x7461_736b;
+
+/// Held while a scheduled task, or the outbox relay, runs so that other
+/// replicas skip it.
+pub struct TaskLock {
+    txn: Option<DatabaseTransaction>,
+}
+
+impl TaskLock {
+    /// A lock that excludes nothing, for databases where only one scheduler
+    /// runs, such as SQLite.
+    pub fn local() -> Self {
+        Self { txn: None }
+    }
+
+    pub async fn release(self) -> Result<(), DbErr> {
+        match self.txn {
+            Some(txn) => txn.commit().await,
+            None => Ok(()),
+        }
+    }
+}
+
+/// Takes the Postgres advisory lock `(class, hashtext(key))` unless another
+/// session holds it. Other databases get a [`TaskLock::local`].
+pub(crate) async fn try_advisory_lock(
+    db: &DatabaseConnection,
+    class: i32,
+    key: &str,
+) -> Result<Option<TaskLock>, DbErr> {
+    if db.get_database_backend() != DatabaseBackend::Postgres {
+        return Ok(Some(TaskLock::local()));
+    }
+
+    // Released when the transaction ends, even if the connection is lost
+    let txn = db.begin().await?;
+    let locked = txn
+        .query_one_raw(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            "SELECT pg_try_advisory_xact_lock($1, hashtext($2)) AS locked",
+            [class.into(), key.into()],
+        ))
+        .await?
+        .map(|row| row.try_get::<bool>("", "locked"))
+        .transpose()?
+        .unwrap_or(false);
+    if !locked {
+        txn.rollback().await?;
+        return Ok(None);
+    }
+    Ok(Some(TaskLock { txn: Some(txn) }))
+}
+
+/// Persistence operations on runs of scheduled tasks.
+#[async_trait]
+pub trait TaskRunRepository: Send + Sync {
+    /// Takes the lock of `task` unless another replica holds it.
+    async fn try_lock(&self, task: &str) -> Result<Option<TaskLock>, DbErr>;
+
+    async fn find(
+        &self,
+        task: &str,
+        scheduled_for: DateTimeWithTimeZone,
+    ) -> Result<Option<task_runs::Model>, DbErr>;
+
+    /// Runs of `task`, or of every task, newest first.
+    async fn list(&self, task: Option<String>, limit: u64) -> Result<Vec<task_runs::Model>, DbErr>;
+
+    async fn insert(&self, run: task_runs::ActiveModel) -> Result<task_runs::Model, DbErr>;
+
+    async fn update(&self, run: task_runs::ActiveModel) -> Result<task_runs::Model, DbErr>;
+}
+
+pub struct SeaOrmTaskRunRepository {
+    db: Arc<DatabaseConnection>,
+}
+
+impl SeaOrmTaskRunRepository {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl TaskRunRepository for SeaOrmTaskRunRepository {
+    async fn try_lock(&self, task: &str) -> Result<Option<TaskLock>, DbErr> {
+        try_advisory_lock(&self.db, TASK_LOCK_CLASS, task).await
+    }
+
+    async fn find(
+        &self,
+        task: &str,
+        scheduled_for: DateTimeWithTimeZone,
+    ) -> Result<Option<task_runs::Model>, DbErr> {
+        task_runs::Entity::find()
+            .filter(task_runs::Column::Task.eq(task))
+            .filter(task_runs::Column::ScheduledFor.eq(scheduled_for))
+            .one(self.db.as_ref())
+            .await
+    }
+
+    async fn list(&self, task: Option<String>, limit: u64) -> Result<Vec<task_runs::Model>, DbErr> {
+        let mut query = task_runs::Entity::find();
+        if let Some(task) = task {
+            query = query.filter(task_runs::Column::Task.eq(task));
+        }
+        query
+            .order_by_desc(task_runs::Column::StartedAt)
+            .limit(limit)
+            .all(self.db.as_ref())
+            .await
+    }
+
+    async fn insert(&self, run: task_runs::ActiveModel) -> Result<task_runs::Model, DbErr> {
+        run.insert(self.db.as_ref()).await
+    }
+
+    async fn update(&self, run: task_runs::ActiveModel) -> Result<task_runs::Model, DbErr> {
+        run.update(self.db.as_ref()).await
+    }
+}
@@ -0,0 +1,71 @@
+use crate::models::tenant_exports;
+use async_trait::async_trait;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Persistence operations on tenant export jobs.
+#[async_trait]
+pub trait TenantExportRepository: Send + Sync {
+    async fn find(
+        &self,
+        tenant_id: Uuid,
+        export_id: Uuid,
+    ) -> Result<Option<tenant_exports::Model>, DbErr>;
+
+    async fn find_by_id(&self, export_id: Uuid) -> Result<Option<tenant_exports::Model>, DbErr>;
+
+    async fn insert(
+        &self,
+        export: tenant_exports::ActiveModel,
+    ) -> Result<tenant_exports::Model, DbErr>;
+
+    async fn update(
+        &self,
+        export: tenant_exports::ActiveModel,
+    ) -> Result<tenant_exports::Model, DbErr>;
+}
+
+pub struct SeaOrmTenantExportRepository {
+    db: Arc<DatabaseConnection>,
+}
+
+impl SeaOrmTenantExportRepository {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl TenantExportRepository for SeaOrmTenantExportRepository {
+    async fn find(
+        &self,
+        tenant_id: Uuid,
+        export_id: Uuid,
+    ) -> Result<Option<tenant_exports::Model>, DbErr> {
+        tenant_exports::Entity::find_by_id(export_id)
+            .filter(tenant_exports::Column::TenantId.eq(tenant_id))
+            .one(self.db.as_ref())
+            .await
+    }
+
+    async fn find_by_id(&self, export_id: Uuid) -> Result<Option<tenant_exports::Model>, DbErr> {
+        tenant_exports::Entity::find_by_id(export_id)
+            .one(self.db.as_ref())
+            .await
+    }
+
+    async fn insert(
+        &self,
+        export: tenant_exports::ActiveModel,
+    ) -> Result<tenant_exports::Model, DbErr> {
+        export.insert(self.db.as_ref()).await
+    }
+
+    async fn update(
+        &self,
+        export: tenant_exports::ActiveModel,
+    ) -> Result<tenant_exports::Model, DbErr> {
+        export.update(self.db.as_ref()).await
+    }
+}
@@ -0,0 +1,64 @@
+use crate::events::DomainEvent;
+use crate::models::tenants;
+use crate::repositories::outbox_repository;
+use async_trait::async_trait;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, DbErr, EntityTrait, TransactionTrait};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Persistence operations on tenants.
+#[async_trait]
+pub trait TenantRepository: Send + Sync {
+    async fn list_all(&self) -> Result<Vec<tenants::Model>, DbErr>;
+
+    async fn find_by_id(&self, tenant_id: Uuid) -> Result<Option<tenants::Model>, DbErr>;
+
+    async fn insert(&self, tenant: tenants::ActiveModel) -> Result<tenants::Model, DbErr>;
+
+    /// Updates the tenant and adds `events` to the outbox, in one
+    /// transaction.
+    async fn update(
+        &self,
+        tenant: tenants::ActiveModel,
+        events: Vec<DomainEvent>,
+    ) -> Result<tenants::Model, DbErr>;
+}
+
+pub struct SeaOrmTenantRepository {
+    db: Arc<DatabaseConnection>,
+}
+
+impl SeaOrmTenantRepository {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl TenantRepository for SeaOrmTenantRepository {
+    async fn list_all(&self) -> Result<Vec<tenants::Model>, DbErr> {
+        tenants::Entity::find().all(self.db.as_ref()).await
+    }
+
+    async fn find_by_id(&self, tenant_id: Uuid) -> Result<Option<tenants::Model>, DbErr> {
+        tenants::Entity::find_by_id(tenant_id)
+            .one(self.db.as_ref())
+            .await
+    }
+
+    async fn insert(&self, tenant: tenants::ActiveModel) -> Result<tenants::Model, DbErr> {
+        tenant.insert(self.db.as_ref()).await
+    }
+
+    async fn update(
+        &self,
+        tenant: tenants::ActiveModel,
+        events: Vec<DomainEvent>,
+    ) -> Result<tenants::Model, DbErr> {
+        let txn = self.db.begin().await?;
+        let tenant = tenant.update(&txn).await?;
+        outbox_repository::append(&txn, events).await?;
+        txn.commit().await?;
+        Ok(tenant)
+    }
+}
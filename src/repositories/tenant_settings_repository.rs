@@ -0,0 +1,84 @@
+use crate::models::tenant_settings;
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, Set, SqlErr,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Persistence operations on tenant settings.
+#[async_trait]
+pub trait TenantSettingsRepository: Send + Sync {
+    async fn find(&self, tenant_id: Uuid) -> Result<Option<tenant_settings::Model>, DbErr>;
+
+    /// Stores `settings` as version `version + 1` if the stored version is
+    /// still `version` (0 when nothing is stored yet). Returns `None` when
+    /// another update got there first.
+    async fn save(
+        &self,
+        tenant_id: Uuid,
+        version: i32,
+        settings: serde_json::Value,
+    ) -> Result<Option<tenant_settings::Model>, DbErr>;
+}
+
+pub struct SeaOrmTenantSettingsRepository {
+    db: Arc<DatabaseConnection>,
+}
+
+impl SeaOrmTenantSettingsRepository {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl TenantSettingsRepository for SeaOrmTenantSettingsRepository {
+    async fn find(&self, tenant_id: Uuid) -> Result<Option<tenant_settings::Model>, DbErr> {
+        tenant_settings::Entity::find_by_id(tenant_id)
+            .one(self.db.as_ref())
+            .await
+    }
+
+    async fn save(
+        &self,
+        tenant_id: Uuid,
+        version: i32,
+        settings: serde_json::Value,
+    ) -> Result<Option<tenant_settings::Model>, DbErr> {
+        if version == 0 {
+            let row = tenant_settings::ActiveModel {
+                tenant_id: Set(tenant_id),
+                version: Set(1),
+                settings: Set(settings),
+                updated_at: Set(Utc::now().fixed_offset()),
+            };
+            return match row.insert(self.db.as_ref()).await {
+                Ok(row) => Ok(Some(row)),
+                Err(e) if matches!(e.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {
+                    Ok(None)
+                }
+                Err(e) => Err(e),
+            };
+        }
+
+        let result = tenant_settings::Entity::update_many()
+            .col_expr(tenant_settings::Column::Version, Expr::value(version + 1))
+            .col_expr(tenant_settings::Column::Settings, Expr::value(settings))
+            .col_expr(
+                tenant_settings::Column::UpdatedAt,
+                Expr::value(Utc::now().fixed_offset()),
+            )
+            .filter(tenant_settings::Column::TenantId.eq(tenant_id))
+            .filter(tenant_settings::Column::Version.eq(version))
+            .exec(self.db.as_ref())
+            .await?;
+        if result.rows_affected == 0 {
+            return Ok(None);
+        }
+
+        self.find(tenant_id).await
+    }
+}
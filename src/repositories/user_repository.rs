@@ -0,0 +1,300 @@
+use crate::events::DomainEvent;
+use crate::models::{
+    memberships, outbox_events, password_history, user_identities, users, webhook_deliveries,
+};
+use crate::repositories::outbox_repository;
+use async_trait::async_trait;
+use sea_orm::sea_query::{Expr, ExprTrait};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DatabaseTransaction,
+    DbErr, EntityTrait, QueryFilter, QueryOrder, SelectTwo, TransactionTrait,
+    prelude::DateTimeWithTimeZone,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Persistence operations on users. Reads return a user as a member of a
+/// tenant: the one asked for, else the one the user was created in. Deleted
+/// users are only returned by the methods that say so.
+#[async_trait]
+pub trait UserRepository: Send + Sync {
+    async fn find_by_id(&self, user_id: Uuid) -> Result<Option<users::Model>, DbErr>;
+
+    async fn find_in_tenant(
+        &self,
+        tenant_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<users::Model>, DbErr>;
+
+    async fn find_by_email(&self, email: &str) -> Result<Option<users::Model>, DbErr>;
+
+    async fn find_by_email_in_tenant(
+        &self,
+        tenant_id: Uuid,
+        email: &str,
+    ) -> Result<Option<users::Model>, DbErr>;
+
+    /// Members of a tenant, most recently joined first.
+    async fn list_by_tenant(&self, tenant_id: Uuid) -> Result<Vec<users::Model>, DbErr>;
+
+    async fn tenant_has_users(&self, tenant_id: Uuid) -> Result<bool, DbErr>;
+
+    /// Inserts the identity with its first membership and adds `events` to
+    /// the outbox, in one transaction. Returns the user as that member.
+    async fn insert_member(
+        &self,
+        user: users::ActiveModel,
+        membership: memberships::ActiveModel,
+        events: Vec<DomainEvent>,
+    ) -> Result<users::Model, DbErr>;
+
+    /// Updates the identity. `role` and `status` live on memberships, so the
+    /// returned values of those are not meaningful.
+    async fn update(&self, user: users::ActiveModel) -> Result<users::Model, DbErr>;
+
+    /// A deleted member of a tenant that has not been erased yet.
+    async fn find_deleted_in_tenant(
+        &self,
+        tenant_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<users::Model>, DbErr>;
+
+    /// Identities deleted before `deleted_before` and not erased yet.
+    async fn list_erasable(
+        &self,
+        deleted_before: DateTimeWithTimeZone,
+    ) -> Result<Vec<users::Model>, DbErr>;
+
+    /// Writes the anonymized identity and, in the same transaction, deletes
+    /// its identity provider links and password history, replaces its email
+    /// in the payloads of its outbox events and webhook deliveries, and
+    /// drops the response bodies of those deliveries. The row itself is kept
+    /// so that references to the user stay valid.
+    async fn erase(&self, user: users::ActiveModel) -> Result<(), DbErr>;
+}
+
+pub struct SeaOrmUserRepository {
+    db: Arc<DatabaseConnection>,
+}
+
+impl SeaOrmUserRepository {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+}
+
+/// Users with their membership of the tenant they are read for, so that
+/// `tenant_id`, `role` and `status` come from the membership.
+fn members() -> SelectTwo<memberships::Entity, users::Entity> {
+    memberships::Entity::find().find_also_related(users::Entity)
+}
+
+fn member((membership, user): (memberships::Model, Option<users::Model>)) -> Option<users::Model> {
+    user.map(|user| user.as_member(&membership))
+}
+
+/// Users that have not been deleted.
+fn not_deleted() -> Expr {
+    Expr::col((users::Entity, users::Column::DeletedAt)).is_null()
+}
+
+/// Replaces `email` with `erased` in the outbox events of the user and in
+/// the webhook deliveries made of them, and drops what their endpoints
+/// answered.
+async fn scrub_events(
+    txn: &DatabaseTransaction,
+    user_id: Uuid,
+    email: &str,
+    erased: &str,
+) -> Result<(), DbErr> {
+    let events = outbox_events::Entity::find()
+        .filter(outbox_events::Column::AggregateType.eq("user"))
+        .filter(outbox_events::Column::AggregateId.eq(user_id))
+        .all(txn)
+        .await?;
+    let event_ids: Vec<Uuid> = events.iter().map(|event| event.id).collect();
+    for event in events {
+        let mut payload = event.payload.clone();
+        if replace_strings(&mut payload, email, erased) {
+            let mut event: outbox_events::ActiveModel = event.into();
+            event.payload = Set(payload);
+            event.update(txn).await?;
+        }
+    }
+
+    let deliveries = webhook_deliveries::Entity::find()
+        .filter(webhook_deliveries::Column::EventId.is_in(event_ids))
+        .all(txn)
+        .await?;
+    for delivery in deliveries {
+        let mut payload = delivery.payload.clone();
+        replace_strings(&mut payload, email, erased);
+        let mut delivery: webhook_deliveries::ActiveModel = delivery.into();
+        delivery.payload = Set(payload);
+        delivery.response_body = Set(None);
+        delivery.update(txn).await?;
+    }
+    Ok(())
+}
+
+/// Replaces every string in `value` equal to `from` with `to`, and returns
+/// whether there was one.
+fn replace_strings(value: &mut serde_json::Value, from: &str, to: &str) -> bool {
+    let children: Vec<&mut serde_json::Value> = match value {
+        serde_json::Value::String(s) if s == from => {
+            *s = to.to_string();
+            return true;
+        }
+        serde_json::Value::Array(values) => values.iter_mut().collect(),
+        serde_json::Value::Object(values) => values.values_mut().collect(),
+        _ => return false,
+    };
+    let mut found = false;
+    for child in children {
+        found |= replace_strings(child, from, to);
+    }
+    found
+}
+
+/// Restricts [`members`] to each user's membership of the tenant it was
+/// created in.
+fn home_membership() -> Expr {
+    Expr::col((memberships::Entity, memberships::Column::TenantId))
+        .equals((users::Entity, users::Column::TenantId))
+}
+
+#[async_trait]
+impl UserRepository for SeaOrmUserRepository {
+    async fn find_by_id(&self, user_id: Uuid) -> Result<Option<users::Model>, DbErr> {
+        Ok(members()
+            .filter(users::Column::Id.eq(user_id))
+            .filter(home_membership())
+            .filter(not_deleted())
+            .one(self.db.as_ref())
+            .await?
+            .and_then(member))
+    }
+
+    async fn find_in_tenant(
+        &self,
+        tenant_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<users::Model>, DbErr> {
+        Ok(members()
+            .filter(memberships::Column::UserId.eq(user_id))
+            .filter(memberships::Column::TenantId.eq(tenant_id))
+            .filter(not_deleted())
+            .one(self.db.as_ref())
+            .await?
+            .and_then(member))
+    }
+
+    async fn find_by_email(&self, email: &str) -> Result<Option<users::Model>, DbErr> {
+        Ok(members()
+            .filter(users::Column::Email.eq(email))
+            .filter(home_membership())
+            .filter(not_deleted())
+            .one(self.db.as_ref())
+            .await?
+            .and_then(member))
+    }
+
+    async fn find_by_email_in_tenant(
+        &self,
+        tenant_id: Uuid,
+        email: &str,
+    ) -> Result<Option<users::Model>, DbErr> {
+        Ok(members()
+            .filter(users::Column::Email.eq(email))
+            .filter(memberships::Column::TenantId.eq(tenant_id))
+            .filter(not_deleted())
+            .one(self.db.as_ref())
+            .await?
+            .and_then(member))
+    }
+
+    async fn list_by_tenant(&self, tenant_id: Uuid) -> Result<Vec<users::Model>, DbErr> {
+        Ok(members()
+            .filter(memberships::Column::TenantId.eq(tenant_id))
+            .filter(not_deleted())
+            .order_by_desc(memberships::Column::CreatedAt)
+            .all(self.db.as_ref())
+            .await?
+            .into_iter()
+            .filter_map(member)
+            .collect())
+    }
+
+    async fn tenant_has_users(&self, tenant_id: Uuid) -> Result<bool, DbErr> {
+        Ok(members()
+            .filter(memberships::Column::TenantId.eq(tenant_id))
+            .filter(not_deleted())
+            .one(self.db.as_ref())
+            .await?
+            .is_some())
+    }
+
+    async fn insert_member(
+        &self,
+        user: users::ActiveModel,
+        membership: memberships::ActiveModel,
+        events: Vec<DomainEvent>,
+    ) -> Result<users::Model, DbErr> {
+        let txn = self.db.begin().await?;
+        let user = user.insert(&txn).await?;
+        let membership = membership.insert(&txn).await?;
+        outbox_repository::append(&txn, events).await?;
+        txn.commit().await?;
+        Ok(user.as_member(&membership))
+    }
+
+    async fn update(&self, user: users::ActiveModel) -> Result<users::Model, DbErr> {
+        user.update(self.db.as_ref()).await
+    }
+
+    async fn find_deleted_in_tenant(
+        &self,
+        tenant_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<users::Model>, DbErr> {
+        Ok(members()
+            .filter(memberships::Column::UserId.eq(user_id))
+            .filter(memberships::Column::TenantId.eq(tenant_id))
+            .filter(users::Column::DeletedAt.is_not_null())
+            .filter(users::Column::ErasedAt.is_null())
+            .one(self.db.as_ref())
+            .await?
+            .and_then(member))
+    }
+
+    async fn list_erasable(
+        &self,
+        deleted_before: DateTimeWithTimeZone,
+    ) -> Result<Vec<users::Model>, DbErr> {
+        users::Entity::find()
+            .filter(users::Column::DeletedAt.lt(deleted_before))
+            .filter(users::Column::ErasedAt.is_null())
+            .order_by_asc(users::Column::DeletedAt)
+            .all(self.db.as_ref())
+            .await
+    }
+
+    async fn erase(&self, user: users::ActiveModel) -> Result<(), DbErr> {
+        let user_id = user.id.clone().unwrap();
+        let txn = self.db.begin().await?;
+        let Some(previous) = users::Entity::find_by_id(user_id).one(&txn).await? else {
+            return Err(DbErr::RecordNotFound(format!("user {}", user_id)));
+        };
+        let erased = user.update(&txn).await?;
+        scrub_events(&txn, user_id, &previous.email, &erased.email).await?;
+        user_identities::Entity::delete_many()
+            .filter(user_identities::Column::UserId.eq(user_id))
+            .exec(&txn)
+            .await?;
+        password_history::Entity::delete_many()
+            .filter(password_history::Column::UserId.eq(user_id))
+            .exec(&txn)
+            .await?;
+        txn.commit().await
+    }
+}
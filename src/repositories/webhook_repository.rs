@@ -0,0 +1,181 @@
+use crate::models::{jobs, webhook_deliveries, webhook_endpoints};
+use async_trait::async_trait;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, TransactionTrait,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Persistence operations on webhook endpoints and their deliveries.
+#[async_trait]
+pub trait WebhookRepository: Send + Sync {
+    async fn insert_endpoint(
+        &self,
+        endpoint: webhook_endpoints::ActiveModel,
+    ) -> Result<webhook_endpoints::Model, DbErr>;
+
+    async fn find_endpoint(
+        &self,
+        tenant_id: Uuid,
+        endpoint_id: Uuid,
+    ) -> Result<Option<webhook_endpoints::Model>, DbErr>;
+
+    async fn list_endpoints(&self, tenant_id: Uuid)
+    -> Result<Vec<webhook_endpoints::Model>, DbErr>;
+
+    /// Returns whether an endpoint of this tenant was deleted. Its
+    /// deliveries go with it.
+    async fn delete_endpoint(&self, tenant_id: Uuid, endpoint_id: Uuid) -> Result<bool, DbErr>;
+
+    async fn insert_delivery(
+        &self,
+        delivery: webhook_deliveries::ActiveModel,
+    ) -> Result<webhook_deliveries::Model, DbErr>;
+
+    /// Inserts each delivery with the job that sends it, all in one
+    /// transaction. A delivery of an event its endpoint already has, other
+    /// than a redelivery, is skipped with its job. Returns how many were
+    /// inserted.
+    async fn insert_deliveries(
+        &self,
+        deliveries: Vec<(webhook_deliveries::ActiveModel, jobs::ActiveModel)>,
+    ) -> Result<u64, DbErr>;
+
+    /// Looks a delivery up by id alone, for the delivery job.
+    async fn find_delivery(
+        &self,
+        delivery_id: Uuid,
+    ) -> Result<Option<webhook_deliveries::Model>, DbErr>;
+
+    async fn update_delivery(
+        &self,
+        delivery: webhook_deliveries::ActiveModel,
+    ) -> Result<webhook_deliveries::Model, DbErr>;
+
+    /// Deliveries to an endpoint, newest first.
+    async fn list_deliveries(
+        &self,
+        endpoint_id: Uuid,
+        limit: u64,
+    ) -> Result<Vec<webhook_deliveries::Model>, DbErr>;
+}
+
+pub struct SeaOrmWebhookRepository {
+    db: Arc<DatabaseConnection>,
+}
+
+impl SeaOrmWebhookRepository {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl WebhookRepository for SeaOrmWebhookRepository {
+    async fn insert_endpoint(
+        &self,
+        endpoint: webhook_endpoints::ActiveModel,
+    ) -> Result<webhook_endpoints::Model, DbErr> {
+        endpoint.insert(self.db.as_ref()).await
+    }
+
+    async fn find_endpoint(
+        &self,
+        tenant_id: Uuid,
+        endpoint_id: Uuid,
+    ) -> Result<Option<webhook_endpoints::Model>, DbErr> {
+        webhook_endpoints::Entity::find_by_id(endpoint_id)
+            .filter(webhook_endpoints::Column::TenantId.eq(tenant_id))
+            .one(self.db.as_ref())
+            .await
+    }
+
+    async fn list_endpoints(
+        &self,
+        tenant_id: Uuid,
+    ) -> Result<Vec<webhook_endpoints::Model>, DbErr> {
+        webhook_endpoints::Entity::find()
+            .filter(webhook_endpoints::Column::TenantId.eq(tenant_id))
+            .order_by_asc(webhook_endpoints::Column::CreatedAt)
+            .all(self.db.as_ref())
+            .await
+    }
+
+    async fn delete_endpoint(&self, tenant_id: Uuid, endpoint_id: Uuid) -> Result<bool, DbErr> {
+        let result = webhook_endpoints::Entity::delete_many()
+            .filter(webhook_endpoints::Column::Id.eq(endpoint_id))
+            .filter(webhook_endpoints::Column::TenantId.eq(tenant_id))
+            .exec(self.db.as_ref())
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn insert_delivery(
+        &self,
+        delivery: webhook_deliveries::ActiveModel,
+    ) -> Result<webhook_deliveries::Model, DbErr> {
+        delivery.insert(self.db.as_ref()).await
+    }
+
+    async fn insert_deliveries(
+        &self,
+        deliveries: Vec<(webhook_deliveries::ActiveModel, jobs::ActiveModel)>,
+    ) -> Result<u64, DbErr> {
+        let txn = self.db.begin().await?;
+        let mut inserted = 0;
+        for (delivery, job) in deliveries {
+            let rows = webhook_deliveries::Entity::insert(delivery)
+                .on_conflict(
+                    OnConflict::columns([
+                        webhook_deliveries::Column::EndpointId,
+                        webhook_deliveries::Column::EventId,
+                    ])
+                    .target_and_where(webhook_deliveries::Column::RedeliveryOf.is_null())
+                    .do_nothing()
+                    .to_owned(),
+                )
+                .exec_without_returning(&txn)
+                .await?;
+            if rows > 0 {
+                job.insert(&txn).await?;
+                inserted += rows;
+            }
+        }
+        txn.commit().await?;
+        Ok(inserted)
+    }
+
+    async fn find_delivery(
+        &self,
+        delivery_id: Uuid,
+    ) -> Result<Option<webhook_deliveries::Model>, DbErr> {
+        webhook_deliveries::Entity::find_by_id(delivery_id)
+            .one(self.db.as_ref())
+            .await
+    }
+
+    async fn update_delivery(
+        &self,
+        delivery: webhook_deliveries::ActiveModel,
+    ) -> Result<webhook_deliveries::Model, DbErr> {
+        delivery.update(self.db.as_ref()).await
+    }
+
+    async fn list_deliveries(
+        &self,
+        endpoint_id: Uuid,
+        limit: u64,
+    ) -> Result<Vec<webhook_deliveries::Model>, DbErr> {
+        // Ids are UUIDv7, so they break ties between rows created within
+        // the same timestamp tick
+        webhook_deliveries::Entity::find()
+            .filter(webhook_deliveries::Column::EndpointId.eq(endpoint_id))
+            .order_by_desc(webhook_deliveries::Column::CreatedAt)
+            .order_by_desc(webhook_deliveries::Column::Id)
+            .limit(limit)
+            .all(self.db.as_ref())
+            .await
+    }
+}
@@ -1,24 +1,187 @@
+use crate::utils::http_client::HttpClient;
+use crate::utils::password_hash::PasswordHashing;
 use crate::{
     api_doc::ApiDoc,
     config::{Config, create_cors_layer},
-    handlers::{auth, health, tenants, users},
+    handlers::{api_keys, auth, health, oidc_providers, scim, tasks, tenants, users, webhooks},
     middleware::auth::AuthState,
-    middleware::tracing_middleware,
+    middleware::{error_format_middleware, locale_middleware, tracing_middleware},
+    repositories::{
+        SeaOrmApiKeyRepository, SeaOrmJobRepository, SeaOrmMembershipRepository,
+        SeaOrmOidcRepository, SeaOrmOutboxRepository, SeaOrmPasswordHistoryRepository,
+        SeaOrmTaskRunRepository, SeaOrmTenantExportRepository, SeaOrmTenantRepository,
+        SeaOrmTenantSettingsRepository, SeaOrmUserRepository, SeaOrmWebhookRepository,
+        TenantRepository, UserRepository,
+    },
+    services::{
+        api_keys_service::{ApiKeyService, ApiKeysService},
+        auth_service::{AuthService, AuthenticationService},
+        exports_service::{ExportService, ExportSources, TenantExporter},
+        health_service::HealthRegistry,
+        jobs_service::{JobQueue, JobsService},
+        maintenance_service::{Maintenance, MaintenanceService},
+        oidc_service::{self, OidcAuthService, OidcService},
+        password_policy_service::{PasswordPolicyService, PasswordsService},
+        scim_service::{ScimService, ScimUsersService},
+        tenant_settings_service::{TenantSettingsService, TenantSettingsStore},
+        tenants_service::{TenantService, TenantsService},
+        users_service::{UserService, UsersService},
+        webhooks_service::{WebhookService, Webhooks},
+    },
 };
 use axum::{
     Router,
     extract::FromRef,
-    routing::{get, post, put},
+    routing::{delete, get, post, put},
 };
 use sea_orm::DatabaseConnection;
 use std::sync::Arc;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
+/// Shared handler state. Services are held as trait objects so tests can
+/// swap in mocks without a database.
 #[derive(Clone)]
 pub struct AppState {
     pub db: Arc<DatabaseConnection>,
     pub config: Arc<Config>,
+    pub health: Arc<HealthRegistry>,
+    pub users: Arc<dyn UserService>,
+    pub tenants: Arc<dyn TenantService>,
+    pub tenant_settings: Arc<dyn TenantSettingsService>,
+    pub passwords: Arc<dyn PasswordPolicyService>,
+    pub auth: Arc<dyn AuthenticationService>,
+    pub api_keys: Arc<dyn ApiKeyService>,
+    pub oidc: Arc<dyn OidcService>,
+    pub scim: Arc<dyn ScimService>,
+    pub exports: Arc<dyn ExportService>,
+    pub jobs: Arc<dyn JobQueue>,
+    pub maintenance: Arc<dyn MaintenanceService>,
+    pub webhooks: Arc<dyn WebhookService>,
+}
+
+impl AppState {
+    /// Wires the SeaORM-backed repositories and the default services.
+    pub fn new(
+        db: Arc<DatabaseConnection>,
+        config: Arc<Config>,
+        health: Arc<HealthRegistry>,
+    ) -> Self {
+        let tenant_repository: Arc<dyn TenantRepository> =
+            Arc::new(SeaOrmTenantRepository::new(db.clone()));
+        let tenants = Arc::new(TenantsService::new(tenant_repository.clone()));
+        let tenant_settings_repository = Arc::new(SeaOrmTenantSettingsRepository::new(db.clone()));
+        let tenant_settings: Arc<dyn TenantSettingsService> = Arc::new(TenantSettingsStore::new(
+            tenant_settings_repository.clone(),
+            tenant_repository.clone(),
+        ));
+        let hashing = PasswordHashing::new(
+            config.password.argon2.clone(),
+            config.password.pepper.as_deref(),
+        );
+        let passwords: Arc<dyn PasswordPolicyService> = Arc::new(PasswordsService::new(
+            config.password.policy.clone(),
+            config.password.breached_passwords(),
+            tenant_settings.clone(),
+            tenant_repository.clone(),
+            Arc::new(SeaOrmPasswordHistoryRepository::new(db.clone())),
+            hashing.clone(),
+        ));
+        let jobs: Arc<dyn JobQueue> = Arc::new(JobsService::new(
+            Arc::new(SeaOrmJobRepository::new(db.clone())),
+            config.jobs.max_attempts,
+        ));
+        let webhooks: Arc<dyn WebhookService> = Arc::new(Webhooks::new(
+            Arc::new(SeaOrmWebhookRepository::new(db.clone())),
+            jobs.clone(),
+            HttpClient::new(
+                config.webhooks.timeout,
+                config.outbound_allow_private_addresses,
+            ),
+            config.webhooks.max_attempts,
+        ));
+
+        let user_repository: Arc<dyn UserRepository> =
+            Arc::new(SeaOrmUserRepository::new(db.clone()));
+        let membership_repository = Arc::new(SeaOrmMembershipRepository::new(db.clone()));
+        let users: Arc<dyn UserService> = Arc::new(UsersService::new(
+            user_repository.clone(),
+            membership_repository.clone(),
+            passwords.clone(),
+            hashing,
+            chrono::Duration::days(config.user_deletion_grace_days.into()),
+        ));
+        let auth: Arc<dyn AuthenticationService> = Arc::new(AuthService::new(
+            user_repository.clone(),
+            tenant_repository.clone(),
+            users.clone(),
+            tenant_settings.clone(),
+            passwords.clone(),
+            config.jwt_secret.clone(),
+            config.jwt_expiration_minutes,
+        ));
+
+        let api_key_repository = Arc::new(SeaOrmApiKeyRepository::new(db.clone()));
+        let api_keys = Arc::new(ApiKeysService::new(api_key_repository.clone()));
+
+        let oidc_repository = Arc::new(SeaOrmOidcRepository::new(db.clone()));
+        let oidc = Arc::new(OidcAuthService::new(
+            oidc_repository.clone(),
+            user_repository.clone(),
+            users.clone(),
+            auth.clone(),
+            HttpClient::new(
+                oidc_service::PROVIDER_TIMEOUT,
+                config.outbound_allow_private_addresses,
+            ),
+        ));
+
+        let scim = Arc::new(ScimUsersService::new(users.clone()));
+
+        let maintenance = Arc::new(Maintenance::new(
+            oidc_repository.clone(),
+            api_key_repository.clone(),
+            membership_repository.clone(),
+            tenant_repository.clone(),
+            tenant_settings.clone(),
+            Arc::new(SeaOrmTaskRunRepository::new(db.clone())),
+            Arc::new(SeaOrmOutboxRepository::new(db.clone())),
+        ));
+
+        let exports = Arc::new(TenantExporter::new(
+            Arc::new(SeaOrmTenantExportRepository::new(db.clone())),
+            ExportSources {
+                tenants: tenant_repository,
+                settings: tenant_settings_repository,
+                users: user_repository,
+                memberships: membership_repository,
+                api_keys: api_key_repository,
+                oidc: oidc_repository,
+            },
+            jobs.clone(),
+            config.export.dir.clone(),
+            config.jwt_secret.clone(),
+            chrono::Duration::minutes(config.export.link_ttl_minutes),
+        ));
+
+        Self {
+            db,
+            config,
+            health,
+            users,
+            tenants,
+            tenant_settings,
+            passwords,
+            auth,
+            api_keys,
+            oidc,
+            scim,
+            exports,
+            jobs,
+            maintenance,
+            webhooks,
+        }
+    }
 }
 
 impl FromRef<AppState> for Arc<DatabaseConnection> {
@@ -33,30 +196,112 @@ impl FromRef<AppState> for Arc<Config> {
     }
 }
 
-pub fn create_router(db: Arc<DatabaseConnection>, config: Arc<Config>) -> Router {
+impl FromRef<AppState> for Arc<HealthRegistry> {
+    fn from_ref(state: &AppState) -> Self {
+        state.health.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<dyn UserService> {
+    fn from_ref(state: &AppState) -> Self {
+        state.users.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<dyn TenantService> {
+    fn from_ref(state: &AppState) -> Self {
+        state.tenants.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<dyn TenantSettingsService> {
+    fn from_ref(state: &AppState) -> Self {
+        state.tenant_settings.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<dyn AuthenticationService> {
+    fn from_ref(state: &AppState) -> Self {
+        state.auth.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<dyn ApiKeyService> {
+    fn from_ref(state: &AppState) -> Self {
+        state.api_keys.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<dyn OidcService> {
+    fn from_ref(state: &AppState) -> Self {
+        state.oidc.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<dyn ScimService> {
+    fn from_ref(state: &AppState) -> Self {
+        state.scim.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<dyn ExportService> {
+    fn from_ref(state: &AppState) -> Self {
+        state.exports.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<dyn JobQueue> {
+    fn from_ref(state: &AppState) -> Self {
+        state.jobs.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<dyn MaintenanceService> {
+    fn from_ref(state: &AppState) -> Self {
+        state.maintenance.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<dyn WebhookService> {
+    fn from_ref(state: &AppState) -> Self {
+        state.webhooks.clone()
+    }
+}
+
+pub fn create_router(app_state: AppState) -> Router {
     let auth_state = Arc::new(AuthState {
-        secret: config.jwt_secret.clone(),
-        bearer_token: config.jwt_secret.clone(),
+        secret: app_state.config.jwt_secret.clone(),
+        bearer_token: app_state.config.jwt_secret.clone(),
     });
 
-    let app_state = AppState {
-        db,
-        config: config.clone(),
-    };
-
-    let cors = create_cors_layer(&config);
+    let cors = create_cors_layer(&app_state.config);
 
     let auth_routes = Router::new()
         .route("/api/auth/register", post(auth::register))
         .route("/api/auth/login", post(auth::login))
-        .route("/api/auth/refresh", post(auth::refresh));
+        .route("/api/auth/refresh", post(auth::refresh))
+        .route("/api/auth/switch-tenant", post(auth::switch_tenant))
+        .route(
+            "/api/auth/oidc/{provider_id}/authorize",
+            post(auth::oidc_authorize),
+        )
+        .route("/api/auth/oidc/callback", post(auth::oidc_callback));
 
     let public_routes = Router::new()
         .route("/health", get(health::health_check))
-        .route("/api/tenants", get(tenants::list_tenants));
+        .route("/health/live", get(health::liveness))
+        .route("/health/ready", get(health::readiness))
+        .route("/api/tenants", get(tenants::list_tenants))
+        .route(
+            "/api/exports/{export_id}/download",
+            get(tenants::download_export),
+        );
 
     let authenticated_routes = Router::new()
+        .route("/health/details", get(health::health_details))
         .route("/api/me", get(users::me))
+        .route("/api/me/locale", put(users::set_locale))
+        .route("/api/me/password", put(users::change_password))
         .route("/api/tenants/{tenant_id}", get(tenants::get_tenant))
         .route(
             "/api/tenants/{tenant_id}/users/{user_id}",
@@ -65,6 +310,27 @@ pub fn create_router(db: Arc<DatabaseConnection>, config: Arc<Config>) -> Router
 
     let admin_routes = Router::new()
         .route("/api/tenants/{tenant_id}/users", get(users::get_users))
+        .route("/api/tenants/{tenant_id}/members", post(users::add_member))
+        .route(
+            "/api/tenants/{tenant_id}/exports",
+            post(tenants::request_export),
+        )
+        .route(
+            "/api/tenants/{tenant_id}/exports/{export_id}",
+            get(tenants::get_export),
+        )
+        .route(
+            "/api/tenants/{tenant_id}/settings",
+            get(tenants::get_settings).put(tenants::update_settings),
+        )
+        .route(
+            "/api/tenants/{tenant_id}/users/{user_id}",
+            delete(users::delete_user),
+        )
+        .route(
+            "/api/tenants/{tenant_id}/users/{user_id}/restore",
+            post(users::restore_user),
+        )
         .route(
             "/api/tenants/{tenant_id}/users/{user_id}/change-status",
             put(users::change_user_status),
@@ -72,6 +338,72 @@ pub fn create_router(db: Arc<DatabaseConnection>, config: Arc<Config>) -> Router
         .route(
             "/api/tenants/{tenant_id}/users/{user_id}/change-role",
             put(users::change_role),
+        )
+        .route(
+            "/api/tenants/{tenant_id}/service-accounts",
+            get(api_keys::list_service_accounts).post(api_keys::create_service_account),
+        )
+        .route(
+            "/api/tenants/{tenant_id}/service-accounts/{service_account_id}/api-keys",
+            post(api_keys::create_api_key),
+        )
+        .route(
+            "/api/tenants/{tenant_id}/api-keys",
+            get(api_keys::list_api_keys),
+        )
+        .route(
+            "/api/tenants/{tenant_id}/api-keys/{key_id}",
+            delete(api_keys::revoke_api_key),
+        )
+        .route(
+            "/api/tenants/{tenant_id}/api-keys/{key_id}/rotate",
+            post(api_keys::rotate_api_key),
+        )
+        .route(
+            "/api/tenants/{tenant_id}/oidc-providers",
+            get(oidc_providers::list_oidc_providers).post(oidc_providers::create_oidc_provider),
+        )
+        .route(
+            "/api/tenants/{tenant_id}/oidc-providers/{provider_id}",
+            delete(oidc_providers::delete_oidc_provider),
+        )
+        .route(
+            "/api/tenants/{tenant_id}/webhooks",
+            get(webhooks::list_webhooks).post(webhooks::create_webhook),
+        )
+        .route(
+            "/api/tenants/{tenant_id}/webhooks/{webhook_id}",
+            delete(webhooks::delete_webhook),
+        )
+        .route(
+            "/api/tenants/{tenant_id}/webhooks/{webhook_id}/ping",
+            post(webhooks::ping_webhook),
+        )
+        .route(
+            "/api/tenants/{tenant_id}/webhooks/{webhook_id}/deliveries",
+            get(webhooks::list_webhook_deliveries),
+        )
+        .route(
+            "/api/tenants/{tenant_id}/webhooks/{webhook_id}/deliveries/{delivery_id}/redeliver",
+            post(webhooks::redeliver_webhook),
+        )
+        .route("/api/tasks/runs", get(tasks::list_task_runs));
+
+    let scim_routes = Router::new()
+        .route(
+            "/scim/v2/ServiceProviderConfig",
+            get(scim::service_provider_config),
+        )
+        .route(
+            "/scim/v2/Users",
+            get(scim::list_users).post(scim::create_user),
+        )
+        .route(
+            "/scim/v2/Users/{user_id}",
+            get(scim::get_user)
+                .put(scim::replace_user)
+                .patch(scim::patch_user)
+                .delete(scim::delete_user),
         );
 
     Router::new()
@@ -80,6 +412,9 @@ pub fn create_router(db: Arc<DatabaseConnection>, config: Arc<Config>) -> Router
         .merge(auth_routes)
         .merge(authenticated_routes)
         .merge(admin_routes)
+        .merge(scim_routes)
+        .layer(axum::middleware::from_fn(locale_middleware))
+        .layer(axum::middleware::from_fn(error_format_middleware))
         .layer(axum::middleware::from_fn(tracing_middleware))
         .layer(cors)
         .layer(axum::Extension(auth_state))
@@ -0,0 +1,233 @@
+//! Maintenance tasks run on cron schedules. Every replica runs the
+//! scheduler; a Postgres advisory lock per task, and the run recorded for
+//! each tick of its schedule, make sure a tick is run by one replica only.
+
+pub mod tasks;
+
+use crate::enums::TaskRunStatus;
+use crate::models::task_runs;
+use crate::repositories::TaskRunRepository;
+use crate::routes::AppState;
+use crate::utils::cron::Schedule;
+use crate::utils::error::AppError;
+use crate::utils::shutdown::Shutdown;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::Set;
+use std::sync::Arc;
+use tokio::task::JoinSet;
+use uuid::Uuid;
+
+/// A unit of housekeeping run on a schedule.
+#[async_trait]
+pub trait ScheduledTask: Send + Sync + 'static {
+    /// Recorded with each run; also names the task's lock.
+    fn name(&self) -> &'static str;
+
+    /// Does the work and returns how many rows it changed.
+    async fn run(&self) -> Result<u64, AppError>;
+}
+
+struct Entry {
+    schedule: Schedule,
+    task: Arc<dyn ScheduledTask>,
+}
+
+/// Runs each registered task at the ticks of its schedule.
+pub struct Scheduler {
+    runs: Arc<dyn TaskRunRepository>,
+    entries: Vec<Entry>,
+    id: String,
+}
+
+impl Scheduler {
+    pub fn new(runs: Arc<dyn TaskRunRepository>) -> Self {
+        Self {
+            runs,
+            entries: Vec::new(),
+            id: format!("scheduler-{}", Uuid::now_v7()),
+        }
+    }
+
+    /// Panics if a task with the same name is already added.
+    pub fn add<T: ScheduledTask>(mut self, schedule: Schedule, task: T) -> Self {
+        assert!(
+            self.entries
+                .iter()
+                .all(|entry| entry.task.name() != task.name()),
+            "task '{}' added twice",
+            task.name()
+        );
+        self.entries.push(Entry {
+            schedule,
+            task: Arc::new(task),
+        });
+        self
+    }
+
+    /// Name recorded as the `runner` of the runs this scheduler starts.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Runs tasks as their ticks come until shutdown, then waits for the
+    /// runs in progress. Ticks missed while the process was down are not
+    /// made up for.
+    pub async fn run(self, shutdown: Shutdown) {
+        let scheduler = Arc::new(self);
+        let mut next: Vec<Option<DateTime<Utc>>> = scheduler
+            .entries
+            .iter()
+            .map(|entry| entry.schedule.next_after(Utc::now()))
+            .collect();
+        for (entry, next) in scheduler.entries.iter().zip(&next) {
+            tracing::info!(
+                task = entry.task.name(),
+                schedule = %entry.schedule,
+                next = ?next,
+                "Task scheduled"
+            );
+        }
+
+        let mut running = JoinSet::new();
+        while let Some(due) = next.iter().flatten().min().copied() {
+            let wait = (due - Utc::now()).to_std().unwrap_or_default();
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {}
+                _ = shutdown.wait() => break,
+            }
+            // Reap finished runs so the set does not grow
+            while running.try_join_next().is_some() {}
+
+            for (index, tick) in next.iter_mut().enumerate() {
+                let Some(scheduled_for) = *tick else {
+                    continue;
+                };
+                if scheduled_for > due {
+                    continue;
+                }
+                let runner = scheduler.clone();
+                running.spawn(async move {
+                    let task = runner.entries[index].task.name();
+                    if let Err(e) = runner.run_tick(index, scheduled_for).await {
+                        tracing::error!(task, "Failed to run scheduled task: {}", e);
+                    }
+                });
+                *tick = scheduler.entries[index]
+                    .schedule
+                    .next_after(scheduled_for.max(Utc::now()));
+            }
+        }
+
+        running.join_all().await;
+        tracing::info!(scheduler = %scheduler.id, "Scheduler stopped");
+    }
+
+    /// Runs the task named `task` for its tick at `scheduled_for`, unless
+    /// another replica is running it or already has. Returns the finished
+    /// run, if this call ran the task.
+    pub async fn run_due(
+        &self,
+        task: &str,
+        scheduled_for: DateTime<Utc>,
+    ) -> Result<Option<task_runs::Model>, AppError> {
+        let index = self
+            .entries
+            .iter()
+            .position(|entry| entry.task.name() == task)
+            .ok_or(AppError::Internal)?;
+        self.run_tick(index, scheduled_for).await
+    }
+
+    async fn run_tick(
+        &self,
+        index: usize,
+        scheduled_for: DateTime<Utc>,
+    ) -> Result<Option<task_runs::Model>, AppError> {
+        let task = self.entries[index].task.clone();
+        let Some(lock) = self.runs.try_lock(task.name()).await? else {
+            tracing::debug!(task = task.name(), "Task is running on another replica");
+            return Ok(None);
+        };
+        let result = self.run_locked(task, scheduled_for).await;
+        lock.release().await?;
+        result
+    }
+
+    async fn run_locked(
+        &self,
+        task: Arc<dyn ScheduledTask>,
+        scheduled_for: DateTime<Utc>,
+    ) -> Result<Option<task_runs::Model>, AppError> {
+        let name = task.name();
+        let scheduled_for = scheduled_for.fixed_offset();
+        if self.runs.find(name, scheduled_for).await?.is_some() {
+            tracing::debug!(task = name, "Task already ran on another replica");
+            return Ok(None);
+        }
+
+        let run = self
+            .runs
+            .insert(task_runs::ActiveModel {
+                id: Set(Uuid::now_v7()),
+                task: Set(name.to_string()),
+                scheduled_for: Set(scheduled_for),
+                status: Set(TaskRunStatus::Running),
+                runner: Set(self.id.clone()),
+                affected: Set(None),
+                error: Set(None),
+                started_at: Set(Utc::now().fixed_offset()),
+                finished_at: Set(None),
+            })
+            .await?;
+
+        // Its own task, so a panic fails the run instead of leaving it running
+        let outcome = tokio::spawn(async move { task.run().await })
+            .await
+            .unwrap_or_else(|e| {
+                tracing::error!(task = name, "Task panicked: {}", e);
+                Err(AppError::Internal)
+            });
+
+        let mut finished: task_runs::ActiveModel = run.into();
+        finished.finished_at = Set(Some(Utc::now().fixed_offset()));
+        match outcome {
+            Ok(affected) => {
+                tracing::info!(task = name, affected, "Task succeeded");
+                finished.status = Set(TaskRunStatus::Succeeded);
+                finished.affected = Set(Some(affected.try_into().unwrap_or(i64::MAX)));
+            }
+            Err(e) => {
+                tracing::warn!(task = name, "Task failed: {}", e);
+                finished.status = Set(TaskRunStatus::Failed);
+                finished.error = Set(Some(e.to_string()));
+            }
+        }
+        Ok(Some(self.runs.update(finished).await?))
+    }
+}
+
+/// The scheduler with every built-in task, on the configured schedules.
+pub fn scheduler(state: &AppState, runs: Arc<dyn TaskRunRepository>) -> Scheduler {
+    let config = &state.config.scheduler;
+    Scheduler::new(runs)
+        .add(
+            config.erase_deleted_users.clone(),
+            tasks::EraseDeletedUsers::new(state.users.clone()),
+        )
+        .add(
+            config.purge_auth_artifacts.clone(),
+            tasks::PurgeAuthArtifacts::new(state.maintenance.clone()),
+        )
+        .add(
+            config.deactivate_idle_members.clone(),
+            tasks::DeactivateIdleMembers::new(state.maintenance.clone()),
+        )
+        .add(
+            config.purge_outbox.clone(),
+            tasks::PurgeOutbox::new(
+                state.maintenance.clone(),
+                chrono::Duration::days(state.config.outbox.retention_days.into()),
+            ),
+        )
+}
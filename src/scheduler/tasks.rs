@@ -0,0 +1,99 @@
+use crate::scheduler::ScheduledTask;
+use crate::services::maintenance_service::MaintenanceService;
+use crate::services::users_service::UserService;
+use crate::utils::error::AppError;
+use async_trait::async_trait;
+use chrono::Duration;
+use std::sync::Arc;
+
+/// Erases the personal data of users whose deletion grace period is over.
+pub struct EraseDeletedUsers {
+    users: Arc<dyn UserService>,
+}
+
+impl EraseDeletedUsers {
+    pub fn new(users: Arc<dyn UserService>) -> Self {
+        Self { users }
+    }
+}
+
+#[async_trait]
+impl ScheduledTask for EraseDeletedUsers {
+    fn name(&self) -> &'static str {
+        "erase_deleted_users"
+    }
+
+    async fn run(&self) -> Result<u64, AppError> {
+        Ok(self.users.erase_deleted().await? as u64)
+    }
+}
+
+/// Deletes expired SSO login states and long-ended API keys.
+pub struct PurgeAuthArtifacts {
+    maintenance: Arc<dyn MaintenanceService>,
+}
+
+impl PurgeAuthArtifacts {
+    pub fn new(maintenance: Arc<dyn MaintenanceService>) -> Self {
+        Self { maintenance }
+    }
+}
+
+#[async_trait]
+impl ScheduledTask for PurgeAuthArtifacts {
+    fn name(&self) -> &'static str {
+        "purge_auth_artifacts"
+    }
+
+    async fn run(&self) -> Result<u64, AppError> {
+        self.maintenance.purge_expired_auth_artifacts().await
+    }
+}
+
+/// Deactivates members idle beyond their tenant's `idle_deactivation_days`.
+pub struct DeactivateIdleMembers {
+    maintenance: Arc<dyn MaintenanceService>,
+}
+
+impl DeactivateIdleMembers {
+    pub fn new(maintenance: Arc<dyn MaintenanceService>) -> Self {
+        Self { maintenance }
+    }
+}
+
+#[async_trait]
+impl ScheduledTask for DeactivateIdleMembers {
+    fn name(&self) -> &'static str {
+        "deactivate_idle_members"
+    }
+
+    async fn run(&self) -> Result<u64, AppError> {
+        self.maintenance.deactivate_idle_members().await
+    }
+}
+
+/// Deletes domain events relayed longer than `retention` ago.
+pub struct PurgeOutbox {
+    maintenance: Arc<dyn MaintenanceService>,
+    retention: Duration,
+}
+
+impl PurgeOutbox {
+    pub fn new(maintenance: Arc<dyn MaintenanceService>, retention: Duration) -> Self {
+        Self {
+            maintenance,
+            retention,
+        }
+    }
+}
+
+#[async_trait]
+impl ScheduledTask for PurgeOutbox {
+    fn name(&self) -> &'static str {
+        "purge_outbox"
+    }
+
+    async fn run(&self) -> Result<u64, AppError> {
+        self.maintenance.purge_relayed_events(self.retention).await
+    }
+}
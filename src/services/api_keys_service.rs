@@ -0,0 +1,314 @@
+use crate::enums::{ApiScope, UserRole};
+use crate::middleware::auth::{Principal, PrincipalKind};
+use crate::models::{api_keys, service_accounts};
+use crate::repositories::ApiKeyRepository;
+use crate::utils::error::{AppError, AuthError};
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, FixedOffset, Utc};
+use sea_orm::Set;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Every API key starts with this, so it can be told apart from a JWT.
+pub const API_KEY_PREFIX: &str = "sk_";
+
+/// `last_used_at` is only written when older than this, so a busy key does
+/// not turn every request into a write.
+const LAST_USED_RESOLUTION: Duration = Duration::minutes(1);
+
+/// A newly created key. `secret` is the full key and is never shown again.
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct IssuedApiKey {
+    pub secret: String,
+    pub key: api_keys::Model,
+}
+
+#[async_trait]
+pub trait ApiKeyService: Send + Sync {
+    /// `caller` must hold `role` itself.
+    async fn create_service_account(
+        &self,
+        caller: &Principal,
+        tenant_id: Uuid,
+        name: String,
+        role: UserRole,
+    ) -> Result<service_accounts::Model, AppError>;
+
+    async fn list_service_accounts(
+        &self,
+        tenant_id: Uuid,
+    ) -> Result<Vec<service_accounts::Model>, AppError>;
+
+    /// `caller` must hold the service account's role and every one of
+    /// `scopes`.
+    async fn create_key(
+        &self,
+        caller: &Principal,
+        tenant_id: Uuid,
+        service_account_id: Uuid,
+        name: String,
+        scopes: Vec<ApiScope>,
+        expires_at: Option<DateTime<FixedOffset>>,
+    ) -> Result<IssuedApiKey, AppError>;
+
+    async fn list_keys(&self, tenant_id: Uuid) -> Result<Vec<api_keys::Model>, AppError>;
+
+    /// Issues a replacement with the same name and scopes, and revokes the
+    /// old key. A key with an expiry gets the same lifetime again, counted
+    /// from now. Revoked and expired keys cannot be rotated, and `caller`
+    /// must be allowed to create the replacement.
+    async fn rotate_key(
+        &self,
+        caller: &Principal,
+        tenant_id: Uuid,
+        key_id: Uuid,
+    ) -> Result<IssuedApiKey, AppError>;
+
+    async fn revoke_key(&self, tenant_id: Uuid, key_id: Uuid) -> Result<api_keys::Model, AppError>;
+
+    /// Resolves a presented key to its service account.
+    async fn authenticate(&self, secret: &str) -> Result<Principal, AppError>;
+}
+
+pub struct ApiKeysService {
+    keys: Arc<dyn ApiKeyRepository>,
+}
+
+impl ApiKeysService {
+    pub fn new(keys: Arc<dyn ApiKeyRepository>) -> Self {
+        Self { keys }
+    }
+
+    /// Generates `sk_<12 hex>_<64 hex>`; the part before the second `_` is
+    /// the public prefix stored in clear.
+    pub fn generate_secret() -> (String, String) {
+        let mut id = [0u8; 6];
+        let mut secret = [0u8; 32];
+        OsRng.fill_bytes(&mut id);
+        OsRng.fill_bytes(&mut secret);
+        let prefix = format!("{}{}", API_KEY_PREFIX, hex::encode(id));
+        let full = format!("{}_{}", prefix, hex::encode(secret));
+        (prefix, full)
+    }
+
+    /// Keys carry 256 bits of entropy, so a fast hash is enough.
+    pub fn hash_secret(secret: &str) -> String {
+        hex::encode(Sha256::digest(secret.as_bytes()))
+    }
+
+    fn prefix_of(secret: &str) -> Option<&str> {
+        let rest = secret.strip_prefix(API_KEY_PREFIX)?;
+        let (id, _) = rest.split_once('_')?;
+        Some(&secret[..API_KEY_PREFIX.len() + id.len()])
+    }
+
+    async fn insert_key(
+        &self,
+        tenant_id: Uuid,
+        service_account_id: Uuid,
+        name: String,
+        scopes: String,
+        expires_at: Option<DateTime<FixedOffset>>,
+    ) -> Result<IssuedApiKey, AppError> {
+        let (key, secret) = Self::new_key(tenant_id, service_account_id, name, scopes, expires_at);
+        let key = self.keys.insert_key(key).await?;
+        tracing::info!(
+            "API key created: key_id={}, service_account_id={}, tenant_id={}",
+            key.id,
+            key.service_account_id,
+            key.tenant_id
+        );
+        Ok(IssuedApiKey { secret, key })
+    }
+
+    /// A key with a fresh secret, returned along with it.
+    fn new_key(
+        tenant_id: Uuid,
+        service_account_id: Uuid,
+        name: String,
+        scopes: String,
+        expires_at: Option<DateTime<FixedOffset>>,
+    ) -> (api_keys::ActiveModel, String) {
+        let (prefix, secret) = Self::generate_secret();
+        let key = api_keys::ActiveModel {
+            id: Set(Uuid::now_v7()),
+            tenant_id: Set(tenant_id),
+            service_account_id: Set(service_account_id),
+            name: Set(name),
+            prefix: Set(prefix),
+            key_hash: Set(Self::hash_secret(&secret)),
+            scopes: Set(scopes),
+            expires_at: Set(expires_at),
+            last_used_at: Set(None),
+            revoked_at: Set(None),
+            created_at: Set(Utc::now().fixed_offset()),
+        };
+        (key, secret)
+    }
+}
+
+#[async_trait]
+impl ApiKeyService for ApiKeysService {
+    async fn create_service_account(
+        &self,
+        caller: &Principal,
+        tenant_id: Uuid,
+        name: String,
+        role: UserRole,
+    ) -> Result<service_accounts::Model, AppError> {
+        caller.require_grant(role, &[])?;
+
+        let account = service_accounts::ActiveModel {
+            id: Set(Uuid::now_v7()),
+            tenant_id: Set(tenant_id),
+            name: Set(name),
+            role: Set(role),
+            created_at: Set(Utc::now().fixed_offset()),
+        };
+
+        Ok(self.keys.insert_service_account(account).await?)
+    }
+
+    async fn list_service_accounts(
+        &self,
+        tenant_id: Uuid,
+    ) -> Result<Vec<service_accounts::Model>, AppError> {
+        Ok(self.keys.list_service_accounts(tenant_id).await?)
+    }
+
+    async fn create_key(
+        &self,
+        caller: &Principal,
+        tenant_id: Uuid,
+        service_account_id: Uuid,
+        name: String,
+        scopes: Vec<ApiScope>,
+        expires_at: Option<DateTime<FixedOffset>>,
+    ) -> Result<IssuedApiKey, AppError> {
+        let account = self
+            .keys
+            .find_service_account(tenant_id, service_account_id)
+            .await?
+            .ok_or(AppError::ServiceAccountNotFound)?;
+        caller.require_grant(account.role, &scopes)?;
+
+        self.insert_key(
+            tenant_id,
+            service_account_id,
+            name,
+            ApiScope::join(&scopes),
+            expires_at,
+        )
+        .await
+    }
+
+    async fn list_keys(&self, tenant_id: Uuid) -> Result<Vec<api_keys::Model>, AppError> {
+        Ok(self.keys.list_keys(tenant_id).await?)
+    }
+
+    async fn rotate_key(
+        &self,
+        caller: &Principal,
+        tenant_id: Uuid,
+        key_id: Uuid,
+    ) -> Result<IssuedApiKey, AppError> {
+        let old = self
+            .keys
+            .find_key(tenant_id, key_id)
+            .await?
+            .ok_or(AppError::ApiKeyNotFound)?;
+        let now = Utc::now().fixed_offset();
+        if old.revoked_at.is_some() || old.expires_at.is_some_and(|expires_at| expires_at <= now) {
+            return Err(AppError::ApiKeyInactive);
+        }
+        let account = self
+            .keys
+            .find_service_account(tenant_id, old.service_account_id)
+            .await?
+            .ok_or(AppError::ServiceAccountNotFound)?;
+        caller.require_grant(account.role, &ApiScope::parse_list(&old.scopes))?;
+
+        let expires_at = old
+            .expires_at
+            .map(|expires_at| now + (expires_at - old.created_at));
+        let (key, secret) = Self::new_key(
+            old.tenant_id,
+            old.service_account_id,
+            old.name.clone(),
+            old.scopes.clone(),
+            expires_at,
+        );
+        let mut revoked: api_keys::ActiveModel = old.into();
+        revoked.revoked_at = Set(Some(now));
+
+        // Both or neither, so a failed rotation leaves the old key working
+        let key = self.keys.rotate_key(revoked, key).await?;
+        tracing::info!(
+            "API key rotated: key_id={}, new_key_id={}, tenant_id={}",
+            key_id,
+            key.id,
+            key.tenant_id
+        );
+        Ok(IssuedApiKey { secret, key })
+    }
+
+    async fn revoke_key(&self, tenant_id: Uuid, key_id: Uuid) -> Result<api_keys::Model, AppError> {
+        let key = self
+            .keys
+            .find_key(tenant_id, key_id)
+            .await?
+            .ok_or(AppError::ApiKeyNotFound)?;
+        if key.revoked_at.is_some() {
+            return Ok(key);
+        }
+
+        let mut key: api_keys::ActiveModel = key.into();
+        key.revoked_at = Set(Some(Utc::now().fixed_offset()));
+        let key = self.keys.update_key(key).await?;
+        tracing::info!(
+            "API key revoked: key_id={}, tenant_id={}",
+            key.id,
+            key.tenant_id
+        );
+        Ok(key)
+    }
+
+    async fn authenticate(&self, secret: &str) -> Result<Principal, AppError> {
+        let prefix = Self::prefix_of(secret).ok_or(AuthError::InvalidToken)?;
+        let (key, account) = self
+            .keys
+            .find_key_by_prefix(prefix)
+            .await?
+            .ok_or(AuthError::InvalidToken)?;
+
+        if key.key_hash != Self::hash_secret(secret) || key.revoked_at.is_some() {
+            tracing::warn!("Rejected API key: key_id={}", key.id);
+            return Err(AuthError::InvalidToken.into());
+        }
+
+        let now = Utc::now().fixed_offset();
+        if key.expires_at.is_some_and(|expires_at| expires_at <= now) {
+            return Err(AuthError::ExpiredToken.into());
+        }
+
+        if key
+            .last_used_at
+            .is_none_or(|last_used| now - last_used >= LAST_USED_RESOLUTION)
+        {
+            self.keys.touch_key(key.id, now).await?;
+        }
+
+        Ok(Principal {
+            tenant_id: key.tenant_id,
+            role: account.role,
+            kind: PrincipalKind::ServiceAccount {
+                service_account_id: account.id,
+                api_key_id: key.id,
+                scopes: ApiScope::parse_list(&key.scopes),
+            },
+        })
+    }
+}
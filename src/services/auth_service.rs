@@ -1,13 +1,15 @@
-use crate::enums::{UserRole, UserStatus};
-use crate::models::users;
-use crate::utils::error::AppError;
-use argon2::password_hash::SaltString;
-use argon2::password_hash::rand_core::OsRng;
-use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
+use crate::enums::{Locale, UserRole, UserStatus};
+use crate::models::{memberships, users};
+use crate::repositories::{TenantRepository, UserRepository};
+use crate::services::password_policy_service::PasswordPolicyService;
+use crate::services::tenant_settings_service::TenantSettingsService;
+use crate::services::users_service::UserService;
+use crate::utils::error::{AppError, AuthError};
+use async_trait::async_trait;
 use chrono::{Duration, Utc};
 use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
-use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use uuid::Uuid;
 use validator::Validate;
 
@@ -18,69 +20,102 @@ pub struct Claims {
     pub email: String,
     pub role: UserRole,
     pub exp: i64,
+    /// Preferred locale for messages: the user's own, else the tenant default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<Locale>,
+    /// Set when the user signed in through `tenant_id`'s identity provider,
+    /// which vouches for them in that tenant only: the token cannot be used
+    /// for, or switched to, the user's other tenants.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub tenant_bound: bool,
 }
 
 #[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
 pub struct RegisterRequest {
     pub tenant_id: Uuid,
-    #[validate(email(message = "Invalid email format"))]
+    #[validate(email(message = "email_invalid"))]
     pub email: String,
-    #[validate(length(
-        min = 8,
-        max = 100,
-        message = "Password must be between 8 and 100 characters"
-    ))]
+    #[validate(length(min = 8, max = 100, message = "password_length"))]
     pub password: String,
 }
 
 #[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
 pub struct LoginRequest {
-    #[validate(email(message = "Invalid email format"))]
+    #[validate(email(message = "email_invalid"))]
     pub email: String,
-    #[validate(length(min = 1, message = "Password is required"))]
+    #[validate(length(min = 1, message = "password_required"))]
     pub password: String,
 }
 
+#[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
+pub struct SwitchTenantRequest {
+    pub tenant_id: Uuid,
+}
+
+/// A token for `user` as a member of `user.tenant_id`, and every tenant the
+/// user belongs to.
 #[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct AuthResponse {
     pub token: String,
     pub user: users::Model,
+    pub memberships: Vec<memberships::Model>,
 }
 
-pub struct AuthService;
+#[async_trait]
+pub trait AuthenticationService: Send + Sync {
+    async fn register(&self, req: RegisterRequest) -> Result<AuthResponse, AppError>;
 
-impl AuthService {
-    pub fn hash_password(password: &str) -> Result<String, AppError> {
-        let salt = SaltString::generate(&mut OsRng);
-        let argon2 = Argon2::default();
-        let password_hash = argon2
-            .hash_password(password.as_bytes(), &salt)
-            .map_err(|_| AppError::Internal)?
-            .to_string();
-        Ok(password_hash)
-    }
+    async fn login(&self, req: LoginRequest) -> Result<AuthResponse, AppError>;
 
-    pub fn verify_password(password: &str, hash: &str) -> Result<bool, AppError> {
-        let parsed_hash = PasswordHash::new(hash).map_err(|_| AppError::Internal)?;
-        let argon2 = Argon2::default();
-        Ok(argon2
-            .verify_password(password.as_bytes(), &parsed_hash)
-            .is_ok())
-    }
+    /// Issues a new token for the same tenant as `claims`.
+    async fn refresh_token(&self, claims: Claims) -> Result<AuthResponse, AppError>;
+
+    /// Issues a token for another tenant the user is an active member of.
+    async fn switch_tenant(
+        &self,
+        claims: Claims,
+        tenant_id: Uuid,
+    ) -> Result<AuthResponse, AppError>;
 
+    /// Issues a token bound to `user.tenant_id` for a user authenticated by
+    /// that tenant's identity provider.
+    async fn sign_in(&self, user: users::Model) -> Result<AuthResponse, AppError>;
+}
+
+/// Issues JWTs for registration, login and refresh. The token helpers are
+/// associated functions so they can be used without an instance.
+///
+/// Tenant settings decide whether registration is open and, when set, the
+/// token lifetime; `expiration_minutes` applies to tenants that leave it
+/// unset. New passwords must satisfy the tenant's password policy.
+pub struct AuthService {
+    users: Arc<dyn UserRepository>,
+    tenants: Arc<dyn TenantRepository>,
+    user_service: Arc<dyn UserService>,
+    settings: Arc<dyn TenantSettingsService>,
+    passwords: Arc<dyn PasswordPolicyService>,
+    jwt_secret: String,
+    expiration_minutes: i64,
+}
+
+impl AuthService {
     pub fn verify_token(token: &str, secret: &str) -> Result<Claims, AppError> {
-        tracing::debug!("Verifying token with secret length: {}", secret.len());
         let token_data = decode::<Claims>(
             token,
             &DecodingKey::from_secret(secret.as_ref()),
             &Validation::default(),
         )
-        .map_err(|_| AppError::Internal)?;
+        .map_err(|e| {
+            tracing::warn!("JWT token verification failed: {:?}", e);
+            match e.kind() {
+                jsonwebtoken::errors::ErrorKind::ExpiredSignature => AuthError::ExpiredToken,
+                _ => AuthError::InvalidToken,
+            }
+        })?;
         tracing::debug!(
-            "Token decoded successfully, claims: user_id={}, tenant_id={}, email={}",
+            "Token decoded successfully, claims: user_id={}, tenant_id={}",
             token_data.claims.user_id,
-            token_data.claims.tenant_id,
-            token_data.claims.email
+            token_data.claims.tenant_id
         );
         Ok(token_data.claims)
     }
@@ -100,102 +135,155 @@ impl AuthService {
             email,
             role,
             exp,
+            locale: None,
+            tenant_bound: false,
         };
-        let token = encode(
+        Self::sign(&claims, secret)
+    }
+
+    pub fn sign(claims: &Claims, secret: &str) -> Result<String, AppError> {
+        encode(
             &Header::default(),
-            &claims,
+            claims,
             &EncodingKey::from_secret(secret.as_ref()),
         )
-        .map_err(|_| AppError::Internal)?;
-        Ok(token)
+        .map_err(|e| {
+            tracing::error!("JWT encoding failed: {}", e);
+            AppError::Internal
+        })
     }
+}
 
-    pub async fn register(
-        db: &DatabaseConnection,
-        req: RegisterRequest,
-        secret: &str,
+impl AuthService {
+    pub fn new(
+        users: Arc<dyn UserRepository>,
+        tenants: Arc<dyn TenantRepository>,
+        user_service: Arc<dyn UserService>,
+        settings: Arc<dyn TenantSettingsService>,
+        passwords: Arc<dyn PasswordPolicyService>,
+        jwt_secret: String,
         expiration_minutes: i64,
+    ) -> Self {
+        Self {
+            users,
+            tenants,
+            user_service,
+            settings,
+            passwords,
+            jwt_secret,
+            expiration_minutes,
+        }
+    }
+
+    /// `user` is read as a member of the tenant the token is for.
+    async fn issue(
+        &self,
+        user: users::Model,
+        tenant_bound: bool,
     ) -> Result<AuthResponse, AppError> {
-        let existing_user = users::Entity::find()
-            .filter(users::Column::Email.eq(&req.email))
-            .filter(users::Column::TenantId.eq(req.tenant_id))
-            .one(db)
+        let locale = match user.locale {
+            Some(locale) => Some(locale),
+            None => self
+                .tenants
+                .find_by_id(user.tenant_id)
+                .await?
+                .and_then(|tenant| tenant.default_locale),
+        };
+        let expiration_minutes = self
+            .settings
+            .get(user.tenant_id)
+            .await?
+            .settings
+            .jwt_expiration_minutes
+            .unwrap_or(self.expiration_minutes);
+        let claims = Claims {
+            user_id: user.id,
+            tenant_id: user.tenant_id,
+            email: user.email.clone(),
+            role: user.role,
+            exp: (Utc::now() + Duration::minutes(expiration_minutes)).timestamp(),
+            locale,
+            tenant_bound,
+        };
+        let token = Self::sign(&claims, &self.jwt_secret)?;
+        self.user_service
+            .record_login(user.tenant_id, user.id)
             .await?;
+        let memberships = self.user_service.list_memberships(user.id).await?;
 
-        if existing_user.is_some() {
-            return Err(AppError::UserAlreadyExists);
-        }
-
-        let role = Self::determine_user_role(db, req.tenant_id).await?;
+        Ok(AuthResponse {
+            token,
+            user,
+            memberships,
+        })
+    }
 
-        let password_hash = Self::hash_password(&req.password)?;
+    /// The first user of a tenant becomes its admin.
+    async fn determine_user_role(&self, tenant_id: Uuid) -> Result<UserRole, AppError> {
+        Ok(if self.users.tenant_has_users(tenant_id).await? {
+            UserRole::Regular
+        } else {
+            UserRole::Admin
+        })
+    }
+}
 
-        let user = users::ActiveModel {
-            id: Set(uuid::Uuid::now_v7()),
-            tenant_id: Set(req.tenant_id),
-            email: Set(req.email),
-            password_hash: Set(password_hash),
-            role: Set(role),
-            status: Set(UserStatus::Active),
-            created_at: Set(Utc::now().fixed_offset()),
-            updated_at: Set(Utc::now().fixed_offset()),
-        };
+#[async_trait]
+impl AuthenticationService for AuthService {
+    async fn register(&self, req: RegisterRequest) -> Result<AuthResponse, AppError> {
+        let settings = self.settings.get(req.tenant_id).await?.settings;
+        if !settings.registration_open {
+            return Err(AppError::RegistrationClosed);
+        }
+        self.passwords
+            .check(&[req.tenant_id], &req.email, &req.password)
+            .await?;
 
-        let user = user.insert(db).await?;
+        let role = self.determine_user_role(req.tenant_id).await?;
 
-        let token = Self::generate_token(
-            user.id,
-            user.tenant_id,
-            user.email.clone(),
-            user.role,
-            secret,
-            expiration_minutes,
-        )?;
+        let user = self
+            .user_service
+            .create(req.tenant_id, req.email, &req.password, role)
+            .await?;
 
-        Ok(AuthResponse { token, user })
+        self.issue(user, false).await
     }
 
-    pub async fn login(
-        db: &DatabaseConnection,
-        req: LoginRequest,
-        secret: &str,
-        expiration_minutes: i64,
-    ) -> Result<AuthResponse, AppError> {
-        let user = users::Entity::find()
-            .filter(users::Column::Email.eq(&req.email))
-            .one(db)
+    async fn login(&self, req: LoginRequest) -> Result<AuthResponse, AppError> {
+        let user = self
+            .users
+            .find_by_email(&req.email)
             .await?
             .ok_or(AppError::InvalidCredentials)?;
 
-        if !Self::verify_password(&req.password, &user.password_hash)? {
+        if !self
+            .user_service
+            .verify_password(&user, &req.password)
+            .await?
+        {
             return Err(AppError::InvalidCredentials);
         }
 
-        if user.status != UserStatus::Active {
-            return Err(AppError::UserNotValidated);
+        // Start in the tenant the user was created in, else in the first
+        // other tenant where the membership is active
+        if user.status == UserStatus::Active {
+            return self.issue(user, false).await;
         }
+        let membership = self
+            .user_service
+            .list_memberships(user.id)
+            .await?
+            .into_iter()
+            .find(|membership| membership.status == UserStatus::Active)
+            .ok_or(AppError::UserNotValidated)?;
 
-        let token = Self::generate_token(
-            user.id,
-            user.tenant_id,
-            user.email.clone(),
-            user.role,
-            secret,
-            expiration_minutes,
-        )?;
-
-        Ok(AuthResponse { token, user })
+        self.issue(user.as_member(&membership), false).await
     }
 
-    pub async fn refresh_token(
-        db: &DatabaseConnection,
-        claims: Claims,
-        secret: &str,
-        expiration_minutes: i64,
-    ) -> Result<AuthResponse, AppError> {
-        let user = users::Entity::find()
-            .filter(users::Column::Id.eq(claims.user_id))
-            .one(db)
+    async fn refresh_token(&self, claims: Claims) -> Result<AuthResponse, AppError> {
+        let user = self
+            .users
+            .find_in_tenant(claims.tenant_id, claims.user_id)
             .await?
             .ok_or(AppError::UserNotFound)?;
 
@@ -203,32 +291,33 @@ impl AuthService {
             return Err(AppError::UserNotValidated);
         }
 
-        let token = Self::generate_token(
-            user.id,
-            user.tenant_id,
-            user.email.clone(),
-            user.role,
-            secret,
-            expiration_minutes,
-        )?;
-
-        Ok(AuthResponse { token, user })
+        self.issue(user, claims.tenant_bound).await
     }
 
-    async fn determine_user_role(
-        db: &DatabaseConnection,
+    async fn switch_tenant(
+        &self,
+        claims: Claims,
         tenant_id: Uuid,
-    ) -> Result<UserRole, AppError> {
-        let has_tenant_users = users::Entity::find()
-            .filter(users::Column::TenantId.eq(tenant_id))
-            .one(db)
+    ) -> Result<AuthResponse, AppError> {
+        if claims.tenant_bound && tenant_id != claims.tenant_id {
+            return Err(AppError::TenantAccessDenied);
+        }
+
+        let user = self
+            .users
+            .find_in_tenant(tenant_id, claims.user_id)
             .await?
-            .is_some();
+            .filter(|user| user.status == UserStatus::Active)
+            .ok_or(AppError::TenantAccessDenied)?;
 
-        Ok(if has_tenant_users {
-            UserRole::Regular
-        } else {
-            UserRole::Admin
-        })
+        self.issue(user, claims.tenant_bound).await
+    }
+
+    async fn sign_in(&self, user: users::Model) -> Result<AuthResponse, AppError> {
+        if user.status != UserStatus::Active {
+            return Err(AppError::UserNotValidated);
+        }
+
+        self.issue(user, true).await
     }
 }
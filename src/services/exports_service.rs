@@ -0,0 +1,489 @@
+use crate::enums::{ExportFormat, ExportStatus};
+use crate::jobs::export::RunExport;
+use crate::models::{
+    api_keys, memberships, oidc_providers, service_accounts, tenant_exports, tenant_settings,
+    tenants,
+};
+use crate::repositories::{
+    ApiKeyRepository, MembershipRepository, OidcRepository, TenantExportRepository,
+    TenantRepository, TenantSettingsRepository, UserRepository,
+};
+use crate::services::jobs_service::{JobQueue, NewJob};
+use crate::utils::error::AppError;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use sea_orm::Set;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Version of the archive layout, bumped whenever a record type is added,
+/// removed or changes shape.
+pub const ARCHIVE_FORMAT_VERSION: u32 = 1;
+
+/// Audience of download link tokens, so that access tokens signed with the
+/// same secret are not accepted as links, nor links as access tokens.
+const DOWNLOAD_AUDIENCE: &str = "tenant-export";
+
+/// An export job and, once it has completed, a link to download it.
+#[derive(Debug, Clone, PartialEq, Serialize, utoipa::ToSchema)]
+pub struct ExportResponse {
+    #[serde(flatten)]
+    pub export: tenant_exports::Model,
+    /// Path of the archive, signed for `EXPORT_LINK_TTL_MINUTES`. Usable
+    /// without an `Authorization` header.
+    pub download_url: Option<String>,
+    #[schema(value_type = Option<String>)]
+    pub download_expires_at: Option<DateTime<Utc>>,
+}
+
+/// A completed archive, read back for download.
+#[derive(Debug)]
+pub struct ExportArchive {
+    pub format: ExportFormat,
+    pub file_name: String,
+    pub content: Vec<u8>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DownloadClaims {
+    export_id: Uuid,
+    aud: String,
+    exp: i64,
+}
+
+#[async_trait]
+pub trait ExportService: Send + Sync {
+    /// Records a pending export of the tenant and queues the job that writes
+    /// it.
+    async fn request(
+        &self,
+        tenant_id: Uuid,
+        requested_by: Uuid,
+        format: ExportFormat,
+    ) -> Result<ExportResponse, AppError>;
+
+    /// An export of the tenant, with a freshly signed link once completed.
+    async fn get(&self, tenant_id: Uuid, export_id: Uuid) -> Result<ExportResponse, AppError>;
+
+    /// Checks a download link and reads the archive it points to.
+    async fn download(&self, export_id: Uuid, token: &str) -> Result<ExportArchive, AppError>;
+
+    /// Writes the archive of an export and records the outcome, unless it
+    /// has already finished. Run by the export job.
+    async fn run(&self, export_id: Uuid) -> Result<(), AppError>;
+}
+
+/// Where the records of an export are read from.
+#[derive(Clone)]
+pub struct ExportSources {
+    pub tenants: Arc<dyn TenantRepository>,
+    pub settings: Arc<dyn TenantSettingsRepository>,
+    pub users: Arc<dyn UserRepository>,
+    pub memberships: Arc<dyn MembershipRepository>,
+    pub api_keys: Arc<dyn ApiKeyRepository>,
+    pub oidc: Arc<dyn OidcRepository>,
+}
+
+/// Writes exports to files under `dir`, named after the export, and signs
+/// download links with the JWT secret.
+pub struct TenantExporter {
+    exports: Arc<dyn TenantExportRepository>,
+    sources: ExportSources,
+    queue: Arc<dyn JobQueue>,
+    dir: PathBuf,
+    jwt_secret: String,
+    link_ttl: Duration,
+}
+
+/// A user without credentials. Role and status are per tenant and are in
+/// `memberships`.
+#[derive(Debug, Serialize)]
+struct ExportedUser {
+    id: Uuid,
+    email: String,
+    locale: Option<crate::enums::Locale>,
+    created_at: DateTime<chrono::FixedOffset>,
+    updated_at: DateTime<chrono::FixedOffset>,
+}
+
+#[derive(Debug, Serialize)]
+struct ArchiveHeader {
+    format_version: u32,
+    export_id: Uuid,
+    tenant_id: Uuid,
+    exported_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+struct Archive {
+    #[serde(flatten)]
+    header: ArchiveHeader,
+    tenant: tenants::Model,
+    settings: Option<tenant_settings::Model>,
+    users: Vec<ExportedUser>,
+    memberships: Vec<memberships::Model>,
+    service_accounts: Vec<service_accounts::Model>,
+    api_keys: Vec<api_keys::Model>,
+    oidc_providers: Vec<oidc_providers::Model>,
+}
+
+impl Archive {
+    fn encode(&self, format: ExportFormat) -> serde_json::Result<Vec<u8>> {
+        match format {
+            ExportFormat::Json => serde_json::to_vec_pretty(self),
+            ExportFormat::Ndjson => self.to_ndjson(),
+        }
+    }
+
+    /// The header on the first line, then one `{"type", "data"}` record per
+    /// line.
+    fn to_ndjson(&self) -> serde_json::Result<Vec<u8>> {
+        let mut out = serde_json::to_vec(&self.header)?;
+        out.push(b'\n');
+        record(&mut out, "tenant", &self.tenant)?;
+        if let Some(settings) = &self.settings {
+            record(&mut out, "settings", settings)?;
+        }
+        for user in &self.users {
+            record(&mut out, "user", user)?;
+        }
+        for membership in &self.memberships {
+            record(&mut out, "membership", membership)?;
+        }
+        for account in &self.service_accounts {
+            record(&mut out, "service_account", account)?;
+        }
+        for key in &self.api_keys {
+            record(&mut out, "api_key", key)?;
+        }
+        for provider in &self.oidc_providers {
+            record(&mut out, "oidc_provider", provider)?;
+        }
+        Ok(out)
+    }
+}
+
+fn record<T: Serialize>(out: &mut Vec<u8>, kind: &str, data: &T) -> serde_json::Result<()> {
+    serde_json::to_writer(
+        &mut *out,
+        &serde_json::json!({ "type": kind, "data": data }),
+    )?;
+    out.push(b'\n');
+    Ok(())
+}
+
+impl TenantExporter {
+    pub fn new(
+        exports: Arc<dyn TenantExportRepository>,
+        sources: ExportSources,
+        queue: Arc<dyn JobQueue>,
+        dir: PathBuf,
+        jwt_secret: String,
+        link_ttl: Duration,
+    ) -> Self {
+        Self {
+            exports,
+            sources,
+            queue,
+            dir,
+            jwt_secret,
+            link_ttl,
+        }
+    }
+
+    fn path(&self, export: &tenant_exports::Model) -> PathBuf {
+        self.dir
+            .join(format!("{}.{}", export.id, export.format.extension()))
+    }
+
+    async fn write_archive(&self, export: tenant_exports::Model) -> Result<(), AppError> {
+        let mut running: tenant_exports::ActiveModel = export.clone().into();
+        running.status = Set(ExportStatus::Running);
+        let export = self.exports.update(running).await?;
+
+        let outcome = match self.collect(&export).await {
+            Ok(archive) => self.write(&export, &archive).await,
+            Err(e) => Err(e),
+        };
+
+        let mut finished: tenant_exports::ActiveModel = export.clone().into();
+        finished.completed_at = Set(Some(Utc::now().fixed_offset()));
+        match outcome {
+            Ok(size) => {
+                finished.status = Set(ExportStatus::Completed);
+                finished.size_bytes = Set(Some(size));
+                tracing::info!(export_id = %export.id, size, "Tenant export completed");
+            }
+            Err(error) => {
+                tracing::error!(export_id = %export.id, %error, "Tenant export failed");
+                finished.status = Set(ExportStatus::Failed);
+                finished.error = Set(Some(error));
+            }
+        }
+        self.exports.update(finished).await?;
+        Ok(())
+    }
+
+    async fn collect(&self, export: &tenant_exports::Model) -> Result<Archive, String> {
+        let tenant_id = export.tenant_id;
+        let read = |e: sea_orm::DbErr| {
+            tracing::error!(export_id = %export.id, "Cannot read tenant data: {}", e);
+            "Could not read the tenant's data".to_string()
+        };
+        let tenant = self
+            .sources
+            .tenants
+            .find_by_id(tenant_id)
+            .await
+            .map_err(read)?
+            .ok_or_else(|| "The tenant no longer exists".to_string())?;
+        let settings = self.sources.settings.find(tenant_id).await.map_err(read)?;
+        let users = self
+            .sources
+            .users
+            .list_by_tenant(tenant_id)
+            .await
+            .map_err(read)?;
+        // Deleted users are left out, and so are their memberships.
+        let user_ids: HashSet<Uuid> = users.iter().map(|user| user.id).collect();
+        let memberships = self
+            .sources
+            .memberships
+            .list_by_tenant(tenant_id)
+            .await
+            .map_err(read)?
+            .into_iter()
+            .filter(|membership| user_ids.contains(&membership.user_id))
+            .collect();
+        let service_accounts = self
+            .sources
+            .api_keys
+            .list_service_accounts(tenant_id)
+            .await
+            .map_err(read)?;
+        let api_keys = self
+            .sources
+            .api_keys
+            .list_keys(tenant_id)
+            .await
+            .map_err(read)?;
+        let oidc_providers = self
+            .sources
+            .oidc
+            .list_providers(tenant_id)
+            .await
+            .map_err(read)?;
+
+        Ok(Archive {
+            header: ArchiveHeader {
+                format_version: ARCHIVE_FORMAT_VERSION,
+                export_id: export.id,
+                tenant_id,
+                exported_at: Utc::now(),
+            },
+            tenant,
+            settings,
+            users: users
+                .into_iter()
+                .map(|user| ExportedUser {
+                    id: user.id,
+                    email: user.email,
+                    locale: user.locale,
+                    created_at: user.created_at,
+                    updated_at: user.updated_at,
+                })
+                .collect(),
+            memberships,
+            service_accounts,
+            api_keys,
+            oidc_providers,
+        })
+    }
+
+    /// Writes next to the final path and renames, so a download never sees a
+    /// partial archive. Returns the size written.
+    async fn write(
+        &self,
+        export: &tenant_exports::Model,
+        archive: &Archive,
+    ) -> Result<i64, String> {
+        let content = archive.encode(export.format).map_err(|e| {
+            tracing::error!(export_id = %export.id, "Cannot encode export: {}", e);
+            "Could not encode the archive".to_string()
+        })?;
+        let path = self.path(export);
+        let partial = path.with_extension("partial");
+        let written = async {
+            tokio::fs::create_dir_all(&self.dir).await?;
+            tokio::fs::write(&partial, &content).await?;
+            tokio::fs::rename(&partial, &path).await
+        }
+        .await;
+        written.map_err(|e| {
+            tracing::error!(export_id = %export.id, path = %path.display(), "Cannot write export: {}", e);
+            "Could not write the archive".to_string()
+        })?;
+        Ok(content.len() as i64)
+    }
+
+    fn sign_link(&self, export_id: Uuid) -> Result<(String, DateTime<Utc>), AppError> {
+        let expires_at = Utc::now() + self.link_ttl;
+        let claims = DownloadClaims {
+            export_id,
+            aud: DOWNLOAD_AUDIENCE.to_string(),
+            exp: expires_at.timestamp(),
+        };
+        let token = encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(self.jwt_secret.as_ref()),
+        )
+        .map_err(|e| {
+            tracing::error!("Download link signing failed: {}", e);
+            AppError::Internal
+        })?;
+        Ok((token, expires_at))
+    }
+
+    fn verify_link(&self, export_id: Uuid, token: &str) -> Result<(), AppError> {
+        let mut validation = Validation::default();
+        validation.set_audience(&[DOWNLOAD_AUDIENCE]);
+        let claims = decode::<DownloadClaims>(
+            token,
+            &DecodingKey::from_secret(self.jwt_secret.as_ref()),
+            &validation,
+        )
+        .map_err(|e| {
+            tracing::warn!("Download link rejected: {:?}", e);
+            AppError::DownloadLinkInvalid
+        })?
+        .claims;
+        if claims.export_id != export_id {
+            return Err(AppError::DownloadLinkInvalid);
+        }
+        Ok(())
+    }
+
+    fn respond(&self, export: tenant_exports::Model) -> Result<ExportResponse, AppError> {
+        if export.status != ExportStatus::Completed {
+            return Ok(ExportResponse {
+                export,
+                download_url: None,
+                download_expires_at: None,
+            });
+        }
+        let (token, expires_at) = self.sign_link(export.id)?;
+        Ok(ExportResponse {
+            download_url: Some(format!(
+                "/api/exports/{}/download?token={}",
+                export.id, token
+            )),
+            download_expires_at: Some(expires_at),
+            export,
+        })
+    }
+}
+
+#[async_trait]
+impl ExportService for TenantExporter {
+    async fn request(
+        &self,
+        tenant_id: Uuid,
+        requested_by: Uuid,
+        format: ExportFormat,
+    ) -> Result<ExportResponse, AppError> {
+        let export = self
+            .exports
+            .insert(tenant_exports::ActiveModel {
+                id: Set(Uuid::now_v7()),
+                tenant_id: Set(tenant_id),
+                requested_by: Set(requested_by),
+                format: Set(format),
+                status: Set(ExportStatus::Pending),
+                size_bytes: Set(None),
+                error: Set(None),
+                created_at: Set(Utc::now().fixed_offset()),
+                completed_at: Set(None),
+            })
+            .await?;
+
+        let queued = match NewJob::of(&RunExport {
+            export_id: export.id,
+        }) {
+            Ok(job) => self.queue.enqueue(job).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = queued {
+            // Nothing would ever run it
+            let mut failed: tenant_exports::ActiveModel = export.into();
+            failed.status = Set(ExportStatus::Failed);
+            failed.error = Set(Some("Could not queue the export".to_string()));
+            failed.completed_at = Set(Some(Utc::now().fixed_offset()));
+            self.exports.update(failed).await?;
+            return Err(e);
+        }
+
+        self.respond(export)
+    }
+
+    async fn get(&self, tenant_id: Uuid, export_id: Uuid) -> Result<ExportResponse, AppError> {
+        let export = self
+            .exports
+            .find(tenant_id, export_id)
+            .await?
+            .ok_or(AppError::ExportNotFound)?;
+        self.respond(export)
+    }
+
+    async fn download(&self, export_id: Uuid, token: &str) -> Result<ExportArchive, AppError> {
+        self.verify_link(export_id, token)?;
+        let export = self
+            .exports
+            .find_by_id(export_id)
+            .await?
+            .filter(|export| export.status == ExportStatus::Completed)
+            .ok_or(AppError::ExportNotFound)?;
+        let path = self.path(&export);
+        let content = tokio::fs::read(&path).await.map_err(|e| {
+            tracing::error!(%export_id, path = %path.display(), "Cannot read export: {}", e);
+            AppError::ExportNotFound
+        })?;
+
+        Ok(ExportArchive {
+            format: export.format,
+            file_name: file_name(&export),
+            content,
+        })
+    }
+
+    async fn run(&self, export_id: Uuid) -> Result<(), AppError> {
+        match self.exports.find_by_id(export_id).await? {
+            // Deleted along with its tenant
+            None => Ok(()),
+            Some(export)
+                if matches!(
+                    export.status,
+                    ExportStatus::Completed | ExportStatus::Failed
+                ) =>
+            {
+                Ok(())
+            }
+            Some(export) => self.write_archive(export).await,
+        }
+    }
+}
+
+/// `tenant-<tenant id>-<created at>.<ext>`, so downloads of several exports
+/// do not overwrite each other.
+fn file_name(export: &tenant_exports::Model) -> String {
+    format!(
+        "tenant-{}-{}.{}",
+        export.tenant_id,
+        export.created_at.format("%Y%m%d%H%M%S"),
+        export.format.extension()
+    )
+}
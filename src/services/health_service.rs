@@ -0,0 +1,246 @@
+use async_trait::async_trait;
+use migration::{Migrator, MigratorTrait};
+use sea_orm::{DatabaseConnection, DatabaseConnectionType};
+use serde::Serialize;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+
+const DEFAULT_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A single dependency probed by the readiness endpoint.
+///
+/// Implement this for any subsystem the service cannot run without (mailer,
+/// cache, ...) and add it with [`HealthRegistry::register`].
+#[async_trait]
+pub trait HealthCheck: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn timeout(&self) -> Duration {
+        DEFAULT_CHECK_TIMEOUT
+    }
+
+    async fn check(&self) -> Result<(), String>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Up,
+    Down,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CheckResult {
+    pub name: String,
+    pub status: CheckStatus,
+    pub duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct HealthReport {
+    pub ready: bool,
+    pub shutting_down: bool,
+    pub checks: Vec<CheckResult>,
+}
+
+pub struct HealthRegistry {
+    checks: Vec<Arc<dyn HealthCheck>>,
+    shutting_down: AtomicBool,
+    started_at: Instant,
+}
+
+impl HealthRegistry {
+    pub fn new() -> Self {
+        Self {
+            checks: Vec::new(),
+            shutting_down: AtomicBool::new(false),
+            started_at: Instant::now(),
+        }
+    }
+
+    /// Registry with the checks every deployment needs: database
+    /// connectivity and an up-to-date schema.
+    pub fn with_defaults(db: Arc<DatabaseConnection>) -> Self {
+        Self::new()
+            .register(DatabaseHealthCheck::new(db.clone()))
+            .register(MigrationsHealthCheck::new(db))
+    }
+
+    pub fn register<C: HealthCheck + 'static>(mut self, check: C) -> Self {
+        self.checks.push(Arc::new(check));
+        self
+    }
+
+    /// Marks the process as draining so readiness starts failing while
+    /// in-flight requests complete.
+    pub fn begin_shutdown(&self) {
+        self.shutting_down.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_shutting_down(&self) -> bool {
+        self.shutting_down.load(Ordering::SeqCst)
+    }
+
+    pub fn uptime(&self) -> Duration {
+        self.started_at.elapsed()
+    }
+
+    /// Runs every registered check concurrently, each bounded by its own timeout.
+    pub async fn run(&self) -> HealthReport {
+        let handles: Vec<_> = self
+            .checks
+            .iter()
+            .cloned()
+            .map(|check| tokio::spawn(async move { Self::run_check(check).await }))
+            .collect();
+
+        let mut checks = Vec::with_capacity(handles.len());
+        for handle in handles {
+            match handle.await {
+                Ok(result) => checks.push(result),
+                Err(e) => {
+                    tracing::warn!("Health check panicked: {}", e);
+                    checks.push(CheckResult {
+                        name: "unknown".to_string(),
+                        status: CheckStatus::Down,
+                        duration_ms: 0,
+                        error: Some(format!("Health check panicked: {}", e)),
+                    })
+                }
+            }
+        }
+
+        let shutting_down = self.is_shutting_down();
+        let ready = !shutting_down && checks.iter().all(|c| c.status == CheckStatus::Up);
+
+        HealthReport {
+            ready,
+            shutting_down,
+            checks,
+        }
+    }
+
+    async fn run_check(check: Arc<dyn HealthCheck>) -> CheckResult {
+        let start = Instant::now();
+        let outcome = tokio::time::timeout(check.timeout(), check.check()).await;
+        let duration_ms = start.elapsed().as_millis() as u64;
+
+        let error = match outcome {
+            Ok(Ok(())) => None,
+            Ok(Err(e)) => Some(e),
+            Err(_) => Some(format!("Timed out after {}ms", check.timeout().as_millis())),
+        };
+
+        if let Some(error) = &error {
+            tracing::warn!("Health check '{}' failed: {}", check.name(), error);
+        }
+
+        CheckResult {
+            name: check.name().to_string(),
+            status: if error.is_none() {
+                CheckStatus::Up
+            } else {
+                CheckStatus::Down
+            },
+            duration_ms,
+            error,
+        }
+    }
+}
+
+impl Default for HealthRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct DatabaseHealthCheck {
+    db: Arc<DatabaseConnection>,
+}
+
+impl DatabaseHealthCheck {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl HealthCheck for DatabaseHealthCheck {
+    fn name(&self) -> &'static str {
+        "database"
+    }
+
+    async fn check(&self) -> Result<(), String> {
+        self.db.ping().await.map_err(|e| e.to_string())
+    }
+}
+
+pub struct MigrationsHealthCheck {
+    db: Arc<DatabaseConnection>,
+}
+
+impl MigrationsHealthCheck {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl HealthCheck for MigrationsHealthCheck {
+    fn name(&self) -> &'static str {
+        "migrations"
+    }
+
+    async fn check(&self) -> Result<(), String> {
+        let pending = HealthService::pending_migrations(&self.db).await?;
+        if pending.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("{} pending migration(s)", pending.len()))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PoolStats {
+    pub size: u32,
+    pub idle: usize,
+}
+
+pub struct HealthService;
+
+impl HealthService {
+    pub async fn pending_migrations(db: &DatabaseConnection) -> Result<Vec<String>, String> {
+        let pending = Migrator::get_pending_migrations_read_only(db)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(pending.iter().map(|m| m.name().to_string()).collect())
+    }
+
+    /// Connection pool usage, or `None` for connections without a sqlx pool
+    /// (e.g. mock or disconnected connections).
+    pub fn pool_stats(db: &DatabaseConnection) -> Option<PoolStats> {
+        match &db.inner {
+            DatabaseConnectionType::SqlxPostgresPoolConnection(_) => {
+                let pool = db.get_postgres_connection_pool();
+                Some(PoolStats {
+                    size: pool.size(),
+                    idle: pool.num_idle(),
+                })
+            }
+            #[cfg(feature = "sqlite")]
+            DatabaseConnectionType::SqlxSqlitePoolConnection(_) => {
+                let pool = db.get_sqlite_connection_pool();
+                Some(PoolStats {
+                    size: pool.size(),
+                    idle: pool.num_idle(),
+                })
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use crate::enums::JobStatus;
+use crate::jobs::Job;
+use crate::models::jobs;
+use crate::repositories::JobRepository;
+use crate::utils::error::AppError;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::Set;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// A job to enqueue: due now unless scheduled, with the configured number of
+/// attempts unless it asks for another.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewJob {
+    pub kind: String,
+    pub payload: serde_json::Value,
+    pub run_at: Option<DateTime<Utc>>,
+    pub max_attempts: Option<i32>,
+}
+
+impl NewJob {
+    pub fn of<J: Job>(job: &J) -> Result<Self, AppError> {
+        let payload = serde_json::to_value(job).map_err(|e| {
+            tracing::error!("Cannot serialize {} job: {}", J::KIND, e);
+            AppError::Internal
+        })?;
+        Ok(Self {
+            kind: J::KIND.to_string(),
+            payload,
+            run_at: None,
+            max_attempts: None,
+        })
+    }
+
+    /// Runs the job no earlier than `run_at`.
+    pub fn at(mut self, run_at: DateTime<Utc>) -> Self {
+        self.run_at = Some(run_at);
+        self
+    }
+
+    /// Runs the job no earlier than `delay` from now.
+    pub fn after(self, delay: Duration) -> Self {
+        self.at(Utc::now() + delay)
+    }
+
+    pub fn max_attempts(mut self, max_attempts: i32) -> Self {
+        self.max_attempts = Some(max_attempts);
+        self
+    }
+}
+
+#[async_trait]
+pub trait JobQueue: Send + Sync {
+    async fn enqueue(&self, job: NewJob) -> Result<jobs::Model, AppError>;
+
+    /// The row `enqueue` inserts for `job`, for callers that insert it in
+    /// the same transaction as the rows it works on.
+    fn job(&self, job: NewJob) -> jobs::ActiveModel;
+
+    /// Dead jobs, the longest dead first.
+    async fn list_dead(&self, limit: u64) -> Result<Vec<jobs::Model>, AppError>;
+
+    /// Gives a dead job a fresh set of attempts, due now. Returns `None` when
+    /// there is no dead job with that id.
+    async fn retry(&self, job_id: Uuid) -> Result<Option<jobs::Model>, AppError>;
+}
+
+/// Stores jobs in the database, where workers claim them.
+pub struct JobsService {
+    jobs: Arc<dyn JobRepository>,
+    max_attempts: i32,
+}
+
+impl JobsService {
+    pub fn new(jobs: Arc<dyn JobRepository>, max_attempts: i32) -> Self {
+        Self { jobs, max_attempts }
+    }
+}
+
+#[async_trait]
+impl JobQueue for JobsService {
+    async fn enqueue(&self, job: NewJob) -> Result<jobs::Model, AppError> {
+        let job = self.jobs.insert(self.job(job)).await?;
+        tracing::debug!(job_id = %job.id, kind = %job.kind, "Job enqueued");
+
+        Ok(job)
+    }
+
+    fn job(&self, job: NewJob) -> jobs::ActiveModel {
+        let now = Utc::now().fixed_offset();
+        jobs::ActiveModel {
+            id: Set(Uuid::now_v7()),
+            kind: Set(job.kind),
+            payload: Set(job.payload),
+            status: Set(JobStatus::Pending),
+            attempts: Set(0),
+            max_attempts: Set(job.max_attempts.unwrap_or(self.max_attempts)),
+            run_at: Set(job.run_at.map_or(now, |at| at.fixed_offset())),
+            locked_by: Set(None),
+            locked_at: Set(None),
+            last_error: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+            completed_at: Set(None),
+        }
+    }
+
+    async fn list_dead(&self, limit: u64) -> Result<Vec<jobs::Model>, AppError> {
+        Ok(self.jobs.list_by_status(JobStatus::Dead, limit).await?)
+    }
+
+    async fn retry(&self, job_id: Uuid) -> Result<Option<jobs::Model>, AppError> {
+        let Some(job) = self
+            .jobs
+            .find_by_id(job_id)
+            .await?
+            .filter(|job| job.status == JobStatus::Dead)
+        else {
+            return Ok(None);
+        };
+
+        let now = Utc::now().fixed_offset();
+        let mut retried: jobs::ActiveModel = job.into();
+        retried.status = Set(JobStatus::Pending);
+        retried.attempts = Set(0);
+        retried.run_at = Set(now);
+        retried.updated_at = Set(now);
+        Ok(Some(self.jobs.update(retried).await?))
+    }
+}
@@ -0,0 +1,143 @@
+use crate::models::task_runs;
+use crate::repositories::{
+    ApiKeyRepository, MembershipRepository, OidcRepository, OutboxRepository, TaskRunRepository,
+    TenantRepository,
+};
+use crate::services::tenant_settings_service::TenantSettingsService;
+use crate::utils::error::AppError;
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+
+/// How long revoked and expired API keys stay listed before they are
+/// deleted.
+pub const ENDED_API_KEY_RETENTION: Duration = Duration::days(30);
+
+/// Runs returned when no limit is given, and the most returned at once.
+const DEFAULT_RUN_LIMIT: u64 = 50;
+const MAX_RUN_LIMIT: u64 = 200;
+
+/// Housekeeping done by the scheduled tasks, and the record of their runs.
+#[async_trait]
+pub trait MaintenanceService: Send + Sync {
+    /// Deletes expired SSO login states, and API keys revoked or expired more
+    /// than [`ENDED_API_KEY_RETENTION`] ago. Returns how many rows were
+    /// deleted.
+    async fn purge_expired_auth_artifacts(&self) -> Result<u64, AppError>;
+
+    /// Deactivates, in each tenant with `idle_deactivation_days` set, the
+    /// members other than admins who have not logged in for that long.
+    /// Returns how many were deactivated.
+    async fn deactivate_idle_members(&self) -> Result<u64, AppError>;
+
+    /// Deletes the domain events relayed more than `retention` ago. Returns
+    /// how many were deleted.
+    async fn purge_relayed_events(&self, retention: Duration) -> Result<u64, AppError>;
+
+    /// Runs of `task`, or of every task, newest first.
+    async fn list_runs(
+        &self,
+        task: Option<String>,
+        limit: Option<u64>,
+    ) -> Result<Vec<task_runs::Model>, AppError>;
+}
+
+pub struct Maintenance {
+    oidc: Arc<dyn OidcRepository>,
+    api_keys: Arc<dyn ApiKeyRepository>,
+    memberships: Arc<dyn MembershipRepository>,
+    tenants: Arc<dyn TenantRepository>,
+    settings: Arc<dyn TenantSettingsService>,
+    runs: Arc<dyn TaskRunRepository>,
+    outbox: Arc<dyn OutboxRepository>,
+}
+
+impl Maintenance {
+    pub fn new(
+        oidc: Arc<dyn OidcRepository>,
+        api_keys: Arc<dyn ApiKeyRepository>,
+        memberships: Arc<dyn MembershipRepository>,
+        tenants: Arc<dyn TenantRepository>,
+        settings: Arc<dyn TenantSettingsService>,
+        runs: Arc<dyn TaskRunRepository>,
+        outbox: Arc<dyn OutboxRepository>,
+    ) -> Self {
+        Self {
+            oidc,
+            api_keys,
+            memberships,
+            tenants,
+            settings,
+            runs,
+            outbox,
+        }
+    }
+}
+
+#[async_trait]
+impl MaintenanceService for Maintenance {
+    async fn purge_expired_auth_artifacts(&self) -> Result<u64, AppError> {
+        let now = Utc::now();
+        let login_states = self
+            .oidc
+            .delete_expired_login_states(now.fixed_offset())
+            .await?;
+        let api_keys = self
+            .api_keys
+            .delete_keys_ended_before((now - ENDED_API_KEY_RETENTION).fixed_offset())
+            .await?;
+        if login_states + api_keys > 0 {
+            tracing::info!(
+                login_states,
+                api_keys,
+                "Purged expired authentication artifacts"
+            );
+        }
+        Ok(login_states + api_keys)
+    }
+
+    async fn deactivate_idle_members(&self) -> Result<u64, AppError> {
+        let mut total = 0;
+        for tenant in self.tenants.list_all().await? {
+            let settings = self.settings.get(tenant.id).await?.settings;
+            let Some(days) = settings.idle_deactivation_days else {
+                continue;
+            };
+            let idle_since = (Utc::now() - Duration::days(days.into())).fixed_offset();
+            let deactivated = self
+                .memberships
+                .deactivate_idle(tenant.id, idle_since)
+                .await?;
+            if deactivated > 0 {
+                tracing::info!(
+                    tenant_id = %tenant.id,
+                    deactivated,
+                    "Deactivated members idle for {} days",
+                    days
+                );
+            }
+            total += deactivated;
+        }
+        Ok(total)
+    }
+
+    async fn purge_relayed_events(&self, retention: Duration) -> Result<u64, AppError> {
+        let purged = self
+            .outbox
+            .purge_dispatched((Utc::now() - retention).fixed_offset())
+            .await?;
+        if purged > 0 {
+            tracing::info!(purged, "Purged relayed domain events");
+        }
+        Ok(purged)
+    }
+
+    async fn list_runs(
+        &self,
+        task: Option<String>,
+        limit: Option<u64>,
+    ) -> Result<Vec<task_runs::Model>, AppError> {
+        let limit = limit.unwrap_or(DEFAULT_RUN_LIMIT).clamp(1, MAX_RUN_LIMIT);
+        Ok(self.runs.list(task, limit).await?)
+    }
+}
@@ -1,3 +1,13 @@
+pub mod api_keys_service;
 pub mod auth_service;
+pub mod exports_service;
+pub mod health_service;
+pub mod jobs_service;
+pub mod maintenance_service;
+pub mod oidc_service;
+pub mod password_policy_service;
+pub mod scim_service;
+pub mod tenant_settings_service;
 pub mod tenants_service;
 pub mod users_service;
+pub mod webhooks_service;
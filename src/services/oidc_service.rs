@@ -0,0 +1,532 @@
+use crate::enums::UserRole;
+use crate::models::{oidc_login_states, oidc_providers, user_identities, users};
+use crate::repositories::{OidcRepository, UserRepository};
+use crate::services::auth_service::{AuthResponse, AuthenticationService};
+use crate::services::users_service::UserService;
+use crate::utils::error::AppError;
+use crate::utils::http_client::{HttpClient, HttpError};
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use chrono::{Duration, Utc};
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode, decode_header};
+use sea_orm::Set;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use url::Url;
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+/// How long a user has to complete the login at the provider.
+const LOGIN_STATE_TTL: Duration = Duration::minutes(10);
+
+/// Deadline for each request to an identity provider.
+pub const PROVIDER_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
+pub struct CreateOidcProviderRequest {
+    #[validate(length(min = 1, max = 100, message = "name_length"))]
+    pub name: String,
+    #[validate(
+        url(message = "url_invalid"),
+        custom(function = "validate_issuer", message = "issuer_invalid")
+    )]
+    pub issuer: String,
+    #[validate(length(min = 1, message = "field_required"))]
+    pub client_id: String,
+    #[validate(length(min = 1, message = "field_required"))]
+    pub client_secret: String,
+    /// Space-separated; defaults to `openid email profile`. `openid` is
+    /// always requested.
+    pub scopes: Option<String>,
+    #[validate(url(message = "url_invalid"))]
+    pub redirect_uri: String,
+    /// Defaults to `email`.
+    pub email_claim: Option<String>,
+    pub role_claim: Option<String>,
+    pub admin_role_value: Option<String>,
+    /// Defaults to `Regular`.
+    pub default_role: Option<UserRole>,
+}
+
+/// OpenID Connect Discovery §4.3: an `http` or `https` URL without
+/// credentials, query or fragment.
+fn validate_issuer(issuer: &str) -> Result<(), ValidationError> {
+    match Url::parse(issuer) {
+        Ok(url)
+            if matches!(url.scheme(), "http" | "https")
+                && url.username().is_empty()
+                && url.password().is_none()
+                && url.query().is_none()
+                && url.fragment().is_none() =>
+        {
+            Ok(())
+        }
+        _ => Err(ValidationError::new("issuer")),
+    }
+}
+
+/// Where to send the browser to sign in. `state` comes back on the callback.
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct AuthorizationRequest {
+    pub authorization_url: String,
+    pub state: String,
+}
+
+/// The subset of the provider's discovery document this flow uses.
+#[derive(Debug, Deserialize)]
+struct Discovery {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+/// Verified claims of an ID token, reduced to what login needs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdentityClaims {
+    pub subject: String,
+    pub email: String,
+    pub email_verified: bool,
+    pub is_admin: bool,
+}
+
+#[async_trait]
+pub trait OidcService: Send + Sync {
+    async fn create_provider(
+        &self,
+        tenant_id: Uuid,
+        req: CreateOidcProviderRequest,
+    ) -> Result<oidc_providers::Model, AppError>;
+
+    async fn list_providers(&self, tenant_id: Uuid)
+    -> Result<Vec<oidc_providers::Model>, AppError>;
+
+    async fn delete_provider(&self, tenant_id: Uuid, provider_id: Uuid) -> Result<(), AppError>;
+
+    /// Starts an authorization-code login with PKCE at the provider.
+    async fn authorize(&self, provider_id: Uuid) -> Result<AuthorizationRequest, AppError>;
+
+    /// Completes a login: redeems the code, verifies the ID token, and signs
+    /// in the linked, matched or newly provisioned user.
+    async fn callback(&self, state: &str, code: &str) -> Result<AuthResponse, AppError>;
+}
+
+pub struct OidcAuthService {
+    oidc: Arc<dyn OidcRepository>,
+    users: Arc<dyn UserRepository>,
+    user_service: Arc<dyn UserService>,
+    auth: Arc<dyn AuthenticationService>,
+    http: HttpClient,
+}
+
+impl OidcAuthService {
+    pub fn new(
+        oidc: Arc<dyn OidcRepository>,
+        users: Arc<dyn UserRepository>,
+        user_service: Arc<dyn UserService>,
+        auth: Arc<dyn AuthenticationService>,
+        http: HttpClient,
+    ) -> Self {
+        Self {
+            oidc,
+            users,
+            user_service,
+            auth,
+            http,
+        }
+    }
+
+    /// 256 random bits, base64url-encoded: used for `state`, `nonce` and the
+    /// PKCE verifier.
+    pub fn random_token() -> String {
+        let mut bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut bytes);
+        URL_SAFE_NO_PAD.encode(bytes)
+    }
+
+    /// The `S256` PKCE challenge for a verifier (RFC 7636 §4.2).
+    pub fn pkce_challenge(verifier: &str) -> String {
+        URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+    }
+
+    /// Verifies an ID token's signature against the provider's keys, and its
+    /// issuer, audience, expiry and nonce, then applies the claim mapping.
+    pub fn verify_id_token(
+        id_token: &str,
+        keys: &JwkSet,
+        provider: &oidc_providers::Model,
+        nonce: &str,
+    ) -> Result<IdentityClaims, AppError> {
+        let rejected = |reason: &str| {
+            tracing::warn!(
+                "ID token rejected: provider_id={}, reason={}",
+                provider.id,
+                reason
+            );
+            AppError::OidcLoginFailed
+        };
+
+        let header = decode_header(id_token).map_err(|_| rejected("malformed header"))?;
+        // Keys come from the JWKS, so symmetric algorithms are never valid
+        if matches!(
+            header.alg,
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+        ) {
+            return Err(rejected("symmetric algorithm"));
+        }
+        let jwk = match &header.kid {
+            Some(kid) => keys.find(kid),
+            None if keys.keys.len() == 1 => keys.keys.first(),
+            None => None,
+        }
+        .ok_or_else(|| rejected("unknown signing key"))?;
+        let key = DecodingKey::from_jwk(jwk).map_err(|_| rejected("unusable signing key"))?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&provider.issuer]);
+        validation.set_audience(&[&provider.client_id]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+        let claims = decode::<Map<String, Value>>(id_token, &key, &validation)
+            .map_err(|e| rejected(&e.to_string()))?
+            .claims;
+
+        if claims.get("nonce").and_then(Value::as_str) != Some(nonce) {
+            return Err(rejected("nonce mismatch"));
+        }
+        let subject = claims
+            .get("sub")
+            .and_then(Value::as_str)
+            .filter(|sub| !sub.is_empty())
+            .ok_or_else(|| rejected("missing sub"))?
+            .to_string();
+        let email = claims
+            .get(&provider.email_claim)
+            .and_then(Value::as_str)
+            .ok_or_else(|| rejected("missing email claim"))?
+            .to_lowercase();
+        // Some providers send the flag as a string
+        let email_verified = match claims.get("email_verified") {
+            Some(Value::Bool(verified)) => *verified,
+            Some(Value::String(verified)) => verified == "true",
+            _ => false,
+        };
+        let is_admin = match (&provider.role_claim, &provider.admin_role_value) {
+            (Some(claim), Some(admin)) => match claims.get(claim) {
+                Some(Value::String(role)) => role == admin,
+                Some(Value::Array(roles)) => roles.iter().any(|r| r.as_str() == Some(admin)),
+                _ => false,
+            },
+            _ => false,
+        };
+
+        Ok(IdentityClaims {
+            subject,
+            email,
+            email_verified,
+            is_admin,
+        })
+    }
+
+    async fn discover(&self, provider: &oidc_providers::Model) -> Result<Discovery, AppError> {
+        let url = format!(
+            "{}/.well-known/openid-configuration",
+            provider.issuer.trim_end_matches('/')
+        );
+        let discovery: Discovery = self
+            .http
+            .get_json(&url)
+            .await
+            .map_err(|e| unavailable(provider, e))?;
+        if discovery.issuer != provider.issuer {
+            tracing::warn!(
+                "Discovery issuer mismatch: provider_id={}, expected={}, got={}",
+                provider.id,
+                provider.issuer,
+                discovery.issuer
+            );
+            return Err(AppError::OidcProviderUnavailable);
+        }
+        Ok(discovery)
+    }
+
+    async fn redeem_code(
+        &self,
+        provider: &oidc_providers::Model,
+        discovery: &Discovery,
+        code: &str,
+        code_verifier: &str,
+    ) -> Result<String, AppError> {
+        let response: TokenResponse = self
+            .http
+            .post_form(
+                &discovery.token_endpoint,
+                Some((&provider.client_id, &provider.client_secret)),
+                &[
+                    ("grant_type", "authorization_code"),
+                    ("code", code),
+                    ("redirect_uri", &provider.redirect_uri),
+                    ("code_verifier", code_verifier),
+                ],
+            )
+            .await
+            .map_err(|e| match e {
+                // invalid_grant and friends: the code, not the provider, is bad
+                HttpError::Status { status, body } if (400..500).contains(&status) => {
+                    tracing::warn!(
+                        "Code exchange rejected: provider_id={}, status={}, body={}",
+                        provider.id,
+                        status,
+                        body
+                    );
+                    AppError::OidcLoginFailed
+                }
+                e => unavailable(provider, e),
+            })?;
+        Ok(response.id_token)
+    }
+
+    /// The user this identity signs in as, linking or provisioning as needed.
+    async fn resolve_user(
+        &self,
+        provider: &oidc_providers::Model,
+        identity: IdentityClaims,
+    ) -> Result<users::Model, AppError> {
+        if let Some(linked) = self
+            .oidc
+            .find_identity(provider.id, &identity.subject)
+            .await?
+        {
+            return self
+                .users
+                .find_in_tenant(provider.tenant_id, linked.user_id)
+                .await?
+                .ok_or(AppError::UserNotFound);
+        }
+
+        let user = match self
+            .users
+            .find_by_email_in_tenant(provider.tenant_id, &identity.email)
+            .await?
+        {
+            // Linking on an unverified email would let anyone who can set
+            // that address at the provider take over the account
+            Some(_) if !identity.email_verified => return Err(AppError::OidcAccountConflict),
+            Some(user) => {
+                tracing::info!(
+                    "Linking identity to existing user: user_id={}, provider_id={}",
+                    user.id,
+                    provider.id
+                );
+                user
+            }
+            None => {
+                let role = if identity.is_admin {
+                    UserRole::Admin
+                } else {
+                    provider.default_role
+                };
+                match self.users.find_by_email(&identity.email).await? {
+                    // The same takeover applies to an account of another tenant
+                    Some(_) if !identity.email_verified => {
+                        return Err(AppError::OidcAccountConflict);
+                    }
+                    Some(_) => {
+                        let user = self
+                            .user_service
+                            .add_member(provider.tenant_id, &identity.email, role)
+                            .await?;
+                        tracing::info!(
+                            "Added existing user from identity provider: user_id={}, provider_id={}",
+                            user.id,
+                            provider.id
+                        );
+                        user
+                    }
+                    None => {
+                        // Provisioned users sign in through the provider; the
+                        // random password is never disclosed
+                        let user = self
+                            .user_service
+                            .create(
+                                provider.tenant_id,
+                                identity.email.clone(),
+                                &Self::random_token(),
+                                role,
+                            )
+                            .await?;
+                        tracing::info!(
+                            "Provisioned user from identity provider: user_id={}, provider_id={}",
+                            user.id,
+                            provider.id
+                        );
+                        user
+                    }
+                }
+            }
+        };
+
+        self.oidc
+            .insert_identity(user_identities::ActiveModel {
+                id: Set(Uuid::now_v7()),
+                user_id: Set(user.id),
+                provider_id: Set(provider.id),
+                subject: Set(identity.subject),
+                created_at: Set(Utc::now().fixed_offset()),
+            })
+            .await?;
+        Ok(user)
+    }
+}
+
+fn unavailable(provider: &oidc_providers::Model, e: HttpError) -> AppError {
+    tracing::warn!(
+        "Identity provider request failed: provider_id={}, error={}",
+        provider.id,
+        e
+    );
+    AppError::OidcProviderUnavailable
+}
+
+/// `openid` first, then the configured scopes without duplicates.
+fn request_scopes(scopes: &str) -> String {
+    let mut requested = vec!["openid"];
+    requested.extend(scopes.split_whitespace().filter(|s| *s != "openid"));
+    requested.join(" ")
+}
+
+#[async_trait]
+impl OidcService for OidcAuthService {
+    async fn create_provider(
+        &self,
+        tenant_id: Uuid,
+        req: CreateOidcProviderRequest,
+    ) -> Result<oidc_providers::Model, AppError> {
+        self.http.validate_url("issuer", &req.issuer).await?;
+
+        let now = Utc::now().fixed_offset();
+        let provider = oidc_providers::ActiveModel {
+            id: Set(Uuid::now_v7()),
+            tenant_id: Set(tenant_id),
+            name: Set(req.name),
+            issuer: Set(req.issuer),
+            client_id: Set(req.client_id),
+            client_secret: Set(req.client_secret),
+            scopes: Set(request_scopes(
+                req.scopes.as_deref().unwrap_or("openid email profile"),
+            )),
+            redirect_uri: Set(req.redirect_uri),
+            email_claim: Set(req.email_claim.unwrap_or_else(|| "email".to_string())),
+            role_claim: Set(req.role_claim),
+            admin_role_value: Set(req.admin_role_value),
+            default_role: Set(req.default_role.unwrap_or(UserRole::Regular)),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+
+        let provider = self.oidc.insert_provider(provider).await?;
+        tracing::info!(
+            "OIDC provider created: provider_id={}, tenant_id={}, issuer={}",
+            provider.id,
+            provider.tenant_id,
+            provider.issuer
+        );
+        Ok(provider)
+    }
+
+    async fn list_providers(
+        &self,
+        tenant_id: Uuid,
+    ) -> Result<Vec<oidc_providers::Model>, AppError> {
+        Ok(self.oidc.list_providers(tenant_id).await?)
+    }
+
+    async fn delete_provider(&self, tenant_id: Uuid, provider_id: Uuid) -> Result<(), AppError> {
+        if !self.oidc.delete_provider(tenant_id, provider_id).await? {
+            return Err(AppError::OidcProviderNotFound);
+        }
+        tracing::info!(
+            "OIDC provider deleted: provider_id={}, tenant_id={}",
+            provider_id,
+            tenant_id
+        );
+        Ok(())
+    }
+
+    async fn authorize(&self, provider_id: Uuid) -> Result<AuthorizationRequest, AppError> {
+        let provider = self
+            .oidc
+            .find_provider(provider_id)
+            .await?
+            .ok_or(AppError::OidcProviderNotFound)?;
+        let discovery = self.discover(&provider).await?;
+
+        let state = Self::random_token();
+        let nonce = Self::random_token();
+        let code_verifier = Self::random_token();
+
+        let mut url = Url::parse(&discovery.authorization_endpoint)
+            .map_err(|e| unavailable(&provider, HttpError::Url(e.to_string())))?;
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &provider.client_id)
+            .append_pair("redirect_uri", &provider.redirect_uri)
+            .append_pair("scope", &request_scopes(&provider.scopes))
+            .append_pair("state", &state)
+            .append_pair("nonce", &nonce)
+            .append_pair("code_challenge", &Self::pkce_challenge(&code_verifier))
+            .append_pair("code_challenge_method", "S256");
+
+        self.oidc
+            .insert_login_state(oidc_login_states::ActiveModel {
+                state: Set(state.clone()),
+                provider_id: Set(provider.id),
+                nonce: Set(nonce),
+                code_verifier: Set(code_verifier),
+                expires_at: Set((Utc::now() + LOGIN_STATE_TTL).fixed_offset()),
+            })
+            .await?;
+
+        Ok(AuthorizationRequest {
+            authorization_url: url.into(),
+            state,
+        })
+    }
+
+    async fn callback(&self, state: &str, code: &str) -> Result<AuthResponse, AppError> {
+        let login = self
+            .oidc
+            .take_login_state(state)
+            .await?
+            .filter(|login| login.expires_at > Utc::now().fixed_offset())
+            .ok_or(AppError::OidcStateInvalid)?;
+        let provider = self
+            .oidc
+            .find_provider(login.provider_id)
+            .await?
+            .ok_or(AppError::OidcProviderNotFound)?;
+
+        let discovery = self.discover(&provider).await?;
+        let id_token = self
+            .redeem_code(&provider, &discovery, code, &login.code_verifier)
+            .await?;
+        let keys: JwkSet = self
+            .http
+            .get_json(&discovery.jwks_uri)
+            .await
+            .map_err(|e| unavailable(&provider, e))?;
+        let identity = Self::verify_id_token(&id_token, &keys, &provider, &login.nonce)?;
+
+        let user = self.resolve_user(&provider, identity).await?;
+        self.auth.sign_in(user).await
+    }
+}
@@ -0,0 +1,182 @@
+use crate::models::{password_history, users};
+use crate::repositories::{PasswordHistoryRepository, TenantRepository};
+use crate::services::tenant_settings_service::TenantSettingsService;
+use crate::utils::error::AppError;
+use crate::utils::password_hash::PasswordHashing;
+use crate::utils::password_policy::{
+    BreachedPasswords, MAX_PASSWORD_HISTORY, PasswordPolicy, PasswordViolation,
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::Set;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[async_trait]
+pub trait PasswordPolicyService: Send + Sync {
+    /// Checks the password of a new account against the strictest policy of
+    /// `tenant_ids`, failing with a validation error on `password` that lists
+    /// every rule it breaks.
+    async fn check(&self, tenant_ids: &[Uuid], email: &str, password: &str)
+    -> Result<(), AppError>;
+
+    /// As [`check`](Self::check) for a password replacing `user`'s, which
+    /// must also differ from their recent ones.
+    async fn check_change(
+        &self,
+        tenant_ids: &[Uuid],
+        user: &users::Model,
+        password: &str,
+    ) -> Result<(), AppError>;
+
+    /// Records `password_hash`, which the user is replacing, so it cannot be
+    /// reused.
+    async fn remember(&self, user_id: Uuid, password_hash: String) -> Result<(), AppError>;
+}
+
+/// Applies the global [`PasswordPolicy`] with each tenant's overrides, and
+/// rejects passwords in the breached password list when one is configured.
+pub struct PasswordsService {
+    policy: PasswordPolicy,
+    breached: Option<Arc<BreachedPasswords>>,
+    settings: Arc<dyn TenantSettingsService>,
+    tenants: Arc<dyn TenantRepository>,
+    history: Arc<dyn PasswordHistoryRepository>,
+    hashing: PasswordHashing,
+}
+
+impl PasswordsService {
+    pub fn new(
+        policy: PasswordPolicy,
+        breached: Option<Arc<BreachedPasswords>>,
+        settings: Arc<dyn TenantSettingsService>,
+        tenants: Arc<dyn TenantRepository>,
+        history: Arc<dyn PasswordHistoryRepository>,
+        hashing: PasswordHashing,
+    ) -> Self {
+        Self {
+            policy,
+            breached,
+            settings,
+            tenants,
+            history,
+            hashing,
+        }
+    }
+
+    async fn validate(
+        &self,
+        tenant_ids: &[Uuid],
+        email: &str,
+        user: Option<&users::Model>,
+        password: &str,
+    ) -> Result<(), AppError> {
+        let mut policy: Option<PasswordPolicy> = None;
+        let mut personal = vec![email.to_string()];
+        for tenant_id in tenant_ids {
+            let settings = self.settings.get(*tenant_id).await?.settings;
+            let tenant_policy = self.policy.for_tenant(&settings);
+            policy = Some(match policy {
+                Some(policy) => policy.strictest(&tenant_policy),
+                None => tenant_policy,
+            });
+            if let Some(tenant) = self.tenants.find_by_id(*tenant_id).await? {
+                personal.push(tenant.name);
+            }
+        }
+        let policy = policy.unwrap_or_else(|| self.policy.clone());
+
+        let personal: Vec<&str> = personal.iter().map(String::as_str).collect();
+        let mut violations = policy.violations(password, &personal);
+        if let Some(breached) = &self.breached {
+            match breached.contains(password).await {
+                Ok(true) => violations.push(PasswordViolation::Breached),
+                Ok(false) => {}
+                Err(e) => tracing::error!("Cannot read breached passwords: {}", e),
+            }
+        }
+        // Hash checks are slow, so only once everything else passed
+        let reused = match user {
+            Some(user) if violations.is_empty() && policy.history > 0 => {
+                self.is_reused(user, password, policy.history).await?
+            }
+            _ => false,
+        };
+        if reused {
+            violations.push(PasswordViolation::Reused {
+                history: policy.history,
+            });
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::Validation(
+                violations
+                    .iter()
+                    .map(|violation| violation.field_error("password"))
+                    .collect(),
+            ))
+        }
+    }
+
+    /// Whether `password` is the user's current one or one of the
+    /// `history - 1` before it.
+    async fn is_reused(
+        &self,
+        user: &users::Model,
+        password: &str,
+        history: u32,
+    ) -> Result<bool, AppError> {
+        if self.hashing.verify(password, &user.password_hash).await? {
+            return Ok(true);
+        }
+        let previous = self
+            .history
+            .list_recent(user.id, u64::from(history - 1))
+            .await?;
+        for entry in previous {
+            if self.hashing.verify(password, &entry.password_hash).await? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+#[async_trait]
+impl PasswordPolicyService for PasswordsService {
+    async fn check(
+        &self,
+        tenant_ids: &[Uuid],
+        email: &str,
+        password: &str,
+    ) -> Result<(), AppError> {
+        self.validate(tenant_ids, email, None, password).await
+    }
+
+    async fn check_change(
+        &self,
+        tenant_ids: &[Uuid],
+        user: &users::Model,
+        password: &str,
+    ) -> Result<(), AppError> {
+        self.validate(tenant_ids, &user.email, Some(user), password)
+            .await
+    }
+
+    async fn remember(&self, user_id: Uuid, password_hash: String) -> Result<(), AppError> {
+        self.history
+            .insert(password_history::ActiveModel {
+                id: Set(Uuid::now_v7()),
+                user_id: Set(user_id),
+                password_hash: Set(password_hash),
+                created_at: Set(Utc::now().fixed_offset()),
+            })
+            .await?;
+        self.history
+            .prune(user_id, u64::from(MAX_PASSWORD_HISTORY))
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,438 @@
+//! SCIM 2.0 user provisioning (RFC 7643, RFC 7644) on top of [`UserService`].
+//!
+//! `userName` is the user's email, `active` maps to [`UserStatus`] and the
+//! primary `roles` entry to [`UserRole`]. Other attributes directories send
+//! (`name`, `externalId`, enterprise extensions, ...) are accepted and
+//! ignored: users have nowhere to keep them.
+
+use crate::enums::{UserRole, UserStatus};
+use crate::i18n;
+use crate::middleware::error_format::SCIM_PATH_PREFIX;
+use crate::models::users;
+use crate::services::users_service::{UserChanges, UserService};
+use crate::utils::error::{AppError, FieldError};
+use crate::utils::scim_filter::Filter;
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use async_trait::async_trait;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use uuid::Uuid;
+use validator::{Validate, ValidateEmail};
+
+pub const USER_SCHEMA: &str = "urn:ietf:params:scim:schemas:core:2.0:User";
+pub const LIST_RESPONSE_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:ListResponse";
+pub const PATCH_OP_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:PatchOp";
+
+/// Largest page a list request returns, and the page size when the client
+/// does not ask for one.
+pub const MAX_PAGE_SIZE: usize = 200;
+
+/// An entry of a multi-valued attribute such as `emails` or `roles`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ScimMultiValue {
+    pub value: String,
+    #[serde(default)]
+    pub primary: bool,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimMeta {
+    pub resource_type: String,
+    #[schema(value_type = String)]
+    pub created: DateTimeWithTimeZone,
+    #[schema(value_type = String)]
+    pub last_modified: DateTimeWithTimeZone,
+    pub location: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimUser {
+    pub schemas: Vec<String>,
+    pub id: Uuid,
+    pub user_name: String,
+    pub active: bool,
+    pub emails: Vec<ScimMultiValue>,
+    pub roles: Vec<ScimMultiValue>,
+    pub meta: ScimMeta,
+}
+
+impl From<users::Model> for ScimUser {
+    fn from(user: users::Model) -> Self {
+        ScimUser {
+            schemas: vec![USER_SCHEMA.to_string()],
+            id: user.id,
+            user_name: user.email.clone(),
+            active: user.status == UserStatus::Active,
+            emails: vec![ScimMultiValue {
+                value: user.email,
+                primary: true,
+                kind: Some("work".to_string()),
+            }],
+            roles: vec![ScimMultiValue {
+                value: format!("{:?}", user.role),
+                primary: true,
+                kind: None,
+            }],
+            meta: ScimMeta {
+                resource_type: "User".to_string(),
+                created: user.created_at,
+                last_modified: user.updated_at,
+                location: format!("{}/Users/{}", SCIM_PATH_PREFIX, user.id),
+            },
+        }
+    }
+}
+
+/// Body of a create (`POST`) or replace (`PUT`). Attributes that are not
+/// listed here are ignored.
+#[derive(Debug, Clone, Deserialize, Validate, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimUserRequest {
+    #[serde(default)]
+    pub schemas: Vec<String>,
+    #[validate(email(message = "email_invalid"))]
+    pub user_name: String,
+    #[serde(default = "default_active")]
+    pub active: bool,
+    /// Left unchanged on replace when omitted; `Regular` on create.
+    #[serde(default)]
+    pub roles: Vec<ScimMultiValue>,
+}
+
+fn default_active() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, utoipa::ToSchema)]
+pub struct ScimPatchRequest {
+    #[serde(default)]
+    pub schemas: Vec<String>,
+    #[serde(rename = "Operations")]
+    #[validate(length(min = 1, message = "field_required"))]
+    pub operations: Vec<ScimPatchOperation>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ScimPatchOperation {
+    /// `add`, `replace` or `remove`, in any case.
+    pub op: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Object)]
+    pub value: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, utoipa::IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct ScimListQuery {
+    /// e.g. `userName eq "jane@example.com"`
+    pub filter: Option<String>,
+    /// 1-based index of the first result.
+    pub start_index: Option<i64>,
+    pub count: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimUserList {
+    pub schemas: Vec<String>,
+    pub total_results: usize,
+    pub start_index: usize,
+    pub items_per_page: usize,
+    #[serde(rename = "Resources")]
+    pub resources: Vec<ScimUser>,
+}
+
+#[async_trait]
+pub trait ScimService: Send + Sync {
+    /// Users of the tenant matching `filter`, oldest first, one page at a time.
+    async fn list_users(
+        &self,
+        tenant_id: Uuid,
+        query: ScimListQuery,
+    ) -> Result<ScimUserList, AppError>;
+
+    async fn get_user(&self, tenant_id: Uuid, user_id: Uuid) -> Result<ScimUser, AppError>;
+
+    async fn create_user(
+        &self,
+        tenant_id: Uuid,
+        req: ScimUserRequest,
+    ) -> Result<ScimUser, AppError>;
+
+    async fn replace_user(
+        &self,
+        tenant_id: Uuid,
+        user_id: Uuid,
+        req: ScimUserRequest,
+    ) -> Result<ScimUser, AppError>;
+
+    async fn patch_user(
+        &self,
+        tenant_id: Uuid,
+        user_id: Uuid,
+        req: ScimPatchRequest,
+    ) -> Result<ScimUser, AppError>;
+
+    async fn delete_user(&self, tenant_id: Uuid, user_id: Uuid) -> Result<(), AppError>;
+}
+
+pub struct ScimUsersService {
+    users: Arc<dyn UserService>,
+}
+
+impl ScimUsersService {
+    pub fn new(users: Arc<dyn UserService>) -> Self {
+        Self { users }
+    }
+
+    /// The [`UserChanges`] a list of patch operations amounts to.
+    pub fn patch_changes(operations: &[ScimPatchOperation]) -> Result<UserChanges, AppError> {
+        let mut changes = UserChanges::default();
+        for operation in operations {
+            match operation.op.to_ascii_lowercase().as_str() {
+                "add" | "replace" => {
+                    let value = operation.value.as_ref().ok_or_else(|| {
+                        patch_invalid(format!("'{}' requires a value", operation.op))
+                    })?;
+                    match &operation.path {
+                        Some(path) => set_attribute(&mut changes, path, value)?,
+                        None => {
+                            let Value::Object(attributes) = value else {
+                                return Err(patch_invalid(
+                                    "value must be an object when there is no path",
+                                ));
+                            };
+                            for (name, value) in attributes {
+                                set_attribute(&mut changes, name, value)?;
+                            }
+                        }
+                    }
+                }
+                "remove" => {
+                    let path = operation
+                        .path
+                        .as_deref()
+                        .ok_or_else(|| patch_invalid("'remove' requires a path"))?;
+                    match attribute_name(path).as_str() {
+                        "roles" => changes.role = Some(UserRole::Regular),
+                        "username" | "active" => {
+                            return Err(patch_invalid(format!("'{}' cannot be removed", path)));
+                        }
+                        _ => {}
+                    }
+                }
+                other => return Err(patch_invalid(format!("unknown operation '{}'", other))),
+            }
+        }
+        Ok(changes)
+    }
+}
+
+fn patch_invalid(detail: impl Into<String>) -> AppError {
+    AppError::ScimPatchInvalid(detail.into())
+}
+
+fn invalid_field(field: &str, code: &str, key: &str) -> AppError {
+    let message = i18n::translate(i18n::current(), &format!("validation.{}", key), &[])
+        .unwrap_or_else(|| key.to_string());
+    AppError::Validation(vec![FieldError::new(field, code, message)])
+}
+
+/// Lowercased attribute name without a schema URN prefix.
+fn attribute_name(path: &str) -> String {
+    path.rsplit(':').next().unwrap_or(path).to_ascii_lowercase()
+}
+
+fn set_attribute(changes: &mut UserChanges, path: &str, value: &Value) -> Result<(), AppError> {
+    match attribute_name(path).as_str() {
+        "active" => {
+            // Some directories send booleans as strings
+            let active = match value {
+                Value::Bool(active) => *active,
+                Value::String(s) if s.eq_ignore_ascii_case("true") => true,
+                Value::String(s) if s.eq_ignore_ascii_case("false") => false,
+                _ => return Err(patch_invalid("'active' must be a boolean")),
+            };
+            changes.status = Some(status(active));
+        }
+        "username" => {
+            let email = value
+                .as_str()
+                .ok_or_else(|| patch_invalid("'userName' must be a string"))?;
+            if !email.validate_email() {
+                return Err(invalid_field("userName", "email", "email_invalid"));
+            }
+            changes.email = Some(email.to_lowercase());
+        }
+        "roles" => {
+            let roles: Vec<ScimMultiValue> = match value {
+                Value::Array(_) => serde_json::from_value(value.clone()),
+                _ => serde_json::from_value(value.clone()).map(|role| vec![role]),
+            }
+            .map_err(|_| patch_invalid("'roles' must be a list of { value }"))?;
+            if let Some(role) = role(&roles)? {
+                changes.role = Some(role);
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// The primary role, or the first one.
+fn role(roles: &[ScimMultiValue]) -> Result<Option<UserRole>, AppError> {
+    let Some(role) = roles.iter().find(|r| r.primary).or(roles.first()) else {
+        return Ok(None);
+    };
+    match role.value.to_ascii_lowercase().as_str() {
+        "admin" => Ok(Some(UserRole::Admin)),
+        "regular" => Ok(Some(UserRole::Regular)),
+        _ => Err(invalid_field("roles", "role", "role_invalid")),
+    }
+}
+
+fn status(active: bool) -> UserStatus {
+    if active {
+        UserStatus::Active
+    } else {
+        UserStatus::Inactive
+    }
+}
+
+/// Directory users sign in through SSO; the random password only fills the
+/// required hash and is never handed out.
+fn unusable_password() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+#[async_trait]
+impl ScimService for ScimUsersService {
+    async fn list_users(
+        &self,
+        tenant_id: Uuid,
+        query: ScimListQuery,
+    ) -> Result<ScimUserList, AppError> {
+        let filter = query.filter.as_deref().map(Filter::parse).transpose()?;
+
+        let mut users = self.users.list_by_tenant(tenant_id).await?;
+        users.reverse();
+        let mut matching = Vec::new();
+        for user in users.into_iter().map(ScimUser::from) {
+            if let Some(filter) = &filter {
+                let resource = serde_json::to_value(&user).map_err(|_| AppError::Internal)?;
+                if !filter.matches(&resource) {
+                    continue;
+                }
+            }
+            matching.push(user);
+        }
+
+        // RFC 7644 §3.4.2.4: indexes below 1 mean 1, negative counts mean 0
+        let start_index = query.start_index.unwrap_or(1).max(1) as usize;
+        let count = query.count.map_or(MAX_PAGE_SIZE, |count| {
+            count.clamp(0, MAX_PAGE_SIZE as i64) as usize
+        });
+        let total_results = matching.len();
+        let resources: Vec<ScimUser> = matching
+            .into_iter()
+            .skip(start_index - 1)
+            .take(count)
+            .collect();
+
+        Ok(ScimUserList {
+            schemas: vec![LIST_RESPONSE_SCHEMA.to_string()],
+            total_results,
+            start_index,
+            items_per_page: resources.len(),
+            resources,
+        })
+    }
+
+    async fn get_user(&self, tenant_id: Uuid, user_id: Uuid) -> Result<ScimUser, AppError> {
+        Ok(self.users.get_in_tenant(tenant_id, user_id).await?.into())
+    }
+
+    async fn create_user(
+        &self,
+        tenant_id: Uuid,
+        req: ScimUserRequest,
+    ) -> Result<ScimUser, AppError> {
+        let role = role(&req.roles)?.unwrap_or(UserRole::Regular);
+        let email = req.user_name.to_lowercase();
+        let mut user = match self
+            .users
+            .create(tenant_id, email.clone(), &unusable_password(), role)
+            .await
+        {
+            // Someone with an account in another tenant joins this one
+            Err(AppError::UserAlreadyExists) => self
+                .users
+                .add_member(tenant_id, &email, role)
+                .await
+                .map_err(|e| match e {
+                    AppError::MembershipAlreadyExists => AppError::UserAlreadyExists,
+                    e => e,
+                })?,
+            created => created?,
+        };
+        if !req.active {
+            let changes = UserChanges {
+                status: Some(UserStatus::Inactive),
+                ..Default::default()
+            };
+            user = self.users.update(tenant_id, user.id, changes).await?;
+        }
+
+        tracing::info!(
+            "Provisioned user via SCIM: user_id={}, tenant_id={}",
+            user.id,
+            tenant_id
+        );
+        Ok(user.into())
+    }
+
+    async fn replace_user(
+        &self,
+        tenant_id: Uuid,
+        user_id: Uuid,
+        req: ScimUserRequest,
+    ) -> Result<ScimUser, AppError> {
+        let changes = UserChanges {
+            email: Some(req.user_name.to_lowercase()),
+            role: role(&req.roles)?,
+            status: Some(status(req.active)),
+        };
+        Ok(self.users.update(tenant_id, user_id, changes).await?.into())
+    }
+
+    async fn patch_user(
+        &self,
+        tenant_id: Uuid,
+        user_id: Uuid,
+        req: ScimPatchRequest,
+    ) -> Result<ScimUser, AppError> {
+        let changes = Self::patch_changes(&req.operations)?;
+        Ok(self.users.update(tenant_id, user_id, changes).await?.into())
+    }
+
+    async fn delete_user(&self, tenant_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        self.users.delete(tenant_id, user_id).await?;
+        tracing::info!(
+            "Deprovisioned user via SCIM: user_id={}, tenant_id={}",
+            user_id,
+            tenant_id
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,159 @@
+use crate::models::tenant_settings::{self, TenantSettings};
+use crate::repositories::{TenantRepository, TenantSettingsRepository};
+use crate::utils::error::AppError;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+use validator::Validate;
+
+/// How long cached settings are trusted before being read again, which
+/// bounds how stale another instance's update can look here.
+pub const CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Settings of a tenant at a given version. Version 0 means nothing was
+/// stored and the defaults apply.
+#[derive(Clone, Debug, PartialEq, Serialize, utoipa::ToSchema)]
+pub struct TenantSettingsResponse {
+    pub tenant_id: Uuid,
+    pub version: i32,
+    pub settings: TenantSettings,
+}
+
+#[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
+pub struct UpdateTenantSettingsRequest {
+    /// The version the changes are based on, as last read.
+    pub version: i32,
+    #[validate(nested)]
+    pub settings: TenantSettings,
+}
+
+#[async_trait]
+pub trait TenantSettingsService: Send + Sync {
+    async fn get(&self, tenant_id: Uuid) -> Result<TenantSettingsResponse, AppError>;
+
+    /// Replaces the settings, failing with `SETTINGS_VERSION_CONFLICT` when
+    /// `req.version` is no longer current.
+    async fn update(
+        &self,
+        tenant_id: Uuid,
+        req: UpdateTenantSettingsRequest,
+    ) -> Result<TenantSettingsResponse, AppError>;
+}
+
+struct CachedSettings {
+    settings: TenantSettingsResponse,
+    loaded_at: Instant,
+}
+
+/// Serves settings from an in-memory cache, refreshed when an entry is older
+/// than [`CACHE_TTL`] and replaced on every update made through it. Only
+/// existing tenants are cached, so the cache is bounded by their number.
+pub struct TenantSettingsStore {
+    settings: Arc<dyn TenantSettingsRepository>,
+    tenants: Arc<dyn TenantRepository>,
+    cache: RwLock<HashMap<Uuid, CachedSettings>>,
+}
+
+impl TenantSettingsStore {
+    pub fn new(
+        settings: Arc<dyn TenantSettingsRepository>,
+        tenants: Arc<dyn TenantRepository>,
+    ) -> Self {
+        Self {
+            settings,
+            tenants,
+            cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    fn cached(&self, tenant_id: Uuid) -> Option<TenantSettingsResponse> {
+        let cache = self.cache.read().expect("settings cache poisoned");
+        cache
+            .get(&tenant_id)
+            .filter(|entry| entry.loaded_at.elapsed() < CACHE_TTL)
+            .map(|entry| entry.settings.clone())
+    }
+
+    fn store(&self, settings: TenantSettingsResponse) {
+        self.cache.write().expect("settings cache poisoned").insert(
+            settings.tenant_id,
+            CachedSettings {
+                settings,
+                loaded_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Stored settings that no longer parse are an error rather than a
+    /// silent fallback to the defaults, which could e.g. reopen registration.
+    fn parse(row: tenant_settings::Model) -> Result<TenantSettingsResponse, AppError> {
+        let settings = serde_json::from_value(row.settings).map_err(|e| {
+            tracing::error!(
+                "Stored settings of tenant {} are invalid: {}",
+                row.tenant_id,
+                e
+            );
+            AppError::Internal
+        })?;
+        Ok(TenantSettingsResponse {
+            tenant_id: row.tenant_id,
+            version: row.version,
+            settings,
+        })
+    }
+}
+
+#[async_trait]
+impl TenantSettingsService for TenantSettingsStore {
+    async fn get(&self, tenant_id: Uuid) -> Result<TenantSettingsResponse, AppError> {
+        if let Some(settings) = self.cached(tenant_id) {
+            return Ok(settings);
+        }
+
+        let settings = match self.settings.find(tenant_id).await? {
+            Some(row) => Self::parse(row)?,
+            None => {
+                let defaults = TenantSettingsResponse {
+                    tenant_id,
+                    version: 0,
+                    settings: TenantSettings::default(),
+                };
+                // Any id can be asked for, e.g. at registration; only
+                // tenants get an entry
+                if self.tenants.find_by_id(tenant_id).await?.is_none() {
+                    return Ok(defaults);
+                }
+                defaults
+            }
+        };
+        self.store(settings.clone());
+        Ok(settings)
+    }
+
+    async fn update(
+        &self,
+        tenant_id: Uuid,
+        req: UpdateTenantSettingsRequest,
+    ) -> Result<TenantSettingsResponse, AppError> {
+        let json = serde_json::to_value(&req.settings).map_err(|e| {
+            tracing::error!("Failed to serialize tenant settings: {}", e);
+            AppError::Internal
+        })?;
+
+        let Some(row) = self.settings.save(tenant_id, req.version, json).await? else {
+            // Whatever is cached is out of date
+            self.cache
+                .write()
+                .expect("settings cache poisoned")
+                .remove(&tenant_id);
+            return Err(AppError::SettingsVersionConflict);
+        };
+
+        let settings = Self::parse(row)?;
+        self.store(settings.clone());
+        Ok(settings)
+    }
+}
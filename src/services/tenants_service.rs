@@ -1,24 +1,106 @@
+use crate::enums::{Locale, TenantStatus};
+use crate::events::DomainEvent;
 use crate::models::tenants;
+use crate::repositories::TenantRepository;
 use crate::utils::error::AppError;
-use sea_orm::{DatabaseConnection, EntityTrait};
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::Set;
+use std::sync::Arc;
 use uuid::Uuid;
 
-pub struct TenantsService;
+#[async_trait]
+pub trait TenantService: Send + Sync {
+    async fn list_all(&self) -> Result<Vec<tenants::Model>, AppError>;
+
+    async fn get_by_id(&self, tenant_id: Uuid) -> Result<tenants::Model, AppError>;
+
+    async fn create(&self, name: String) -> Result<tenants::Model, AppError>;
+
+    async fn set_status(
+        &self,
+        tenant_id: Uuid,
+        status: TenantStatus,
+    ) -> Result<tenants::Model, AppError>;
+
+    /// Sets or clears the locale used for users without their own preference.
+    async fn set_default_locale(
+        &self,
+        tenant_id: Uuid,
+        locale: Option<Locale>,
+    ) -> Result<tenants::Model, AppError>;
+}
+
+pub struct TenantsService {
+    tenants: Arc<dyn TenantRepository>,
+}
 
 impl TenantsService {
-    pub async fn list_all(db: &DatabaseConnection) -> Result<Vec<tenants::Model>, AppError> {
-        let tenants_list = tenants::Entity::find().all(db).await?;
-        Ok(tenants_list)
+    pub fn new(tenants: Arc<dyn TenantRepository>) -> Self {
+        Self { tenants }
     }
+}
 
-    pub async fn get_by_id(
-        db: &DatabaseConnection,
+#[async_trait]
+impl TenantService for TenantsService {
+    async fn list_all(&self) -> Result<Vec<tenants::Model>, AppError> {
+        Ok(self.tenants.list_all().await?)
+    }
+
+    async fn get_by_id(&self, tenant_id: Uuid) -> Result<tenants::Model, AppError> {
+        self.tenants
+            .find_by_id(tenant_id)
+            .await?
+            .ok_or(AppError::TenantNotFound)
+    }
+
+    async fn create(&self, name: String) -> Result<tenants::Model, AppError> {
+        let tenant = tenants::ActiveModel {
+            id: Set(Uuid::now_v7()),
+            name: Set(name),
+            status: Set(TenantStatus::Active),
+            default_locale: Set(None),
+            created_at: Set(Utc::now().fixed_offset()),
+            updated_at: Set(Utc::now().fixed_offset()),
+        };
+
+        Ok(self.tenants.insert(tenant).await?)
+    }
+
+    async fn set_status(
+        &self,
         tenant_id: Uuid,
+        status: TenantStatus,
     ) -> Result<tenants::Model, AppError> {
-        let tenant = tenants::Entity::find_by_id(tenant_id)
-            .one(db)
-            .await?
-            .ok_or(AppError::TenantNotFound)?;
-        Ok(tenant)
+        let tenant = self.get_by_id(tenant_id).await?;
+        let events = if tenant.status == status {
+            Vec::new()
+        } else {
+            vec![DomainEvent::TenantStatusChanged {
+                tenant_id,
+                status,
+                previous_status: tenant.status,
+            }]
+        };
+
+        let mut tenant: tenants::ActiveModel = tenant.into();
+        tenant.status = Set(status);
+        tenant.updated_at = Set(Utc::now().fixed_offset());
+
+        Ok(self.tenants.update(tenant, events).await?)
+    }
+
+    async fn set_default_locale(
+        &self,
+        tenant_id: Uuid,
+        locale: Option<Locale>,
+    ) -> Result<tenants::Model, AppError> {
+        let tenant = self.get_by_id(tenant_id).await?;
+
+        let mut tenant: tenants::ActiveModel = tenant.into();
+        tenant.default_locale = Set(locale);
+        tenant.updated_at = Set(Utc::now().fixed_offset());
+
+        Ok(self.tenants.update(tenant, Vec::new()).await?)
     }
 }
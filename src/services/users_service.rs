@@ -1,57 +1,643 @@
-use crate::enums::{UserRole, UserStatus};
-use crate::models::users;
-use crate::utils::error::AppError;
-use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use crate::enums::{Locale, UserRole, UserStatus};
+use crate::events::{DomainEvent, MemberSnapshot};
+use crate::i18n;
+use crate::models::{memberships, users};
+use crate::repositories::{MembershipRepository, UserRepository};
+use crate::services::password_policy_service::PasswordPolicyService;
+use crate::utils::error::{AppError, FieldError};
+use crate::utils::password_hash::PasswordHashing;
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use sea_orm::{ActiveModelTrait, DbErr, Set, SqlErr};
+use std::sync::Arc;
 use uuid::Uuid;
 
-pub struct UsersService;
+/// Fields to change on a user; `None` leaves a field as it is.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UserChanges {
+    pub email: Option<String>,
+    pub role: Option<UserRole>,
+    pub status: Option<UserStatus>,
+}
+
+#[async_trait]
+pub trait UserService: Send + Sync {
+    async fn get_by_id(&self, user_id: Uuid) -> Result<users::Model, AppError>;
+
+    async fn get_in_tenant(&self, tenant_id: Uuid, user_id: Uuid)
+    -> Result<users::Model, AppError>;
+
+    async fn list_by_tenant(&self, tenant_id: Uuid) -> Result<Vec<users::Model>, AppError>;
+
+    /// Creates an identity with its first membership, of `tenant_id`.
+    async fn create(
+        &self,
+        tenant_id: Uuid,
+        email: String,
+        password: &str,
+        role: UserRole,
+    ) -> Result<users::Model, AppError>;
+
+    /// Adds an existing user, found by email, to `tenant_id`.
+    async fn add_member(
+        &self,
+        tenant_id: Uuid,
+        email: &str,
+        role: UserRole,
+    ) -> Result<users::Model, AppError>;
+
+    async fn find_membership(
+        &self,
+        tenant_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<memberships::Model>, AppError>;
+
+    /// Memberships of a user, oldest first.
+    async fn list_memberships(&self, user_id: Uuid) -> Result<Vec<memberships::Model>, AppError>;
+
+    /// Records that the user obtained a token for the tenant, which keeps
+    /// the membership from being deactivated as idle.
+    async fn record_login(&self, tenant_id: Uuid, user_id: Uuid) -> Result<(), AppError>;
+
+    /// Whether `password` is the user's. A correct password stored with
+    /// outdated hashing parameters is rehashed on the way.
+    async fn verify_password(&self, user: &users::Model, password: &str) -> Result<bool, AppError>;
+
+    /// Sets a new password chosen for the user, which must satisfy the
+    /// password policy of every tenant they belong to.
+    async fn reset_password(
+        &self,
+        tenant_id: Uuid,
+        email: &str,
+        new_password: &str,
+    ) -> Result<users::Model, AppError>;
+
+    /// Replaces the user's own password after checking the current one. The
+    /// new password must satisfy the password policy of every tenant the
+    /// user belongs to.
+    async fn change_password(
+        &self,
+        user_id: Uuid,
+        current_password: &str,
+        new_password: &str,
+    ) -> Result<(), AppError>;
+
+    async fn change_user_status(
+        &self,
+        user_id: Uuid,
+        tenant_id: Uuid,
+    ) -> Result<users::Model, AppError>;
+
+    async fn change_role(&self, user_id: Uuid, tenant_id: Uuid) -> Result<users::Model, AppError>;
+
+    /// Sets or clears the user's preferred locale.
+    async fn set_locale(
+        &self,
+        user_id: Uuid,
+        locale: Option<Locale>,
+    ) -> Result<users::Model, AppError>;
+
+    /// Applies `changes`: the email to the identity, which must stay unique
+    /// and may only be changed while the user belongs to no other tenant;
+    /// role and status to the membership of `tenant_id`.
+    async fn update(
+        &self,
+        tenant_id: Uuid,
+        user_id: Uuid,
+        changes: UserChanges,
+    ) -> Result<users::Model, AppError>;
+
+    /// Removes the user from `tenant_id`. A user who belongs to no other
+    /// tenant is soft-deleted instead: hidden everywhere, unable to log in,
+    /// and restorable until erased.
+    async fn delete(&self, tenant_id: Uuid, user_id: Uuid) -> Result<(), AppError>;
+
+    /// Undoes the deletion of a member of `tenant_id` within the grace
+    /// period.
+    async fn restore(&self, tenant_id: Uuid, user_id: Uuid) -> Result<users::Model, AppError>;
+
+    /// Anonymizes the users deleted longer than the grace period ago and
+    /// returns how many were erased.
+    async fn erase_deleted(&self) -> Result<usize, AppError>;
+}
+
+/// Passwords given to `create` are not checked against the password policy:
+/// callers check them where users choose their own, and identity providers
+/// create users with generated ones.
+///
+/// New members and changes of role and status are recorded as domain events
+/// in the transaction that writes them.
+pub struct UsersService {
+    users: Arc<dyn UserRepository>,
+    memberships: Arc<dyn MembershipRepository>,
+    passwords: Arc<dyn PasswordPolicyService>,
+    hashing: PasswordHashing,
+    /// How long deleted users can be restored before they are erased.
+    deletion_grace: Duration,
+}
 
 impl UsersService {
-    pub async fn change_user_status(
-        db: &DatabaseConnection,
+    pub fn new(
+        users: Arc<dyn UserRepository>,
+        memberships: Arc<dyn MembershipRepository>,
+        passwords: Arc<dyn PasswordPolicyService>,
+        hashing: PasswordHashing,
+        deletion_grace: Duration,
+    ) -> Self {
+        Self {
+            users,
+            memberships,
+            passwords,
+            hashing,
+            deletion_grace,
+        }
+    }
+
+    /// Writes the membership of `user` and records how the member changed.
+    async fn update_membership(
+        &self,
+        user: users::Model,
+        membership: memberships::ActiveModel,
+    ) -> Result<users::Model, AppError> {
+        let changed = users::Model {
+            role: *membership.role.as_ref(),
+            status: *membership.status.as_ref(),
+            ..user.clone()
+        };
+        let events = DomainEvent::member_changes(&user, &changed);
+        Ok(user.as_member(&self.memberships.update(membership, events).await?))
+    }
+
+    /// Checks `new_password` against the policies of the user's tenants.
+    async fn check_password(
+        &self,
+        user: &users::Model,
+        new_password: &str,
+    ) -> Result<(), AppError> {
+        let tenant_ids: Vec<Uuid> = self
+            .memberships
+            .list_by_user(user.id)
+            .await?
+            .into_iter()
+            .map(|membership| membership.tenant_id)
+            .collect();
+        self.passwords
+            .check_change(&tenant_ids, user, new_password)
+            .await
+    }
+
+    /// Stores `new_password` and keeps the replaced hash in the history.
+    async fn set_password(
+        &self,
+        user: &users::Model,
+        new_password: &str,
+    ) -> Result<users::Model, AppError> {
+        let password_hash = self.hashing.hash(new_password).await?;
+
+        let mut identity: users::ActiveModel = user.clone().into();
+        identity.password_hash = Set(password_hash);
+        identity.updated_at = Set(Utc::now().fixed_offset());
+        let updated = self.update_identity(user, identity).await?;
+
+        self.passwords
+            .remember(user.id, user.password_hash.clone())
+            .await?;
+        Ok(updated)
+    }
+
+    async fn get_membership(
+        &self,
+        tenant_id: Uuid,
         user_id: Uuid,
+    ) -> Result<memberships::Model, AppError> {
+        self.memberships
+            .find(user_id, tenant_id)
+            .await?
+            .ok_or(AppError::UserNotFound)
+    }
+
+    /// Writes the identity and returns it as the same member as `user`.
+    async fn update_identity(
+        &self,
+        user: &users::Model,
+        identity: users::ActiveModel,
+    ) -> Result<users::Model, AppError> {
+        let updated = self.users.update(identity).await?;
+        Ok(users::Model {
+            tenant_id: user.tenant_id,
+            role: user.role,
+            status: user.status,
+            ..updated
+        })
+    }
+}
+
+#[async_trait]
+impl UserService for UsersService {
+    async fn get_by_id(&self, user_id: Uuid) -> Result<users::Model, AppError> {
+        self.users
+            .find_by_id(user_id)
+            .await?
+            .ok_or(AppError::UserNotFound)
+    }
+
+    async fn get_in_tenant(
+        &self,
         tenant_id: Uuid,
+        user_id: Uuid,
     ) -> Result<users::Model, AppError> {
-        let user = users::Entity::find()
-            .filter(users::Column::Id.eq(user_id))
-            .filter(users::Column::TenantId.eq(tenant_id))
-            .one(db)
+        self.users
+            .find_in_tenant(tenant_id, user_id)
             .await?
-            .ok_or(AppError::UserNotFound)?;
+            .ok_or(AppError::UserNotFound)
+    }
 
-        let new_status = match user.status {
-            UserStatus::Active => UserStatus::Inactive,
-            UserStatus::Inactive => UserStatus::Active,
+    async fn list_by_tenant(&self, tenant_id: Uuid) -> Result<Vec<users::Model>, AppError> {
+        Ok(self.users.list_by_tenant(tenant_id).await?)
+    }
+
+    async fn create(
+        &self,
+        tenant_id: Uuid,
+        email: String,
+        password: &str,
+        role: UserRole,
+    ) -> Result<users::Model, AppError> {
+        // Emails identify users across tenants; someone who already has an
+        // account joins another tenant through `add_member`
+        if self.users.find_by_email(&email).await?.is_some() {
+            return Err(AppError::UserAlreadyExists);
+        }
+
+        let password_hash = self.hashing.hash(password).await?;
+        let now = Utc::now().fixed_offset();
+
+        let user_id = Uuid::now_v7();
+        let event = DomainEvent::UserRegistered {
+            user: MemberSnapshot {
+                id: user_id,
+                tenant_id,
+                email: email.clone(),
+                role,
+                status: UserStatus::Active,
+            },
+        };
+
+        let user = users::ActiveModel {
+            id: Set(user_id),
+            tenant_id: Set(tenant_id),
+            email: Set(email),
+            password_hash: Set(password_hash),
+            role: Set(role),
+            status: Set(UserStatus::Active),
+            locale: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+            deleted_at: Set(None),
+            erased_at: Set(None),
+        };
+        let membership = memberships::ActiveModel {
+            user_id: Set(user_id),
+            tenant_id: Set(tenant_id),
+            role: Set(role),
+            status: Set(UserStatus::Active),
+            created_at: Set(now),
+            updated_at: Set(now),
+            last_login_at: Set(None),
         };
+        self.users
+            .insert_member(user, membership, vec![event])
+            .await
+            .map_err(email_taken)
+    }
 
-        let mut user: users::ActiveModel = user.into();
-        user.status = Set(new_status);
-        let user = user.update(db).await?;
+    async fn add_member(
+        &self,
+        tenant_id: Uuid,
+        email: &str,
+        role: UserRole,
+    ) -> Result<users::Model, AppError> {
+        let user = self
+            .users
+            .find_by_email(email)
+            .await?
+            .ok_or(AppError::UserNotFound)?;
+
+        if self.memberships.find(user.id, tenant_id).await?.is_some() {
+            return Err(AppError::MembershipAlreadyExists);
+        }
+
+        let now = Utc::now().fixed_offset();
+        let member = users::Model {
+            tenant_id,
+            role,
+            status: UserStatus::Active,
+            ..user.clone()
+        };
+        let event = DomainEvent::MemberAdded {
+            user: (&member).into(),
+        };
+        let membership = self
+            .memberships
+            .insert(
+                memberships::ActiveModel {
+                    user_id: Set(user.id),
+                    tenant_id: Set(tenant_id),
+                    role: Set(role),
+                    status: Set(UserStatus::Active),
+                    created_at: Set(now),
+                    updated_at: Set(now),
+                    last_login_at: Set(None),
+                },
+                vec![event],
+            )
+            .await?;
 
-        Ok(user)
+        Ok(user.as_member(&membership))
     }
 
-    pub async fn change_role(
-        db: &DatabaseConnection,
+    async fn find_membership(
+        &self,
+        tenant_id: Uuid,
         user_id: Uuid,
+    ) -> Result<Option<memberships::Model>, AppError> {
+        Ok(self.memberships.find(user_id, tenant_id).await?)
+    }
+
+    async fn list_memberships(&self, user_id: Uuid) -> Result<Vec<memberships::Model>, AppError> {
+        Ok(self.memberships.list_by_user(user_id).await?)
+    }
+
+    async fn record_login(&self, tenant_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        Ok(self
+            .memberships
+            .touch(user_id, tenant_id, Utc::now().fixed_offset())
+            .await?)
+    }
+
+    async fn verify_password(&self, user: &users::Model, password: &str) -> Result<bool, AppError> {
+        if !self.hashing.verify(password, &user.password_hash).await? {
+            return Ok(false);
+        }
+
+        if self.hashing.needs_rehash(&user.password_hash) {
+            // The password is right either way, so a failed upgrade is only
+            // retried at the next login
+            let rehashed = async {
+                let mut identity: users::ActiveModel = user.clone().into();
+                identity.password_hash = Set(self.hashing.hash(password).await?);
+                self.users.update(identity).await?;
+                Ok::<_, AppError>(())
+            };
+            match rehashed.await {
+                Ok(()) => tracing::info!("Rehashed password of user {}", user.id),
+                Err(e) => tracing::warn!("Cannot rehash password of user {}: {}", user.id, e),
+            }
+        }
+        Ok(true)
+    }
+
+    async fn reset_password(
+        &self,
         tenant_id: Uuid,
+        email: &str,
+        new_password: &str,
     ) -> Result<users::Model, AppError> {
-        let user = users::Entity::find()
-            .filter(users::Column::Id.eq(user_id))
-            .filter(users::Column::TenantId.eq(tenant_id))
-            .one(db)
+        let user = self
+            .users
+            .find_by_email_in_tenant(tenant_id, email)
             .await?
             .ok_or(AppError::UserNotFound)?;
 
-        let new_role = match user.role {
+        self.check_password(&user, new_password).await?;
+        self.set_password(&user, new_password).await
+    }
+
+    async fn change_password(
+        &self,
+        user_id: Uuid,
+        current_password: &str,
+        new_password: &str,
+    ) -> Result<(), AppError> {
+        let user = self.get_by_id(user_id).await?;
+
+        if !self
+            .hashing
+            .verify(current_password, &user.password_hash)
+            .await?
+        {
+            let message = i18n::translate(i18n::current(), "validation.password_incorrect", &[])
+                .unwrap_or_else(|| "password_incorrect".to_string());
+            return Err(AppError::Validation(vec![FieldError::new(
+                "current_password",
+                "invalid",
+                message,
+            )]));
+        }
+        self.check_password(&user, new_password)
+            .await
+            .map_err(|e| match e {
+                AppError::Validation(errors) => AppError::Validation(
+                    errors
+                        .into_iter()
+                        .map(|error| FieldError {
+                            field: "new_password".to_string(),
+                            ..error
+                        })
+                        .collect(),
+                ),
+                e => e,
+            })?;
+
+        self.set_password(&user, new_password).await?;
+        Ok(())
+    }
+
+    async fn change_user_status(
+        &self,
+        user_id: Uuid,
+        tenant_id: Uuid,
+    ) -> Result<users::Model, AppError> {
+        let user = self.get_in_tenant(tenant_id, user_id).await?;
+        let membership = self.get_membership(tenant_id, user_id).await?;
+
+        let new_status = match membership.status {
+            UserStatus::Active => UserStatus::Inactive,
+            UserStatus::Inactive => UserStatus::Active,
+        };
+
+        let mut membership: memberships::ActiveModel = membership.into();
+        membership.status = Set(new_status);
+        membership.updated_at = Set(Utc::now().fixed_offset());
+
+        self.update_membership(user, membership).await
+    }
+
+    async fn change_role(&self, user_id: Uuid, tenant_id: Uuid) -> Result<users::Model, AppError> {
+        let user = self.get_in_tenant(tenant_id, user_id).await?;
+        let membership = self.get_membership(tenant_id, user_id).await?;
+
+        let new_role = match membership.role {
             UserRole::Admin => UserRole::Regular,
             UserRole::Regular => UserRole::Admin,
         };
 
-        let mut user: users::ActiveModel = user.into();
-        user.role = Set(new_role);
-        let user = user.update(db).await?;
+        let mut membership: memberships::ActiveModel = membership.into();
+        membership.role = Set(new_role);
+        membership.updated_at = Set(Utc::now().fixed_offset());
+
+        self.update_membership(user, membership).await
+    }
+
+    async fn set_locale(
+        &self,
+        user_id: Uuid,
+        locale: Option<Locale>,
+    ) -> Result<users::Model, AppError> {
+        let user = self.get_by_id(user_id).await?;
+
+        let mut identity: users::ActiveModel = user.clone().into();
+        identity.locale = Set(locale);
+        identity.updated_at = Set(Utc::now().fixed_offset());
+
+        self.update_identity(&user, identity).await
+    }
+
+    async fn update(
+        &self,
+        tenant_id: Uuid,
+        user_id: Uuid,
+        changes: UserChanges,
+    ) -> Result<users::Model, AppError> {
+        let mut user = self.get_in_tenant(tenant_id, user_id).await?;
+        let now = Utc::now().fixed_offset();
+
+        if let Some(email) = changes.email.filter(|email| *email != user.email) {
+            if self.users.find_by_email(&email).await?.is_some() {
+                return Err(AppError::UserAlreadyExists);
+            }
+            // The identity is shared, so one tenant must not rename it for
+            // the others
+            if self.memberships.list_by_user(user_id).await?.len() > 1 {
+                return Err(AppError::Forbidden(
+                    "the user belongs to other tenants".to_string(),
+                ));
+            }
+
+            let mut identity: users::ActiveModel = user.clone().into();
+            identity.email = Set(email);
+            identity.updated_at = Set(now);
+            user = self
+                .update_identity(&user, identity)
+                .await
+                .map_err(|e| match e {
+                    AppError::Database(e) => email_taken(e),
+                    e => e,
+                })?;
+        }
+
+        let mut membership: memberships::ActiveModel =
+            self.get_membership(tenant_id, user_id).await?.into();
+        if let Some(role) = changes.role.filter(|role| *role != user.role) {
+            membership.role = Set(role);
+        }
+        if let Some(status) = changes.status.filter(|status| *status != user.status) {
+            membership.status = Set(status);
+        }
+        if !membership.is_changed() {
+            return Ok(user);
+        }
+        membership.updated_at = Set(now);
+
+        self.update_membership(user, membership).await
+    }
+
+    async fn delete(&self, tenant_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        // Read first: lookups by id go through the membership being deleted
+        // when it is the one of the tenant the user was created in
+        let user = self.get_by_id(user_id).await?;
+        let (removed, remaining): (Vec<_>, Vec<_>) = self
+            .memberships
+            .list_by_user(user_id)
+            .await?
+            .into_iter()
+            .partition(|membership| membership.tenant_id == tenant_id);
+        if removed.is_empty() {
+            return Err(AppError::UserNotFound);
+        }
+
+        let Some(membership) = remaining.first() else {
+            // The membership is kept so that a restored user is back where
+            // it was
+            let now = Utc::now().fixed_offset();
+            let mut identity: users::ActiveModel = user.into();
+            identity.deleted_at = Set(Some(now));
+            identity.updated_at = Set(now);
+            self.users.update(identity).await?;
+            tracing::info!("Deleted user {}", user_id);
+            return Ok(());
+        };
+
+        // Logins start in the tenant the user was created in, so move that
+        // to a tenant the user still belongs to
+        let home_tenant_id = (user.tenant_id == tenant_id).then_some(membership.tenant_id);
+        if !self
+            .memberships
+            .delete(user_id, tenant_id, home_tenant_id)
+            .await?
+        {
+            return Err(AppError::UserNotFound);
+        }
+        Ok(())
+    }
+
+    async fn restore(&self, tenant_id: Uuid, user_id: Uuid) -> Result<users::Model, AppError> {
+        let user = self
+            .users
+            .find_deleted_in_tenant(tenant_id, user_id)
+            .await?
+            .ok_or(AppError::UserNotFound)?;
+        // Past the grace period the user is only waiting to be erased
+        let cutoff = Utc::now() - self.deletion_grace;
+        if user
+            .deleted_at
+            .is_some_and(|deleted_at| deleted_at < cutoff)
+        {
+            return Err(AppError::UserNotFound);
+        }
+
+        let mut identity: users::ActiveModel = user.clone().into();
+        identity.deleted_at = Set(None);
+        identity.updated_at = Set(Utc::now().fixed_offset());
+        let restored = self.update_identity(&user, identity).await?;
+        tracing::info!("Restored user {}", user_id);
+        Ok(restored)
+    }
+
+    async fn erase_deleted(&self) -> Result<usize, AppError> {
+        let cutoff = (Utc::now() - self.deletion_grace).fixed_offset();
+        let erasable = self.users.list_erasable(cutoff).await?;
+
+        for user in &erasable {
+            let now = Utc::now().fixed_offset();
+            let mut identity: users::ActiveModel = user.clone().into();
+            // Unique and undeliverable, so the address can be used again
+            identity.email = Set(format!("erased-{}@erased.invalid", user.id));
+            identity.password_hash = Set(String::new());
+            identity.locale = Set(None);
+            identity.erased_at = Set(Some(now));
+            identity.updated_at = Set(now);
+            self.users.erase(identity).await?;
+            tracing::info!("Erased personal data of deleted user {}", user.id);
+        }
+        Ok(erasable.len())
+    }
+}
 
-        Ok(user)
+/// Another identity, possibly a deleted one, already has the email.
+fn email_taken(e: DbErr) -> AppError {
+    match e.sql_err() {
+        Some(SqlErr::UniqueConstraintViolation(_)) => AppError::UserAlreadyExists,
+        _ => e.into(),
     }
 }
@@ -0,0 +1,482 @@
+use crate::enums::{WebhookDeliveryStatus, WebhookEvent};
+use crate::jobs::webhook::DeliverWebhook;
+use crate::jobs::worker::retry_delay;
+use crate::models::{webhook_deliveries, webhook_endpoints};
+use crate::repositories::WebhookRepository;
+use crate::services::jobs_service::{JobQueue, NewJob};
+use crate::utils::error::AppError;
+use crate::utils::http_client::HttpClient;
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use async_trait::async_trait;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sea_orm::Set;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::sync::Arc;
+use uuid::Uuid;
+use validator::Validate;
+
+pub const WEBHOOK_SECRET_PREFIX: &str = "whsec_";
+
+/// Headers sent with each delivery. The event id is the same for every
+/// delivery of an event, redeliveries included.
+pub const EVENT_ID_HEADER: &str = "X-Webhook-Id";
+pub const DELIVERY_ID_HEADER: &str = "X-Webhook-Delivery";
+pub const EVENT_HEADER: &str = "X-Webhook-Event";
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+
+/// Bytes of a successful answer kept in the delivery log.
+const MAX_RESPONSE_BODY: usize = 1024;
+
+/// Deliveries returned when no limit is given, and the most returned at
+/// once.
+const DEFAULT_DELIVERY_LIMIT: u64 = 50;
+const MAX_DELIVERY_LIMIT: u64 = 200;
+
+#[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
+pub struct CreateWebhookRequest {
+    /// Receives a `POST` per event; must be `http` or `https`, without
+    /// credentials, and resolve to a public address.
+    #[validate(url(message = "url_invalid"))]
+    pub url: String,
+    /// Events to send. `ping` is sent on request whether subscribed or not.
+    #[validate(length(min = 1, message = "events_required"))]
+    pub events: Vec<WebhookEvent>,
+}
+
+/// A newly created endpoint. `secret` signs its payloads and is never shown
+/// again.
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct CreatedWebhook {
+    pub secret: String,
+    pub endpoint: webhook_endpoints::Model,
+}
+
+#[async_trait]
+pub trait WebhookService: Send + Sync {
+    async fn create_endpoint(
+        &self,
+        tenant_id: Uuid,
+        request: CreateWebhookRequest,
+    ) -> Result<CreatedWebhook, AppError>;
+
+    async fn list_endpoints(
+        &self,
+        tenant_id: Uuid,
+    ) -> Result<Vec<webhook_endpoints::Model>, AppError>;
+
+    /// Deletes the endpoint with its delivery log. Pending deliveries are
+    /// dropped.
+    async fn delete_endpoint(&self, tenant_id: Uuid, endpoint_id: Uuid) -> Result<(), AppError>;
+
+    /// Deliveries to the endpoint, newest first.
+    async fn list_deliveries(
+        &self,
+        tenant_id: Uuid,
+        endpoint_id: Uuid,
+        limit: Option<u64>,
+    ) -> Result<Vec<webhook_deliveries::Model>, AppError>;
+
+    /// Queues a new delivery of the same event, with a fresh set of
+    /// attempts. Returns the new delivery.
+    async fn redeliver(
+        &self,
+        tenant_id: Uuid,
+        endpoint_id: Uuid,
+        delivery_id: Uuid,
+    ) -> Result<webhook_deliveries::Model, AppError>;
+
+    /// Sends a `ping` event to the endpoint right away, once, and returns
+    /// the delivery with its outcome.
+    async fn ping(
+        &self,
+        tenant_id: Uuid,
+        endpoint_id: Uuid,
+    ) -> Result<webhook_deliveries::Model, AppError>;
+
+    /// Queues a delivery of the event to each endpoint of the tenant
+    /// subscribed to it. `event_id` becomes the `id` of the payload, and
+    /// `data` its `data` field.
+    async fn publish(
+        &self,
+        tenant_id: Uuid,
+        event_id: Uuid,
+        event: WebhookEvent,
+        data: serde_json::Value,
+    ) -> Result<(), AppError>;
+
+    /// Makes the next attempt of a pending delivery and records its outcome,
+    /// scheduling another attempt after a failure until `max_attempts` have
+    /// been made. Run by the delivery job.
+    async fn deliver(&self, delivery_id: Uuid) -> Result<(), AppError>;
+}
+
+/// Value of the signature header of a payload sent at `timestamp` (Unix
+/// seconds): `t=<timestamp>,v1=<hex HMAC-SHA256 of "<timestamp>.<body>">`,
+/// keyed with the endpoint secret. Receivers recompute it, and reject old
+/// timestamps to stop replays.
+pub fn signature_header(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any length");
+    mac.update(format!("{}.", timestamp).as_bytes());
+    mac.update(body);
+    format!(
+        "t={},v1={}",
+        timestamp,
+        hex::encode(mac.finalize().into_bytes())
+    )
+}
+
+pub struct Webhooks {
+    webhooks: Arc<dyn WebhookRepository>,
+    queue: Arc<dyn JobQueue>,
+    client: HttpClient,
+    max_attempts: i32,
+}
+
+impl Webhooks {
+    pub fn new(
+        webhooks: Arc<dyn WebhookRepository>,
+        queue: Arc<dyn JobQueue>,
+        client: HttpClient,
+        max_attempts: i32,
+    ) -> Self {
+        Self {
+            webhooks,
+            queue,
+            client,
+            max_attempts,
+        }
+    }
+
+    pub fn generate_secret() -> String {
+        let mut secret = [0u8; 32];
+        OsRng.fill_bytes(&mut secret);
+        format!("{}{}", WEBHOOK_SECRET_PREFIX, hex::encode(secret))
+    }
+
+    async fn get_endpoint(
+        &self,
+        tenant_id: Uuid,
+        endpoint_id: Uuid,
+    ) -> Result<webhook_endpoints::Model, AppError> {
+        self.webhooks
+            .find_endpoint(tenant_id, endpoint_id)
+            .await?
+            .ok_or(AppError::WebhookNotFound)
+    }
+
+    /// A pending delivery of the event to `endpoint`, due now.
+    fn new_delivery(
+        endpoint: &webhook_endpoints::Model,
+        event_id: Uuid,
+        event: &str,
+        payload: serde_json::Value,
+    ) -> webhook_deliveries::ActiveModel {
+        webhook_deliveries::ActiveModel {
+            id: Set(Uuid::now_v7()),
+            tenant_id: Set(endpoint.tenant_id),
+            endpoint_id: Set(endpoint.id),
+            event_id: Set(event_id),
+            event: Set(event.to_string()),
+            redelivery_of: Set(None),
+            payload: Set(payload),
+            status: Set(WebhookDeliveryStatus::Pending),
+            attempts: Set(0),
+            next_attempt_at: Set(Some(Utc::now().fixed_offset())),
+            response_status: Set(None),
+            response_body: Set(None),
+            error: Set(None),
+            created_at: Set(Utc::now().fixed_offset()),
+            last_attempt_at: Set(None),
+            delivered_at: Set(None),
+        }
+    }
+
+    async fn enqueue(&self, delivery: &webhook_deliveries::Model) -> Result<(), AppError> {
+        let job = NewJob::of(&DeliverWebhook {
+            delivery_id: delivery.id,
+        })?;
+        let job = match delivery.next_attempt_at {
+            Some(at) => job.at(at.to_utc()),
+            None => job,
+        };
+        self.queue.enqueue(job).await?;
+        Ok(())
+    }
+
+    /// POSTs the payload of `delivery` and records the outcome. A failed
+    /// attempt stays pending, due after the backoff, while `retry` and
+    /// attempts remain.
+    async fn attempt(
+        &self,
+        endpoint: &webhook_endpoints::Model,
+        delivery: webhook_deliveries::Model,
+        retry: bool,
+    ) -> Result<webhook_deliveries::Model, AppError> {
+        let body = serde_json::to_vec(&delivery.payload).map_err(|e| {
+            tracing::error!(delivery_id = %delivery.id, "Cannot encode webhook payload: {}", e);
+            AppError::Internal
+        })?;
+        let headers = vec![
+            ("Content-Type", "application/json".to_string()),
+            (EVENT_ID_HEADER, delivery.event_id.to_string()),
+            (DELIVERY_ID_HEADER, delivery.id.to_string()),
+            (EVENT_HEADER, delivery.event.clone()),
+            (
+                SIGNATURE_HEADER,
+                signature_header(&endpoint.secret, Utc::now().timestamp(), &body),
+            ),
+        ];
+        let result = self.client.post(&endpoint.url, headers, body).await;
+
+        let now = Utc::now();
+        let attempts = delivery.attempts + 1;
+        let delivery_id = delivery.id;
+        let mut update: webhook_deliveries::ActiveModel = delivery.into();
+        update.attempts = Set(attempts);
+        update.last_attempt_at = Set(Some(now.fixed_offset()));
+        let error = match result {
+            Ok(response) => {
+                update.response_status = Set(Some(response.status.into()));
+                // What an endpoint answers when failing may be anything,
+                // internal errors included, so only the status is kept
+                update.response_body = Set(response.is_success().then(|| {
+                    String::from_utf8_lossy(&response.body)
+                        .chars()
+                        .take(MAX_RESPONSE_BODY)
+                        .collect()
+                }));
+                (!response.is_success())
+                    .then(|| format!("Endpoint answered with status {}", response.status))
+            }
+            Err(e) => {
+                update.response_status = Set(None);
+                update.response_body = Set(None);
+                Some(e.to_string())
+            }
+        };
+
+        match error {
+            None => {
+                tracing::info!(%delivery_id, attempts, "Webhook delivered");
+                update.status = Set(WebhookDeliveryStatus::Succeeded);
+                update.next_attempt_at = Set(None);
+                update.error = Set(None);
+                update.delivered_at = Set(Some(now.fixed_offset()));
+            }
+            Some(error) if retry && attempts < self.max_attempts => {
+                let delay = retry_delay(attempts);
+                tracing::warn!(
+                    %delivery_id,
+                    attempts,
+                    retry_in_secs = delay.num_seconds(),
+                    "Webhook delivery failed, will retry: {}",
+                    error
+                );
+                update.status = Set(WebhookDeliveryStatus::Pending);
+                update.next_attempt_at = Set(Some((now + delay).fixed_offset()));
+                update.error = Set(Some(error));
+            }
+            Some(error) => {
+                tracing::warn!(%delivery_id, attempts, "Webhook delivery failed: {}", error);
+                update.status = Set(WebhookDeliveryStatus::Failed);
+                update.next_attempt_at = Set(None);
+                update.error = Set(Some(error));
+            }
+        }
+
+        let delivery = self.webhooks.update_delivery(update).await?;
+        if delivery.status == WebhookDeliveryStatus::Pending {
+            self.enqueue(&delivery).await?;
+        }
+        Ok(delivery)
+    }
+}
+
+/// The body of every delivery: the event, whose `id` identifies it across
+/// redeliveries, around its `data`.
+fn envelope(
+    event_id: Uuid,
+    event: WebhookEvent,
+    tenant_id: Uuid,
+    data: serde_json::Value,
+) -> serde_json::Value {
+    serde_json::json!({
+        "id": event_id,
+        "type": event,
+        "tenant_id": tenant_id,
+        "created_at": Utc::now(),
+        "data": data,
+    })
+}
+
+#[async_trait]
+impl WebhookService for Webhooks {
+    async fn create_endpoint(
+        &self,
+        tenant_id: Uuid,
+        request: CreateWebhookRequest,
+    ) -> Result<CreatedWebhook, AppError> {
+        self.client.validate_url("url", &request.url).await?;
+        let secret = Self::generate_secret();
+        let now = Utc::now().fixed_offset();
+
+        let endpoint = self
+            .webhooks
+            .insert_endpoint(webhook_endpoints::ActiveModel {
+                id: Set(Uuid::now_v7()),
+                tenant_id: Set(tenant_id),
+                url: Set(request.url),
+                secret: Set(secret.clone()),
+                events: Set(WebhookEvent::join(&request.events)),
+                created_at: Set(now),
+                updated_at: Set(now),
+            })
+            .await?;
+
+        Ok(CreatedWebhook { secret, endpoint })
+    }
+
+    async fn list_endpoints(
+        &self,
+        tenant_id: Uuid,
+    ) -> Result<Vec<webhook_endpoints::Model>, AppError> {
+        Ok(self.webhooks.list_endpoints(tenant_id).await?)
+    }
+
+    async fn delete_endpoint(&self, tenant_id: Uuid, endpoint_id: Uuid) -> Result<(), AppError> {
+        if !self
+            .webhooks
+            .delete_endpoint(tenant_id, endpoint_id)
+            .await?
+        {
+            return Err(AppError::WebhookNotFound);
+        }
+        Ok(())
+    }
+
+    async fn list_deliveries(
+        &self,
+        tenant_id: Uuid,
+        endpoint_id: Uuid,
+        limit: Option<u64>,
+    ) -> Result<Vec<webhook_deliveries::Model>, AppError> {
+        let endpoint = self.get_endpoint(tenant_id, endpoint_id).await?;
+        let limit = limit
+            .unwrap_or(DEFAULT_DELIVERY_LIMIT)
+            .clamp(1, MAX_DELIVERY_LIMIT);
+        Ok(self.webhooks.list_deliveries(endpoint.id, limit).await?)
+    }
+
+    async fn redeliver(
+        &self,
+        tenant_id: Uuid,
+        endpoint_id: Uuid,
+        delivery_id: Uuid,
+    ) -> Result<webhook_deliveries::Model, AppError> {
+        let endpoint = self.get_endpoint(tenant_id, endpoint_id).await?;
+        let original = self
+            .webhooks
+            .find_delivery(delivery_id)
+            .await?
+            .filter(|delivery| delivery.endpoint_id == endpoint.id)
+            .ok_or(AppError::WebhookDeliveryNotFound)?;
+
+        let mut delivery = Self::new_delivery(
+            &endpoint,
+            original.event_id,
+            &original.event,
+            original.payload,
+        );
+        delivery.redelivery_of = Set(Some(original.id));
+        let delivery = self.webhooks.insert_delivery(delivery).await?;
+        self.enqueue(&delivery).await?;
+        Ok(delivery)
+    }
+
+    async fn ping(
+        &self,
+        tenant_id: Uuid,
+        endpoint_id: Uuid,
+    ) -> Result<webhook_deliveries::Model, AppError> {
+        let endpoint = self.get_endpoint(tenant_id, endpoint_id).await?;
+        let event_id = Uuid::now_v7();
+        let payload = envelope(
+            event_id,
+            WebhookEvent::Ping,
+            tenant_id,
+            serde_json::json!({ "webhook_id": endpoint.id }),
+        );
+        let delivery = self
+            .webhooks
+            .insert_delivery(Self::new_delivery(
+                &endpoint,
+                event_id,
+                WebhookEvent::Ping.as_str(),
+                payload,
+            ))
+            .await?;
+        self.attempt(&endpoint, delivery, false).await
+    }
+
+    async fn publish(
+        &self,
+        tenant_id: Uuid,
+        event_id: Uuid,
+        event: WebhookEvent,
+        data: serde_json::Value,
+    ) -> Result<(), AppError> {
+        let endpoints: Vec<_> = self
+            .webhooks
+            .list_endpoints(tenant_id)
+            .await?
+            .into_iter()
+            .filter(|endpoint| WebhookEvent::parse_list(&endpoint.events).contains(&event))
+            .collect();
+        if endpoints.is_empty() {
+            return Ok(());
+        }
+
+        let payload = envelope(event_id, event, tenant_id, data);
+        let mut deliveries = Vec::with_capacity(endpoints.len());
+        for endpoint in &endpoints {
+            let delivery_id = Uuid::now_v7();
+            let delivery = webhook_deliveries::ActiveModel {
+                id: Set(delivery_id),
+                ..Self::new_delivery(endpoint, event_id, event.as_str(), payload.clone())
+            };
+            let job = NewJob::of(&DeliverWebhook { delivery_id })?;
+            deliveries.push((delivery, self.queue.job(job)));
+        }
+        // Publishing an event again, as the outbox relay does when it could
+        // not record a dispatch, skips the endpoints that already have it
+        let inserted = self.webhooks.insert_deliveries(deliveries).await?;
+        if inserted < endpoints.len() as u64 {
+            tracing::debug!(%event_id, "Webhook event already published to some endpoints");
+        }
+        Ok(())
+    }
+
+    async fn deliver(&self, delivery_id: Uuid) -> Result<(), AppError> {
+        // Gone with its endpoint, or already settled by an earlier run of
+        // this job
+        let Some(delivery) = self.webhooks.find_delivery(delivery_id).await? else {
+            return Ok(());
+        };
+        if delivery.status != WebhookDeliveryStatus::Pending {
+            return Ok(());
+        }
+        let Some(endpoint) = self
+            .webhooks
+            .find_endpoint(delivery.tenant_id, delivery.endpoint_id)
+            .await?
+        else {
+            return Ok(());
+        };
+
+        self.attempt(&endpoint, delivery, true).await?;
+        Ok(())
+    }
+}
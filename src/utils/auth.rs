@@ -1,148 +1,198 @@
-use crate::enums::UserRole;
-use crate::middleware::auth::Claims;
+use crate::enums::{UserRole, UserStatus};
+use crate::middleware::auth::{Claims, Principal, PrincipalKind};
+use crate::services::api_keys_service::ApiKeyService;
+use crate::services::users_service::UserService;
+use crate::utils::error::AppError;
 use axum::{
-    extract::FromRequestParts,
-    http::{StatusCode, request::Parts},
-    response::{IntoResponse, Response},
+    extract::{FromRef, FromRequestParts},
+    http::request::Parts,
 };
-use serde_json::json;
+use serde::Deserialize;
+use std::sync::Arc;
 use uuid::Uuid;
 
-pub fn check_tenant_access(claims: &Claims, tenant_id: Uuid) -> Result<(), StatusCode> {
-    if claims.tenant_id != tenant_id {
-        return Err(StatusCode::FORBIDDEN);
+pub fn check_tenant_access(principal: &Principal, tenant_id: Uuid) -> Result<(), AppError> {
+    if principal.tenant_id != tenant_id {
+        return Err(AppError::TenantAccessDenied);
     }
     Ok(())
 }
 
-pub fn check_role(claims: &Claims, required_role: UserRole) -> Result<(), StatusCode> {
-    if claims.role != required_role {
-        return Err(StatusCode::FORBIDDEN);
+pub fn check_role(principal: &Principal, required_role: UserRole) -> Result<(), AppError> {
+    if principal.role != required_role {
+        return Err(match required_role {
+            UserRole::Admin => AppError::AdminRoleRequired,
+            UserRole::Regular => AppError::Forbidden(format!("{:?} role required", required_role)),
+        });
     }
     Ok(())
 }
 
 pub fn check_tenant_and_role(
-    claims: &Claims,
+    principal: &Principal,
     tenant_id: Uuid,
     required_role: UserRole,
-) -> Result<(), StatusCode> {
-    check_tenant_access(claims, tenant_id)?;
-    check_role(claims, required_role)?;
+) -> Result<(), AppError> {
+    check_tenant_access(principal, tenant_id)?;
+    check_role(principal, required_role)?;
     Ok(())
 }
 
-pub struct AdminRole(pub Claims);
+/// A user signed in with a JWT who is an admin of the token's tenant. API
+/// keys are not accepted.
+pub struct AdminRole(pub Principal);
 
 impl<S> FromRequestParts<S> for AdminRole
 where
     S: Send + Sync,
+    Arc<dyn UserService>: FromRef<S>,
 {
-    type Rejection = Response;
-
-    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        let claims = Claims::from_request_parts(parts, _state)
-            .await
-            .map_err(|e| e.into_response())?;
-
-        if claims.role != UserRole::Admin {
-            let body = json!({
-                "error": "FORBIDDEN",
-                "message": "ADMIN_ROLE_REQUIRED"
-            });
-            return Err((StatusCode::FORBIDDEN, axum::Json(body)).into_response());
-        }
-
-        Ok(AdminRole(claims))
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let principal: Principal = Claims::from_request_parts(parts, state).await?.into();
+        let principal = own_tenant(principal, state).await?;
+        check_role(&principal, UserRole::Admin)?;
+
+        Ok(AdminRole(principal))
+    }
+}
+
+/// An admin (user or service account) acting on its own tenant, for routes
+/// without a `{tenant_id}` segment.
+pub struct AdminPrincipal(pub Principal);
+
+impl<S> FromRequestParts<S> for AdminPrincipal
+where
+    S: Send + Sync,
+    Arc<dyn ApiKeyService>: FromRef<S>,
+    Arc<dyn UserService>: FromRef<S>,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let principal = Principal::from_request_parts(parts, state).await?;
+        let principal = own_tenant(principal, state).await?;
+        check_role(&principal, UserRole::Admin)?;
+
+        Ok(AdminPrincipal(principal))
+    }
+}
+
+/// The `{tenant_id}` segment of a route. Deserialized by name so that routes
+/// with further path parameters (e.g. `{user_id}`) are accepted too.
+#[derive(Deserialize)]
+struct TenantPath {
+    tenant_id: Uuid,
+}
+
+async fn tenant_id_from_path<S: Send + Sync>(
+    parts: &mut Parts,
+    state: &S,
+) -> Result<Uuid, AppError> {
+    let path = axum::extract::Path::<TenantPath>::from_request_parts(parts, state)
+        .await
+        .map_err(|_| AppError::InvalidTenantId)?;
+    Ok(path.tenant_id)
+}
+
+/// `principal` acting on `tenant_id`. A user needs an active membership of
+/// the tenant and has the role it grants, so role changes apply without a new
+/// token; a service account only acts on its own tenant.
+async fn principal_for_tenant(
+    principal: Principal,
+    tenant_id: Uuid,
+    users: &dyn UserService,
+) -> Result<Principal, AppError> {
+    let PrincipalKind::User {
+        user_id,
+        tenant_bound,
+        ..
+    } = &principal.kind
+    else {
+        check_tenant_access(&principal, tenant_id)?;
+        return Ok(principal);
+    };
+    if *tenant_bound {
+        check_tenant_access(&principal, tenant_id)?;
     }
+
+    let membership = users
+        .find_membership(tenant_id, *user_id)
+        .await?
+        .filter(|membership| membership.status == UserStatus::Active)
+        .ok_or(AppError::TenantAccessDenied)?;
+
+    Ok(Principal {
+        tenant_id,
+        role: membership.role,
+        ..principal
+    })
 }
 
+/// `principal` acting on the tenant it authenticated for, with its current
+/// membership rather than the role in its token.
+async fn own_tenant<S>(principal: Principal, state: &S) -> Result<Principal, AppError>
+where
+    S: Send + Sync,
+    Arc<dyn UserService>: FromRef<S>,
+{
+    let users = Arc::<dyn UserService>::from_ref(state);
+    let tenant_id = principal.tenant_id;
+    principal_for_tenant(principal, tenant_id, users.as_ref()).await
+}
+
+/// An admin (user or service account) of the `{tenant_id}` in the path.
 pub struct AdminRoleWithTenant {
-    pub claims: Claims,
+    pub principal: Principal,
     pub tenant_id: Uuid,
 }
 
 impl<S> FromRequestParts<S> for AdminRoleWithTenant
 where
     S: Send + Sync,
+    Arc<dyn ApiKeyService>: FromRef<S>,
+    Arc<dyn UserService>: FromRef<S>,
 {
-    type Rejection = Response;
-
-    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        let claims = Claims::from_request_parts(parts, _state)
-            .await
-            .map_err(|e| e.into_response())?;
-
-        if claims.role != UserRole::Admin {
-            let body = json!({
-                "error": "FORBIDDEN",
-                "message": "ADMIN_ROLE_REQUIRED"
-            });
-            return Err((StatusCode::FORBIDDEN, axum::Json(body)).into_response());
-        }
-
-        let tenant_id = axum::extract::Path::<Uuid>::from_request_parts(parts, _state)
-            .await
-            .map_err(|_| {
-                (
-                    StatusCode::BAD_REQUEST,
-                    axum::Json(json!({"error": "INVALID_TENANT_ID"})),
-                )
-                    .into_response()
-            })?;
-
-        if claims.tenant_id != *tenant_id {
-            let body = json!({
-                "error": "FORBIDDEN",
-                "message": "ACCESS_DENIED_FOR_THIS_TENANT"
-            });
-            return Err((StatusCode::FORBIDDEN, axum::Json(body)).into_response());
-        }
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let principal = Principal::from_request_parts(parts, state).await?;
+        let tenant_id = tenant_id_from_path(parts, state).await?;
+        let users = Arc::<dyn UserService>::from_ref(state);
+        let principal = principal_for_tenant(principal, tenant_id, users.as_ref()).await?;
+        check_role(&principal, UserRole::Admin)?;
 
         Ok(AdminRoleWithTenant {
-            claims,
-            tenant_id: *tenant_id,
+            principal,
+            tenant_id,
         })
     }
 }
 
+/// Any member or service account of the `{tenant_id}` in the path.
 pub struct TenantAccess {
-    pub claims: Claims,
+    pub principal: Principal,
     pub tenant_id: Uuid,
 }
 
 impl<S> FromRequestParts<S> for TenantAccess
 where
     S: Send + Sync,
+    Arc<dyn ApiKeyService>: FromRef<S>,
+    Arc<dyn UserService>: FromRef<S>,
 {
-    type Rejection = Response;
-
-    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        let claims = Claims::from_request_parts(parts, _state)
-            .await
-            .map_err(|e| e.into_response())?;
-
-        let tenant_id = axum::extract::Path::<Uuid>::from_request_parts(parts, _state)
-            .await
-            .map_err(|_| {
-                (
-                    StatusCode::BAD_REQUEST,
-                    axum::Json(json!({"error": "INVALID_TENANT_ID"})),
-                )
-                    .into_response()
-            })?;
-
-        if claims.tenant_id != *tenant_id {
-            let body = json!({
-                "error": "FORBIDDEN",
-                "message": "ACCESS_DENIED_FOR_THIS_TENANT"
-            });
-            return Err((StatusCode::FORBIDDEN, axum::Json(body)).into_response());
-        }
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let principal = Principal::from_request_parts(parts, state).await?;
+        let tenant_id = tenant_id_from_path(parts, state).await?;
+        let users = Arc::<dyn UserService>::from_ref(state);
+        let principal = principal_for_tenant(principal, tenant_id, users.as_ref()).await?;
 
         Ok(TenantAccess {
-            claims,
-            tenant_id: *tenant_id,
+            principal,
+            tenant_id,
         })
     }
 }
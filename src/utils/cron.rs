@@ -0,0 +1,60 @@
+//! Cron expressions with the five standard fields, evaluated in UTC:
+//!
+//! ```text
+//! minute (0-59)  hour (0-23)  day of month (1-31)  month (1-12)  day of week (0-7, 0 and 7 are Sunday)
+//! ```
+//!
+//! Parsing and matching are done by [`croner`]: each field is `*`, a value,
+//! a range `a-b`, any of them with a step (`*/15`, `1-30/2`), or a
+//! comma-separated list of those. Months and days of the week also take
+//! their English three-letter names (`jan`, `mon`). As in standard cron,
+//! when both day fields are restricted a day matching either one is due.
+//! `@hourly`, `@daily`, `@weekly`, `@monthly` and `@yearly` are accepted as
+//! shorthands, and `L` for the last day of the month.
+
+use chrono::{DateTime, Utc};
+use croner::Cron;
+use std::fmt;
+use std::str::FromStr;
+
+/// A parsed cron expression. Displays as the expression it was parsed from.
+#[derive(Debug, Clone)]
+pub struct Schedule {
+    source: String,
+    cron: Cron,
+}
+
+impl Schedule {
+    /// The first minute strictly after `after` that the schedule matches, or
+    /// `None` if it never matches.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.cron.find_next_occurrence(&after, false).ok()
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let source = input.trim();
+        let cron = Cron::new(source).parse().map_err(|e| e.to_string())?;
+        Ok(Self {
+            source: source.to_string(),
+            cron,
+        })
+    }
+}
+
+impl PartialEq for Schedule {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Eq for Schedule {}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
@@ -1,17 +1,333 @@
+use crate::enums::ApiScope;
+use crate::i18n;
+use crate::middleware::error_format::{ErrorFormat, PROBLEM_JSON, SCIM_ERROR_SCHEMA, SCIM_JSON};
+use crate::middleware::request_id::RequestId;
 use axum::{
-    http::StatusCode,
+    extract::rejection::{JsonRejection, PathRejection, QueryRejection},
+    http::{HeaderValue, StatusCode, header},
     response::{IntoResponse, Response},
 };
 
 use serde::Serialize;
-use serde_json::json;
+use std::collections::BTreeMap;
 use thiserror::Error;
 use utoipa::ToSchema;
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct ErrorResponse {
-    pub error: String,
+    pub error: ErrorCode,
     pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+/// RFC 9457 problem details, sent as `application/problem+json` to clients
+/// that ask for it. `code` and `request_id` are extension members.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    pub code: ErrorCode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+}
+
+/// SCIM error body (RFC 7644 §3.12), sent as `application/scim+json` on the
+/// `/scim/v2` endpoints. `status` is a string, as the RFC requires.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ScimError {
+    pub schemas: Vec<&'static str>,
+    pub status: String,
+    #[serde(rename = "scimType", skip_serializing_if = "Option::is_none")]
+    pub scim_type: Option<&'static str>,
+    pub detail: String,
+}
+
+/// A single validation issue. `field` is a dotted path into the request
+/// body (e.g. `address.lines[0]`), `code` the validator rule that failed.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct FieldError {
+    pub field: String,
+    pub code: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    #[schema(value_type = Object)]
+    pub params: BTreeMap<String, serde_json::Value>,
+}
+
+impl FieldError {
+    pub fn new(
+        field: impl Into<String>,
+        code: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            field: field.into(),
+            code: code.into(),
+            message: message.into(),
+            params: BTreeMap::new(),
+        }
+    }
+}
+
+/// Stable, machine-readable error codes returned in [`ErrorResponse::error`].
+///
+/// Clients should branch on these rather than on messages, which may change.
+/// Adding a variant is fine; renaming or removing one is a breaking change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    MissingToken,
+    InvalidToken,
+    TokenExpired,
+    InvalidCredentials,
+    AdminRoleRequired,
+    TenantAccessDenied,
+    InsufficientScope,
+    UserNotValidated,
+    Forbidden,
+    RegistrationClosed,
+    DownloadLinkInvalid,
+    UserNotFound,
+    TenantNotFound,
+    ServiceAccountNotFound,
+    ApiKeyNotFound,
+    OidcProviderNotFound,
+    ExportNotFound,
+    WebhookNotFound,
+    WebhookDeliveryNotFound,
+    UserAlreadyExists,
+    MembershipAlreadyExists,
+    OidcAccountConflict,
+    SettingsVersionConflict,
+    ApiKeyInactive,
+    ValidationError,
+    InvalidRequestBody,
+    InvalidPathParameter,
+    InvalidQueryParameter,
+    InvalidTenantId,
+    OidcStateInvalid,
+    ScimFilterInvalid,
+    ScimPatchInvalid,
+    OidcLoginFailed,
+    DatabaseError,
+    InternalError,
+    OidcProviderUnavailable,
+    ServiceUnavailable,
+}
+
+impl ErrorCode {
+    pub const ALL: &'static [ErrorCode] = &[
+        ErrorCode::MissingToken,
+        ErrorCode::InvalidToken,
+        ErrorCode::TokenExpired,
+        ErrorCode::InvalidCredentials,
+        ErrorCode::AdminRoleRequired,
+        ErrorCode::TenantAccessDenied,
+        ErrorCode::InsufficientScope,
+        ErrorCode::UserNotValidated,
+        ErrorCode::Forbidden,
+        ErrorCode::RegistrationClosed,
+        ErrorCode::DownloadLinkInvalid,
+        ErrorCode::UserNotFound,
+        ErrorCode::TenantNotFound,
+        ErrorCode::ServiceAccountNotFound,
+        ErrorCode::ApiKeyNotFound,
+        ErrorCode::OidcProviderNotFound,
+        ErrorCode::ExportNotFound,
+        ErrorCode::WebhookNotFound,
+        ErrorCode::WebhookDeliveryNotFound,
+        ErrorCode::UserAlreadyExists,
+        ErrorCode::MembershipAlreadyExists,
+        ErrorCode::OidcAccountConflict,
+        ErrorCode::SettingsVersionConflict,
+        ErrorCode::ApiKeyInactive,
+        ErrorCode::ValidationError,
+        ErrorCode::InvalidRequestBody,
+        ErrorCode::InvalidPathParameter,
+        ErrorCode::InvalidQueryParameter,
+        ErrorCode::InvalidTenantId,
+        ErrorCode::OidcStateInvalid,
+        ErrorCode::ScimFilterInvalid,
+        ErrorCode::ScimPatchInvalid,
+        ErrorCode::OidcLoginFailed,
+        ErrorCode::DatabaseError,
+        ErrorCode::InternalError,
+        ErrorCode::OidcProviderUnavailable,
+        ErrorCode::ServiceUnavailable,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::MissingToken => "MISSING_TOKEN",
+            ErrorCode::InvalidToken => "INVALID_TOKEN",
+            ErrorCode::TokenExpired => "TOKEN_EXPIRED",
+            ErrorCode::InvalidCredentials => "INVALID_CREDENTIALS",
+            ErrorCode::AdminRoleRequired => "ADMIN_ROLE_REQUIRED",
+            ErrorCode::TenantAccessDenied => "TENANT_ACCESS_DENIED",
+            ErrorCode::InsufficientScope => "INSUFFICIENT_SCOPE",
+            ErrorCode::UserNotValidated => "USER_NOT_VALIDATED",
+            ErrorCode::Forbidden => "FORBIDDEN",
+            ErrorCode::RegistrationClosed => "REGISTRATION_CLOSED",
+            ErrorCode::DownloadLinkInvalid => "DOWNLOAD_LINK_INVALID",
+            ErrorCode::UserNotFound => "USER_NOT_FOUND",
+            ErrorCode::TenantNotFound => "TENANT_NOT_FOUND",
+            ErrorCode::ServiceAccountNotFound => "SERVICE_ACCOUNT_NOT_FOUND",
+            ErrorCode::ApiKeyNotFound => "API_KEY_NOT_FOUND",
+            ErrorCode::OidcProviderNotFound => "OIDC_PROVIDER_NOT_FOUND",
+            ErrorCode::ExportNotFound => "EXPORT_NOT_FOUND",
+            ErrorCode::WebhookNotFound => "WEBHOOK_NOT_FOUND",
+            ErrorCode::WebhookDeliveryNotFound => "WEBHOOK_DELIVERY_NOT_FOUND",
+            ErrorCode::UserAlreadyExists => "USER_ALREADY_EXISTS",
+            ErrorCode::MembershipAlreadyExists => "MEMBERSHIP_ALREADY_EXISTS",
+            ErrorCode::OidcAccountConflict => "OIDC_ACCOUNT_CONFLICT",
+            ErrorCode::SettingsVersionConflict => "SETTINGS_VERSION_CONFLICT",
+            ErrorCode::ApiKeyInactive => "API_KEY_INACTIVE",
+            ErrorCode::ValidationError => "VALIDATION_ERROR",
+            ErrorCode::InvalidRequestBody => "INVALID_REQUEST_BODY",
+            ErrorCode::InvalidPathParameter => "INVALID_PATH_PARAMETER",
+            ErrorCode::InvalidQueryParameter => "INVALID_QUERY_PARAMETER",
+            ErrorCode::InvalidTenantId => "INVALID_TENANT_ID",
+            ErrorCode::OidcStateInvalid => "OIDC_STATE_INVALID",
+            ErrorCode::ScimFilterInvalid => "SCIM_FILTER_INVALID",
+            ErrorCode::ScimPatchInvalid => "SCIM_PATCH_INVALID",
+            ErrorCode::OidcLoginFailed => "OIDC_LOGIN_FAILED",
+            ErrorCode::DatabaseError => "DATABASE_ERROR",
+            ErrorCode::InternalError => "INTERNAL_ERROR",
+            ErrorCode::OidcProviderUnavailable => "OIDC_PROVIDER_UNAVAILABLE",
+            ErrorCode::ServiceUnavailable => "SERVICE_UNAVAILABLE",
+        }
+    }
+
+    pub fn status(self) -> StatusCode {
+        match self {
+            ErrorCode::MissingToken
+            | ErrorCode::InvalidToken
+            | ErrorCode::TokenExpired
+            | ErrorCode::InvalidCredentials
+            | ErrorCode::OidcLoginFailed => StatusCode::UNAUTHORIZED,
+            ErrorCode::AdminRoleRequired
+            | ErrorCode::TenantAccessDenied
+            | ErrorCode::InsufficientScope
+            | ErrorCode::UserNotValidated
+            | ErrorCode::Forbidden
+            | ErrorCode::RegistrationClosed
+            | ErrorCode::DownloadLinkInvalid => StatusCode::FORBIDDEN,
+            ErrorCode::UserNotFound
+            | ErrorCode::TenantNotFound
+            | ErrorCode::ServiceAccountNotFound
+            | ErrorCode::ApiKeyNotFound
+            | ErrorCode::OidcProviderNotFound
+            | ErrorCode::ExportNotFound
+            | ErrorCode::WebhookNotFound
+            | ErrorCode::WebhookDeliveryNotFound => StatusCode::NOT_FOUND,
+            ErrorCode::UserAlreadyExists
+            | ErrorCode::MembershipAlreadyExists
+            | ErrorCode::OidcAccountConflict
+            | ErrorCode::SettingsVersionConflict
+            | ErrorCode::ApiKeyInactive => StatusCode::CONFLICT,
+            ErrorCode::ValidationError
+            | ErrorCode::InvalidRequestBody
+            | ErrorCode::InvalidPathParameter
+            | ErrorCode::InvalidQueryParameter
+            | ErrorCode::InvalidTenantId
+            | ErrorCode::OidcStateInvalid
+            | ErrorCode::ScimFilterInvalid
+            | ErrorCode::ScimPatchInvalid => StatusCode::BAD_REQUEST,
+            ErrorCode::DatabaseError | ErrorCode::InternalError => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+            ErrorCode::OidcProviderUnavailable => StatusCode::BAD_GATEWAY,
+            ErrorCode::ServiceUnavailable => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+
+    /// Problem `type` URI for this code, e.g. `urn:problem-type:user-not-found`.
+    pub fn problem_type(self) -> String {
+        format!(
+            "urn:problem-type:{}",
+            self.as_str().to_ascii_lowercase().replace('_', "-")
+        )
+    }
+
+    /// `scimType` reported in SCIM error bodies (RFC 7644 §3.12), for the
+    /// codes that have one.
+    pub fn scim_type(self) -> Option<&'static str> {
+        match self {
+            ErrorCode::UserAlreadyExists => Some("uniqueness"),
+            ErrorCode::ScimFilterInvalid => Some("invalidFilter"),
+            ErrorCode::ScimPatchInvalid => Some("invalidPath"),
+            ErrorCode::ValidationError => Some("invalidValue"),
+            ErrorCode::InvalidRequestBody => Some("invalidSyntax"),
+            _ => None,
+        }
+    }
+
+    /// Catalog message for this code in the current request's locale.
+    pub fn title(self) -> String {
+        i18n::translate(i18n::current(), &format!("errors.{}", self.as_str()), &[])
+            .unwrap_or_else(|| self.description().to_string())
+    }
+
+    /// English description, used for the OpenAPI catalog.
+    pub fn description(self) -> &'static str {
+        match self {
+            ErrorCode::MissingToken => "Authentication token required",
+            ErrorCode::InvalidToken => "Invalid authentication token",
+            ErrorCode::TokenExpired => "Token has expired",
+            ErrorCode::InvalidCredentials => "Invalid email or password",
+            ErrorCode::AdminRoleRequired => "Admin role required",
+            ErrorCode::TenantAccessDenied => "Access denied for this tenant",
+            ErrorCode::InsufficientScope => "API key lacks the required scope",
+            ErrorCode::UserNotValidated => "User account is not validated",
+            ErrorCode::Forbidden => "Forbidden",
+            ErrorCode::RegistrationClosed => "Registration is closed for this tenant",
+            ErrorCode::DownloadLinkInvalid => "Download link is invalid or has expired",
+            ErrorCode::UserNotFound => "User not found",
+            ErrorCode::TenantNotFound => "Tenant not found",
+            ErrorCode::ServiceAccountNotFound => "Service account not found",
+            ErrorCode::ApiKeyNotFound => "API key not found",
+            ErrorCode::OidcProviderNotFound => "Identity provider not found",
+            ErrorCode::ExportNotFound => "Export not found",
+            ErrorCode::WebhookNotFound => "Webhook not found",
+            ErrorCode::WebhookDeliveryNotFound => "Webhook delivery not found",
+            ErrorCode::UserAlreadyExists => "User already exists for this tenant",
+            ErrorCode::MembershipAlreadyExists => "User is already a member of this tenant",
+            ErrorCode::OidcAccountConflict => {
+                "An account with this email exists but the identity provider did not verify the email"
+            }
+            ErrorCode::SettingsVersionConflict => {
+                "Settings were changed since they were read; reload and retry"
+            }
+            ErrorCode::ApiKeyInactive => "API key is revoked or has expired",
+            ErrorCode::ValidationError => "Request failed validation",
+            ErrorCode::InvalidRequestBody => "Request body is malformed",
+            ErrorCode::InvalidPathParameter => "Path parameter is malformed",
+            ErrorCode::InvalidQueryParameter => "Query parameter is malformed",
+            ErrorCode::InvalidTenantId => "Tenant id in the path is not a valid UUID",
+            ErrorCode::OidcStateInvalid => "Login state is unknown, expired or already used",
+            ErrorCode::ScimFilterInvalid => "SCIM filter is malformed or unsupported",
+            ErrorCode::ScimPatchInvalid => "SCIM patch operation is malformed or unsupported",
+            ErrorCode::OidcLoginFailed => "Sign-in with the identity provider failed",
+            ErrorCode::DatabaseError => "Database operation failed",
+            ErrorCode::InternalError => "Internal server error",
+            ErrorCode::OidcProviderUnavailable => "Identity provider is unavailable",
+            ErrorCode::ServiceUnavailable => "Service is currently unavailable",
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 #[derive(Debug, Error)]
@@ -28,26 +344,98 @@ pub enum AppError {
     #[error("User already exists for tenant")]
     UserAlreadyExists,
 
+    #[error("User is already a member of this tenant")]
+    MembershipAlreadyExists,
+
     #[error("Invalid credentials")]
     InvalidCredentials,
 
     #[error("User not validated")]
     UserNotValidated,
 
+    #[error("Admin role required")]
+    AdminRoleRequired,
+
+    #[error("Access denied for this tenant")]
+    TenantAccessDenied,
+
+    #[error("Missing scope: {0}")]
+    InsufficientScope(ApiScope),
+
     #[error("Forbidden: {0}")]
     Forbidden(String),
 
+    #[error("Registration is closed")]
+    RegistrationClosed,
+
+    #[error("Settings version conflict")]
+    SettingsVersionConflict,
+
+    #[error("API key is revoked or expired")]
+    ApiKeyInactive,
+
     #[error("Internal server error")]
     Internal,
 
     #[error("Tenant not found")]
     TenantNotFound,
 
+    #[error("Service account not found")]
+    ServiceAccountNotFound,
+
+    #[error("API key not found")]
+    ApiKeyNotFound,
+
+    #[error("OIDC provider not found")]
+    OidcProviderNotFound,
+
+    #[error("Export not found")]
+    ExportNotFound,
+
+    #[error("Webhook not found")]
+    WebhookNotFound,
+
+    #[error("Webhook delivery not found")]
+    WebhookDeliveryNotFound,
+
+    #[error("Download link invalid")]
+    DownloadLinkInvalid,
+
+    #[error("OIDC login state invalid")]
+    OidcStateInvalid,
+
+    #[error("OIDC login failed")]
+    OidcLoginFailed,
+
+    #[error("OIDC account conflict")]
+    OidcAccountConflict,
+
+    #[error("OIDC provider unavailable")]
+    OidcProviderUnavailable,
+
     #[error("Service unavailable")]
     ServiceUnavailable,
 
-    #[error("Validation error: {0}")]
-    Validation(String),
+    #[error("Validation error: {}", join_field_errors(.0))]
+    Validation(Vec<FieldError>),
+
+    #[error("Invalid request body: {0}")]
+    InvalidRequestBody(String),
+
+    #[error("Invalid path parameter: {0}")]
+    InvalidPathParameter(String),
+
+    #[error("Invalid query parameter: {0}")]
+    InvalidQueryParameter(String),
+
+    #[error("Invalid SCIM filter: {0}")]
+    ScimFilterInvalid(String),
+
+    #[error("Invalid SCIM patch: {0}")]
+    ScimPatchInvalid(String),
+
+    #[error("Invalid tenant id")]
+    InvalidTenantId,
 }
 
 #[derive(Debug, Error)]
@@ -62,77 +450,171 @@ pub enum AuthError {
     MissingToken,
 }
 
+impl AppError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            AppError::Auth(AuthError::ExpiredToken) => ErrorCode::TokenExpired,
+            AppError::Auth(AuthError::InvalidToken) => ErrorCode::InvalidToken,
+            AppError::Auth(AuthError::MissingToken) => ErrorCode::MissingToken,
+            AppError::Database(_) => ErrorCode::DatabaseError,
+            AppError::UserNotFound => ErrorCode::UserNotFound,
+            AppError::UserAlreadyExists => ErrorCode::UserAlreadyExists,
+            AppError::MembershipAlreadyExists => ErrorCode::MembershipAlreadyExists,
+            AppError::InvalidCredentials => ErrorCode::InvalidCredentials,
+            AppError::UserNotValidated => ErrorCode::UserNotValidated,
+            AppError::AdminRoleRequired => ErrorCode::AdminRoleRequired,
+            AppError::TenantAccessDenied => ErrorCode::TenantAccessDenied,
+            AppError::InsufficientScope(_) => ErrorCode::InsufficientScope,
+            AppError::Forbidden(_) => ErrorCode::Forbidden,
+            AppError::RegistrationClosed => ErrorCode::RegistrationClosed,
+            AppError::SettingsVersionConflict => ErrorCode::SettingsVersionConflict,
+            AppError::ApiKeyInactive => ErrorCode::ApiKeyInactive,
+            AppError::Internal => ErrorCode::InternalError,
+            AppError::TenantNotFound => ErrorCode::TenantNotFound,
+            AppError::ServiceAccountNotFound => ErrorCode::ServiceAccountNotFound,
+            AppError::ApiKeyNotFound => ErrorCode::ApiKeyNotFound,
+            AppError::OidcProviderNotFound => ErrorCode::OidcProviderNotFound,
+            AppError::ExportNotFound => ErrorCode::ExportNotFound,
+            AppError::WebhookNotFound => ErrorCode::WebhookNotFound,
+            AppError::WebhookDeliveryNotFound => ErrorCode::WebhookDeliveryNotFound,
+            AppError::DownloadLinkInvalid => ErrorCode::DownloadLinkInvalid,
+            AppError::OidcStateInvalid => ErrorCode::OidcStateInvalid,
+            AppError::OidcLoginFailed => ErrorCode::OidcLoginFailed,
+            AppError::OidcAccountConflict => ErrorCode::OidcAccountConflict,
+            AppError::OidcProviderUnavailable => ErrorCode::OidcProviderUnavailable,
+            AppError::ServiceUnavailable => ErrorCode::ServiceUnavailable,
+            AppError::Validation(_) => ErrorCode::ValidationError,
+            AppError::InvalidRequestBody(_) => ErrorCode::InvalidRequestBody,
+            AppError::InvalidPathParameter(_) => ErrorCode::InvalidPathParameter,
+            AppError::InvalidQueryParameter(_) => ErrorCode::InvalidQueryParameter,
+            AppError::ScimFilterInvalid(_) => ErrorCode::ScimFilterInvalid,
+            AppError::ScimPatchInvalid(_) => ErrorCode::ScimPatchInvalid,
+            AppError::InvalidTenantId => ErrorCode::InvalidTenantId,
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.code().status()
+    }
+
+    /// The message sent to the client, in the locale of the current request.
+    /// Database details are never included.
+    pub fn message(&self) -> String {
+        match self {
+            AppError::Forbidden(msg) => format!("{}: {}", self.code().title(), msg),
+            AppError::InsufficientScope(scope) => format!("{}: {}", self.code().title(), scope),
+            AppError::ScimFilterInvalid(detail) | AppError::ScimPatchInvalid(detail) => {
+                format!("{}: {}", self.code().title(), detail)
+            }
+            AppError::Validation(errors) => join_field_errors(errors),
+            AppError::InvalidRequestBody(msg)
+            | AppError::InvalidPathParameter(msg)
+            | AppError::InvalidQueryParameter(msg) => msg.clone(),
+            _ => self.code().title(),
+        }
+    }
+}
+
+fn join_field_errors(errors: &[FieldError]) -> String {
+    errors
+        .iter()
+        .map(|e| format!("{}: {}", e.field, e.message))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl From<JsonRejection> for AppError {
+    fn from(rejection: JsonRejection) -> Self {
+        AppError::InvalidRequestBody(rejection.body_text())
+    }
+}
+
+impl From<PathRejection> for AppError {
+    fn from(rejection: PathRejection) -> Self {
+        AppError::InvalidPathParameter(rejection.body_text())
+    }
+}
+
+impl From<QueryRejection> for AppError {
+    fn from(rejection: QueryRejection) -> Self {
+        AppError::InvalidQueryParameter(rejection.body_text())
+    }
+}
+
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
-        let (status, error_code, message) = match self {
-            AppError::Auth(AuthError::ExpiredToken) => (
-                StatusCode::UNAUTHORIZED,
-                "TOKEN_EXPIRED",
-                "Token has expired".to_string(),
-            ),
-            AppError::Auth(AuthError::InvalidToken) => (
-                StatusCode::UNAUTHORIZED,
-                "INVALID_TOKEN",
-                "Invalid authentication token".to_string(),
-            ),
-            AppError::Auth(AuthError::MissingToken) => (
-                StatusCode::UNAUTHORIZED,
-                "MISSING_TOKEN",
-                "Authentication token required".to_string(),
-            ),
-            AppError::UserNotFound => (
-                StatusCode::NOT_FOUND,
-                "USER_NOT_FOUND",
-                "User not found".to_string(),
-            ),
-            AppError::UserAlreadyExists => (
-                StatusCode::CONFLICT,
-                "USER_ALREADY_EXISTS",
-                "User already exists for this tenant".to_string(),
-            ),
-            AppError::InvalidCredentials => (
-                StatusCode::UNAUTHORIZED,
-                "INVALID_CREDENTIALS",
-                "Invalid email or password".to_string(),
-            ),
-            AppError::UserNotValidated => (
-                StatusCode::FORBIDDEN,
-                "USER_NOT_VALIDATED",
-                "User account is not validated".to_string(),
-            ),
-            AppError::Forbidden(msg) => (
-                StatusCode::FORBIDDEN,
-                "FORBIDDEN",
-                format!("Forbidden: {}", msg),
-            ),
-            AppError::Database(_) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "DATABASE_ERROR",
-                "Database operation failed".to_string(),
-            ),
-            AppError::Internal => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "INTERNAL_ERROR",
-                "Internal server error".to_string(),
-            ),
-            AppError::TenantNotFound => (
-                StatusCode::NOT_FOUND,
-                "TENANT_NOT_FOUND",
-                "Tenant not found".to_string(),
+        let request_id = RequestId::current().map(|id| id.as_str().to_string());
+
+        match &self {
+            AppError::Database(err) => tracing::error!(
+                request_id = request_id.as_deref().unwrap_or("-"),
+                error = %err,
+                "database error"
             ),
-            AppError::ServiceUnavailable => (
-                StatusCode::SERVICE_UNAVAILABLE,
-                "SERVICE_UNAVAILABLE",
-                "Service is currently unavailable".to_string(),
+            AppError::Internal => tracing::error!(
+                request_id = request_id.as_deref().unwrap_or("-"),
+                "internal error"
             ),
-            AppError::Validation(msg) => (StatusCode::BAD_REQUEST, "VALIDATION_ERROR", msg),
-        };
+            _ => {}
+        }
 
-        let body = json!({
-            "error": error_code,
-            "message": message
-        });
+        let status = self.status();
+        let code = self.code();
+        let message = self.message();
 
-        (status, axum::Json(body)).into_response()
+        let mut response = match ErrorFormat::current() {
+            ErrorFormat::Legacy => {
+                let body = ErrorResponse {
+                    error: code,
+                    message,
+                    request_id,
+                };
+                (status, axum::Json(body)).into_response()
+            }
+            ErrorFormat::Problem { instance } => {
+                let errors = match self {
+                    AppError::Validation(errors) => errors,
+                    _ => Vec::new(),
+                };
+                let body = ProblemDetails {
+                    problem_type: code.problem_type(),
+                    title: code.title(),
+                    status: status.as_u16(),
+                    detail: message,
+                    instance: Some(instance),
+                    code,
+                    request_id,
+                    errors,
+                };
+                let mut response = (status, axum::Json(body)).into_response();
+                response
+                    .headers_mut()
+                    .insert(header::CONTENT_TYPE, HeaderValue::from_static(PROBLEM_JSON));
+                response
+            }
+            ErrorFormat::Scim => {
+                let body = ScimError {
+                    schemas: vec![SCIM_ERROR_SCHEMA],
+                    status: status.as_u16().to_string(),
+                    scim_type: code.scim_type(),
+                    detail: message,
+                };
+                let mut response = (status, axum::Json(body)).into_response();
+                response
+                    .headers_mut()
+                    .insert(header::CONTENT_TYPE, HeaderValue::from_static(SCIM_JSON));
+                response
+            }
+        };
+        let headers = response.headers_mut();
+        headers.insert(
+            header::VARY,
+            HeaderValue::from_static("accept, accept-language"),
+        );
+        headers.insert(
+            header::CONTENT_LANGUAGE,
+            HeaderValue::from_static(i18n::current().as_str()),
+        );
+        response
     }
 }
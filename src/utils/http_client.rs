@@ -0,0 +1,335 @@
+//! HTTP client for talking to identity providers and webhook receivers.
+//!
+//! Their URLs are chosen by tenants, so by default a request may only reach
+//! public addresses: names are resolved by [`PublicResolver`], which drops
+//! loopback, private, link-local and similar addresses, and the connection
+//! is made to an address it returned, so a name cannot be re-pointed between
+//! the check and the connect. Redirects are not followed and each request
+//! has one deadline covering connect, TLS, upload and download.
+
+use crate::i18n;
+use crate::utils::error::{AppError, FieldError};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE};
+use serde::de::DeserializeOwned;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use url::{Host, Url};
+
+/// Responses larger than this are rejected.
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+#[derive(Debug, Error)]
+pub enum HttpError {
+    #[error("invalid url: {0}")]
+    Url(String),
+
+    #[error("{0} does not resolve to a public address")]
+    Blocked(String),
+
+    #[error("request failed: {0}")]
+    Request(String),
+
+    #[error("response too large")]
+    TooLarge,
+
+    #[error("unexpected status {status}: {body}")]
+    Status { status: u16, body: String },
+
+    #[error("invalid response body: {0}")]
+    Decode(#[from] serde_json::Error),
+}
+
+impl From<reqwest::Error> for HttpError {
+    /// Keeps the causes, which is where a blocked address or a TLS failure
+    /// is reported.
+    fn from(e: reqwest::Error) -> Self {
+        let mut message = e.to_string();
+        let mut source = std::error::Error::source(&e);
+        while let Some(cause) = source {
+            message.push_str(": ");
+            message.push_str(&cause.to_string());
+            source = cause.source();
+        }
+        HttpError::Request(message)
+    }
+}
+
+/// A response whatever its status, from [`HttpClient::post`].
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+#[derive(Clone)]
+pub struct HttpClient {
+    client: reqwest::Client,
+    allow_private_addresses: bool,
+}
+
+impl Default for HttpClient {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(10), false)
+    }
+}
+
+impl HttpClient {
+    /// `timeout` is the deadline for a whole request. With
+    /// `allow_private_addresses`, any address can be reached; only for
+    /// development against local services.
+    pub fn new(timeout: Duration, allow_private_addresses: bool) -> Self {
+        let mut builder = reqwest::Client::builder()
+            .timeout(timeout)
+            .redirect(reqwest::redirect::Policy::none())
+            // A proxy would resolve the name itself, past the check
+            .no_proxy()
+            .user_agent(concat!(
+                env!("CARGO_PKG_NAME"),
+                "/",
+                env!("CARGO_PKG_VERSION")
+            ));
+        if !allow_private_addresses {
+            builder = builder.dns_resolver(Arc::new(PublicResolver));
+        }
+
+        Self {
+            client: builder.build().expect("HTTP client configuration is valid"),
+            allow_private_addresses,
+        }
+    }
+
+    /// Checks that `url` is an `http` or `https` URL without credentials
+    /// whose host resolves to an address requests may reach. For validating
+    /// URLs when they are configured; requests check again when sent.
+    pub async fn check_url(&self, url: &str) -> Result<Url, HttpError> {
+        let url = self.parse(url)?;
+        if self.allow_private_addresses {
+            return Ok(url);
+        }
+        if let Some(Host::Domain(domain)) = url.host() {
+            PublicResolver::lookup(domain).await?;
+        }
+        Ok(url)
+    }
+
+    /// [`Self::check_url`], failing as a validation error on `field`:
+    /// `url_invalid` for a URL requests cannot be sent to, `url_not_public`
+    /// for one at an address they may not reach.
+    pub async fn validate_url(&self, field: &str, url: &str) -> Result<Url, AppError> {
+        self.check_url(url).await.map_err(|e| {
+            tracing::warn!("URL rejected: field={}, url={}, error={}", field, url, e);
+            let code = match e {
+                HttpError::Url(_) => "url_invalid",
+                _ => "url_not_public",
+            };
+            let message = i18n::translate(i18n::current(), &format!("validation.{}", code), &[])
+                .unwrap_or_else(|| code.to_string());
+            AppError::Validation(vec![FieldError::new(field, code, message)])
+        })
+    }
+
+    pub async fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T, HttpError> {
+        let request = self
+            .client
+            .get(self.parse(url)?)
+            .header(ACCEPT, "application/json");
+        self.send(request).await
+    }
+
+    /// POSTs an `application/x-www-form-urlencoded` body, optionally with
+    /// HTTP Basic credentials.
+    pub async fn post_form<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        basic_auth: Option<(&str, &str)>,
+        form: &[(&str, &str)],
+    ) -> Result<T, HttpError> {
+        let body = url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(form)
+            .finish();
+        let mut request = self
+            .client
+            .post(self.parse(url)?)
+            .header(ACCEPT, "application/json")
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(body);
+        if let Some((user, password)) = basic_auth {
+            request = request.header(AUTHORIZATION, basic_authorization(user, password));
+        }
+        self.send(request).await
+    }
+
+    /// POSTs `body` with the given headers and returns the response as is;
+    /// a non-2xx status is not an error.
+    pub async fn post(
+        &self,
+        url: &str,
+        headers: Vec<(&'static str, String)>,
+        body: Vec<u8>,
+    ) -> Result<HttpResponse, HttpError> {
+        let mut request = self.client.post(self.parse(url)?).body(body);
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+        let (status, body) = self.execute(request).await?;
+        Ok(HttpResponse { status, body })
+    }
+
+    /// Parses `url` and rejects IP literals that may not be reached, as they
+    /// are connected to without resolving.
+    fn parse(&self, url: &str) -> Result<Url, HttpError> {
+        let url = Url::parse(url).map_err(|e| HttpError::Url(e.to_string()))?;
+        if !matches!(url.scheme(), "http" | "https")
+            || url.host().is_none()
+            || !url.username().is_empty()
+            || url.password().is_some()
+        {
+            return Err(HttpError::Url(format!("unsupported url '{}'", url)));
+        }
+        let literal = match url.host() {
+            Some(Host::Ipv4(ip)) => Some(IpAddr::V4(ip)),
+            Some(Host::Ipv6(ip)) => Some(IpAddr::V6(ip)),
+            _ => None,
+        };
+        if literal.is_some_and(|ip| !self.allow_private_addresses && !is_public(ip)) {
+            return Err(HttpError::Blocked(
+                url.host_str().unwrap_or_default().to_string(),
+            ));
+        }
+        Ok(url)
+    }
+
+    async fn send<T: DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<T, HttpError> {
+        let (status, body) = self.execute(request).await?;
+        if !(200..300).contains(&status) {
+            return Err(HttpError::Status {
+                status,
+                body: String::from_utf8_lossy(&body).chars().take(512).collect(),
+            });
+        }
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    async fn execute(&self, request: reqwest::RequestBuilder) -> Result<(u16, Vec<u8>), HttpError> {
+        let mut response = request.send().await?;
+        let status = response.status().as_u16();
+
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if body.len() + chunk.len() > MAX_BODY_BYTES {
+                return Err(HttpError::TooLarge);
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok((status, body))
+    }
+}
+
+/// RFC 6749 §2.3.1: both parts are form-encoded before being joined.
+fn basic_authorization(user: &str, password: &str) -> String {
+    use base64::Engine;
+
+    let encode = |s: &str| url::form_urlencoded::byte_serialize(s.as_bytes()).collect::<String>();
+    let credentials = format!("{}:{}", encode(user), encode(password));
+    format!(
+        "Basic {}",
+        base64::engine::general_purpose::STANDARD.encode(credentials)
+    )
+}
+
+/// Resolves names with the system resolver and keeps only public addresses.
+struct PublicResolver;
+
+impl PublicResolver {
+    async fn lookup(host: &str) -> Result<Vec<SocketAddr>, HttpError> {
+        let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, 0))
+            .await
+            .map_err(|e| HttpError::Request(format!("{} did not resolve: {}", host, e)))?
+            .filter(|addr| is_public(addr.ip()))
+            .collect();
+        if addrs.is_empty() {
+            return Err(HttpError::Blocked(host.to_string()));
+        }
+        Ok(addrs)
+    }
+}
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let addrs = Self::lookup(&host).await?;
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Whether `ip` is a globally routable unicast address: not loopback,
+/// private, shared, link-local, unique-local, multicast, documentation or
+/// otherwise reserved. IPv6 forms that embed an IPv4 address are judged by
+/// that address.
+pub fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, c, _] = ip.octets();
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || ip.is_multicast()
+                || a == 0
+                || a >= 240
+                // Shared address space (RFC 6598)
+                || (a == 100 && (64..128).contains(&b))
+                // Benchmarking (RFC 2544)
+                || (a == 198 && (18..20).contains(&b))
+                // IETF protocol assignments (RFC 6890)
+                || (a == 192 && b == 0 && c == 0))
+        }
+        IpAddr::V6(ip) => {
+            if let Some(v4) = ip.to_ipv4_mapped() {
+                return is_public(IpAddr::V4(v4));
+            }
+            let s = ip.segments();
+            let embedded = |high: u16, low: u16| {
+                let [a, b] = high.to_be_bytes();
+                let [c, d] = low.to_be_bytes();
+                IpAddr::V4(Ipv4Addr::new(a, b, c, d))
+            };
+            match s {
+                // NAT64 (RFC 6052)
+                [0x64, 0xff9b, 0, 0, 0, 0, high, low] => is_public(embedded(high, low)),
+                // 6to4 (RFC 3056)
+                [0x2002, high, low, ..] => is_public(embedded(high, low)),
+                _ => {
+                    !(ip.is_unspecified()
+                        || ip.is_loopback()
+                        || ip.is_multicast()
+                        // IPv4-compatible, deprecated
+                        || s[..6] == [0; 6]
+                        // Unique local (fc00::/7)
+                        || (s[0] & 0xfe00) == 0xfc00
+                        // Link-local and the deprecated site-local
+                        || (s[0] & 0xffc0) == 0xfe80
+                        || (s[0] & 0xffc0) == 0xfec0
+                        // Documentation
+                        || (s[0] == 0x2001 && s[1] == 0x0db8))
+                }
+            }
+        }
+    }
+}
@@ -1,5 +1,14 @@
 pub mod auth;
+pub mod cron;
 pub mod error;
+pub mod http_client;
+pub mod password_hash;
+pub mod password_policy;
+pub mod redact;
+pub mod scim_filter;
+pub mod shutdown;
 
 pub use auth::*;
-pub use error::{AppError, AuthError, ErrorResponse};
+pub use error::{
+    AppError, AuthError, ErrorCode, ErrorResponse, FieldError, ProblemDetails, ScimError,
+};
@@ -0,0 +1,157 @@
+use crate::utils::error::AppError;
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, SaltString};
+use argon2::{
+    Algorithm, Argon2, KeyId, Params, ParamsBuilder, PasswordHasher, PasswordVerifier, Version,
+};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+/// Hashes and verifies passwords with Argon2id at the configured cost.
+///
+/// When a pepper is configured it is used as the Argon2 secret, so stolen
+/// hashes cannot be cracked without it. Hashes record which pepper they use
+/// in their `keyid` parameter (a fingerprint, not the pepper), so hashes
+/// made before a pepper was introduced still verify.
+///
+/// Hashing is CPU-heavy and runs on the blocking thread pool.
+#[derive(Clone, Default)]
+pub struct PasswordHashing {
+    params: Params,
+    pepper: Option<Pepper>,
+}
+
+#[derive(Clone)]
+struct Pepper {
+    secret: Arc<[u8]>,
+    key_id: [u8; 4],
+}
+
+impl PasswordHashing {
+    pub fn new(params: Params, pepper: Option<&str>) -> Self {
+        let pepper = pepper.map(|pepper| {
+            let digest = Sha256::digest(pepper.as_bytes());
+            Pepper {
+                secret: Arc::from(pepper.as_bytes()),
+                key_id: [digest[0], digest[1], digest[2], digest[3]],
+            }
+        });
+        Self { params, pepper }
+    }
+
+    pub async fn hash(&self, password: &str) -> Result<String, AppError> {
+        let password = password.to_string();
+        let hashing = self.clone();
+        blocking(move || {
+            let salt = SaltString::generate(&mut OsRng);
+            let params = hashing.current_params()?;
+            let secret = hashing.pepper.as_ref().map(|pepper| &pepper.secret[..]);
+            let hash = argon2(secret, params)?
+                .hash_password(password.as_bytes(), &salt)
+                .map_err(|e| {
+                    tracing::error!("Password hashing failed: {}", e);
+                    AppError::Internal
+                })?;
+            Ok(hash.to_string())
+        })
+        .await
+    }
+
+    /// Whether `password` matches `hash`, whatever parameters `hash` was made
+    /// with.
+    pub async fn verify(&self, password: &str, hash: &str) -> Result<bool, AppError> {
+        let parsed = Self::parse(hash)?;
+        let key_id = Params::try_from(&parsed)
+            .map(|params| params.keyid().to_vec())
+            .unwrap_or_default();
+        let secret = match &self.pepper {
+            _ if key_id.is_empty() => None,
+            Some(pepper) if key_id == pepper.key_id => Some(pepper.secret.clone()),
+            _ => {
+                tracing::warn!(
+                    "Password hash uses pepper {} which is not configured",
+                    hex::encode(&key_id)
+                );
+                return Ok(false);
+            }
+        };
+
+        let password = password.to_string();
+        let hash = hash.to_string();
+        blocking(move || {
+            let parsed = Self::parse(&hash)?;
+            let argon2 = argon2(secret.as_deref(), Params::default())?;
+            Ok(argon2.verify_password(password.as_bytes(), &parsed).is_ok())
+        })
+        .await
+    }
+
+    /// Whether `hash` was made with another algorithm, parameters or pepper
+    /// than new hashes are, and should be replaced once the password is
+    /// known.
+    pub fn needs_rehash(&self, hash: &str) -> bool {
+        let Ok(parsed) = PasswordHash::new(hash) else {
+            return true;
+        };
+        let Ok(params) = Params::try_from(&parsed) else {
+            return true;
+        };
+        let Ok(current) = self.current_params() else {
+            return false;
+        };
+        parsed.algorithm != Algorithm::Argon2id.ident()
+            || parsed.version != Some(Version::V0x13.into())
+            || params.m_cost() != current.m_cost()
+            || params.t_cost() != current.t_cost()
+            || params.p_cost() != current.p_cost()
+            || params.keyid() != current.keyid()
+    }
+
+    /// The configured parameters, tagged with the pepper's key id.
+    fn current_params(&self) -> Result<Params, AppError> {
+        let mut builder = ParamsBuilder::new();
+        builder
+            .m_cost(self.params.m_cost())
+            .t_cost(self.params.t_cost())
+            .p_cost(self.params.p_cost());
+        if let Some(pepper) = &self.pepper {
+            builder.keyid(KeyId::new(&pepper.key_id).map_err(invalid_params)?);
+        }
+        builder.build().map_err(invalid_params)
+    }
+
+    fn parse(hash: &str) -> Result<PasswordHash<'_>, AppError> {
+        PasswordHash::new(hash).map_err(|e| {
+            tracing::error!("Stored password hash is malformed: {}", e);
+            AppError::Internal
+        })
+    }
+}
+
+fn invalid_params(e: argon2::Error) -> AppError {
+    tracing::error!("Invalid Argon2 parameters: {}", e);
+    AppError::Internal
+}
+
+fn argon2(secret: Option<&[u8]>, params: Params) -> Result<Argon2<'_>, AppError> {
+    match secret {
+        Some(secret) => {
+            Argon2::new_with_secret(secret, Algorithm::Argon2id, Version::V0x13, params).map_err(
+                |e| {
+                    tracing::error!("Invalid password pepper: {}", e);
+                    AppError::Internal
+                },
+            )
+        }
+        None => Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params)),
+    }
+}
+
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, AppError> + Send + 'static,
+) -> Result<T, AppError> {
+    tokio::task::spawn_blocking(f).await.map_err(|e| {
+        tracing::error!("Password hashing task failed: {}", e);
+        AppError::Internal
+    })?
+}
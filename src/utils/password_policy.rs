@@ -0,0 +1,318 @@
+use crate::i18n;
+use crate::models::tenant_settings::TenantSettings;
+use crate::utils::error::FieldError;
+use sha1::{Digest, Sha1};
+use std::collections::BTreeMap;
+use std::io;
+use std::path::PathBuf;
+
+/// Longest password accepted anywhere, as in `RegisterRequest`.
+pub const MAX_PASSWORD_LENGTH: u64 = 100;
+
+/// Most previous passwords a policy can forbid reusing, and so the most
+/// kept per user.
+pub const MAX_PASSWORD_HISTORY: u32 = 24;
+
+/// Rules a new password must satisfy. The global policy comes from the
+/// configuration; tenants override parts of it in their settings.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PasswordPolicy {
+    pub min_length: u64,
+    /// How many of lowercase, uppercase, digits and symbols must appear.
+    pub min_character_classes: u8,
+    /// Lowest accepted [`strength`] score, 0 to 4.
+    pub min_strength: u8,
+    /// Reject passwords containing the email or the tenant name.
+    pub reject_personal_info: bool,
+    /// How many previous passwords, the current one included, cannot be
+    /// reused. 0 allows reuse.
+    pub history: u32,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            min_character_classes: 1,
+            min_strength: 0,
+            reject_personal_info: true,
+            history: 0,
+        }
+    }
+}
+
+/// A rule a password breaks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PasswordViolation {
+    TooShort { min: u64 },
+    CharacterClasses { min: u8 },
+    TooWeak { min: u8 },
+    PersonalInfo,
+    Reused { history: u32 },
+    Breached,
+}
+
+impl PasswordPolicy {
+    /// This policy with the values `settings` sets.
+    pub fn for_tenant(&self, settings: &TenantSettings) -> Self {
+        Self {
+            min_length: settings.password_min_length.unwrap_or(self.min_length),
+            min_character_classes: settings
+                .password_min_character_classes
+                .unwrap_or(self.min_character_classes),
+            min_strength: settings.password_min_strength.unwrap_or(self.min_strength),
+            reject_personal_info: settings
+                .password_reject_personal_info
+                .unwrap_or(self.reject_personal_info),
+            history: settings.password_history.unwrap_or(self.history),
+        }
+    }
+
+    /// The stricter value of each rule, for a user who belongs to tenants
+    /// with different policies.
+    pub fn strictest(self, other: &Self) -> Self {
+        Self {
+            min_length: self.min_length.max(other.min_length),
+            min_character_classes: self.min_character_classes.max(other.min_character_classes),
+            min_strength: self.min_strength.max(other.min_strength),
+            reject_personal_info: self.reject_personal_info || other.reject_personal_info,
+            history: self.history.max(other.history),
+        }
+    }
+
+    /// Rules `password` breaks that need nothing but the password itself and
+    /// the `personal` values (email, tenant names) it must not contain.
+    pub fn violations(&self, password: &str, personal: &[&str]) -> Vec<PasswordViolation> {
+        let mut violations = Vec::new();
+        if (password.chars().count() as u64) < self.min_length {
+            violations.push(PasswordViolation::TooShort {
+                min: self.min_length,
+            });
+        }
+        if character_classes(password) < self.min_character_classes {
+            violations.push(PasswordViolation::CharacterClasses {
+                min: self.min_character_classes,
+            });
+        }
+        if strength(password) < self.min_strength {
+            violations.push(PasswordViolation::TooWeak {
+                min: self.min_strength,
+            });
+        }
+        if self.reject_personal_info && contains_personal_info(password, personal) {
+            violations.push(PasswordViolation::PersonalInfo);
+        }
+        violations
+    }
+}
+
+impl PasswordViolation {
+    /// A validation error on `field`, in the current request's locale.
+    pub fn field_error(&self, field: &str) -> FieldError {
+        let (code, key, params): (&str, &str, Vec<(&str, serde_json::Value)>) = match self {
+            PasswordViolation::TooShort { min } => (
+                "length",
+                "password_length",
+                vec![("min", (*min).into()), ("max", MAX_PASSWORD_LENGTH.into())],
+            ),
+            PasswordViolation::CharacterClasses { min } => (
+                "character_classes",
+                "password_character_classes",
+                vec![("min", (*min).into())],
+            ),
+            PasswordViolation::TooWeak { min } => {
+                ("strength", "password_weak", vec![("min", (*min).into())])
+            }
+            PasswordViolation::PersonalInfo => ("personal_info", "password_personal_info", vec![]),
+            PasswordViolation::Reused { history } => (
+                "reused",
+                "password_reused",
+                vec![("count", (*history).into())],
+            ),
+            PasswordViolation::Breached => ("breached", "password_breached", vec![]),
+        };
+
+        let args: Vec<(&str, String)> = params
+            .iter()
+            .map(|(name, value)| (*name, value.to_string()))
+            .collect();
+        let message = i18n::translate(i18n::current(), &format!("validation.{}", key), &args)
+            .unwrap_or_else(|| key.to_string());
+        let mut error = FieldError::new(field, code, message);
+        error.params = params
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect::<BTreeMap<_, _>>();
+        error
+    }
+}
+
+fn character_classes(password: &str) -> u8 {
+    let mut classes = [false; 4];
+    for c in password.chars() {
+        let class = if c.is_lowercase() {
+            0
+        } else if c.is_uppercase() {
+            1
+        } else if c.is_numeric() {
+            2
+        } else {
+            3
+        };
+        classes[class] = true;
+    }
+    classes.iter().filter(|present| **present).count() as u8
+}
+
+/// Values shorter than this are too common to reject on.
+const MIN_PERSONAL_LENGTH: usize = 4;
+
+/// Whether the password contains one of `personal`, or one of their words
+/// (`jane.doe@example.com` gives `jane`, `example`, ...).
+fn contains_personal_info(password: &str, personal: &[&str]) -> bool {
+    let password = password.to_lowercase();
+    personal.iter().any(|value| {
+        let value = value.to_lowercase();
+        std::iter::once(value.as_str())
+            .chain(value.split(|c: char| !c.is_alphanumeric()))
+            .filter(|part| part.chars().count() >= MIN_PERSONAL_LENGTH)
+            .any(|part| password.contains(part))
+    })
+}
+
+/// Base words of the most common passwords; a password that is one of them
+/// with a few digits or symbols around is at most "weak".
+const COMMON_WORDS: &[&str] = &[
+    "password", "passw0rd", "welcome", "letmein", "qwerty", "dragon", "monkey", "football",
+    "baseball", "sunshine", "princess", "iloveyou", "admin", "login", "master", "shadow",
+    "superman", "batman", "trustno1", "summer", "winter", "spring", "autumn", "hello", "freedom",
+    "whatever", "secret", "changeme", "default", "starwars", "computer", "michael", "charlie",
+];
+
+/// Keyboard rows and the digit row; runs along them are cheap to guess.
+const KEYBOARD_ROWS: &[&str] = &["qwertyuiop", "asdfghjkl", "zxcvbnm", "1234567890"];
+
+/// Estimated guessability from 0 (trivial) to 4 (very strong), on the scale
+/// of zxcvbn: the log10 of the estimated guesses bucketed at 3, 6, 8 and 10.
+///
+/// Guesses are the character pool raised to an effective length, where
+/// repeats, sequences (`abc`, `987`), keyboard runs and common words count
+/// for little. This is a coarse estimate, not a port of zxcvbn.
+pub fn strength(password: &str) -> u8 {
+    let lower = password.to_lowercase();
+    let pool: f64 = [
+        (password.chars().any(char::is_lowercase), 26.0),
+        (password.chars().any(char::is_uppercase), 26.0),
+        (password.chars().any(char::is_numeric), 10.0),
+        (password.chars().any(|c| !c.is_alphanumeric()), 33.0),
+    ]
+    .iter()
+    .filter(|(present, _)| *present)
+    .map(|(_, size)| size)
+    .sum();
+    if pool == 0.0 {
+        return 0;
+    }
+
+    let chars: Vec<i64> = lower.chars().map(|c| c as i64).collect();
+    let mut effective_length = 0.0;
+    let mut previous_step = None;
+    for (i, c) in chars.iter().enumerate() {
+        let step = i.checked_sub(1).map(|j| c - chars[j]);
+        let predictable =
+            step == Some(0) || (matches!(step, Some(-1 | 1)) && step == previous_step);
+        effective_length += if predictable { 0.2 } else { 1.0 };
+        previous_step = step;
+    }
+
+    // A run of n keys, or a common word of n letters, is about one guess
+    let mut discount = 0.0;
+    for row in KEYBOARD_ROWS {
+        for len in (4..=row.len()).rev() {
+            if let Some(run) = (0..=row.len() - len)
+                .map(|start| &row[start..start + len])
+                .find(|run| lower.contains(run))
+            {
+                discount += (run.len() - 1) as f64 * 0.8;
+                break;
+            }
+        }
+    }
+    let unleeted = unleet(&lower);
+    if let Some(word) = COMMON_WORDS
+        .iter()
+        .filter(|word| lower.contains(*word) || unleeted.contains(*word))
+        .max_by_key(|word| word.len())
+    {
+        discount += (word.len() - 1) as f64;
+    }
+    let effective_length = (effective_length - discount).max(1.0);
+
+    // Only the ends are trimmed before undoing substitutions, so digits
+    // appended to a word stay digits
+    let core = unleet(lower.trim_matches(|c: char| !c.is_alphabetic()));
+    let log10_guesses = effective_length * pool.log10();
+    let score = match log10_guesses {
+        g if g < 3.0 => 0,
+        g if g < 6.0 => 1,
+        g if g < 8.0 => 2,
+        g if g < 10.0 => 3,
+        _ => 4,
+    };
+    if COMMON_WORDS.contains(&core.as_str()) {
+        score.min(1)
+    } else {
+        score
+    }
+}
+
+/// Undoes the usual letter substitutions (`p@ssw0rd`).
+fn unleet(password: &str) -> String {
+    password
+        .chars()
+        .map(|c| match c {
+            '0' => 'o',
+            '1' | '!' => 'i',
+            '3' => 'e',
+            '4' | '@' => 'a',
+            '5' | '$' => 's',
+            '7' => 't',
+            other => other,
+        })
+        .collect()
+}
+
+/// SHA-1 hashes of passwords known from data breaches, kept in a local
+/// directory so no password, or part of its hash, leaves the server.
+///
+/// The directory uses the k-anonymity range layout of the Pwned Passwords
+/// downloads: one `<prefix>.txt` file per 5-character hexadecimal prefix of
+/// the hash, listing the 35-character suffixes under it, each followed by
+/// `:count`. A lookup reads only the file of the password's prefix.
+#[derive(Debug, Clone)]
+pub struct BreachedPasswords {
+    dir: PathBuf,
+}
+
+impl BreachedPasswords {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Whether the password's hash is listed. A missing range file lists
+    /// nothing; suffixes with a count of 0 are padding.
+    pub async fn contains(&self, password: &str) -> io::Result<bool> {
+        let hash = hex::encode_upper(Sha1::digest(password.as_bytes()));
+        let (prefix, suffix) = hash.split_at(5);
+        let range = match tokio::fs::read_to_string(self.dir.join(format!("{}.txt", prefix))).await
+        {
+            Ok(range) => range,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(e),
+        };
+        Ok(range.lines().any(|line| {
+            let (listed, count) = line.trim().split_once(':').unwrap_or((line.trim(), ""));
+            listed.eq_ignore_ascii_case(suffix) && count.trim() != "0"
+        }))
+    }
+}
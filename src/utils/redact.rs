@@ -0,0 +1,108 @@
+use regex::{Captures, Regex};
+use std::io::{self, Write};
+use std::sync::LazyLock;
+use tracing_subscriber::fmt::MakeWriter;
+
+pub const REDACTED: &str = "[REDACTED]";
+
+static SENSITIVE_FIELD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?i)("?\b(?:(?:current_|new_)?password|password_hash|secret|client_secret|jwt_secret|bearer_token|token|access_token|refresh_token|id_token|code_verifier|api_key|authorization)"?(?:\x1b\[[0-9;]*m)*\s*[=:]\s*(?:\x1b\[[0-9;]*m)*)("[^"]*"|[^\s,}\x1b]+)"#,
+    )
+    .expect("valid sensitive field regex")
+});
+
+static BEARER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\bbearer\s+[A-Za-z0-9._~+/=-]{16,}").expect("valid bearer regex")
+});
+
+static JWT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\beyJ[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+").expect("valid jwt regex")
+});
+
+static EMAIL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b([A-Za-z0-9._%+-])[A-Za-z0-9._%+-]*@([A-Za-z0-9.-]+\.[A-Za-z]{2,})\b")
+        .expect("valid email regex")
+});
+
+/// Masks secrets, tokens and email addresses in a formatted log line.
+///
+/// Values of sensitive fields (`password`, `token`, `secret`, ...) are replaced
+/// entirely, bearer credentials and JWTs are dropped, and emails keep only their
+/// first character and domain (`j***@example.com`) so lines stay correlatable.
+pub fn redact(input: &str) -> String {
+    let output = SENSITIVE_FIELD.replace_all(input, |caps: &Captures| {
+        let quoted = caps[2].starts_with('"');
+        if quoted {
+            format!("{}\"{}\"", &caps[1], REDACTED)
+        } else {
+            format!("{}{}", &caps[1], REDACTED)
+        }
+    });
+    let output = BEARER.replace_all(&output, format!("Bearer {}", REDACTED));
+    let output = JWT.replace_all(&output, REDACTED);
+    let output = EMAIL.replace_all(&output, "$1***@$2");
+    output.into_owned()
+}
+
+/// `MakeWriter` wrapper that runs every formatted event through [`redact`]
+/// before handing it to the inner writer.
+#[derive(Clone, Debug)]
+pub struct RedactingMakeWriter<M> {
+    inner: M,
+}
+
+impl<M> RedactingMakeWriter<M> {
+    pub fn new(inner: M) -> Self {
+        Self { inner }
+    }
+}
+
+impl<'a, M> MakeWriter<'a> for RedactingMakeWriter<M>
+where
+    M: MakeWriter<'a>,
+{
+    type Writer = RedactingWriter<M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactingWriter {
+            inner: self.inner.make_writer(),
+            buffer: Vec::new(),
+        }
+    }
+}
+
+/// Buffers a single event and writes the redacted line on flush or drop.
+pub struct RedactingWriter<W: Write> {
+    inner: W,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> RedactingWriter<W> {
+    fn write_redacted(&mut self) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let line = redact(&String::from_utf8_lossy(&self.buffer));
+        self.buffer.clear();
+        self.inner.write_all(line.as_bytes())
+    }
+}
+
+impl<W: Write> Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_redacted()?;
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> Drop for RedactingWriter<W> {
+    fn drop(&mut self) {
+        let _ = self.write_redacted();
+    }
+}
@@ -0,0 +1,353 @@
+//! SCIM filter expressions (RFC 7644 §3.4.2.2), evaluated against the JSON
+//! representation of a resource.
+//!
+//! Supports attribute comparisons, `pr`, `and`, `or`, `not (...)` and
+//! grouping. Attribute names and string comparisons are case-insensitive;
+//! value paths (`emails[type eq "work"]`) are not supported.
+
+use crate::utils::error::AppError;
+use chrono::{DateTime, FixedOffset};
+use serde_json::Value;
+use std::cmp::Ordering;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Eq,
+    Ne,
+    Co,
+    Sw,
+    Ew,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl Operator {
+    fn parse(word: &str) -> Option<Self> {
+        Some(match word.to_ascii_lowercase().as_str() {
+            "eq" => Operator::Eq,
+            "ne" => Operator::Ne,
+            "co" => Operator::Co,
+            "sw" => Operator::Sw,
+            "ew" => Operator::Ew,
+            "gt" => Operator::Gt,
+            "ge" => Operator::Ge,
+            "lt" => Operator::Lt,
+            "le" => Operator::Le,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+    Not(Box<Filter>),
+    Present(String),
+    Compare {
+        attribute: String,
+        op: Operator,
+        value: Value,
+    },
+}
+
+impl Filter {
+    pub fn parse(input: &str) -> Result<Self, AppError> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let filter = parser.or()?;
+        if let Some(token) = parser.tokens.get(parser.pos) {
+            return Err(invalid(format!("unexpected {}", token)));
+        }
+        Ok(filter)
+    }
+
+    pub fn matches(&self, resource: &Value) -> bool {
+        match self {
+            Filter::And(left, right) => left.matches(resource) && right.matches(resource),
+            Filter::Or(left, right) => left.matches(resource) || right.matches(resource),
+            Filter::Not(inner) => !inner.matches(resource),
+            Filter::Present(attribute) => resolve(resource, attribute).iter().any(|v| match v {
+                Value::Null => false,
+                Value::String(s) => !s.is_empty(),
+                _ => true,
+            }),
+            Filter::Compare {
+                attribute,
+                op: Operator::Ne,
+                value,
+            } => !Filter::Compare {
+                attribute: attribute.clone(),
+                op: Operator::Eq,
+                value: value.clone(),
+            }
+            .matches(resource),
+            Filter::Compare {
+                attribute,
+                op,
+                value,
+            } => resolve(resource, attribute)
+                .iter()
+                .any(|actual| compare(actual, *op, value)),
+        }
+    }
+}
+
+fn invalid(detail: impl Into<String>) -> AppError {
+    AppError::ScimFilterInvalid(detail.into())
+}
+
+/// Values at `attribute` (e.g. `emails.value`), looking through multi-valued
+/// attributes. A schema URN prefix is accepted and ignored.
+fn resolve<'a>(resource: &'a Value, attribute: &str) -> Vec<&'a Value> {
+    let path = attribute.rsplit(':').next().unwrap_or(attribute);
+    let mut current = vec![resource];
+    for segment in path.split('.') {
+        current = current
+            .into_iter()
+            .flat_map(|value| match value {
+                Value::Array(items) => items.iter().collect(),
+                other => vec![other],
+            })
+            .filter_map(|value| {
+                value
+                    .as_object()?
+                    .iter()
+                    .find(|(key, _)| key.eq_ignore_ascii_case(segment))
+                    .map(|(_, v)| v)
+            })
+            .collect();
+    }
+    current
+        .into_iter()
+        .flat_map(|value| match value {
+            Value::Array(items) => items.iter().collect(),
+            other => vec![other],
+        })
+        .collect()
+}
+
+fn compare(actual: &Value, op: Operator, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::String(actual), Value::String(expected)) => {
+            let (actual, expected) = (actual.to_lowercase(), expected.to_lowercase());
+            match op {
+                Operator::Eq => actual == expected,
+                Operator::Co => actual.contains(&expected),
+                Operator::Sw => actual.starts_with(&expected),
+                Operator::Ew => actual.ends_with(&expected),
+                _ => ordered(op, compare_strings(&actual, &expected)),
+            }
+        }
+        (Value::Number(actual), Value::Number(expected)) => {
+            let (Some(actual), Some(expected)) = (actual.as_f64(), expected.as_f64()) else {
+                return false;
+            };
+            match op {
+                Operator::Eq => actual == expected,
+                _ => actual
+                    .partial_cmp(&expected)
+                    .is_some_and(|o| ordered(op, o)),
+            }
+        }
+        (Value::Bool(actual), Value::Bool(expected)) => op == Operator::Eq && actual == expected,
+        (actual, Value::Null) => op == Operator::Eq && actual.is_null(),
+        _ => false,
+    }
+}
+
+/// Timestamps compare chronologically, everything else lexically.
+fn compare_strings(actual: &str, expected: &str) -> Ordering {
+    let parse = |s: &str| DateTime::<FixedOffset>::parse_from_rfc3339(s).ok();
+    match (parse(actual), parse(expected)) {
+        (Some(actual), Some(expected)) => actual.cmp(&expected),
+        _ => actual.cmp(expected),
+    }
+}
+
+fn ordered(op: Operator, ordering: Ordering) -> bool {
+    match op {
+        Operator::Gt => ordering == Ordering::Greater,
+        Operator::Ge => ordering != Ordering::Less,
+        Operator::Lt => ordering == Ordering::Less,
+        Operator::Le => ordering != Ordering::Greater,
+        _ => false,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    Word(String),
+    Text(String),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Open => f.write_str("'('"),
+            Token::Close => f.write_str("')'"),
+            Token::Word(word) => write!(f, "'{}'", word),
+            Token::Text(text) => write!(f, "\"{}\"", text),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, AppError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            '[' | ']' => return Err(invalid("value paths are not supported")),
+            '"' => {
+                chars.next();
+                let mut escaped = false;
+                let end = loop {
+                    let Some((i, c)) = chars.next() else {
+                        return Err(invalid("unterminated string"));
+                    };
+                    match c {
+                        '\\' if !escaped => escaped = true,
+                        '"' if !escaped => break i,
+                        _ => escaped = false,
+                    }
+                };
+                let text = serde_json::from_str(&input[start..=end])
+                    .map_err(|_| invalid("invalid string literal"))?;
+                tokens.push(Token::Text(text));
+            }
+            _ => {
+                let mut end = input.len();
+                while let Some(&(i, c)) = chars.peek() {
+                    if c.is_whitespace() || matches!(c, '(' | ')' | '"' | '[' | ']') {
+                        end = i;
+                        break;
+                    }
+                    chars.next();
+                }
+                tokens.push(Token::Word(input[start..end].to_string()));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        match self.tokens.get(self.pos) {
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword) => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), AppError> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => Err(invalid(format!("expected {}, found {}", expected, token))),
+            None => Err(invalid(format!("expected {}", expected))),
+        }
+    }
+
+    fn or(&mut self) -> Result<Filter, AppError> {
+        let mut filter = self.and()?;
+        while self.keyword("or") {
+            filter = Filter::Or(Box::new(filter), Box::new(self.and()?));
+        }
+        Ok(filter)
+    }
+
+    fn and(&mut self) -> Result<Filter, AppError> {
+        let mut filter = self.unary()?;
+        while self.keyword("and") {
+            filter = Filter::And(Box::new(filter), Box::new(self.unary()?));
+        }
+        Ok(filter)
+    }
+
+    fn unary(&mut self) -> Result<Filter, AppError> {
+        if self.keyword("not") {
+            self.expect(Token::Open)?;
+            let inner = self.or()?;
+            self.expect(Token::Close)?;
+            return Ok(Filter::Not(Box::new(inner)));
+        }
+
+        match self.next() {
+            Some(Token::Open) => {
+                let inner = self.or()?;
+                self.expect(Token::Close)?;
+                Ok(inner)
+            }
+            Some(Token::Word(attribute)) => self.comparison(attribute),
+            Some(token) => Err(invalid(format!("expected an attribute, found {}", token))),
+            None => Err(invalid("expected an attribute")),
+        }
+    }
+
+    fn comparison(&mut self, attribute: String) -> Result<Filter, AppError> {
+        if !attribute
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | ':' | '_' | '-' | '$'))
+        {
+            return Err(invalid(format!("invalid attribute '{}'", attribute)));
+        }
+
+        let op = match self.next() {
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case("pr") => {
+                return Ok(Filter::Present(attribute));
+            }
+            Some(Token::Word(word)) => Operator::parse(&word)
+                .ok_or_else(|| invalid(format!("unknown operator '{}'", word)))?,
+            Some(token) => return Err(invalid(format!("expected an operator, found {}", token))),
+            None => return Err(invalid(format!("missing operator after '{}'", attribute))),
+        };
+
+        let value = match self.next() {
+            Some(Token::Text(text)) => Value::String(text),
+            Some(Token::Word(word)) => match word.to_ascii_lowercase().as_str() {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                "null" => Value::Null,
+                _ => serde_json::from_str::<serde_json::Number>(&word)
+                    .map(Value::Number)
+                    .map_err(|_| invalid(format!("invalid value '{}'", word)))?,
+            },
+            Some(token) => return Err(invalid(format!("expected a value, found {}", token))),
+            None => return Err(invalid(format!("missing value after '{}'", attribute))),
+        };
+
+        Ok(Filter::Compare {
+            attribute,
+            op,
+            value,
+        })
+    }
+}
@@ -0,0 +1,124 @@
+use std::future::Future;
+use std::mem;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinSet;
+
+/// Process-wide shutdown coordinator.
+///
+/// Cloned into anything that needs to stop when the server drains: the HTTP
+/// server waits on [`Shutdown::wait`], and background tasks started with
+/// [`Shutdown::spawn`] are joined (then aborted past the deadline) by
+/// [`Shutdown::join_tasks`].
+#[derive(Clone)]
+pub struct Shutdown {
+    sender: Arc<watch::Sender<bool>>,
+    tasks: Arc<Mutex<JoinSet<()>>>,
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        let (sender, _) = watch::channel(false);
+        Self {
+            sender: Arc::new(sender),
+            tasks: Arc::new(Mutex::new(JoinSet::new())),
+        }
+    }
+
+    pub fn trigger(&self) {
+        self.sender.send_replace(true);
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        *self.sender.borrow()
+    }
+
+    /// Resolves once [`Shutdown::trigger`] has been called.
+    pub async fn wait(&self) {
+        let mut receiver = self.sender.subscribe();
+        // The sender lives in `self`, so this only errors if it is dropped mid-wait
+        let _ = receiver.wait_for(|triggered| *triggered).await;
+    }
+
+    /// Spawns a background task tracked for orderly shutdown. The task is
+    /// expected to watch [`Shutdown::wait`] and return promptly once it fires.
+    pub fn spawn<F>(&self, name: &'static str, task: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        tracing::debug!("Starting background task '{}'", name);
+        self.tasks
+            .lock()
+            .expect("shutdown task set poisoned")
+            .spawn(async move {
+                task.await;
+                tracing::debug!("Background task '{}' stopped", name);
+            });
+    }
+
+    /// Waits for every spawned task to finish, aborting any still running
+    /// after `timeout`.
+    pub async fn join_tasks(&self, timeout: Duration) {
+        let mut tasks = mem::take(&mut *self.tasks.lock().expect("shutdown task set poisoned"));
+        if tasks.is_empty() {
+            return;
+        }
+
+        tracing::info!("Waiting for {} background task(s) to stop", tasks.len());
+        let joined = tokio::time::timeout(timeout, async {
+            while let Some(result) = tasks.join_next().await {
+                if let Err(e) = result {
+                    tracing::error!("Background task failed during shutdown: {}", e);
+                }
+            }
+        })
+        .await;
+
+        if joined.is_err() {
+            tracing::warn!(
+                "{} background task(s) did not stop within {}s, aborting",
+                tasks.len(),
+                timeout.as_secs()
+            );
+            tasks.shutdown().await;
+        }
+    }
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Resolves on SIGINT (Ctrl+C) or, on Unix, SIGTERM.
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => tracing::info!("Received SIGINT, starting graceful shutdown"),
+        _ = terminate => tracing::info!("Received SIGTERM, starting graceful shutdown"),
+    }
+}
@@ -1,31 +1,8 @@
 use template_rust_backend::enums::UserRole;
 use template_rust_backend::services::auth_service::AuthService;
+use template_rust_backend::utils::error::{AppError, AuthError};
 use uuid::Uuid;
 
-#[test]
-fn test_hash_password() {
-    let password = "test_password_123";
-    let hash = AuthService::hash_password(password).unwrap();
-    assert!(!hash.is_empty());
-    assert_ne!(hash, password);
-}
-
-#[test]
-fn test_verify_password_correct() {
-    let password = "test_password_123";
-    let hash = AuthService::hash_password(password).unwrap();
-    let result = AuthService::verify_password(password, &hash).unwrap();
-    assert!(result);
-}
-
-#[test]
-fn test_verify_password_incorrect() {
-    let password = "test_password_123";
-    let hash = AuthService::hash_password(password).unwrap();
-    let result = AuthService::verify_password("wrong_password", &hash).unwrap();
-    assert!(!result);
-}
-
 #[test]
 fn test_generate_and_verify_token() {
     let secret = "test_secret_key_for_jwt_token_generation";
@@ -82,3 +59,29 @@ fn test_verify_token_wrong_secret() {
     let result = AuthService::verify_token(&token, secret2);
     assert!(result.is_err());
 }
+
+#[test]
+fn test_verify_token_distinguishes_expired() {
+    let secret = "test_secret_key";
+    let token = AuthService::generate_token(
+        Uuid::now_v7(),
+        Uuid::now_v7(),
+        "test@example.com".to_string(),
+        UserRole::Regular,
+        secret,
+        -10,
+    )
+    .unwrap();
+
+    let result = AuthService::verify_token(&token, secret);
+    assert!(matches!(
+        result,
+        Err(AppError::Auth(AuthError::ExpiredToken))
+    ));
+
+    let result = AuthService::verify_token("invalid.token.here", secret);
+    assert!(matches!(
+        result,
+        Err(AppError::Auth(AuthError::InvalidToken))
+    ));
+}
@@ -0,0 +1,117 @@
+use clap::Parser;
+use template_rust_backend::cli::{
+    Cli, Command, job::JobCommand, migrate::MigrateCommand, tenant::TenantCommand,
+    user::UserCommand,
+};
+use uuid::Uuid;
+
+#[test]
+fn test_no_subcommand_defaults_to_serve() {
+    let cli = Cli::try_parse_from(["app"]).unwrap();
+    assert!(cli.command.is_none());
+}
+
+#[test]
+fn test_parse_migrate_subcommands() {
+    let cli = Cli::try_parse_from(["app", "migrate", "up", "--steps", "2"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Command::Migrate(MigrateCommand::Up { steps: Some(2) }))
+    ));
+
+    let cli = Cli::try_parse_from(["app", "migrate", "down"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Command::Migrate(MigrateCommand::Down { steps: 1 }))
+    ));
+
+    let cli = Cli::try_parse_from(["app", "migrate", "fresh"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Command::Migrate(MigrateCommand::Fresh { yes: false }))
+    ));
+}
+
+#[test]
+fn test_parse_tenant_suspend_requires_uuid() {
+    let tenant_id = Uuid::now_v7();
+    let cli = Cli::try_parse_from([
+        "app",
+        "tenant",
+        "suspend",
+        "--tenant-id",
+        &tenant_id.to_string(),
+    ])
+    .unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Command::Tenant(TenantCommand::Suspend { tenant_id: id })) if id == tenant_id
+    ));
+
+    assert!(Cli::try_parse_from(["app", "tenant", "suspend", "--tenant-id", "nope"]).is_err());
+}
+
+#[test]
+fn test_parse_user_create_admin() {
+    let tenant_id = Uuid::now_v7();
+    let cli = Cli::try_parse_from([
+        "app",
+        "user",
+        "create-admin",
+        "--tenant-id",
+        &tenant_id.to_string(),
+        "--email",
+        "admin@example.com",
+        "--password",
+        "password123",
+    ])
+    .unwrap();
+    match cli.command {
+        Some(Command::User(UserCommand::CreateAdmin {
+            email, password, ..
+        })) => {
+            assert_eq!(email, "admin@example.com");
+            assert_eq!(password.as_deref(), Some("password123"));
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_user_erase_deleted() {
+    let cli = Cli::try_parse_from(["app", "user", "erase-deleted"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Command::User(UserCommand::EraseDeleted))
+    ));
+}
+
+#[test]
+fn test_parse_worker_and_job_subcommands() {
+    let cli = Cli::try_parse_from(["app", "worker"]).unwrap();
+    assert!(matches!(cli.command, Some(Command::Worker)));
+
+    let cli = Cli::try_parse_from(["app", "job", "dead"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Command::Job(JobCommand::Dead { limit: 50 }))
+    ));
+
+    let id = Uuid::now_v7();
+    let cli = Cli::try_parse_from(["app", "job", "retry", "--job-id", &id.to_string()]).unwrap();
+    match cli.command {
+        Some(Command::Job(JobCommand::Retry { job_id })) => assert_eq!(job_id, id),
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn test_legacy_run_migrations_alias() {
+    let cli = Cli::try_parse_from(["app", "run_migrations"]).unwrap();
+    assert!(matches!(cli.command, Some(Command::RunMigrations)));
+}
+
+#[test]
+fn test_unknown_subcommand_is_rejected() {
+    assert!(Cli::try_parse_from(["app", "bogus"]).is_err());
+}
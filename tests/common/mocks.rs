@@ -0,0 +1,644 @@
+//! Mock repositories and services for tests that run without a database.
+
+use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset, Utc};
+use mockall::mock;
+use sea_orm::{DatabaseConnection, DbErr};
+use std::sync::Arc;
+use template_rust_backend::enums::{
+    ApiScope, ExportFormat, JobStatus, Locale, TaskRunStatus, TenantStatus, UserRole, UserStatus,
+    WebhookDeliveryStatus, WebhookEvent,
+};
+use template_rust_backend::events::DomainEvent;
+use template_rust_backend::middleware::auth::{Claims, Principal, PrincipalKind};
+use template_rust_backend::models::{
+    api_keys, jobs, memberships, oidc_login_states, oidc_providers, outbox_events,
+    password_history, service_accounts, task_runs, tenant_exports, tenant_settings, tenants,
+    user_identities, users, webhook_deliveries, webhook_endpoints,
+};
+use template_rust_backend::repositories::{
+    ApiKeyRepository, JobRepository, MembershipRepository, OidcRepository, OutboxRepository,
+    PasswordHistoryRepository, TaskLock, TaskRunRepository, TenantExportRepository,
+    TenantRepository, TenantSettingsRepository, UserRepository, WebhookRepository,
+};
+use template_rust_backend::routes::AppState;
+use template_rust_backend::services::api_keys_service::{ApiKeyService, IssuedApiKey};
+use template_rust_backend::services::auth_service::{
+    AuthResponse, AuthenticationService, LoginRequest, RegisterRequest,
+};
+use template_rust_backend::services::exports_service::{
+    ExportArchive, ExportResponse, ExportService,
+};
+use template_rust_backend::services::health_service::HealthRegistry;
+use template_rust_backend::services::jobs_service::{JobQueue, NewJob};
+use template_rust_backend::services::maintenance_service::MaintenanceService;
+use template_rust_backend::services::oidc_service::{
+    AuthorizationRequest, CreateOidcProviderRequest, OidcService,
+};
+use template_rust_backend::services::password_policy_service::PasswordPolicyService;
+use template_rust_backend::services::scim_service::{
+    ScimListQuery, ScimPatchRequest, ScimService, ScimUser, ScimUserList, ScimUserRequest,
+};
+use template_rust_backend::services::tenant_settings_service::{
+    TenantSettingsResponse, TenantSettingsService, UpdateTenantSettingsRequest,
+};
+use template_rust_backend::services::tenants_service::TenantService;
+use template_rust_backend::services::users_service::{UserChanges, UserService};
+use template_rust_backend::services::webhooks_service::{
+    CreateWebhookRequest, CreatedWebhook, WebhookService,
+};
+use template_rust_backend::utils::error::AppError;
+use uuid::Uuid;
+
+mock! {
+    pub UserRepository {}
+
+    #[async_trait]
+    impl UserRepository for UserRepository {
+        async fn find_by_id(&self, user_id: Uuid) -> Result<Option<users::Model>, DbErr>;
+        async fn find_in_tenant(&self, tenant_id: Uuid, user_id: Uuid) -> Result<Option<users::Model>, DbErr>;
+        async fn find_by_email(&self, email: &str) -> Result<Option<users::Model>, DbErr>;
+        async fn find_by_email_in_tenant(&self, tenant_id: Uuid, email: &str) -> Result<Option<users::Model>, DbErr>;
+        async fn list_by_tenant(&self, tenant_id: Uuid) -> Result<Vec<users::Model>, DbErr>;
+        async fn tenant_has_users(&self, tenant_id: Uuid) -> Result<bool, DbErr>;
+        async fn insert_member(&self, user: users::ActiveModel, membership: memberships::ActiveModel, events: Vec<DomainEvent>) -> Result<users::Model, DbErr>;
+        async fn update(&self, user: users::ActiveModel) -> Result<users::Model, DbErr>;
+        async fn find_deleted_in_tenant(&self, tenant_id: Uuid, user_id: Uuid) -> Result<Option<users::Model>, DbErr>;
+        async fn list_erasable(&self, deleted_before: DateTime<FixedOffset>) -> Result<Vec<users::Model>, DbErr>;
+        async fn erase(&self, user: users::ActiveModel) -> Result<(), DbErr>;
+    }
+}
+
+mock! {
+    pub MembershipRepository {}
+
+    #[async_trait]
+    impl MembershipRepository for MembershipRepository {
+        async fn find(&self, user_id: Uuid, tenant_id: Uuid) -> Result<Option<memberships::Model>, DbErr>;
+        async fn list_by_user(&self, user_id: Uuid) -> Result<Vec<memberships::Model>, DbErr>;
+        async fn list_by_tenant(&self, tenant_id: Uuid) -> Result<Vec<memberships::Model>, DbErr>;
+        async fn insert(&self, membership: memberships::ActiveModel, events: Vec<DomainEvent>) -> Result<memberships::Model, DbErr>;
+        async fn update(&self, membership: memberships::ActiveModel, events: Vec<DomainEvent>) -> Result<memberships::Model, DbErr>;
+        async fn delete(&self, user_id: Uuid, tenant_id: Uuid, home_tenant_id: Option<Uuid>) -> Result<bool, DbErr>;
+        async fn touch(&self, user_id: Uuid, tenant_id: Uuid, at: DateTime<FixedOffset>) -> Result<(), DbErr>;
+        async fn deactivate_idle(&self, tenant_id: Uuid, idle_since: DateTime<FixedOffset>) -> Result<u64, DbErr>;
+    }
+}
+
+mock! {
+    pub TenantRepository {}
+
+    #[async_trait]
+    impl TenantRepository for TenantRepository {
+        async fn list_all(&self) -> Result<Vec<tenants::Model>, DbErr>;
+        async fn find_by_id(&self, tenant_id: Uuid) -> Result<Option<tenants::Model>, DbErr>;
+        async fn insert(&self, tenant: tenants::ActiveModel) -> Result<tenants::Model, DbErr>;
+        async fn update(&self, tenant: tenants::ActiveModel, events: Vec<DomainEvent>) -> Result<tenants::Model, DbErr>;
+    }
+}
+
+mock! {
+    pub TenantSettingsRepository {}
+
+    #[async_trait]
+    impl TenantSettingsRepository for TenantSettingsRepository {
+        async fn find(&self, tenant_id: Uuid) -> Result<Option<tenant_settings::Model>, DbErr>;
+        async fn save(&self, tenant_id: Uuid, version: i32, settings: serde_json::Value) -> Result<Option<tenant_settings::Model>, DbErr>;
+    }
+}
+
+mock! {
+    pub PasswordHistoryRepository {}
+
+    #[async_trait]
+    impl PasswordHistoryRepository for PasswordHistoryRepository {
+        async fn list_recent(&self, user_id: Uuid, limit: u64) -> Result<Vec<password_history::Model>, DbErr>;
+        async fn insert(&self, entry: password_history::ActiveModel) -> Result<password_history::Model, DbErr>;
+        async fn prune(&self, user_id: Uuid, keep: u64) -> Result<(), DbErr>;
+    }
+}
+
+mock! {
+    pub ApiKeyRepository {}
+
+    #[async_trait]
+    impl ApiKeyRepository for ApiKeyRepository {
+        async fn insert_service_account(&self, account: service_accounts::ActiveModel) -> Result<service_accounts::Model, DbErr>;
+        async fn find_service_account(&self, tenant_id: Uuid, service_account_id: Uuid) -> Result<Option<service_accounts::Model>, DbErr>;
+        async fn list_service_accounts(&self, tenant_id: Uuid) -> Result<Vec<service_accounts::Model>, DbErr>;
+        async fn find_key(&self, tenant_id: Uuid, key_id: Uuid) -> Result<Option<api_keys::Model>, DbErr>;
+        async fn find_key_by_prefix(&self, prefix: &str) -> Result<Option<(api_keys::Model, service_accounts::Model)>, DbErr>;
+        async fn list_keys(&self, tenant_id: Uuid) -> Result<Vec<api_keys::Model>, DbErr>;
+        async fn insert_key(&self, key: api_keys::ActiveModel) -> Result<api_keys::Model, DbErr>;
+        async fn update_key(&self, key: api_keys::ActiveModel) -> Result<api_keys::Model, DbErr>;
+        async fn rotate_key(&self, revoked: api_keys::ActiveModel, key: api_keys::ActiveModel) -> Result<api_keys::Model, DbErr>;
+        async fn touch_key(&self, key_id: Uuid, at: DateTime<FixedOffset>) -> Result<(), DbErr>;
+        async fn delete_keys_ended_before(&self, before: DateTime<FixedOffset>) -> Result<u64, DbErr>;
+    }
+}
+
+mock! {
+    pub OidcRepository {}
+
+    #[async_trait]
+    impl OidcRepository for OidcRepository {
+        async fn insert_provider(&self, provider: oidc_providers::ActiveModel) -> Result<oidc_providers::Model, DbErr>;
+        async fn find_provider(&self, provider_id: Uuid) -> Result<Option<oidc_providers::Model>, DbErr>;
+        async fn list_providers(&self, tenant_id: Uuid) -> Result<Vec<oidc_providers::Model>, DbErr>;
+        async fn delete_provider(&self, tenant_id: Uuid, provider_id: Uuid) -> Result<bool, DbErr>;
+        async fn find_identity(&self, provider_id: Uuid, subject: &str) -> Result<Option<user_identities::Model>, DbErr>;
+        async fn insert_identity(&self, identity: user_identities::ActiveModel) -> Result<user_identities::Model, DbErr>;
+        async fn insert_login_state(&self, state: oidc_login_states::ActiveModel) -> Result<oidc_login_states::Model, DbErr>;
+        async fn take_login_state(&self, state: &str) -> Result<Option<oidc_login_states::Model>, DbErr>;
+        async fn delete_expired_login_states(&self, before: DateTime<FixedOffset>) -> Result<u64, DbErr>;
+    }
+}
+
+mock! {
+    pub UserService {}
+
+    #[async_trait]
+    impl UserService for UserService {
+        async fn get_by_id(&self, user_id: Uuid) -> Result<users::Model, AppError>;
+        async fn get_in_tenant(&self, tenant_id: Uuid, user_id: Uuid) -> Result<users::Model, AppError>;
+        async fn list_by_tenant(&self, tenant_id: Uuid) -> Result<Vec<users::Model>, AppError>;
+        async fn create(&self, tenant_id: Uuid, email: String, password: &str, role: UserRole) -> Result<users::Model, AppError>;
+        async fn add_member(&self, tenant_id: Uuid, email: &str, role: UserRole) -> Result<users::Model, AppError>;
+        async fn find_membership(&self, tenant_id: Uuid, user_id: Uuid) -> Result<Option<memberships::Model>, AppError>;
+        async fn list_memberships(&self, user_id: Uuid) -> Result<Vec<memberships::Model>, AppError>;
+        async fn verify_password(&self, user: &users::Model, password: &str) -> Result<bool, AppError>;
+        async fn reset_password(&self, tenant_id: Uuid, email: &str, new_password: &str) -> Result<users::Model, AppError>;
+        async fn change_password(&self, user_id: Uuid, current_password: &str, new_password: &str) -> Result<(), AppError>;
+        async fn change_user_status(&self, user_id: Uuid, tenant_id: Uuid) -> Result<users::Model, AppError>;
+        async fn change_role(&self, user_id: Uuid, tenant_id: Uuid) -> Result<users::Model, AppError>;
+        async fn set_locale(&self, user_id: Uuid, locale: Option<Locale>) -> Result<users::Model, AppError>;
+        async fn update(&self, tenant_id: Uuid, user_id: Uuid, changes: UserChanges) -> Result<users::Model, AppError>;
+        async fn delete(&self, tenant_id: Uuid, user_id: Uuid) -> Result<(), AppError>;
+        async fn restore(&self, tenant_id: Uuid, user_id: Uuid) -> Result<users::Model, AppError>;
+        async fn erase_deleted(&self) -> Result<usize, AppError>;
+        async fn record_login(&self, tenant_id: Uuid, user_id: Uuid) -> Result<(), AppError>;
+    }
+}
+
+mock! {
+    pub TenantService {}
+
+    #[async_trait]
+    impl TenantService for TenantService {
+        async fn list_all(&self) -> Result<Vec<tenants::Model>, AppError>;
+        async fn get_by_id(&self, tenant_id: Uuid) -> Result<tenants::Model, AppError>;
+        async fn create(&self, name: String) -> Result<tenants::Model, AppError>;
+        async fn set_status(&self, tenant_id: Uuid, status: TenantStatus) -> Result<tenants::Model, AppError>;
+        async fn set_default_locale(&self, tenant_id: Uuid, locale: Option<Locale>) -> Result<tenants::Model, AppError>;
+    }
+}
+
+mock! {
+    pub TenantSettingsService {}
+
+    #[async_trait]
+    impl TenantSettingsService for TenantSettingsService {
+        async fn get(&self, tenant_id: Uuid) -> Result<TenantSettingsResponse, AppError>;
+        async fn update(&self, tenant_id: Uuid, req: UpdateTenantSettingsRequest) -> Result<TenantSettingsResponse, AppError>;
+    }
+}
+
+mock! {
+    pub PasswordPolicyService {}
+
+    #[async_trait]
+    impl PasswordPolicyService for PasswordPolicyService {
+        async fn check(&self, tenant_ids: &[Uuid], email: &str, password: &str) -> Result<(), AppError>;
+        async fn check_change(&self, tenant_ids: &[Uuid], user: &users::Model, password: &str) -> Result<(), AppError>;
+        async fn remember(&self, user_id: Uuid, password_hash: String) -> Result<(), AppError>;
+    }
+}
+
+mock! {
+    pub AuthenticationService {}
+
+    #[async_trait]
+    impl AuthenticationService for AuthenticationService {
+        async fn register(&self, req: RegisterRequest) -> Result<AuthResponse, AppError>;
+        async fn login(&self, req: LoginRequest) -> Result<AuthResponse, AppError>;
+        async fn refresh_token(&self, claims: Claims) -> Result<AuthResponse, AppError>;
+        async fn switch_tenant(&self, claims: Claims, tenant_id: Uuid) -> Result<AuthResponse, AppError>;
+        async fn sign_in(&self, user: users::Model) -> Result<AuthResponse, AppError>;
+    }
+}
+
+mock! {
+    pub ApiKeyService {}
+
+    #[async_trait]
+    impl ApiKeyService for ApiKeyService {
+        async fn create_service_account(&self, caller: &Principal, tenant_id: Uuid, name: String, role: UserRole) -> Result<service_accounts::Model, AppError>;
+        async fn list_service_accounts(&self, tenant_id: Uuid) -> Result<Vec<service_accounts::Model>, AppError>;
+        async fn create_key(&self, caller: &Principal, tenant_id: Uuid, service_account_id: Uuid, name: String, scopes: Vec<ApiScope>, expires_at: Option<DateTime<FixedOffset>>) -> Result<IssuedApiKey, AppError>;
+        async fn list_keys(&self, tenant_id: Uuid) -> Result<Vec<api_keys::Model>, AppError>;
+        async fn rotate_key(&self, caller: &Principal, tenant_id: Uuid, key_id: Uuid) -> Result<IssuedApiKey, AppError>;
+        async fn revoke_key(&self, tenant_id: Uuid, key_id: Uuid) -> Result<api_keys::Model, AppError>;
+        async fn authenticate(&self, secret: &str) -> Result<Principal, AppError>;
+    }
+}
+
+mock! {
+    pub OidcService {}
+
+    #[async_trait]
+    impl OidcService for OidcService {
+        async fn create_provider(&self, tenant_id: Uuid, req: CreateOidcProviderRequest) -> Result<oidc_providers::Model, AppError>;
+        async fn list_providers(&self, tenant_id: Uuid) -> Result<Vec<oidc_providers::Model>, AppError>;
+        async fn delete_provider(&self, tenant_id: Uuid, provider_id: Uuid) -> Result<(), AppError>;
+        async fn authorize(&self, provider_id: Uuid) -> Result<AuthorizationRequest, AppError>;
+        async fn callback(&self, state: &str, code: &str) -> Result<AuthResponse, AppError>;
+    }
+}
+
+mock! {
+    pub ScimService {}
+
+    #[async_trait]
+    impl ScimService for ScimService {
+        async fn list_users(&self, tenant_id: Uuid, query: ScimListQuery) -> Result<ScimUserList, AppError>;
+        async fn get_user(&self, tenant_id: Uuid, user_id: Uuid) -> Result<ScimUser, AppError>;
+        async fn create_user(&self, tenant_id: Uuid, req: ScimUserRequest) -> Result<ScimUser, AppError>;
+        async fn replace_user(&self, tenant_id: Uuid, user_id: Uuid, req: ScimUserRequest) -> Result<ScimUser, AppError>;
+        async fn patch_user(&self, tenant_id: Uuid, user_id: Uuid, req: ScimPatchRequest) -> Result<ScimUser, AppError>;
+        async fn delete_user(&self, tenant_id: Uuid, user_id: Uuid) -> Result<(), AppError>;
+    }
+}
+
+mock! {
+    pub TenantExportRepository {}
+
+    #[async_trait]
+    impl TenantExportRepository for TenantExportRepository {
+        async fn find(&self, tenant_id: Uuid, export_id: Uuid) -> Result<Option<tenant_exports::Model>, DbErr>;
+        async fn find_by_id(&self, export_id: Uuid) -> Result<Option<tenant_exports::Model>, DbErr>;
+        async fn insert(&self, export: tenant_exports::ActiveModel) -> Result<tenant_exports::Model, DbErr>;
+        async fn update(&self, export: tenant_exports::ActiveModel) -> Result<tenant_exports::Model, DbErr>;
+    }
+}
+
+mock! {
+    pub ExportService {}
+
+    #[async_trait]
+    impl ExportService for ExportService {
+        async fn request(&self, tenant_id: Uuid, requested_by: Uuid, format: ExportFormat) -> Result<ExportResponse, AppError>;
+        async fn get(&self, tenant_id: Uuid, export_id: Uuid) -> Result<ExportResponse, AppError>;
+        async fn download(&self, export_id: Uuid, token: &str) -> Result<ExportArchive, AppError>;
+        async fn run(&self, export_id: Uuid) -> Result<(), AppError>;
+    }
+}
+
+mock! {
+    pub JobRepository {}
+
+    #[async_trait]
+    impl JobRepository for JobRepository {
+        async fn find_by_id(&self, job_id: Uuid) -> Result<Option<jobs::Model>, DbErr>;
+        async fn list_by_status(&self, status: JobStatus, limit: u64) -> Result<Vec<jobs::Model>, DbErr>;
+        async fn insert(&self, job: jobs::ActiveModel) -> Result<jobs::Model, DbErr>;
+        async fn update(&self, job: jobs::ActiveModel) -> Result<jobs::Model, DbErr>;
+        async fn finish(&self, job: jobs::ActiveModel, worker: &str) -> Result<Option<jobs::Model>, DbErr>;
+        async fn claim(&self, worker: &str, limit: u64, stale_before: DateTime<FixedOffset>) -> Result<Vec<jobs::Model>, DbErr>;
+    }
+}
+
+mock! {
+    pub JobQueue {}
+
+    #[async_trait]
+    impl JobQueue for JobQueue {
+        async fn enqueue(&self, job: NewJob) -> Result<jobs::Model, AppError>;
+        fn job(&self, job: NewJob) -> jobs::ActiveModel;
+        async fn list_dead(&self, limit: u64) -> Result<Vec<jobs::Model>, AppError>;
+        async fn retry(&self, job_id: Uuid) -> Result<Option<jobs::Model>, AppError>;
+    }
+}
+
+mock! {
+    pub TaskRunRepository {}
+
+    #[async_trait]
+    impl TaskRunRepository for TaskRunRepository {
+        async fn try_lock(&self, task: &str) -> Result<Option<TaskLock>, DbErr>;
+        async fn find(&self, task: &str, scheduled_for: DateTime<FixedOffset>) -> Result<Option<task_runs::Model>, DbErr>;
+        async fn list(&self, task: Option<String>, limit: u64) -> Result<Vec<task_runs::Model>, DbErr>;
+        async fn insert(&self, run: task_runs::ActiveModel) -> Result<task_runs::Model, DbErr>;
+        async fn update(&self, run: task_runs::ActiveModel) -> Result<task_runs::Model, DbErr>;
+    }
+}
+
+mock! {
+    pub MaintenanceService {}
+
+    #[async_trait]
+    impl MaintenanceService for MaintenanceService {
+        async fn purge_expired_auth_artifacts(&self) -> Result<u64, AppError>;
+        async fn deactivate_idle_members(&self) -> Result<u64, AppError>;
+        async fn purge_relayed_events(&self, retention: chrono::Duration) -> Result<u64, AppError>;
+        async fn list_runs(&self, task: Option<String>, limit: Option<u64>) -> Result<Vec<task_runs::Model>, AppError>;
+    }
+}
+
+mock! {
+    pub WebhookRepository {}
+
+    #[async_trait]
+    impl WebhookRepository for WebhookRepository {
+        async fn insert_endpoint(&self, endpoint: webhook_endpoints::ActiveModel) -> Result<webhook_endpoints::Model, DbErr>;
+        async fn find_endpoint(&self, tenant_id: Uuid, endpoint_id: Uuid) -> Result<Option<webhook_endpoints::Model>, DbErr>;
+        async fn list_endpoints(&self, tenant_id: Uuid) -> Result<Vec<webhook_endpoints::Model>, DbErr>;
+        async fn delete_endpoint(&self, tenant_id: Uuid, endpoint_id: Uuid) -> Result<bool, DbErr>;
+        async fn insert_delivery(&self, delivery: webhook_deliveries::ActiveModel) -> Result<webhook_deliveries::Model, DbErr>;
+        async fn insert_deliveries(&self, deliveries: Vec<(webhook_deliveries::ActiveModel, jobs::ActiveModel)>) -> Result<u64, DbErr>;
+        async fn find_delivery(&self, delivery_id: Uuid) -> Result<Option<webhook_deliveries::Model>, DbErr>;
+        async fn update_delivery(&self, delivery: webhook_deliveries::ActiveModel) -> Result<webhook_deliveries::Model, DbErr>;
+        async fn list_deliveries(&self, endpoint_id: Uuid, limit: u64) -> Result<Vec<webhook_deliveries::Model>, DbErr>;
+    }
+}
+
+mock! {
+    pub OutboxRepository {}
+
+    #[async_trait]
+    impl OutboxRepository for OutboxRepository {
+        async fn try_lock(&self) -> Result<Option<TaskLock>, DbErr>;
+        async fn due(&self, now: DateTime<FixedOffset>, limit: u64) -> Result<Vec<outbox_events::Model>, DbErr>;
+        async fn update(&self, event: outbox_events::ActiveModel) -> Result<outbox_events::Model, DbErr>;
+        async fn purge_dispatched(&self, before: DateTime<FixedOffset>) -> Result<u64, DbErr>;
+    }
+}
+
+mock! {
+    pub WebhookService {}
+
+    #[async_trait]
+    impl WebhookService for WebhookService {
+        async fn create_endpoint(&self, tenant_id: Uuid, request: CreateWebhookRequest) -> Result<CreatedWebhook, AppError>;
+        async fn list_endpoints(&self, tenant_id: Uuid) -> Result<Vec<webhook_endpoints::Model>, AppError>;
+        async fn delete_endpoint(&self, tenant_id: Uuid, endpoint_id: Uuid) -> Result<(), AppError>;
+        async fn list_deliveries(&self, tenant_id: Uuid, endpoint_id: Uuid, limit: Option<u64>) -> Result<Vec<webhook_deliveries::Model>, AppError>;
+        async fn redeliver(&self, tenant_id: Uuid, endpoint_id: Uuid, delivery_id: Uuid) -> Result<webhook_deliveries::Model, AppError>;
+        async fn ping(&self, tenant_id: Uuid, endpoint_id: Uuid) -> Result<webhook_deliveries::Model, AppError>;
+        async fn publish(&self, tenant_id: Uuid, event_id: Uuid, event: WebhookEvent, data: serde_json::Value) -> Result<(), AppError>;
+        async fn deliver(&self, delivery_id: Uuid) -> Result<(), AppError>;
+    }
+}
+
+/// Application state backed by the given mock services and a disconnected
+/// database handle, so any direct database access fails loudly. Every tenant
+/// has the default settings. API keys, OIDC, SCIM, exports, jobs,
+/// maintenance and webhooks are served by expectation-free mocks; replace
+/// `api_keys`, `oidc`, `scim`, `exports`, `jobs`, `maintenance` or
+/// `webhooks` to exercise them.
+pub fn mock_state(
+    users: MockUserService,
+    tenants: MockTenantService,
+    auth: MockAuthenticationService,
+) -> AppState {
+    AppState {
+        db: Arc::new(DatabaseConnection::default()),
+        config: super::get_test_config(),
+        health: Arc::new(HealthRegistry::new()),
+        users: Arc::new(users),
+        tenants: Arc::new(tenants),
+        tenant_settings: Arc::new(default_settings()),
+        passwords: Arc::new(any_password()),
+        auth: Arc::new(auth),
+        api_keys: Arc::new(MockApiKeyService::new()),
+        oidc: Arc::new(MockOidcService::new()),
+        scim: Arc::new(MockScimService::new()),
+        exports: Arc::new(MockExportService::new()),
+        jobs: Arc::new(MockJobQueue::new()),
+        maintenance: Arc::new(MockMaintenanceService::new()),
+        webhooks: Arc::new(MockWebhookService::new()),
+    }
+}
+
+/// Settings service where every tenant has the defaults.
+pub fn default_settings() -> MockTenantSettingsService {
+    let mut settings = MockTenantSettingsService::new();
+    settings.expect_get().returning(|tenant_id| {
+        Ok(TenantSettingsResponse {
+            tenant_id,
+            version: 0,
+            settings: Default::default(),
+        })
+    });
+    settings
+}
+
+/// Password policy service that accepts every password and forgets them.
+pub fn any_password() -> MockPasswordPolicyService {
+    let mut passwords = MockPasswordPolicyService::new();
+    passwords.expect_check().returning(|_, _, _| Ok(()));
+    passwords.expect_check_change().returning(|_, _, _| Ok(()));
+    passwords.expect_remember().returning(|_, _| Ok(()));
+    passwords
+}
+
+pub fn user_model(tenant_id: Uuid, email: &str, role: UserRole) -> users::Model {
+    users::Model {
+        id: Uuid::now_v7(),
+        tenant_id,
+        email: email.to_string(),
+        password_hash: String::new(),
+        role,
+        status: UserStatus::Active,
+        locale: None,
+        created_at: Utc::now().fixed_offset(),
+        updated_at: Utc::now().fixed_offset(),
+        deleted_at: None,
+        erased_at: None,
+    }
+}
+
+/// The membership `user` is read as.
+pub fn membership_model(user: &users::Model) -> memberships::Model {
+    memberships::Model {
+        user_id: user.id,
+        tenant_id: user.tenant_id,
+        role: user.role,
+        status: user.status,
+        created_at: Utc::now().fixed_offset(),
+        updated_at: Utc::now().fixed_offset(),
+        last_login_at: None,
+    }
+}
+
+pub fn tenant_model(name: &str) -> tenants::Model {
+    tenants::Model {
+        id: Uuid::now_v7(),
+        name: name.to_string(),
+        status: TenantStatus::Active,
+        default_locale: None,
+        created_at: Utc::now().fixed_offset(),
+        updated_at: Utc::now().fixed_offset(),
+    }
+}
+
+/// A service account of `tenant_id` with `role`, authenticated by a key with
+/// `scopes`.
+pub fn service_account_principal(
+    tenant_id: Uuid,
+    role: UserRole,
+    scopes: Vec<ApiScope>,
+) -> Principal {
+    Principal {
+        tenant_id,
+        role,
+        kind: PrincipalKind::ServiceAccount {
+            service_account_id: Uuid::now_v7(),
+            api_key_id: Uuid::now_v7(),
+            scopes,
+        },
+    }
+}
+
+pub fn service_account_model(tenant_id: Uuid, role: UserRole) -> service_accounts::Model {
+    service_accounts::Model {
+        id: Uuid::now_v7(),
+        tenant_id,
+        name: "ci".to_string(),
+        role,
+        created_at: Utc::now().fixed_offset(),
+    }
+}
+
+pub fn api_key_model(
+    account: &service_accounts::Model,
+    prefix: &str,
+    key_hash: &str,
+) -> api_keys::Model {
+    api_keys::Model {
+        id: Uuid::now_v7(),
+        tenant_id: account.tenant_id,
+        service_account_id: account.id,
+        name: "deploy".to_string(),
+        prefix: prefix.to_string(),
+        key_hash: key_hash.to_string(),
+        scopes: "users:read".to_string(),
+        expires_at: None,
+        last_used_at: None,
+        revoked_at: None,
+        created_at: Utc::now().fixed_offset(),
+    }
+}
+
+/// A provider of `tenant_id` at `issuer`, using the mock server's client.
+pub fn oidc_provider_model(tenant_id: Uuid, issuer: &str) -> oidc_providers::Model {
+    oidc_providers::Model {
+        id: Uuid::now_v7(),
+        tenant_id,
+        name: "Corporate SSO".to_string(),
+        issuer: issuer.to_string(),
+        client_id: super::oidc::CLIENT_ID.to_string(),
+        client_secret: super::oidc::CLIENT_SECRET.to_string(),
+        scopes: "openid email profile".to_string(),
+        redirect_uri: super::oidc::REDIRECT_URI.to_string(),
+        email_claim: "email".to_string(),
+        role_claim: None,
+        admin_role_value: None,
+        default_role: UserRole::Regular,
+        created_at: Utc::now().fixed_offset(),
+        updated_at: Utc::now().fixed_offset(),
+    }
+}
+
+/// A pending job, due now.
+pub fn job_model(kind: &str, payload: serde_json::Value) -> jobs::Model {
+    jobs::Model {
+        id: Uuid::now_v7(),
+        kind: kind.to_string(),
+        payload,
+        status: JobStatus::Pending,
+        attempts: 0,
+        max_attempts: 5,
+        run_at: Utc::now().fixed_offset(),
+        locked_by: None,
+        locked_at: None,
+        last_error: None,
+        created_at: Utc::now().fixed_offset(),
+        updated_at: Utc::now().fixed_offset(),
+        completed_at: None,
+    }
+}
+
+/// `event` in the outbox, not relayed yet.
+pub fn outbox_event_model(event: &DomainEvent) -> outbox_events::Model {
+    let (aggregate_type, aggregate_id) = event.aggregate();
+    outbox_events::Model {
+        id: Uuid::now_v7(),
+        position: 1,
+        aggregate_type: aggregate_type.to_string(),
+        aggregate_id,
+        tenant_id: event.tenant_id(),
+        event_type: event.name().to_string(),
+        payload: serde_json::to_value(event).unwrap(),
+        attempts: 0,
+        next_attempt_at: None,
+        last_error: None,
+        created_at: Utc::now().fixed_offset(),
+        dispatched_at: None,
+        dead_at: None,
+    }
+}
+
+/// A finished run of `task` that succeeded.
+pub fn task_run_model(task: &str, affected: i64) -> task_runs::Model {
+    task_runs::Model {
+        id: Uuid::now_v7(),
+        task: task.to_string(),
+        scheduled_for: Utc::now().fixed_offset(),
+        status: TaskRunStatus::Succeeded,
+        runner: "scheduler-test".to_string(),
+        affected: Some(affected),
+        error: None,
+        started_at: Utc::now().fixed_offset(),
+        finished_at: Some(Utc::now().fixed_offset()),
+    }
+}
+
+pub fn webhook_endpoint_model(
+    tenant_id: Uuid,
+    url: &str,
+    events: &[WebhookEvent],
+) -> webhook_endpoints::Model {
+    webhook_endpoints::Model {
+        id: Uuid::now_v7(),
+        tenant_id,
+        url: url.to_string(),
+        secret: "whsec_test".to_string(),
+        events: WebhookEvent::join(events),
+        created_at: Utc::now().fixed_offset(),
+        updated_at: Utc::now().fixed_offset(),
+    }
+}
+
+pub fn webhook_delivery_model(
+    endpoint: &webhook_endpoints::Model,
+    event: WebhookEvent,
+) -> webhook_deliveries::Model {
+    webhook_deliveries::Model {
+        id: Uuid::now_v7(),
+        tenant_id: endpoint.tenant_id,
+        endpoint_id: endpoint.id,
+        event_id: Uuid::now_v7(),
+        event: event.to_string(),
+        redelivery_of: None,
+        payload: serde_json::json!({ "type": event }),
+        status: WebhookDeliveryStatus::Pending,
+        attempts: 0,
+        next_attempt_at: Some(Utc::now().fixed_offset()),
+        response_status: None,
+        response_body: None,
+        error: None,
+        created_at: Utc::now().fixed_offset(),
+        last_attempt_at: None,
+        delivered_at: None,
+    }
+}
@@ -1,20 +1,205 @@
-use axum::Router;
+pub mod mocks;
+pub mod oidc;
+pub mod webhooks;
+
+use axum_test::TestServer;
+use migration::{Migrator, MigratorTrait};
+use sea_orm::{ConnectOptions, ConnectionTrait, Database, DatabaseConnection};
 use std::sync::Arc;
 use template_rust_backend::config;
+use template_rust_backend::enums::UserRole;
+use template_rust_backend::events;
+use template_rust_backend::jobs::{self, Worker};
+use template_rust_backend::models::{tenants, users};
+use template_rust_backend::repositories::{SeaOrmJobRepository, SeaOrmOutboxRepository};
+use template_rust_backend::routes::{self, AppState};
+use template_rust_backend::services::auth_service::AuthService;
+use template_rust_backend::services::health_service::HealthRegistry;
+use uuid::Uuid;
+
+/// Password given to every user created through [`TestApp::create_user`].
+pub const TEST_PASSWORD: &str = "password123";
+
+/// A router backed by a migrated, throwaway database.
+///
+/// With a Postgres `TEST_DATABASE_URL`, each instance creates its own schema
+/// so tests can run in parallel without seeing each other's rows; the schema
+/// is dropped when the value goes out of scope. With a `sqlite:` URL (or no
+/// URL when built with the `sqlite` feature), each instance gets its own
+/// in-memory database.
+pub struct TestApp {
+    pub server: TestServer,
+    pub state: AppState,
+    pub db: Arc<DatabaseConnection>,
+    pub config: Arc<config::Config>,
+    database_url: String,
+    schema: Option<String>,
+}
+
+impl TestApp {
+    /// Returns `None` (and the calling test should return early) when no test
+    /// database is available.
+    pub async fn spawn() -> Option<Self> {
+        let database_url = match std::env::var("TEST_DATABASE_URL") {
+            Ok(url) => url,
+            Err(_) if cfg!(feature = "sqlite") => "sqlite::memory:".to_string(),
+            Err(_) => {
+                eprintln!("TEST_DATABASE_URL is not set, skipping database test");
+                return None;
+            }
+        };
+
+        let (db, schema) = if database_url.starts_with("sqlite:") {
+            let mut opt = ConnectOptions::new(&database_url);
+            // An in-memory database only lives as long as its one connection
+            opt.max_connections(1)
+                .min_connections(1)
+                .idle_timeout(None)
+                .max_lifetime(None)
+                .sqlx_logging(false);
+            let db = Database::connect(opt)
+                .await
+                .expect("failed to connect to TEST_DATABASE_URL");
+            (db, None)
+        } else {
+            let schema = format!("test_{}", Uuid::now_v7().simple());
+
+            let admin = Database::connect(&database_url)
+                .await
+                .expect("failed to connect to TEST_DATABASE_URL");
+            admin
+                .execute_unprepared(&format!("CREATE SCHEMA \"{}\"", schema))
+                .await
+                .expect("failed to create test schema");
+            admin.close().await.ok();
+
+            let mut opt = ConnectOptions::new(&database_url);
+            opt.max_connections(5)
+                .set_schema_search_path(schema.clone())
+                .sqlx_logging(false);
+            let db = Database::connect(opt)
+                .await
+                .expect("failed to connect to test schema");
+            (db, Some(schema))
+        };
+
+        Migrator::up(&db, None)
+            .await
+            .expect("failed to run migrations");
+
+        let db = Arc::new(db);
+        let config = get_test_config();
+        let health = Arc::new(HealthRegistry::with_defaults(db.clone()));
+        let state = AppState::new(db.clone(), config.clone(), health);
+        let server = TestServer::new(routes::create_router(state.clone()))
+            .expect("failed to build test server");
+
+        Some(Self {
+            server,
+            state,
+            db,
+            config,
+            database_url,
+            schema,
+        })
+    }
+
+    pub async fn create_tenant(&self, name: &str) -> tenants::Model {
+        self.state
+            .tenants
+            .create(name.to_string())
+            .await
+            .expect("failed to create tenant")
+    }
+
+    pub async fn create_user(&self, tenant_id: Uuid, email: &str, role: UserRole) -> users::Model {
+        self.state
+            .users
+            .create(tenant_id, email.to_string(), TEST_PASSWORD, role)
+            .await
+            .expect("failed to create user")
+    }
+
+    /// Creates a tenant with an admin and returns both.
+    pub async fn create_tenant_with_admin(&self) -> (tenants::Model, users::Model) {
+        let tenant = self.create_tenant("Acme").await;
+        let admin = self
+            .create_user(tenant.id, "admin@example.com", UserRole::Admin)
+            .await;
+        (tenant, admin)
+    }
+
+    /// Runs queued jobs, and the jobs they queue, until none is due. Returns
+    /// how many ran.
+    pub async fn run_jobs(&self) -> usize {
+        let worker = Worker::new(
+            Arc::new(SeaOrmJobRepository::new(self.db.clone())),
+            jobs::registry(&self.state),
+            self.config.jobs.clone(),
+        );
+        let mut total = 0;
+        loop {
+            match worker.run_once().await.expect("failed to run jobs") {
+                0 => return total,
+                ran => total += ran,
+            }
+        }
+    }
+
+    /// Relays the domain events in the outbox until none is due. Returns how
+    /// many were handed to the subscribers.
+    pub async fn relay_events(&self) -> usize {
+        let relay = events::relay(
+            &self.state,
+            Arc::new(SeaOrmOutboxRepository::new(self.db.clone())),
+        );
+        let mut total = 0;
+        loop {
+            match relay.run_once().await.expect("failed to relay events") {
+                0 => return total,
+                relayed => total += relayed,
+            }
+        }
+    }
+
+    /// Issues a JWT for `user` signed with the test secret.
+    pub fn token_for(&self, user: &users::Model) -> String {
+        AuthService::generate_token(
+            user.id,
+            user.tenant_id,
+            user.email.clone(),
+            user.role,
+            &self.config.jwt_secret,
+            self.config.jwt_expiration_minutes,
+        )
+        .expect("failed to generate token")
+    }
+}
 
-/// Setup a test application router
-/// Note: This requires a test database to be set up
-/// For now, this is a placeholder that will panic
-/// In production, you'd use testcontainers or a test DB
-pub async fn setup_test_app() -> Router {
-    // TODO: Implement actual test database setup
-    // 1. Connect to test database (or use testcontainers)
-    // 2. Run migrations
-    // 3. Create test config
-    // 4. Return router
-
-    // For now, this will fail - tests are marked #[ignore] until implemented
-    panic!("Test database setup not implemented. Use testcontainers or set up test DB connection.")
+impl Drop for TestApp {
+    fn drop(&mut self) {
+        // Drop runs outside of an async context we can await on, so clean up
+        // from a dedicated thread with its own runtime
+        let Some(schema) = self.schema.clone() else {
+            return;
+        };
+        let database_url = self.database_url.clone();
+        let cleanup = std::thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("failed to build cleanup runtime");
+            runtime.block_on(async move {
+                if let Ok(db) = Database::connect(&database_url).await {
+                    db.execute_unprepared(&format!("DROP SCHEMA IF EXISTS \"{}\" CASCADE", schema))
+                        .await
+                        .ok();
+                    db.close().await.ok();
+                }
+            });
+        });
+        cleanup.join().ok();
+    }
 }
 
 /// Get test configuration
@@ -24,8 +209,23 @@ pub fn get_test_config() -> Arc<config::Config> {
         jwt_expiration_minutes: 10,
         server_host: "127.0.0.1".to_string(),
         server_port: 0, // Use 0 for random port in tests
-        environment: "test".to_string(),
+        environment: config::Environment::Test,
         frontend_url: Some("http://localhost:3000".to_string()),
+        cors_allowed_origins: vec!["https://acme.example.com".to_string()],
+        shutdown_drain_timeout_secs: 1,
+        shutdown_pre_drain_delay_secs: 0,
+        password: Default::default(),
+        user_deletion_grace_days: 30,
+        export: config::ExportConfig {
+            dir: std::env::temp_dir().join("template-rust-backend-exports"),
+            link_ttl_minutes: 15,
+        },
+        jobs: Default::default(),
+        scheduler: Default::default(),
+        webhooks: Default::default(),
+        outbox: Default::default(),
+        // Mock identity providers and receivers listen on 127.0.0.1
+        outbound_allow_private_addresses: true,
     })
 }
 
@@ -0,0 +1,174 @@
+//! A local OpenID Connect provider for tests.
+//!
+//! Serves discovery, JWKS and a token endpoint that checks client
+//! credentials, redirect URI and PKCE like a real provider would. There is no
+//! login page: [`MockOidcServer::approve`] stands in for the user signing in
+//! and returns the code the provider would have redirected back with.
+
+use axum::{
+    Form, Json, Router,
+    extract::State,
+    http::{HeaderMap, StatusCode, header},
+    routing::{get, post},
+};
+use base64::Engine;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use jsonwebtoken::{Algorithm, EncodingKey, Header, encode};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+pub const CLIENT_ID: &str = "test-client";
+pub const CLIENT_SECRET: &str = "test-client-secret";
+pub const REDIRECT_URI: &str = "http://localhost:3000/auth/callback";
+
+const SIGNING_KEY: &str = include_str!("../fixtures/oidc_rsa_key.pem");
+const JWKS: &str = include_str!("../fixtures/oidc_jwks.json");
+
+/// What the provider remembers about an issued code.
+struct Grant {
+    nonce: String,
+    code_challenge: String,
+    redirect_uri: String,
+    claims: Value,
+}
+
+#[derive(Clone)]
+struct MockState {
+    issuer: String,
+    grants: Arc<Mutex<HashMap<String, Grant>>>,
+}
+
+pub struct MockOidcServer {
+    pub issuer: String,
+    state: MockState,
+}
+
+impl MockOidcServer {
+    pub async fn start() -> Self {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("failed to bind mock OIDC server");
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+        let state = MockState {
+            issuer: issuer.clone(),
+            grants: Arc::new(Mutex::new(HashMap::new())),
+        };
+
+        let app = Router::new()
+            .route("/.well-known/openid-configuration", get(discovery))
+            .route("/jwks", get(|| async { Json(jwks()) }))
+            .route("/token", post(token))
+            .with_state(state.clone());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.ok();
+        });
+
+        Self { issuer, state }
+    }
+
+    /// Signs the user in at the provider for the given authorization URL and
+    /// returns the authorization code. `claims` are merged into the ID token
+    /// and override the defaults (`iss`, `aud`, `nonce`, `exp`, ...).
+    pub fn approve(&self, authorization_url: &str, claims: Value) -> String {
+        let url = url::Url::parse(authorization_url).expect("valid authorization url");
+        let query: HashMap<String, String> = url.query_pairs().into_owned().collect();
+        assert_eq!(query["response_type"], "code");
+        assert_eq!(query["client_id"], CLIENT_ID);
+        assert_eq!(query["code_challenge_method"], "S256");
+        assert!(query["scope"].split(' ').any(|s| s == "openid"));
+
+        let code = format!("code-{}", uuid::Uuid::now_v7().simple());
+        self.state.grants.lock().unwrap().insert(
+            code.clone(),
+            Grant {
+                nonce: query["nonce"].clone(),
+                code_challenge: query["code_challenge"].clone(),
+                redirect_uri: query["redirect_uri"].clone(),
+                claims,
+            },
+        );
+        code
+    }
+}
+
+/// The keys the provider signs with, as published at `/jwks`.
+pub fn jwks() -> Value {
+    serde_json::from_str(JWKS).expect("valid JWKS fixture")
+}
+
+/// An ID token signed with the provider's key; `claims` are sent as given.
+pub fn sign_id_token(claims: &Value) -> String {
+    let mut header = Header::new(Algorithm::RS256);
+    header.kid = Some("test-key".to_string());
+    let key = EncodingKey::from_rsa_pem(SIGNING_KEY.as_bytes()).expect("valid RSA fixture");
+    encode(&header, claims, &key).expect("failed to sign ID token")
+}
+
+async fn discovery(State(state): State<MockState>) -> Json<Value> {
+    Json(json!({
+        "issuer": state.issuer,
+        "authorization_endpoint": format!("{}/authorize", state.issuer),
+        "token_endpoint": format!("{}/token", state.issuer),
+        "jwks_uri": format!("{}/jwks", state.issuer),
+        "response_types_supported": ["code"],
+        "subject_types_supported": ["public"],
+        "id_token_signing_alg_values_supported": ["RS256"],
+        "code_challenge_methods_supported": ["S256"],
+    }))
+}
+
+async fn token(
+    State(state): State<MockState>,
+    headers: HeaderMap,
+    Form(form): Form<HashMap<String, String>>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let error = |status, code: &str| Err((status, Json(json!({ "error": code }))));
+
+    let expected = format!(
+        "Basic {}",
+        STANDARD.encode(format!("{}:{}", CLIENT_ID, CLIENT_SECRET))
+    );
+    if headers
+        .get(header::AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        != Some(&expected)
+    {
+        return error(StatusCode::UNAUTHORIZED, "invalid_client");
+    }
+    if form.get("grant_type").map(String::as_str) != Some("authorization_code") {
+        return error(StatusCode::BAD_REQUEST, "unsupported_grant_type");
+    }
+
+    // Codes are single use
+    let Some(grant) = form
+        .get("code")
+        .and_then(|code| state.grants.lock().unwrap().remove(code))
+    else {
+        return error(StatusCode::BAD_REQUEST, "invalid_grant");
+    };
+    let verifier = form.get("code_verifier").cloned().unwrap_or_default();
+    let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+    if challenge != grant.code_challenge || form.get("redirect_uri") != Some(&grant.redirect_uri) {
+        return error(StatusCode::BAD_REQUEST, "invalid_grant");
+    }
+
+    let mut claims = json!({
+        "iss": state.issuer,
+        "aud": CLIENT_ID,
+        "nonce": grant.nonce,
+        "iat": chrono::Utc::now().timestamp(),
+        "exp": chrono::Utc::now().timestamp() + 300,
+    });
+    if let Value::Object(extra) = grant.claims {
+        claims.as_object_mut().unwrap().extend(extra);
+    }
+
+    Ok(Json(json!({
+        "access_token": "mock-access-token",
+        "token_type": "Bearer",
+        "expires_in": 300,
+        "id_token": sign_id_token(&claims),
+    })))
+}
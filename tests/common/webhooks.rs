@@ -0,0 +1,97 @@
+//! A local receiver of webhooks for tests.
+//!
+//! Records every request it gets and answers with the statuses it was told
+//! to, in order, then with `200 OK`.
+
+use axum::{
+    Router,
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing::post,
+};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// A request as the receiver got it.
+#[derive(Debug, Clone)]
+pub struct ReceivedWebhook {
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+impl ReceivedWebhook {
+    pub fn header(&self, name: &str) -> &str {
+        self.headers
+            .get(name)
+            .unwrap_or_else(|| panic!("missing header {}", name))
+            .to_str()
+            .unwrap()
+    }
+
+    pub fn json(&self) -> serde_json::Value {
+        serde_json::from_slice(&self.body).expect("webhook body is JSON")
+    }
+}
+
+#[derive(Clone, Default)]
+struct ReceiverState {
+    received: Arc<Mutex<Vec<ReceivedWebhook>>>,
+    statuses: Arc<Mutex<VecDeque<StatusCode>>>,
+}
+
+pub struct WebhookReceiver {
+    pub url: String,
+    state: ReceiverState,
+}
+
+impl WebhookReceiver {
+    pub async fn start() -> Self {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("failed to bind webhook receiver");
+        let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+        let state = ReceiverState::default();
+
+        let app = Router::new()
+            .route("/hooks", post(receive))
+            .with_state(state.clone());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.ok();
+        });
+
+        Self { url, state }
+    }
+
+    /// Answers the next requests with `statuses`, one each.
+    pub fn respond_with(&self, statuses: &[StatusCode]) {
+        self.state
+            .statuses
+            .lock()
+            .unwrap()
+            .extend(statuses.iter().copied());
+    }
+
+    pub fn received(&self) -> Vec<ReceivedWebhook> {
+        self.state.received.lock().unwrap().clone()
+    }
+}
+
+async fn receive(
+    State(state): State<ReceiverState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> (StatusCode, &'static str) {
+    state
+        .received
+        .lock()
+        .unwrap()
+        .push(ReceivedWebhook { headers, body });
+    let status = state
+        .statuses
+        .lock()
+        .unwrap()
+        .pop_front()
+        .unwrap_or(StatusCode::OK);
+    (status, "received")
+}
@@ -0,0 +1,198 @@
+use axum::response::IntoResponse;
+use std::collections::HashSet;
+use template_rust_backend::api_doc::ApiDoc;
+use template_rust_backend::enums::UserRole;
+use template_rust_backend::middleware::auth::Principal;
+use template_rust_backend::services::auth_service::Claims;
+use template_rust_backend::utils::auth::{check_role, check_tenant_access};
+use template_rust_backend::utils::error::{AppError, AuthError, ErrorCode};
+use utoipa::OpenApi;
+use uuid::Uuid;
+
+async fn body_of(error: AppError) -> (u16, serde_json::Value) {
+    let response = error.into_response();
+    let status = response.status().as_u16();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+#[test]
+fn test_error_codes_are_unique_and_serialize_as_strings() {
+    let mut seen = HashSet::new();
+    for code in ErrorCode::ALL {
+        assert!(seen.insert(code.as_str()), "duplicate code {}", code);
+        assert_eq!(serde_json::to_value(code).unwrap(), code.as_str());
+    }
+}
+
+#[tokio::test]
+async fn test_database_error_does_not_leak_details() {
+    let (status, body) = body_of(AppError::Database(sea_orm::DbErr::Custom(
+        "relation \"users\" does not exist".to_string(),
+    )))
+    .await;
+
+    assert_eq!(status, 500);
+    assert_eq!(body["error"], "DATABASE_ERROR");
+    assert_eq!(body["message"], "Database operation failed");
+}
+
+#[tokio::test]
+async fn test_auth_errors_map_to_stable_codes() {
+    let (status, body) = body_of(AuthError::ExpiredToken.into()).await;
+    assert_eq!(status, 401);
+    assert_eq!(body["error"], "TOKEN_EXPIRED");
+
+    let (status, body) = body_of(AppError::AdminRoleRequired).await;
+    assert_eq!(status, 403);
+    assert_eq!(body["error"], "ADMIN_ROLE_REQUIRED");
+    assert_eq!(body["message"], "Admin role required");
+}
+
+#[test]
+fn test_check_helpers_return_app_errors() {
+    let principal: Principal = Claims {
+        user_id: Uuid::now_v7(),
+        tenant_id: Uuid::now_v7(),
+        email: "user@example.com".to_string(),
+        role: UserRole::Regular,
+        exp: 0,
+        locale: None,
+        tenant_bound: false,
+    }
+    .into();
+
+    assert!(check_tenant_access(&principal, principal.tenant_id).is_ok());
+    assert!(matches!(
+        check_tenant_access(&principal, Uuid::now_v7()),
+        Err(AppError::TenantAccessDenied)
+    ));
+    assert!(matches!(
+        check_role(&principal, UserRole::Admin),
+        Err(AppError::AdminRoleRequired)
+    ));
+}
+
+#[test]
+fn test_openapi_lists_error_catalog() {
+    let doc = serde_json::to_value(ApiDoc::openapi()).unwrap();
+    let schema = &doc["components"]["schemas"]["ErrorCode"];
+    let description = schema["description"].as_str().unwrap();
+
+    for code in ErrorCode::ALL {
+        assert!(description.contains(code.as_str()), "{} missing", code);
+    }
+    assert_eq!(
+        schema["enum"].as_array().unwrap().len(),
+        ErrorCode::ALL.len()
+    );
+}
+
+#[test]
+fn test_negotiate_error_format() {
+    use axum::http::{HeaderMap, HeaderValue, header};
+    use template_rust_backend::middleware::ErrorFormat;
+
+    let negotiate = |accept: Option<&'static str>| {
+        let mut headers = HeaderMap::new();
+        if let Some(accept) = accept {
+            headers.insert(header::ACCEPT, HeaderValue::from_static(accept));
+        }
+        ErrorFormat::negotiate(&headers, "/api/me")
+    };
+    let problem = ErrorFormat::Problem {
+        instance: "/api/me".to_string(),
+    };
+
+    assert_eq!(negotiate(None), ErrorFormat::Legacy);
+    assert_eq!(negotiate(Some("*/*")), ErrorFormat::Legacy);
+    assert_eq!(negotiate(Some("application/json")), ErrorFormat::Legacy);
+    assert_eq!(negotiate(Some("application/problem+json")), problem);
+    assert_eq!(
+        negotiate(Some("application/json, application/problem+json")),
+        problem
+    );
+    assert_eq!(
+        negotiate(Some("application/json, application/problem+json;q=0.5")),
+        ErrorFormat::Legacy
+    );
+    assert_eq!(
+        negotiate(Some("application/problem+json;q=0")),
+        ErrorFormat::Legacy
+    );
+
+    // SCIM clients get SCIM errors whatever they accept
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::ACCEPT,
+        HeaderValue::from_static("application/problem+json"),
+    );
+    assert_eq!(
+        ErrorFormat::negotiate(&headers, "/scim/v2/Users"),
+        ErrorFormat::Scim
+    );
+    assert_eq!(
+        ErrorFormat::negotiate(&headers, "/scim/v2x"),
+        ErrorFormat::Problem {
+            instance: "/scim/v2x".to_string()
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_problem_details_body() {
+    use template_rust_backend::middleware::ErrorFormat;
+    use template_rust_backend::utils::error::FieldError;
+
+    let format = ErrorFormat::Problem {
+        instance: "/api/auth/login".to_string(),
+    };
+    let response = format
+        .scope(async {
+            AppError::Validation(vec![FieldError::new(
+                "email",
+                "email",
+                "Invalid email format",
+            )])
+            .into_response()
+        })
+        .await;
+
+    assert_eq!(response.status(), 400);
+    assert_eq!(
+        response.headers()["content-type"],
+        "application/problem+json"
+    );
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "type": "urn:problem-type:validation-error",
+            "title": "Request failed validation",
+            "status": 400,
+            "detail": "email: Invalid email format",
+            "instance": "/api/auth/login",
+            "code": "VALIDATION_ERROR",
+            "errors": [
+                { "field": "email", "code": "email", "message": "Invalid email format" }
+            ]
+        })
+    );
+}
+
+#[test]
+fn test_openapi_offers_problem_json_for_errors() {
+    let doc = serde_json::to_value(ApiDoc::openapi()).unwrap();
+    let content = &doc["paths"]["/api/auth/login"]["post"]["responses"]["400"]["content"];
+
+    assert!(content["application/json"].is_object());
+    assert_eq!(
+        content["application/problem+json"]["schema"]["$ref"],
+        "#/components/schemas/ProblemDetails"
+    );
+}
@@ -0,0 +1,336 @@
+// Domain event and outbox relay tests against mocked repositories; no
+// database required.
+
+pub mod common;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use common::mocks::*;
+use sea_orm::{ActiveValue, TryIntoModel};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use template_rust_backend::enums::{TenantStatus, UserRole, UserStatus};
+use template_rust_backend::events::{DomainEvent, RecordedEvent, Relay, Subscriber};
+use template_rust_backend::jobs::worker::retry_delay;
+use template_rust_backend::models::{memberships, outbox_events, tenants};
+use template_rust_backend::repositories::TaskLock;
+use template_rust_backend::services::tenants_service::{TenantService, TenantsService};
+use template_rust_backend::services::users_service::{UserChanges, UserService, UsersService};
+use template_rust_backend::utils::error::AppError;
+use template_rust_backend::utils::password_hash::PasswordHashing;
+use uuid::Uuid;
+
+type Handled = Arc<Mutex<Vec<(&'static str, RecordedEvent)>>>;
+
+const MAX_ATTEMPTS: i32 = 5;
+
+/// Records the events it is handed, and fails them when `fails`.
+struct Recorder {
+    name: &'static str,
+    fails: bool,
+    handled: Handled,
+}
+
+#[async_trait]
+impl Subscriber for Recorder {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    async fn handle(&self, event: &RecordedEvent) -> Result<(), AppError> {
+        self.handled
+            .lock()
+            .unwrap()
+            .push((self.name, event.clone()));
+        if self.fails {
+            return Err(AppError::ServiceUnavailable);
+        }
+        Ok(())
+    }
+}
+
+fn registered() -> DomainEvent {
+    let user = user_model(Uuid::now_v7(), "jane@example.com", UserRole::Regular);
+    DomainEvent::UserRegistered {
+        user: (&user).into(),
+    }
+}
+
+/// Outbox holding `event`, whose one update is returned as recorded.
+fn outbox_with(
+    event: outbox_events::Model,
+) -> (
+    MockOutboxRepository,
+    Arc<Mutex<Option<outbox_events::Model>>>,
+) {
+    let recorded = Arc::new(Mutex::new(None));
+    let mut outbox = MockOutboxRepository::new();
+    outbox
+        .expect_try_lock()
+        .returning(|| Ok(Some(TaskLock::local())));
+    outbox
+        .expect_due()
+        .withf(|now, limit| (Utc::now() - now.to_utc()).num_seconds().abs() < 60 && *limit > 0)
+        .times(1)
+        .returning(move |_, _| Ok(vec![event.clone()]));
+    let sink = recorded.clone();
+    outbox.expect_update().times(1).returning(move |event| {
+        let event = event.try_into_model().unwrap();
+        *sink.lock().unwrap() = Some(event.clone());
+        Ok(event)
+    });
+    (outbox, recorded)
+}
+
+fn relay(outbox: MockOutboxRepository, subscribers: &[(&'static str, bool)]) -> (Relay, Handled) {
+    let handled: Handled = Default::default();
+    let mut relay = Relay::new(Arc::new(outbox), Duration::from_millis(10), MAX_ATTEMPTS);
+    for (name, fails) in subscribers {
+        relay = relay.subscribe(Recorder {
+            name,
+            fails: *fails,
+            handled: handled.clone(),
+        });
+    }
+    (relay, handled)
+}
+
+#[test]
+fn test_events_are_stored_tagged_with_their_type() {
+    let user = user_model(Uuid::now_v7(), "jane@example.com", UserRole::Admin);
+    let event = DomainEvent::UserRoleChanged {
+        user: (&user).into(),
+        previous_role: UserRole::Regular,
+    };
+
+    let payload = serde_json::to_value(&event).unwrap();
+    assert_eq!(payload["type"], "UserRoleChanged");
+    assert_eq!(payload["type"], event.name());
+    assert_eq!(payload["data"]["user"]["id"], user.id.to_string());
+    assert_eq!(payload["data"]["user"]["role"], "Admin");
+    assert_eq!(payload["data"]["previous_role"], "Regular");
+    assert_eq!(
+        serde_json::from_value::<DomainEvent>(payload).unwrap(),
+        event
+    );
+    assert_eq!(event.aggregate(), ("user", user.id));
+    assert_eq!(event.tenant_id(), user.tenant_id);
+
+    let tenant_id = Uuid::now_v7();
+    let event = DomainEvent::TenantStatusChanged {
+        tenant_id,
+        status: TenantStatus::Inactive,
+        previous_status: TenantStatus::Active,
+    };
+    assert_eq!(event.aggregate(), ("tenant", tenant_id));
+}
+
+#[test]
+fn test_member_changes_lists_role_then_status() {
+    let before = user_model(Uuid::now_v7(), "jane@example.com", UserRole::Regular);
+    assert!(DomainEvent::member_changes(&before, &before).is_empty());
+
+    let mut after = before.clone();
+    after.role = UserRole::Admin;
+    after.status = UserStatus::Inactive;
+    let changes = DomainEvent::member_changes(&before, &after);
+    assert_eq!(
+        changes,
+        [
+            DomainEvent::UserRoleChanged {
+                user: (&after).into(),
+                previous_role: UserRole::Regular,
+            },
+            DomainEvent::UserStatusChanged {
+                user: (&after).into(),
+                previous_status: UserStatus::Active,
+            },
+        ]
+    );
+}
+
+#[test]
+#[should_panic(expected = "subscriber 'audit' added twice")]
+fn test_subscribing_a_name_twice_panics() {
+    let _ = relay(
+        MockOutboxRepository::new(),
+        &[("audit", false), ("audit", false)],
+    );
+}
+
+#[tokio::test]
+async fn test_relay_marks_handled_event_dispatched() {
+    let event = outbox_event_model(&registered());
+    let (outbox, recorded) = outbox_with(event.clone());
+    let (relay, handled) = relay(outbox, &[("audit", false), ("mail", false)]);
+
+    assert_eq!(relay.subscribers(), ["audit", "mail"]);
+    assert_eq!(relay.run_once().await.unwrap(), 1);
+
+    let handled = handled.lock().unwrap();
+    let names: Vec<_> = handled.iter().map(|(name, _)| *name).collect();
+    assert_eq!(names, ["audit", "mail"]);
+    assert_eq!(handled[0].1.id, event.id);
+    assert_eq!(handled[0].1.event.name(), "UserRegistered");
+
+    let recorded = recorded.lock().unwrap().take().unwrap();
+    assert!(recorded.dispatched_at.is_some());
+    assert_eq!(recorded.attempts, 1);
+    assert_eq!(recorded.next_attempt_at, None);
+    assert_eq!(recorded.last_error, None);
+}
+
+#[tokio::test]
+async fn test_relay_retries_failed_event_with_backoff() {
+    let before = Utc::now();
+    let mut event = outbox_event_model(&registered());
+    event.attempts = 2;
+    let (outbox, recorded) = outbox_with(event);
+    let (relay, handled) = relay(outbox, &[("audit", true), ("mail", false)]);
+
+    assert_eq!(relay.run_once().await.unwrap(), 1);
+
+    // Later subscribers get the event with the retry
+    assert_eq!(handled.lock().unwrap().len(), 1);
+    let recorded = recorded.lock().unwrap().take().unwrap();
+    assert_eq!(recorded.dispatched_at, None);
+    assert_eq!(recorded.attempts, 3);
+    let retry_at = recorded.next_attempt_at.unwrap().to_utc();
+    assert!(retry_at >= before + retry_delay(3));
+    assert!(retry_at <= Utc::now() + retry_delay(3));
+    assert!(recorded.last_error.unwrap().starts_with("audit: "));
+    assert_eq!(recorded.dead_at, None);
+}
+
+#[tokio::test]
+async fn test_relay_gives_up_on_event_after_max_attempts() {
+    let mut event = outbox_event_model(&registered());
+    event.attempts = MAX_ATTEMPTS - 1;
+    let (outbox, recorded) = outbox_with(event);
+    let (relay, _) = relay(outbox, &[("audit", true)]);
+
+    assert_eq!(relay.run_once().await.unwrap(), 1);
+
+    let recorded = recorded.lock().unwrap().take().unwrap();
+    assert!(recorded.dead_at.is_some());
+    assert_eq!(recorded.dispatched_at, None);
+    assert_eq!(recorded.attempts, MAX_ATTEMPTS);
+    assert_eq!(recorded.next_attempt_at, None);
+    assert!(recorded.last_error.unwrap().starts_with("audit: "));
+}
+
+#[tokio::test]
+async fn test_relay_retries_event_it_cannot_read() {
+    let mut event = outbox_event_model(&registered());
+    event.payload = serde_json::json!({ "type": "UserExploded", "data": {} });
+    let (outbox, recorded) = outbox_with(event);
+    let (relay, handled) = relay(outbox, &[("audit", false)]);
+
+    assert_eq!(relay.run_once().await.unwrap(), 1);
+
+    assert!(handled.lock().unwrap().is_empty());
+    let recorded = recorded.lock().unwrap().take().unwrap();
+    assert_eq!(recorded.dispatched_at, None);
+    assert!(recorded.next_attempt_at.is_some());
+    assert!(
+        recorded
+            .last_error
+            .unwrap()
+            .starts_with("Invalid payload: ")
+    );
+}
+
+#[tokio::test]
+async fn test_relay_waits_while_another_replica_holds_the_lock() {
+    let mut outbox = MockOutboxRepository::new();
+    outbox.expect_try_lock().times(1).returning(|| Ok(None));
+    outbox.expect_due().never();
+    let (relay, handled) = relay(outbox, &[("audit", false)]);
+
+    assert_eq!(relay.run_once().await.unwrap(), 0);
+    assert!(handled.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_member_update_records_each_change() {
+    let user = user_model(Uuid::now_v7(), "jane@example.com", UserRole::Regular);
+    let mut repo = MockUserRepository::new();
+    let found = user.clone();
+    repo.expect_find_in_tenant()
+        .returning(move |_, _| Ok(Some(found.clone())));
+    let mut memberships = MockMembershipRepository::new();
+    let membership = membership_model(&user);
+    memberships
+        .expect_find()
+        .returning(move |_, _| Ok(Some(membership.clone())));
+    let user_id = user.id;
+    memberships
+        .expect_update()
+        .withf(move |_, events| {
+            matches!(
+                events.as_slice(),
+                [
+                    DomainEvent::UserRoleChanged { user, previous_role: UserRole::Regular },
+                    DomainEvent::UserStatusChanged { previous_status: UserStatus::Active, .. },
+                ] if user.id == user_id
+                    && user.role == UserRole::Admin
+                    && user.status == UserStatus::Inactive
+            )
+        })
+        .times(1)
+        .returning(|update: memberships::ActiveModel, _| Ok(update.try_into_model().unwrap()));
+
+    let service = UsersService::new(
+        Arc::new(repo),
+        Arc::new(memberships),
+        Arc::new(any_password()),
+        PasswordHashing::default(),
+        chrono::Duration::days(30),
+    );
+    let changes = UserChanges {
+        role: Some(UserRole::Admin),
+        status: Some(UserStatus::Inactive),
+        ..Default::default()
+    };
+    service
+        .update(user.tenant_id, user.id, changes)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_tenant_status_change_is_recorded_once() {
+    let tenant = tenant_model("Acme");
+    let tenant_id = tenant.id;
+    let mut repo = MockTenantRepository::new();
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(tenant.clone())));
+    repo.expect_update()
+        .withf(move |_, events| {
+            events
+                == &[DomainEvent::TenantStatusChanged {
+                    tenant_id,
+                    status: TenantStatus::Inactive,
+                    previous_status: TenantStatus::Active,
+                }]
+        })
+        .times(1)
+        .returning(|update: tenants::ActiveModel, _| Ok(update.try_into_model().unwrap()));
+    // Setting the status it already has is not a change
+    repo.expect_update()
+        .withf(|update, events| {
+            update.status == ActiveValue::Set(TenantStatus::Active) && events.is_empty()
+        })
+        .times(1)
+        .returning(|update: tenants::ActiveModel, _| Ok(update.try_into_model().unwrap()));
+
+    let service = TenantsService::new(Arc::new(repo));
+    service
+        .set_status(tenant_id, TenantStatus::Inactive)
+        .await
+        .unwrap();
+    service
+        .set_status(tenant_id, TenantStatus::Active)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,568 @@
+// Handler tests against mocked services; no database required.
+
+pub mod common;
+
+use axum_test::TestServer;
+use common::mocks::*;
+use common::{get_test_bearer_token, get_test_config};
+use mockall::predicate::eq;
+use std::sync::Arc;
+use template_rust_backend::enums::{ApiScope, UserRole, WebhookEvent};
+use template_rust_backend::middleware::auth::{Principal, PrincipalKind};
+use template_rust_backend::models::users;
+use template_rust_backend::routes::create_router;
+use template_rust_backend::services::auth_service::AuthService;
+use template_rust_backend::utils::error::AppError;
+use uuid::Uuid;
+
+fn server(
+    users: MockUserService,
+    tenants: MockTenantService,
+    auth: MockAuthenticationService,
+) -> TestServer {
+    TestServer::new(create_router(mock_state(users, tenants, auth))).unwrap()
+}
+
+fn token_for(user: &users::Model) -> String {
+    let config = get_test_config();
+    AuthService::generate_token(
+        user.id,
+        user.tenant_id,
+        user.email.clone(),
+        user.role,
+        &config.jwt_secret,
+        config.jwt_expiration_minutes,
+    )
+    .unwrap()
+}
+
+/// Lets `user` through the tenant extractors as a member of its tenant.
+fn expect_membership(users: &mut MockUserService, user: &users::Model) {
+    let membership = membership_model(user);
+    users
+        .expect_find_membership()
+        .with(eq(user.tenant_id), eq(user.id))
+        .returning(move |_, _| Ok(Some(membership.clone())));
+}
+
+#[tokio::test]
+async fn test_me_returns_user_from_service() {
+    let user = user_model(Uuid::now_v7(), "me@example.com", UserRole::Regular);
+    let mut users = MockUserService::new();
+    let returned = user.clone();
+    users
+        .expect_get_in_tenant()
+        .with(eq(user.tenant_id), eq(user.id))
+        .times(1)
+        .returning(move |_, _| Ok(returned.clone()));
+
+    let server = server(
+        users,
+        MockTenantService::new(),
+        MockAuthenticationService::new(),
+    );
+    let response = server
+        .get("/api/me")
+        .authorization_bearer(token_for(&user))
+        .await;
+
+    response.assert_status_ok();
+    response.assert_json_contains(&serde_json::json!({
+        "id": user.id,
+        "email": "me@example.com"
+    }));
+}
+
+#[tokio::test]
+async fn test_get_users_is_scoped_to_path_tenant() {
+    let admin = user_model(Uuid::now_v7(), "admin@example.com", UserRole::Admin);
+    let tenant_id = admin.tenant_id;
+    let mut users = MockUserService::new();
+    expect_membership(&mut users, &admin);
+    let listed = vec![admin.clone()];
+    users
+        .expect_list_by_tenant()
+        .with(eq(tenant_id))
+        .times(1)
+        .returning(move |_| Ok(listed.clone()));
+
+    let server = server(
+        users,
+        MockTenantService::new(),
+        MockAuthenticationService::new(),
+    );
+    let response = server
+        .get(&format!("/api/tenants/{}/users", tenant_id))
+        .authorization_bearer(token_for(&admin))
+        .await;
+
+    response.assert_status_ok();
+    let body: Vec<serde_json::Value> = response.json();
+    assert_eq!(body.len(), 1);
+}
+
+#[tokio::test]
+async fn test_get_users_rejects_regular_user_before_service() {
+    let user = user_model(Uuid::now_v7(), "user@example.com", UserRole::Regular);
+
+    // Only the membership lookup: any other service call would panic
+    let mut users = MockUserService::new();
+    expect_membership(&mut users, &user);
+    let server = server(
+        users,
+        MockTenantService::new(),
+        MockAuthenticationService::new(),
+    );
+    let response = server
+        .get(&format!("/api/tenants/{}/users", user.tenant_id))
+        .authorization_bearer(token_for(&user))
+        .await;
+
+    response.assert_status_forbidden();
+    response.assert_json_contains(&serde_json::json!({ "error": "ADMIN_ROLE_REQUIRED" }));
+}
+
+#[tokio::test]
+async fn test_change_role_passes_path_ids() {
+    let admin = user_model(Uuid::now_v7(), "admin@example.com", UserRole::Admin);
+    let target = user_model(admin.tenant_id, "user@example.com", UserRole::Admin);
+    let mut users = MockUserService::new();
+    expect_membership(&mut users, &admin);
+    let changed = target.clone();
+    users
+        .expect_change_role()
+        .with(eq(target.id), eq(admin.tenant_id))
+        .times(1)
+        .returning(move |_, _| Ok(changed.clone()));
+
+    let server = server(
+        users,
+        MockTenantService::new(),
+        MockAuthenticationService::new(),
+    );
+    let response = server
+        .put(&format!(
+            "/api/tenants/{}/users/{}/change-role",
+            admin.tenant_id, target.id
+        ))
+        .authorization_bearer(token_for(&admin))
+        .await;
+
+    response.assert_status_ok();
+    response.assert_json_contains(&serde_json::json!({ "id": target.id }));
+}
+
+#[tokio::test]
+async fn test_tenant_routes_use_membership_of_path_tenant() {
+    // The token is for the user's own tenant, where the user is not an admin
+    let user = user_model(Uuid::now_v7(), "consultant@example.com", UserRole::Regular);
+    let other_tenant = Uuid::now_v7();
+    let mut users = MockUserService::new();
+    let mut membership = membership_model(&user);
+    membership.tenant_id = other_tenant;
+    membership.role = UserRole::Admin;
+    users
+        .expect_find_membership()
+        .returning(move |tenant_id, _| Ok((tenant_id == other_tenant).then(|| membership.clone())));
+    users
+        .expect_list_by_tenant()
+        .with(eq(other_tenant))
+        .times(1)
+        .returning(|_| Ok(vec![]));
+
+    let server = server(
+        users,
+        MockTenantService::new(),
+        MockAuthenticationService::new(),
+    );
+
+    server
+        .get(&format!("/api/tenants/{}/users", other_tenant))
+        .authorization_bearer(token_for(&user))
+        .await
+        .assert_status_ok();
+
+    let response = server
+        .get(&format!("/api/tenants/{}/users", Uuid::now_v7()))
+        .authorization_bearer(token_for(&user))
+        .await;
+    response.assert_status_forbidden();
+    response.assert_json_contains(&serde_json::json!({ "error": "TENANT_ACCESS_DENIED" }));
+}
+
+#[tokio::test]
+async fn test_list_tenants() {
+    let mut tenants = MockTenantService::new();
+    tenants
+        .expect_list_all()
+        .times(1)
+        .returning(|| Ok(vec![tenant_model("Acme"), tenant_model("Globex")]));
+
+    let server = server(
+        MockUserService::new(),
+        tenants,
+        MockAuthenticationService::new(),
+    );
+    let response = server.get("/api/tenants").await;
+
+    response.assert_status_ok();
+    let body: Vec<serde_json::Value> = response.json();
+    assert_eq!(body.len(), 2);
+}
+
+#[tokio::test]
+async fn test_get_tenant_not_found() {
+    let user = user_model(Uuid::now_v7(), "user@example.com", UserRole::Regular);
+    let mut tenants = MockTenantService::new();
+    tenants
+        .expect_get_by_id()
+        .with(eq(user.tenant_id))
+        .returning(|_| Err(AppError::TenantNotFound));
+    let mut users = MockUserService::new();
+    expect_membership(&mut users, &user);
+
+    let server = server(users, tenants, MockAuthenticationService::new());
+    let response = server
+        .get(&format!("/api/tenants/{}", user.tenant_id))
+        .authorization_bearer(token_for(&user))
+        .await;
+
+    response.assert_status_not_found();
+    response.assert_json_contains(&serde_json::json!({ "error": "TENANT_NOT_FOUND" }));
+}
+
+#[tokio::test]
+async fn test_login_maps_service_error() {
+    let mut auth = MockAuthenticationService::new();
+    auth.expect_login()
+        .withf(|req| req.email == "user@example.com")
+        .times(1)
+        .returning(|_| Err(AppError::InvalidCredentials));
+
+    let server = server(MockUserService::new(), MockTenantService::new(), auth);
+    let response = server
+        .post("/api/auth/login")
+        .authorization_bearer(get_test_bearer_token())
+        .json(&serde_json::json!({
+            "email": "user@example.com",
+            "password": "wrongpassword"
+        }))
+        .await;
+
+    response.assert_status_unauthorized();
+    response.assert_json_contains(&serde_json::json!({ "error": "INVALID_CREDENTIALS" }));
+}
+
+#[tokio::test]
+async fn test_login_validation_skips_service() {
+    let server = server(
+        MockUserService::new(),
+        MockTenantService::new(),
+        MockAuthenticationService::new(),
+    );
+    let response = server
+        .post("/api/auth/login")
+        .authorization_bearer(get_test_bearer_token())
+        .json(&serde_json::json!({
+            "email": "invalid-email",
+            "password": ""
+        }))
+        .await;
+
+    response.assert_status_bad_request();
+    response.assert_json_contains(&serde_json::json!({ "error": "VALIDATION_ERROR" }));
+}
+
+#[tokio::test]
+async fn test_malformed_json_body_is_typed_error() {
+    let server = server(
+        MockUserService::new(),
+        MockTenantService::new(),
+        MockAuthenticationService::new(),
+    );
+    let response = server
+        .post("/api/auth/login")
+        .authorization_bearer(get_test_bearer_token())
+        .content_type("application/json")
+        .text("{not json")
+        .await;
+
+    response.assert_status_bad_request();
+    response.assert_json_contains(&serde_json::json!({ "error": "INVALID_REQUEST_BODY" }));
+}
+
+#[tokio::test]
+async fn test_invalid_tenant_id_in_path() {
+    let user = user_model(Uuid::now_v7(), "user@example.com", UserRole::Regular);
+    let server = server(
+        MockUserService::new(),
+        MockTenantService::new(),
+        MockAuthenticationService::new(),
+    );
+    let response = server
+        .get(&format!("/api/tenants/not-a-uuid/users/{}", user.id))
+        .authorization_bearer(token_for(&user))
+        .await;
+
+    response.assert_status_bad_request();
+    response.assert_json_contains(&serde_json::json!({ "error": "INVALID_TENANT_ID" }));
+}
+
+#[tokio::test]
+async fn test_invalid_user_id_in_path() {
+    let user = user_model(Uuid::now_v7(), "user@example.com", UserRole::Regular);
+    let mut users = MockUserService::new();
+    expect_membership(&mut users, &user);
+    let server = server(
+        users,
+        MockTenantService::new(),
+        MockAuthenticationService::new(),
+    );
+    let response = server
+        .get(&format!("/api/tenants/{}/users/not-a-uuid", user.tenant_id))
+        .authorization_bearer(token_for(&user))
+        .await;
+
+    response.assert_status_bad_request();
+    response.assert_json_contains(&serde_json::json!({ "error": "INVALID_PATH_PARAMETER" }));
+}
+
+#[tokio::test]
+async fn test_cors_allows_configured_origins_only() {
+    let server = server(
+        MockUserService::new(),
+        MockTenantService::new(),
+        MockAuthenticationService::new(),
+    );
+    let allowed = |response: &axum_test::TestResponse| {
+        response
+            .maybe_header("access-control-allow-origin")
+            .map(|value| value.to_str().unwrap().to_string())
+    };
+
+    // FRONTEND_URL, then an entry of CORS_ALLOWED_ORIGINS
+    for origin in ["http://localhost:3000", "https://acme.example.com"] {
+        let response = server
+            .method(axum::http::Method::OPTIONS, "/api/me")
+            .add_header("origin", origin)
+            .add_header("access-control-request-method", "GET")
+            .await;
+        assert_eq!(allowed(&response).as_deref(), Some(origin));
+    }
+
+    let response = server
+        .method(axum::http::Method::OPTIONS, "/api/me")
+        .add_header("origin", "https://evil.example.com")
+        .add_header("access-control-request-method", "GET")
+        .await;
+    assert_eq!(allowed(&response), None);
+}
+
+#[tokio::test]
+async fn test_validation_errors_as_problem_json() {
+    let server = server(
+        MockUserService::new(),
+        MockTenantService::new(),
+        MockAuthenticationService::new(),
+    );
+    let response = server
+        .post("/api/auth/register")
+        .authorization_bearer(get_test_bearer_token())
+        .add_header("accept", "application/problem+json")
+        .json(&serde_json::json!({
+            "tenant_id": Uuid::now_v7(),
+            "email": "invalid-email",
+            "password": "short"
+        }))
+        .await;
+
+    response.assert_status_bad_request();
+    assert_eq!(response.header("content-type"), "application/problem+json");
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["instance"], "/api/auth/register");
+    assert_eq!(body["errors"][0]["field"], "email");
+    assert_eq!(body["errors"][1]["field"], "password");
+    assert_eq!(body["errors"][1]["code"], "length");
+    assert_eq!(body["errors"][1]["params"]["min"], 8);
+    assert!(body["errors"][1]["params"].get("value").is_none());
+}
+
+/// A server whose API key service accepts `sk_test_key` as a key of the
+/// given tenant with the given scopes.
+fn api_key_server(users: MockUserService, tenant_id: Uuid, scopes: Vec<ApiScope>) -> TestServer {
+    let mut api_keys = MockApiKeyService::new();
+    api_keys
+        .expect_authenticate()
+        .withf(|secret| secret == "sk_test_key")
+        .returning(move |_| {
+            Ok(Principal {
+                tenant_id,
+                role: UserRole::Admin,
+                kind: PrincipalKind::ServiceAccount {
+                    service_account_id: Uuid::now_v7(),
+                    api_key_id: Uuid::now_v7(),
+                    scopes: scopes.clone(),
+                },
+            })
+        });
+    let mut state = mock_state(
+        users,
+        MockTenantService::new(),
+        MockAuthenticationService::new(),
+    );
+    state.api_keys = Arc::new(api_keys);
+    TestServer::new(create_router(state)).unwrap()
+}
+
+#[tokio::test]
+async fn test_api_key_accepted_in_header_and_bearer() {
+    let tenant_id = Uuid::now_v7();
+    let mut users = MockUserService::new();
+    users
+        .expect_list_by_tenant()
+        .with(eq(tenant_id))
+        .times(2)
+        .returning(|_| Ok(vec![]));
+
+    let server = api_key_server(users, tenant_id, vec![ApiScope::UsersRead]);
+    let path = format!("/api/tenants/{}/users", tenant_id);
+
+    server
+        .get(&path)
+        .add_header("x-api-key", "sk_test_key")
+        .await
+        .assert_status_ok();
+    server
+        .get(&path)
+        .authorization_bearer("sk_test_key")
+        .await
+        .assert_status_ok();
+}
+
+#[tokio::test]
+async fn test_api_key_without_scope_is_rejected() {
+    let tenant_id = Uuid::now_v7();
+
+    // No expectations: the change must not reach the service
+    let server = api_key_server(MockUserService::new(), tenant_id, vec![ApiScope::UsersRead]);
+    let response = server
+        .put(&format!(
+            "/api/tenants/{}/users/{}/change-role",
+            tenant_id,
+            Uuid::now_v7()
+        ))
+        .add_header("x-api-key", "sk_test_key")
+        .await;
+
+    response.assert_status_forbidden();
+    response.assert_json_contains(&serde_json::json!({ "error": "INSUFFICIENT_SCOPE" }));
+}
+
+#[tokio::test]
+async fn test_api_key_is_bound_to_its_tenant() {
+    let server = api_key_server(
+        MockUserService::new(),
+        Uuid::now_v7(),
+        ApiScope::ALL.to_vec(),
+    );
+    let response = server
+        .get(&format!("/api/tenants/{}/users", Uuid::now_v7()))
+        .add_header("x-api-key", "sk_test_key")
+        .await;
+
+    response.assert_status_forbidden();
+    response.assert_json_contains(&serde_json::json!({ "error": "TENANT_ACCESS_DENIED" }));
+}
+
+#[tokio::test]
+async fn test_task_runs_pass_query_to_service() {
+    let mut maintenance = MockMaintenanceService::new();
+    maintenance
+        .expect_list_runs()
+        .with(eq(Some("purge_auth_artifacts".to_string())), eq(Some(5)))
+        .times(1)
+        .returning(|task, _| Ok(vec![task_run_model(&task.unwrap(), 2)]));
+    let mut state = mock_state(
+        MockUserService::new(),
+        MockTenantService::new(),
+        MockAuthenticationService::new(),
+    );
+    state.maintenance = Arc::new(maintenance);
+    let server = TestServer::new(create_router(state)).unwrap();
+
+    let response = server
+        .get("/api/tasks/runs?task=purge_auth_artifacts&limit=5")
+        .authorization_bearer(get_test_bearer_token())
+        .await;
+
+    response.assert_status_ok();
+    let body: Vec<serde_json::Value> = response.json();
+    assert_eq!(body.len(), 1);
+    assert_eq!(body[0]["status"], "succeeded");
+    assert_eq!(body[0]["affected"], 2);
+}
+
+#[tokio::test]
+async fn test_admin_routes_use_the_current_membership_role() {
+    // The token still says Admin, but the membership was demoted since
+    let admin = user_model(Uuid::now_v7(), "admin@example.com", UserRole::Admin);
+    let mut membership = membership_model(&admin);
+    membership.role = UserRole::Regular;
+    let mut users = MockUserService::new();
+    users
+        .expect_find_membership()
+        .with(eq(admin.tenant_id), eq(admin.id))
+        .returning(move |_, _| Ok(Some(membership.clone())));
+    let state = mock_state(
+        users,
+        MockTenantService::new(),
+        MockAuthenticationService::new(),
+    );
+    let server = TestServer::new(create_router(state)).unwrap();
+
+    let response = server
+        .get("/scim/v2/Users")
+        .authorization_bearer(token_for(&admin))
+        .await;
+    response.assert_status_forbidden();
+}
+
+#[tokio::test]
+async fn test_redeliver_webhook_passes_path_ids() {
+    let admin = user_model(Uuid::now_v7(), "admin@example.com", UserRole::Admin);
+    let endpoint = webhook_endpoint_model(
+        admin.tenant_id,
+        "https://hooks.example.com",
+        &[WebhookEvent::UserCreated],
+    );
+    let original = webhook_delivery_model(&endpoint, WebhookEvent::UserCreated);
+    let redelivery = webhook_delivery_model(&endpoint, WebhookEvent::UserCreated);
+    let mut webhooks = MockWebhookService::new();
+    webhooks
+        .expect_redeliver()
+        .with(eq(admin.tenant_id), eq(endpoint.id), eq(original.id))
+        .times(1)
+        .returning(move |_, _, _| Ok(redelivery.clone()));
+    let mut users = MockUserService::new();
+    expect_membership(&mut users, &admin);
+    let mut state = mock_state(
+        users,
+        MockTenantService::new(),
+        MockAuthenticationService::new(),
+    );
+    state.webhooks = Arc::new(webhooks);
+    let server = TestServer::new(create_router(state)).unwrap();
+
+    let response = server
+        .post(&format!(
+            "/api/tenants/{}/webhooks/{}/deliveries/{}/redeliver",
+            admin.tenant_id, endpoint.id, original.id
+        ))
+        .authorization_bearer(token_for(&admin))
+        .await;
+
+    response.assert_status(axum::http::StatusCode::ACCEPTED);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["status"], "pending");
+    assert_eq!(body["event"], "user.created");
+}
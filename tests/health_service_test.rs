@@ -0,0 +1,125 @@
+use async_trait::async_trait;
+use axum::{Router, routing::get};
+use axum_test::TestServer;
+use std::sync::Arc;
+use std::time::Duration;
+use template_rust_backend::handlers::health;
+use template_rust_backend::services::health_service::{
+    CheckStatus, HealthCheck, HealthRegistry, HealthService,
+};
+
+struct StaticCheck {
+    name: &'static str,
+    result: Result<(), String>,
+}
+
+#[async_trait]
+impl HealthCheck for StaticCheck {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    async fn check(&self) -> Result<(), String> {
+        self.result.clone()
+    }
+}
+
+struct SlowCheck;
+
+#[async_trait]
+impl HealthCheck for SlowCheck {
+    fn name(&self) -> &'static str {
+        "slow"
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_millis(20)
+    }
+
+    async fn check(&self) -> Result<(), String> {
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        Ok(())
+    }
+}
+
+fn up(name: &'static str) -> StaticCheck {
+    StaticCheck {
+        name,
+        result: Ok(()),
+    }
+}
+
+#[tokio::test]
+async fn test_registry_ready_when_all_checks_pass() {
+    let registry = HealthRegistry::new().register(up("a")).register(up("b"));
+
+    let report = registry.run().await;
+    assert!(report.ready);
+    assert_eq!(report.checks.len(), 2);
+    assert!(report.checks.iter().all(|c| c.status == CheckStatus::Up));
+}
+
+#[tokio::test]
+async fn test_registry_not_ready_when_check_fails() {
+    let registry = HealthRegistry::new()
+        .register(up("a"))
+        .register(StaticCheck {
+            name: "mailer",
+            result: Err("connection refused".to_string()),
+        });
+
+    let report = registry.run().await;
+    assert!(!report.ready);
+    let mailer = report.checks.iter().find(|c| c.name == "mailer").unwrap();
+    assert_eq!(mailer.status, CheckStatus::Down);
+    assert_eq!(mailer.error.as_deref(), Some("connection refused"));
+}
+
+#[tokio::test]
+async fn test_registry_applies_per_check_timeout() {
+    let registry = HealthRegistry::new().register(SlowCheck);
+
+    let report = registry.run().await;
+    assert!(!report.ready);
+    assert!(
+        report.checks[0]
+            .error
+            .as_ref()
+            .unwrap()
+            .contains("Timed out")
+    );
+}
+
+#[tokio::test]
+async fn test_readiness_fails_during_shutdown() {
+    let registry = Arc::new(HealthRegistry::new().register(up("a")));
+    let app = Router::new()
+        .route("/health/live", get(health::liveness))
+        .route("/health/ready", get(health::readiness))
+        .with_state(registry.clone());
+    let server = TestServer::new(app).unwrap();
+
+    server.get("/health/ready").await.assert_status_ok();
+
+    registry.begin_shutdown();
+
+    let response = server.get("/health/ready").await;
+    response.assert_status_service_unavailable();
+    response.assert_json_contains(&serde_json::json!({ "shutting_down": true }));
+
+    server.get("/health/live").await.assert_status_ok();
+}
+
+#[test]
+fn test_pool_stats_without_pool() {
+    let db = sea_orm::DatabaseConnection::default();
+    assert!(HealthService::pool_stats(&db).is_none());
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_pool_stats_sqlite() {
+    let db = sea_orm::Database::connect("sqlite::memory:").await.unwrap();
+    let stats = HealthService::pool_stats(&db).unwrap();
+    assert!(stats.size >= 1);
+}
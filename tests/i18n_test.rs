@@ -0,0 +1,159 @@
+// Message catalogs and locale resolution.
+
+pub mod common;
+
+use axum::http::{HeaderMap, HeaderValue, header};
+use axum_test::TestServer;
+use common::mocks::*;
+use common::{get_test_bearer_token, get_test_config};
+use std::sync::Arc;
+use template_rust_backend::enums::{Locale, UserRole};
+use template_rust_backend::i18n;
+use template_rust_backend::middleware::auth::AuthState;
+use template_rust_backend::middleware::locale;
+use template_rust_backend::routes::create_router;
+use template_rust_backend::services::auth_service::{AuthService, Claims};
+use template_rust_backend::utils::error::{AppError, ErrorCode};
+use uuid::Uuid;
+
+/// A server where nobody is a member of any tenant.
+fn server() -> TestServer {
+    let mut users = MockUserService::new();
+    users.expect_find_membership().returning(|_, _| Ok(None));
+    TestServer::new(create_router(mock_state(
+        users,
+        MockTenantService::new(),
+        MockAuthenticationService::new(),
+    )))
+    .unwrap()
+}
+
+fn token_with_locale(role: UserRole, locale: Option<Locale>) -> String {
+    let config = get_test_config();
+    let claims = Claims {
+        user_id: Uuid::now_v7(),
+        tenant_id: Uuid::now_v7(),
+        email: "user@example.com".to_string(),
+        role,
+        exp: chrono::Utc::now().timestamp() + 600,
+        locale,
+        tenant_bound: false,
+    };
+    AuthService::sign(&claims, &config.jwt_secret).unwrap()
+}
+
+#[test]
+fn test_catalogs_cover_every_error_code_and_match_english() {
+    let english = i18n::keys(Locale::En);
+    for code in ErrorCode::ALL {
+        assert!(
+            english.contains(&format!("errors.{}", code).as_str()),
+            "en is missing {}",
+            code
+        );
+    }
+    for locale in [Locale::Es, Locale::Pt] {
+        assert_eq!(
+            i18n::keys(locale),
+            english,
+            "{} catalog keys differ",
+            locale
+        );
+    }
+}
+
+#[test]
+fn test_negotiate_accept_language() {
+    assert_eq!(i18n::negotiate("es"), Some(Locale::Es));
+    assert_eq!(i18n::negotiate("pt-BR,pt;q=0.9,en;q=0.8"), Some(Locale::Pt));
+    assert_eq!(i18n::negotiate("fr-FR, es;q=0.5"), Some(Locale::Es));
+    assert_eq!(i18n::negotiate("en;q=0.2, es;q=0.7"), Some(Locale::Es));
+    assert_eq!(i18n::negotiate("es;q=0, de"), None);
+    assert_eq!(i18n::negotiate("*"), None);
+}
+
+#[test]
+fn test_resolve_falls_back_from_header_to_token_to_english() {
+    let auth_state = Arc::new(AuthState {
+        secret: get_test_config().jwt_secret.clone(),
+        bearer_token: get_test_bearer_token(),
+    });
+    let token = token_with_locale(UserRole::Regular, Some(Locale::Pt));
+
+    let mut headers = HeaderMap::new();
+    assert_eq!(locale::resolve(&headers, Some(&auth_state)), Locale::En);
+
+    headers.insert(
+        header::AUTHORIZATION,
+        HeaderValue::from_str(&format!("Bearer {}", token)).unwrap(),
+    );
+    assert_eq!(locale::resolve(&headers, Some(&auth_state)), Locale::Pt);
+
+    headers.insert(header::ACCEPT_LANGUAGE, HeaderValue::from_static("de, es"));
+    assert_eq!(locale::resolve(&headers, Some(&auth_state)), Locale::Es);
+
+    // An unsupported language does not hide the token's preference
+    headers.insert(header::ACCEPT_LANGUAGE, HeaderValue::from_static("de"));
+    assert_eq!(locale::resolve(&headers, Some(&auth_state)), Locale::Pt);
+}
+
+#[tokio::test]
+async fn test_error_message_follows_current_locale() {
+    let message = i18n::scope(Locale::Es, async { AppError::UserNotFound.message() }).await;
+    assert_eq!(message, "Usuario no encontrado");
+    assert_eq!(AppError::UserNotFound.message(), "User not found");
+}
+
+#[tokio::test]
+async fn test_localized_error_keeps_code() {
+    let response = server()
+        .get("/api/me")
+        .add_header("accept-language", "pt-BR")
+        .await;
+
+    response.assert_status_unauthorized();
+    assert_eq!(response.header("content-language"), "pt");
+    response.assert_json(&serde_json::json!({
+        "error": "MISSING_TOKEN",
+        "message": "Token de autenticação obrigatório",
+        "request_id": response.header("x-request-id").to_str().unwrap(),
+    }));
+}
+
+#[tokio::test]
+async fn test_token_locale_applies_without_accept_language() {
+    let response = server()
+        .get(&format!("/api/tenants/{}/users", Uuid::now_v7()))
+        .authorization_bearer(token_with_locale(UserRole::Regular, Some(Locale::Es)))
+        .await;
+
+    response.assert_status_forbidden();
+    response.assert_json_contains(&serde_json::json!({
+        "error": "TENANT_ACCESS_DENIED",
+        "message": "Acceso denegado para este inquilino",
+    }));
+}
+
+#[tokio::test]
+async fn test_validation_messages_are_localized() {
+    let response = server()
+        .post("/api/auth/register")
+        .authorization_bearer(get_test_bearer_token())
+        .add_header("accept-language", "es")
+        .add_header("accept", "application/problem+json")
+        .json(&serde_json::json!({
+            "tenant_id": Uuid::now_v7(),
+            "email": "test@example.com",
+            "password": "short"
+        }))
+        .await;
+
+    response.assert_status_bad_request();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["title"], "La solicitud no superó la validación");
+    assert_eq!(body["errors"][0]["code"], "length");
+    assert_eq!(
+        body["errors"][0]["message"],
+        "La contraseña debe tener entre 8 y 100 caracteres"
+    );
+}
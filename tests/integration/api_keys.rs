@@ -0,0 +1,205 @@
+use crate::common::*;
+use template_rust_backend::enums::UserRole;
+
+#[tokio::test]
+async fn test_api_key_lifecycle() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    let token = app.token_for(&admin);
+
+    let response = app
+        .server
+        .post(&format!("/api/tenants/{}/service-accounts", tenant.id))
+        .authorization_bearer(&token)
+        .json(&serde_json::json!({ "name": "ci", "role": "Admin" }))
+        .await;
+    response.assert_status(axum::http::StatusCode::CREATED);
+    let account: serde_json::Value = response.json();
+
+    let response = app
+        .server
+        .post(&format!(
+            "/api/tenants/{}/service-accounts/{}/api-keys",
+            tenant.id,
+            account["id"].as_str().unwrap()
+        ))
+        .authorization_bearer(&token)
+        .json(&serde_json::json!({ "name": "deploy", "scopes": ["users:read"] }))
+        .await;
+    response.assert_status(axum::http::StatusCode::CREATED);
+    let issued: serde_json::Value = response.json();
+    let secret = issued["secret"].as_str().unwrap().to_string();
+    let key_id = issued["key"]["id"].as_str().unwrap().to_string();
+    assert!(secret.starts_with("sk_"));
+    assert!(issued["key"].get("key_hash").is_none());
+
+    // The key works in either header, within its scopes only
+    let users_path = format!("/api/tenants/{}/users", tenant.id);
+    let response = app
+        .server
+        .get(&users_path)
+        .add_header("x-api-key", &secret)
+        .await;
+    response.assert_status_ok();
+    assert!(!response.text().contains("password_hash"));
+    app.server
+        .get(&users_path)
+        .authorization_bearer(&secret)
+        .await
+        .assert_status_ok();
+    let response = app
+        .server
+        .get(&format!("/api/tenants/{}/api-keys", tenant.id))
+        .add_header("x-api-key", &secret)
+        .await;
+    response.assert_status_forbidden();
+    response.assert_json_contains(&serde_json::json!({ "error": "INSUFFICIENT_SCOPE" }));
+
+    let response = app
+        .server
+        .get(&format!("/api/tenants/{}/api-keys", tenant.id))
+        .authorization_bearer(&token)
+        .await;
+    response.assert_status_ok();
+    let keys: Vec<serde_json::Value> = response.json();
+    assert_eq!(keys.len(), 1);
+    assert!(keys[0]["last_used_at"].is_string());
+
+    // Rotation revokes the old key and issues a working new one
+    let response = app
+        .server
+        .post(&format!(
+            "/api/tenants/{}/api-keys/{}/rotate",
+            tenant.id, key_id
+        ))
+        .authorization_bearer(&token)
+        .await;
+    response.assert_status(axum::http::StatusCode::CREATED);
+    let rotated: serde_json::Value = response.json();
+    let new_secret = rotated["secret"].as_str().unwrap().to_string();
+    assert_eq!(rotated["key"]["scopes"], issued["key"]["scopes"]);
+
+    let response = app
+        .server
+        .get(&users_path)
+        .add_header("x-api-key", &secret)
+        .await;
+    response.assert_status_unauthorized();
+    app.server
+        .get(&users_path)
+        .add_header("x-api-key", &new_secret)
+        .await
+        .assert_status_ok();
+
+    app.server
+        .delete(&format!(
+            "/api/tenants/{}/api-keys/{}",
+            tenant.id,
+            rotated["key"]["id"].as_str().unwrap()
+        ))
+        .authorization_bearer(&token)
+        .await
+        .assert_status_ok();
+    app.server
+        .get(&users_path)
+        .add_header("x-api-key", &new_secret)
+        .await
+        .assert_status_unauthorized();
+}
+
+#[tokio::test]
+async fn test_api_keys_are_admin_only() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, _) = app.create_tenant_with_admin().await;
+    let user = app
+        .create_user(tenant.id, "user@example.com", UserRole::Regular)
+        .await;
+
+    let response = app
+        .server
+        .get(&format!("/api/tenants/{}/api-keys", tenant.id))
+        .authorization_bearer(app.token_for(&user))
+        .await;
+    response.assert_status_forbidden();
+    response.assert_json_contains(&serde_json::json!({ "error": "ADMIN_ROLE_REQUIRED" }));
+}
+
+#[tokio::test]
+async fn test_api_key_cannot_mint_broader_keys() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    let token = app.token_for(&admin);
+    let account: serde_json::Value = app
+        .server
+        .post(&format!("/api/tenants/{}/service-accounts", tenant.id))
+        .authorization_bearer(&token)
+        .json(&serde_json::json!({ "name": "ci", "role": "Admin" }))
+        .await
+        .json();
+    let keys_path = format!(
+        "/api/tenants/{}/service-accounts/{}/api-keys",
+        tenant.id,
+        account["id"].as_str().unwrap()
+    );
+    let manager: serde_json::Value = app
+        .server
+        .post(&keys_path)
+        .authorization_bearer(&token)
+        .json(&serde_json::json!({ "name": "manager", "scopes": ["api_keys:manage"] }))
+        .await
+        .json();
+    let manager_secret = manager["secret"].as_str().unwrap();
+    let reader: serde_json::Value = app
+        .server
+        .post(&keys_path)
+        .authorization_bearer(&token)
+        .json(&serde_json::json!({ "name": "reader", "scopes": ["users:read"] }))
+        .await
+        .json();
+    let rotate_path = format!(
+        "/api/tenants/{}/api-keys/{}/rotate",
+        tenant.id,
+        reader["key"]["id"].as_str().unwrap()
+    );
+
+    // Neither a new key nor a rotated one may carry scopes the caller lacks
+    let response = app
+        .server
+        .post(&keys_path)
+        .add_header("x-api-key", manager_secret)
+        .json(&serde_json::json!({ "name": "escalated", "scopes": ["users:read"] }))
+        .await;
+    response.assert_status_forbidden();
+    response.assert_json_contains(&serde_json::json!({ "error": "INSUFFICIENT_SCOPE" }));
+    let response = app
+        .server
+        .post(&rotate_path)
+        .add_header("x-api-key", manager_secret)
+        .await;
+    response.assert_status_forbidden();
+    response.assert_json_contains(&serde_json::json!({ "error": "INSUFFICIENT_SCOPE" }));
+
+    // A revoked key cannot be brought back by rotating it
+    app.server
+        .delete(&format!(
+            "/api/tenants/{}/api-keys/{}",
+            tenant.id,
+            reader["key"]["id"].as_str().unwrap()
+        ))
+        .authorization_bearer(&token)
+        .await
+        .assert_status_ok();
+    let response = app
+        .server
+        .post(&rotate_path)
+        .authorization_bearer(&token)
+        .await;
+    response.assert_status(axum::http::StatusCode::CONFLICT);
+    response.assert_json_contains(&serde_json::json!({ "error": "API_KEY_INACTIVE" }));
+}
@@ -1,189 +1,284 @@
 use crate::common::*;
-use axum_test::TestServer;
-use template_rust_backend::routes;
-use uuid::Uuid;
+use template_rust_backend::enums::{UserRole, UserStatus};
+use template_rust_backend::services::auth_service::AuthService;
 
 #[tokio::test]
-#[ignore] // Ignore until test DB is set up
 async fn test_register_success() {
-    let app = setup_test_app().await;
-    let server = TestServer::new(app).unwrap();
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let tenant = app.create_tenant("Acme").await;
 
-    let response = server
+    let response = app
+        .server
         .post("/api/auth/register")
-        .add_header(
-            "Authorization",
-            &format!("Bearer {}", get_test_bearer_token()),
-        )
+        .authorization_bearer(get_test_bearer_token())
         .json(&serde_json::json!({
-            "tenant_id": Uuid::new_v4().to_string(),
+            "tenant_id": tenant.id,
             "email": "test@example.com",
             "password": "password123"
         }))
         .await;
 
     response.assert_status_ok();
-    response.assert_json_contains(serde_json::json!({
+    // The first user of a tenant becomes its admin
+    response.assert_json_contains(&serde_json::json!({
         "user": {
-            "email": "test@example.com"
+            "email": "test@example.com",
+            "role": "Admin"
         }
     }));
+    let body: serde_json::Value = response.json();
+    assert!(body["token"].as_str().is_some_and(|t| !t.is_empty()));
 }
 
 #[tokio::test]
-#[ignore]
-async fn test_register_validation_error() {
-    let app = setup_test_app().await;
-    let server = TestServer::new(app).unwrap();
+async fn test_register_second_user_is_regular() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, _) = app.create_tenant_with_admin().await;
 
-    let response = server
+    let response = app
+        .server
         .post("/api/auth/register")
-        .add_header(
-            "Authorization",
-            &format!("Bearer {}", get_test_bearer_token()),
-        )
+        .authorization_bearer(get_test_bearer_token())
         .json(&serde_json::json!({
-            "tenant_id": Uuid::new_v4().to_string(),
-            "email": "invalid-email",
-            "password": "short"
+            "tenant_id": tenant.id,
+            "email": "second@example.com",
+            "password": "password123"
         }))
         .await;
 
-    response.assert_status_code(400);
-    response.assert_json_contains(serde_json::json!({
-        "error": "VALIDATION_ERROR"
+    response.assert_status_ok();
+    response.assert_json_contains(&serde_json::json!({
+        "user": { "role": "Regular" }
     }));
 }
 
 #[tokio::test]
-#[ignore]
-async fn test_register_duplicate_user() {
-    // Test registering the same user twice
-    let app = setup_test_app().await;
-    let server = TestServer::new(app).unwrap();
-    let tenant_id = Uuid::new_v4();
+async fn test_register_requires_bearer_token() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let tenant = app.create_tenant("Acme").await;
 
-    // First registration
-    let _response1 = server
+    let response = app
+        .server
         .post("/api/auth/register")
-        .add_header(
-            "Authorization",
-            &format!("Bearer {}", get_test_bearer_token()),
-        )
+        .authorization_bearer("wrong-token")
         .json(&serde_json::json!({
-            "tenant_id": tenant_id.to_string(),
-            "email": "duplicate@example.com",
+            "tenant_id": tenant.id,
+            "email": "test@example.com",
             "password": "password123"
         }))
         .await;
 
-    // Second registration with same email and tenant
-    let response2 = server
+    response.assert_status_unauthorized();
+    response.assert_json_contains(&serde_json::json!({ "error": "INVALID_TOKEN" }));
+}
+
+#[tokio::test]
+async fn test_register_validation_error() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let tenant = app.create_tenant("Acme").await;
+
+    let response = app
+        .server
         .post("/api/auth/register")
-        .add_header(
-            "Authorization",
-            &format!("Bearer {}", get_test_bearer_token()),
-        )
+        .authorization_bearer(get_test_bearer_token())
         .json(&serde_json::json!({
-            "tenant_id": tenant_id.to_string(),
-            "email": "duplicate@example.com",
-            "password": "password123"
+            "tenant_id": tenant.id,
+            "email": "invalid-email",
+            "password": "short"
         }))
         .await;
 
-    response2.assert_status_code(409);
-    response2.assert_json_contains(serde_json::json!({
-        "error": "USER_ALREADY_EXISTS"
+    response.assert_status_bad_request();
+    response.assert_json_contains(&serde_json::json!({
+        "error": "VALIDATION_ERROR"
     }));
 }
 
 #[tokio::test]
-#[ignore]
-async fn test_login_success() {
-    // First register a user
-    let app = setup_test_app().await;
-    let server = TestServer::new(app).unwrap();
-    let tenant_id = Uuid::new_v4();
+async fn test_register_duplicate_user() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
 
-    // Register
-    let _register_response = server
+    let response = app
+        .server
         .post("/api/auth/register")
-        .add_header(
-            "Authorization",
-            &format!("Bearer {}", get_test_bearer_token()),
-        )
+        .authorization_bearer(get_test_bearer_token())
         .json(&serde_json::json!({
-            "tenant_id": tenant_id.to_string(),
-            "email": "login@example.com",
+            "tenant_id": tenant.id,
+            "email": admin.email,
             "password": "password123"
         }))
         .await;
 
-    // Login
-    let login_response = server
+    response.assert_status_conflict();
+    response.assert_json_contains(&serde_json::json!({
+        "error": "USER_ALREADY_EXISTS"
+    }));
+}
+
+#[tokio::test]
+async fn test_login_success() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (_, admin) = app.create_tenant_with_admin().await;
+
+    let response = app
+        .server
         .post("/api/auth/login")
-        .add_header(
-            "Authorization",
-            &format!("Bearer {}", get_test_bearer_token()),
-        )
+        .authorization_bearer(get_test_bearer_token())
         .json(&serde_json::json!({
-            "email": "login@example.com",
-            "password": "password123"
+            "email": admin.email,
+            "password": TEST_PASSWORD
         }))
         .await;
 
-    login_response.assert_status_ok();
-    login_response.assert_json_contains(serde_json::json!({
+    response.assert_status_ok();
+    response.assert_json_contains(&serde_json::json!({
         "user": {
-            "email": "login@example.com"
+            "id": admin.id,
+            "email": admin.email
         }
     }));
+
+    let body: serde_json::Value = response.json();
+    let claims =
+        AuthService::verify_token(body["token"].as_str().unwrap(), &app.config.jwt_secret).unwrap();
+    assert_eq!(claims.user_id, admin.id);
+    assert_eq!(claims.role, UserRole::Admin);
 }
 
 #[tokio::test]
-#[ignore]
 async fn test_login_invalid_credentials() {
-    let app = setup_test_app().await;
-    let server = TestServer::new(app).unwrap();
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (_, admin) = app.create_tenant_with_admin().await;
+
+    for (email, password) in [
+        ("nonexistent@example.com", TEST_PASSWORD),
+        (admin.email.as_str(), "wrongpassword"),
+    ] {
+        let response = app
+            .server
+            .post("/api/auth/login")
+            .authorization_bearer(get_test_bearer_token())
+            .json(&serde_json::json!({
+                "email": email,
+                "password": password
+            }))
+            .await;
 
-    let response = server
+        response.assert_status_unauthorized();
+        response.assert_json_contains(&serde_json::json!({
+            "error": "INVALID_CREDENTIALS"
+        }));
+    }
+}
+
+#[tokio::test]
+async fn test_login_inactive_user() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    let user = app
+        .state
+        .users
+        .change_user_status(admin.id, tenant.id)
+        .await
+        .unwrap();
+    assert_eq!(user.status, UserStatus::Inactive);
+
+    let response = app
+        .server
         .post("/api/auth/login")
-        .add_header(
-            "Authorization",
-            &format!("Bearer {}", get_test_bearer_token()),
-        )
+        .authorization_bearer(get_test_bearer_token())
         .json(&serde_json::json!({
-            "email": "nonexistent@example.com",
-            "password": "wrongpassword"
+            "email": admin.email,
+            "password": TEST_PASSWORD
         }))
         .await;
 
-    response.assert_status_code(401);
-    response.assert_json_contains(serde_json::json!({
-        "error": "INVALID_CREDENTIALS"
+    response.assert_status_forbidden();
+    response.assert_json_contains(&serde_json::json!({
+        "error": "USER_NOT_VALIDATED"
     }));
 }
 
 #[tokio::test]
-#[ignore]
 async fn test_login_validation_error() {
-    let app = setup_test_app().await;
-    let server = TestServer::new(app).unwrap();
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
 
-    let response = server
+    let response = app
+        .server
         .post("/api/auth/login")
-        .add_header(
-            "Authorization",
-            &format!("Bearer {}", get_test_bearer_token()),
-        )
+        .authorization_bearer(get_test_bearer_token())
         .json(&serde_json::json!({
             "email": "invalid-email",
             "password": ""
         }))
         .await;
 
-    response.assert_status_code(400);
-    response.assert_json_contains(serde_json::json!({
+    response.assert_status_bad_request();
+    response.assert_json_contains(&serde_json::json!({
         "error": "VALIDATION_ERROR"
     }));
 }
+
+#[tokio::test]
+async fn test_refresh_token() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (_, admin) = app.create_tenant_with_admin().await;
+
+    let response = app
+        .server
+        .post("/api/auth/refresh")
+        .authorization_bearer(app.token_for(&admin))
+        .await;
+
+    response.assert_status_ok();
+    response.assert_json_contains(&serde_json::json!({
+        "user": { "id": admin.id }
+    }));
+}
+
+#[tokio::test]
+async fn test_refresh_with_expired_token() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (_, admin) = app.create_tenant_with_admin().await;
+    let expired = AuthService::generate_token(
+        admin.id,
+        admin.tenant_id,
+        admin.email.clone(),
+        admin.role,
+        &app.config.jwt_secret,
+        -10,
+    )
+    .unwrap();
+
+    let response = app
+        .server
+        .post("/api/auth/refresh")
+        .authorization_bearer(expired)
+        .await;
+
+    response.assert_status_unauthorized();
+    response.assert_json_contains(&serde_json::json!({ "error": "TOKEN_EXPIRED" }));
+}
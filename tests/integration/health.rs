@@ -1,25 +1,77 @@
-use axum_test::TestServer;
-use template_rust_backend::routes;
 use crate::common::*;
 
 #[tokio::test]
-#[ignore] // Ignore until test DB is set up
 async fn test_health_check_success() {
-    let app = setup_test_app().await;
-    let server = TestServer::new(app).unwrap();
-    
-    let response = server.get("/health").await;
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+
+    let response = app.server.get("/health").await;
     response.assert_status_ok();
-    response.assert_json_contains(serde_json::json!({
+    response.assert_json_contains(&serde_json::json!({
         "status": "healthy",
         "database": "connected"
     }));
 }
 
 #[tokio::test]
-#[ignore]
-async fn test_health_check_database_disconnected() {
-    // Test case for when database is disconnected
-    // Would require mocking or disconnecting the DB
+async fn test_liveness_and_readiness() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+
+    app.server.get("/health/live").await.assert_status_ok();
+
+    let response = app.server.get("/health/ready").await;
+    response.assert_status_ok();
+    response.assert_json_contains(&serde_json::json!({ "ready": true }));
 }
 
+#[tokio::test]
+async fn test_health_details_requires_auth() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+
+    app.server
+        .get("/health/details")
+        .await
+        .assert_status_unauthorized();
+
+    let (_, admin) = app.create_tenant_with_admin().await;
+    let response = app
+        .server
+        .get("/health/details")
+        .authorization_bearer(app.token_for(&admin))
+        .await;
+    response.assert_status_ok();
+    response.assert_json_contains(&serde_json::json!({
+        "ready": true,
+        "pending_migrations": []
+    }));
+}
+
+#[tokio::test]
+async fn test_health_check_database_disconnected() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+
+    app.db.close_by_ref().await.unwrap();
+
+    let response = app.server.get("/health/ready").await;
+    response.assert_status_service_unavailable();
+    response.assert_json_contains(&serde_json::json!({ "ready": false }));
+    // Only the status of each check; the database error is logged
+    let body: serde_json::Value = response.json();
+    let database = body["checks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|check| check["name"] == "database")
+        .unwrap();
+    assert_eq!(
+        database,
+        &serde_json::json!({ "name": "database", "status": "down" })
+    );
+}
@@ -0,0 +1,189 @@
+use crate::common::*;
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use sea_orm::Set;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+use template_rust_backend::enums::JobStatus;
+use template_rust_backend::jobs::{Job, JobHandler, JobRegistry, Worker};
+use template_rust_backend::models::jobs;
+use template_rust_backend::repositories::{JobRepository, SeaOrmJobRepository};
+use template_rust_backend::services::jobs_service::NewJob;
+use template_rust_backend::utils::error::AppError;
+
+#[derive(Serialize, Deserialize)]
+struct Check {
+    ok: bool,
+}
+
+impl Job for Check {
+    const KIND: &'static str = "check";
+}
+
+struct CheckHandler;
+
+#[async_trait]
+impl JobHandler for CheckHandler {
+    type Job = Check;
+
+    async fn handle(&self, job: Check) -> Result<(), AppError> {
+        if job.ok {
+            Ok(())
+        } else {
+            Err(AppError::ServiceUnavailable)
+        }
+    }
+}
+
+fn repository(app: &TestApp) -> SeaOrmJobRepository {
+    SeaOrmJobRepository::new(app.db.clone())
+}
+
+fn worker(app: &TestApp) -> Worker {
+    Worker::new(
+        Arc::new(repository(app)),
+        JobRegistry::new().register(CheckHandler),
+        app.config.jobs.clone(),
+    )
+}
+
+#[tokio::test]
+async fn test_worker_runs_and_retries_queued_jobs() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let ok = app
+        .state
+        .jobs
+        .enqueue(NewJob::of(&Check { ok: true }).unwrap())
+        .await
+        .unwrap();
+    let failing = app
+        .state
+        .jobs
+        .enqueue(NewJob::of(&Check { ok: false }).unwrap().max_attempts(1))
+        .await
+        .unwrap();
+
+    assert_eq!(worker(&app).run_once().await.unwrap(), 2);
+
+    let repo = repository(&app);
+    let ok = repo.find_by_id(ok.id).await.unwrap().unwrap();
+    assert_eq!(ok.status, JobStatus::Completed);
+    assert_eq!(ok.attempts, 1);
+    let failing = repo.find_by_id(failing.id).await.unwrap().unwrap();
+    assert_eq!(failing.status, JobStatus::Dead);
+    assert_eq!(failing.last_error.as_deref(), Some("Service unavailable"));
+
+    let dead = app.state.jobs.list_dead(10).await.unwrap();
+    assert_eq!(dead.len(), 1);
+    let retried = app.state.jobs.retry(failing.id).await.unwrap().unwrap();
+    assert_eq!(retried.status, JobStatus::Pending);
+    assert_eq!(retried.attempts, 0);
+    assert!(app.state.jobs.retry(ok.id).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_scheduled_job_is_not_claimed_early() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let job = app
+        .state
+        .jobs
+        .enqueue(
+            NewJob::of(&Check { ok: true })
+                .unwrap()
+                .after(Duration::hours(1)),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(worker(&app).run_once().await.unwrap(), 0);
+    let job = repository(&app).find_by_id(job.id).await.unwrap().unwrap();
+    assert_eq!(job.status, JobStatus::Pending);
+    assert_eq!(job.attempts, 0);
+}
+
+#[tokio::test]
+async fn test_concurrent_claims_do_not_overlap() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    for _ in 0..6 {
+        app.state
+            .jobs
+            .enqueue(NewJob::of(&Check { ok: true }).unwrap())
+            .await
+            .unwrap();
+    }
+
+    let (first, second) = (repository(&app), repository(&app));
+    let stale_before = (Utc::now() - Duration::minutes(5)).fixed_offset();
+    let (a, b) = tokio::join!(
+        first.claim("worker-a", 4, stale_before),
+        second.claim("worker-b", 4, stale_before),
+    );
+    let (a, b) = (a.unwrap(), b.unwrap());
+
+    assert_eq!(a.len() + b.len(), 6);
+    let ids: HashSet<_> = a.iter().chain(&b).map(|job| job.id).collect();
+    assert_eq!(ids.len(), 6);
+    assert!(
+        a.iter()
+            .all(|job| job.locked_by.as_deref() == Some("worker-a"))
+    );
+    assert!(
+        b.iter()
+            .all(|job| job.locked_by.as_deref() == Some("worker-b"))
+    );
+}
+
+#[tokio::test]
+async fn test_job_of_a_dead_worker_is_reclaimed() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let job = app
+        .state
+        .jobs
+        .enqueue(NewJob::of(&Check { ok: true }).unwrap())
+        .await
+        .unwrap();
+    let repo = repository(&app);
+    let long_ago = (Utc::now() - Duration::hours(1)).fixed_offset();
+    let claimed = repo.claim("worker-a", 1, long_ago).await.unwrap();
+    assert_eq!(claimed.len(), 1);
+
+    // Still locked by a live worker
+    assert!(
+        repo.claim("worker-b", 1, long_ago)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    // The lock has outlived the timeout
+    let later = (Utc::now() + Duration::seconds(1)).fixed_offset();
+    let reclaimed = repo.claim("worker-b", 1, later).await.unwrap();
+    assert_eq!(reclaimed.len(), 1);
+    assert_eq!(reclaimed[0].id, job.id);
+    assert_eq!(reclaimed[0].attempts, 2);
+    assert_eq!(reclaimed[0].locked_by.as_deref(), Some("worker-b"));
+
+    // The first worker finishing late does not overwrite the new run
+    let mut late: jobs::ActiveModel = claimed[0].clone().into();
+    late.status = Set(JobStatus::Completed);
+    late.locked_by = Set(None);
+    assert!(repo.finish(late, "worker-a").await.unwrap().is_none());
+    let job = repo.find_by_id(job.id).await.unwrap().unwrap();
+    assert_eq!(job.status, JobStatus::Running);
+    assert_eq!(job.locked_by.as_deref(), Some("worker-b"));
+
+    let mut finished: jobs::ActiveModel = job.into();
+    finished.status = Set(JobStatus::Completed);
+    finished.locked_by = Set(None);
+    let finished = repo.finish(finished, "worker-b").await.unwrap().unwrap();
+    assert_eq!(finished.status, JobStatus::Completed);
+}
@@ -0,0 +1,19 @@
+// Integration tests for the API endpoints, run against a real Postgres
+// database given by TEST_DATABASE_URL. Tests return early when it is unset.
+
+#[path = "../common/mod.rs"]
+pub mod common;
+
+pub mod api_keys;
+pub mod auth;
+pub mod health;
+pub mod jobs;
+pub mod memberships;
+pub mod oidc;
+pub mod outbox;
+pub mod scheduler;
+pub mod scim;
+pub mod tenant_settings;
+pub mod tenants;
+pub mod users;
+pub mod webhooks;
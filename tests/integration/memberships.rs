@@ -0,0 +1,199 @@
+use crate::common::*;
+use template_rust_backend::enums::UserRole;
+use template_rust_backend::models::{tenants, users};
+use template_rust_backend::services::auth_service::AuthService;
+use uuid::Uuid;
+
+async fn login(app: &TestApp, email: &str) -> axum_test::TestResponse {
+    app.server
+        .post("/api/auth/login")
+        .authorization_bearer(get_test_bearer_token())
+        .json(&serde_json::json!({
+            "email": email,
+            "password": TEST_PASSWORD
+        }))
+        .await
+}
+
+/// A consultant created in Acme and added to Globex as an admin.
+async fn consultant(app: &TestApp) -> (tenants::Model, tenants::Model, users::Model) {
+    let acme = app.create_tenant("Acme").await;
+    let globex = app.create_tenant("Globex").await;
+    let globex_admin = app
+        .create_user(globex.id, "admin@globex.com", UserRole::Admin)
+        .await;
+    let consultant = app
+        .create_user(acme.id, "consultant@example.com", UserRole::Regular)
+        .await;
+
+    let response = app
+        .server
+        .post(&format!("/api/tenants/{}/members", globex.id))
+        .authorization_bearer(app.token_for(&globex_admin))
+        .json(&serde_json::json!({
+            "email": "consultant@example.com",
+            "role": "Admin"
+        }))
+        .await;
+    response.assert_status(axum::http::StatusCode::CREATED);
+    response.assert_json_contains(&serde_json::json!({
+        "id": consultant.id,
+        "tenant_id": globex.id,
+        "role": "Admin"
+    }));
+
+    (acme, globex, consultant)
+}
+
+#[tokio::test]
+async fn test_login_lists_memberships_and_switches_tenant() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (acme, globex, consultant) = consultant(&app).await;
+
+    // Logins start in the tenant the user was created in
+    let response = login(&app, "consultant@example.com").await;
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["user"]["tenant_id"], acme.id.to_string());
+    assert_eq!(body["user"]["role"], "Regular");
+    let tenant_ids: Vec<_> = body["memberships"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| m["tenant_id"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(tenant_ids, [acme.id.to_string(), globex.id.to_string()]);
+    let acme_token = body["token"].as_str().unwrap().to_string();
+
+    let response = app
+        .server
+        .post("/api/auth/switch-tenant")
+        .authorization_bearer(&acme_token)
+        .json(&serde_json::json!({ "tenant_id": globex.id }))
+        .await;
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    let claims =
+        AuthService::verify_token(body["token"].as_str().unwrap(), &app.config.jwt_secret).unwrap();
+    assert_eq!(claims.user_id, consultant.id);
+    assert_eq!(claims.tenant_id, globex.id);
+    assert_eq!(claims.role, UserRole::Admin);
+
+    let response = app
+        .server
+        .get("/api/me")
+        .authorization_bearer(body["token"].as_str().unwrap())
+        .await;
+    response.assert_json_contains(&serde_json::json!({
+        "tenant_id": globex.id,
+        "role": "Admin"
+    }));
+
+    let response = app
+        .server
+        .post("/api/auth/switch-tenant")
+        .authorization_bearer(&acme_token)
+        .json(&serde_json::json!({ "tenant_id": Uuid::now_v7() }))
+        .await;
+    response.assert_status_forbidden();
+    response.assert_json_contains(&serde_json::json!({ "error": "TENANT_ACCESS_DENIED" }));
+}
+
+#[tokio::test]
+async fn test_tenant_routes_check_membership_of_path_tenant() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (acme, globex, consultant) = consultant(&app).await;
+    let outsider = app.create_tenant("Initech").await;
+    let acme_token = app.token_for(&consultant);
+
+    // An Acme token reaches Globex routes with the Globex role
+    app.server
+        .get(&format!("/api/tenants/{}/users", globex.id))
+        .authorization_bearer(&acme_token)
+        .await
+        .assert_status_ok();
+
+    let response = app
+        .server
+        .get(&format!("/api/tenants/{}/users", acme.id))
+        .authorization_bearer(&acme_token)
+        .await;
+    response.assert_status_forbidden();
+    response.assert_json_contains(&serde_json::json!({ "error": "ADMIN_ROLE_REQUIRED" }));
+
+    let response = app
+        .server
+        .get(&format!("/api/tenants/{}", outsider.id))
+        .authorization_bearer(&acme_token)
+        .await;
+    response.assert_status_forbidden();
+    response.assert_json_contains(&serde_json::json!({ "error": "TENANT_ACCESS_DENIED" }));
+
+    // A deactivated membership no longer grants access
+    app.state
+        .users
+        .change_user_status(consultant.id, globex.id)
+        .await
+        .unwrap();
+    let response = app
+        .server
+        .get(&format!("/api/tenants/{}", globex.id))
+        .authorization_bearer(&acme_token)
+        .await;
+    response.assert_status_forbidden();
+    response.assert_json_contains(&serde_json::json!({ "error": "TENANT_ACCESS_DENIED" }));
+}
+
+#[tokio::test]
+async fn test_add_member_and_remove_from_tenants() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (acme, globex, consultant) = consultant(&app).await;
+    let consultant_in_globex = app
+        .state
+        .users
+        .get_in_tenant(globex.id, consultant.id)
+        .await
+        .unwrap();
+
+    for (email, status, error) in [
+        ("consultant@example.com", 409, "MEMBERSHIP_ALREADY_EXISTS"),
+        ("nobody@example.com", 404, "USER_NOT_FOUND"),
+    ] {
+        let response = app
+            .server
+            .post(&format!("/api/tenants/{}/members", globex.id))
+            .authorization_bearer(app.token_for(&consultant_in_globex))
+            .json(&serde_json::json!({ "email": email }))
+            .await;
+        assert_eq!(response.status_code().as_u16(), status);
+        response.assert_json_contains(&serde_json::json!({ "error": error }));
+    }
+
+    // Leaving the tenant the user was created in moves logins to Globex
+    app.state
+        .users
+        .delete(acme.id, consultant.id)
+        .await
+        .unwrap();
+    let response = login(&app, "consultant@example.com").await;
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["user"]["tenant_id"], globex.id.to_string());
+    assert_eq!(body["memberships"].as_array().unwrap().len(), 1);
+
+    // Leaving the last tenant deletes the account
+    app.state
+        .users
+        .delete(globex.id, consultant.id)
+        .await
+        .unwrap();
+    login(&app, "consultant@example.com")
+        .await
+        .assert_status_unauthorized();
+}
@@ -1,8 +0,0 @@
-// Integration tests module
-// This module contains all integration tests for the API endpoints
-
-pub mod auth;
-pub mod health;
-pub mod users;
-pub mod tenants;
-
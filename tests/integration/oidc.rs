@@ -0,0 +1,264 @@
+use crate::common::oidc::{self, MockOidcServer};
+use crate::common::*;
+use axum::http::StatusCode;
+use serde_json::{Value, json};
+use template_rust_backend::enums::UserRole;
+
+/// Registers the mock provider for the tenant through the admin API.
+async fn create_provider(
+    app: &TestApp,
+    admin_token: &str,
+    tenant_id: uuid::Uuid,
+    idp: &MockOidcServer,
+) -> Value {
+    let response = app
+        .server
+        .post(&format!("/api/tenants/{}/oidc-providers", tenant_id))
+        .authorization_bearer(admin_token)
+        .json(&json!({
+            "name": "Corporate SSO",
+            "issuer": idp.issuer,
+            "client_id": oidc::CLIENT_ID,
+            "client_secret": oidc::CLIENT_SECRET,
+            "redirect_uri": oidc::REDIRECT_URI,
+            "role_claim": "groups",
+            "admin_role_value": "app-admins",
+        }))
+        .await;
+    response.assert_status(StatusCode::CREATED);
+    response.json()
+}
+
+/// Runs the browser side of a login and returns the callback response.
+async fn sign_in(
+    app: &TestApp,
+    idp: &MockOidcServer,
+    provider_id: &str,
+    claims: Value,
+) -> axum_test::TestResponse {
+    let response = app
+        .server
+        .post(&format!("/api/auth/oidc/{}/authorize", provider_id))
+        .authorization_bearer(get_test_bearer_token())
+        .await;
+    response.assert_status_ok();
+    let request: Value = response.json();
+    let code = idp.approve(request["authorization_url"].as_str().unwrap(), claims);
+
+    app.server
+        .post("/api/auth/oidc/callback")
+        .authorization_bearer(get_test_bearer_token())
+        .json(&json!({ "state": request["state"], "code": code }))
+        .await
+}
+
+#[tokio::test]
+async fn test_oidc_login_provisions_then_reuses_user() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let idp = MockOidcServer::start().await;
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    let provider = create_provider(&app, &app.token_for(&admin), tenant.id, &idp).await;
+    let provider_id = provider["id"].as_str().unwrap();
+    assert!(provider.get("client_secret").is_none());
+
+    let claims =
+        json!({ "sub": "idp-jane", "email": "jane@example.com", "groups": ["app-admins"] });
+    let response = sign_in(&app, &idp, provider_id, claims.clone()).await;
+    response.assert_status_ok();
+    let first: Value = response.json();
+    assert_eq!(first["user"]["email"], "jane@example.com");
+    assert_eq!(first["user"]["tenant_id"], json!(tenant.id));
+    // Mapped from the groups claim
+    assert_eq!(first["user"]["role"], "Admin");
+
+    // The token works like one from password login
+    app.server
+        .get("/api/me")
+        .authorization_bearer(first["token"].as_str().unwrap())
+        .await
+        .assert_status_ok();
+
+    // Same subject, even with a changed email, signs in as the same user
+    let response = sign_in(
+        &app,
+        &idp,
+        provider_id,
+        json!({ "sub": "idp-jane", "email": "jane.doe@example.com" }),
+    )
+    .await;
+    response.assert_status_ok();
+    let second: Value = response.json();
+    assert_eq!(second["user"]["id"], first["user"]["id"]);
+}
+
+#[tokio::test]
+async fn test_oidc_login_links_existing_user_by_verified_email() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let idp = MockOidcServer::start().await;
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    let user = app
+        .create_user(tenant.id, "user@example.com", UserRole::Regular)
+        .await;
+    let provider = create_provider(&app, &app.token_for(&admin), tenant.id, &idp).await;
+    let provider_id = provider["id"].as_str().unwrap();
+
+    let response = sign_in(
+        &app,
+        &idp,
+        provider_id,
+        json!({ "sub": "idp-user", "email": "user@example.com", "email_verified": false }),
+    )
+    .await;
+    response.assert_status(StatusCode::CONFLICT);
+    response.assert_json_contains(&json!({ "error": "OIDC_ACCOUNT_CONFLICT" }));
+
+    let response = sign_in(
+        &app,
+        &idp,
+        provider_id,
+        json!({ "sub": "idp-user", "email": "USER@example.com", "email_verified": true, "groups": ["app-admins"] }),
+    )
+    .await;
+    response.assert_status_ok();
+    let body: Value = response.json();
+    assert_eq!(body["user"]["id"], json!(user.id));
+    // Linking keeps the existing role
+    assert_eq!(body["user"]["role"], "Regular");
+}
+
+#[tokio::test]
+async fn test_oidc_login_adds_user_of_another_tenant_as_member() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let idp = MockOidcServer::start().await;
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    let other = app.create_tenant("Globex").await;
+    let user = app
+        .create_user(other.id, "user@example.com", UserRole::Admin)
+        .await;
+    let provider = create_provider(&app, &app.token_for(&admin), tenant.id, &idp).await;
+    let provider_id = provider["id"].as_str().unwrap();
+
+    let response = sign_in(
+        &app,
+        &idp,
+        provider_id,
+        json!({ "sub": "idp-user", "email": "user@example.com", "email_verified": false }),
+    )
+    .await;
+    response.assert_status(StatusCode::CONFLICT);
+    response.assert_json_contains(&json!({ "error": "OIDC_ACCOUNT_CONFLICT" }));
+
+    let response = sign_in(
+        &app,
+        &idp,
+        provider_id,
+        json!({ "sub": "idp-user", "email": "user@example.com", "email_verified": true }),
+    )
+    .await;
+    response.assert_status_ok();
+    let body: Value = response.json();
+    assert_eq!(body["user"]["id"], json!(user.id));
+    assert_eq!(body["user"]["tenant_id"], json!(tenant.id));
+    // The provider's default role, not the one held in the other tenant
+    assert_eq!(body["user"]["role"], "Regular");
+}
+
+#[tokio::test]
+async fn test_oidc_callback_rejects_replayed_state() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let idp = MockOidcServer::start().await;
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    let provider = create_provider(&app, &app.token_for(&admin), tenant.id, &idp).await;
+
+    let response = app
+        .server
+        .post(&format!(
+            "/api/auth/oidc/{}/authorize",
+            provider["id"].as_str().unwrap()
+        ))
+        .authorization_bearer(get_test_bearer_token())
+        .await;
+    let request: Value = response.json();
+    let code = idp.approve(
+        request["authorization_url"].as_str().unwrap(),
+        json!({ "sub": "idp-jane", "email": "jane@example.com" }),
+    );
+    let callback = json!({ "state": request["state"], "code": code });
+
+    app.server
+        .post("/api/auth/oidc/callback")
+        .authorization_bearer(get_test_bearer_token())
+        .json(&callback)
+        .await
+        .assert_status_ok();
+    let response = app
+        .server
+        .post("/api/auth/oidc/callback")
+        .authorization_bearer(get_test_bearer_token())
+        .json(&callback)
+        .await;
+    response.assert_status_bad_request();
+    response.assert_json_contains(&json!({ "error": "OIDC_STATE_INVALID" }));
+}
+
+#[tokio::test]
+async fn test_oidc_providers_are_admin_only_and_tenant_scoped() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let idp = MockOidcServer::start().await;
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    let user = app
+        .create_user(tenant.id, "user@example.com", UserRole::Regular)
+        .await;
+    let provider = create_provider(&app, &app.token_for(&admin), tenant.id, &idp).await;
+
+    let response = app
+        .server
+        .get(&format!("/api/tenants/{}/oidc-providers", tenant.id))
+        .authorization_bearer(app.token_for(&user))
+        .await;
+    response.assert_status_forbidden();
+
+    // Another tenant's admin cannot delete it
+    let other = app.create_tenant("Globex").await;
+    let other_admin = app
+        .create_user(other.id, "admin@globex.com", UserRole::Admin)
+        .await;
+    let response = app
+        .server
+        .delete(&format!(
+            "/api/tenants/{}/oidc-providers/{}",
+            other.id,
+            provider["id"].as_str().unwrap()
+        ))
+        .authorization_bearer(app.token_for(&other_admin))
+        .await;
+    response.assert_status_not_found();
+    response.assert_json_contains(&json!({ "error": "OIDC_PROVIDER_NOT_FOUND" }));
+
+    app.server
+        .delete(&format!(
+            "/api/tenants/{}/oidc-providers/{}",
+            tenant.id,
+            provider["id"].as_str().unwrap()
+        ))
+        .authorization_bearer(app.token_for(&admin))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    let response = app
+        .server
+        .get(&format!("/api/tenants/{}/oidc-providers", tenant.id))
+        .authorization_bearer(app.token_for(&admin))
+        .await;
+    let providers: Vec<Value> = response.json();
+    assert!(providers.is_empty());
+}
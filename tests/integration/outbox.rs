@@ -0,0 +1,446 @@
+use crate::common::*;
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::{ColumnTrait, DatabaseBackend, EntityTrait, QueryFilter, QueryOrder};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use template_rust_backend::enums::{TenantStatus, UserRole};
+use template_rust_backend::events::{DomainEvent, RecordedEvent, Relay, Subscriber};
+use template_rust_backend::models::{outbox_events, users};
+use template_rust_backend::repositories::{OutboxRepository, SeaOrmOutboxRepository};
+use template_rust_backend::utils::error::AppError;
+use uuid::Uuid;
+
+/// Records the events it handles, and fails those of the aggregate in
+/// `failing`.
+#[derive(Clone, Default)]
+struct Recorder {
+    handled: Arc<Mutex<Vec<RecordedEvent>>>,
+    failing: Arc<Mutex<Option<Uuid>>>,
+}
+
+#[async_trait]
+impl Subscriber for Recorder {
+    fn name(&self) -> &'static str {
+        "recorder"
+    }
+
+    async fn handle(&self, event: &RecordedEvent) -> Result<(), AppError> {
+        if *self.failing.lock().unwrap() == Some(event.event.aggregate().1) {
+            return Err(AppError::ServiceUnavailable);
+        }
+        self.handled.lock().unwrap().push(event.clone());
+        Ok(())
+    }
+}
+
+fn repository(app: &TestApp) -> SeaOrmOutboxRepository {
+    SeaOrmOutboxRepository::new(app.db.clone())
+}
+
+fn relay(app: &TestApp, recorder: &Recorder) -> Relay {
+    Relay::new(
+        Arc::new(repository(app)),
+        app.config.outbox.poll_interval,
+        app.config.outbox.max_attempts,
+    )
+    .subscribe(recorder.clone())
+}
+
+async fn relay_all(relay: &Relay) -> usize {
+    let mut total = 0;
+    loop {
+        match relay.run_once().await.unwrap() {
+            0 => return total,
+            relayed => total += relayed,
+        }
+    }
+}
+
+async fn stored(app: &TestApp) -> Vec<outbox_events::Model> {
+    outbox_events::Entity::find()
+        .order_by_asc(outbox_events::Column::Position)
+        .all(app.db.as_ref())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_changes_record_their_events() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    let user = app
+        .create_user(tenant.id, "jane@example.com", UserRole::Regular)
+        .await;
+    app.server
+        .put(&format!(
+            "/api/tenants/{}/users/{}/change-role",
+            tenant.id, user.id
+        ))
+        .authorization_bearer(app.token_for(&admin))
+        .await
+        .assert_status_ok();
+    app.state
+        .tenants
+        .set_status(tenant.id, TenantStatus::Inactive)
+        .await
+        .unwrap();
+    // Not a change
+    app.state
+        .tenants
+        .set_status(tenant.id, TenantStatus::Inactive)
+        .await
+        .unwrap();
+
+    let events = stored(&app).await;
+    let types: Vec<_> = events.iter().map(|e| e.event_type.as_str()).collect();
+    assert_eq!(
+        types,
+        [
+            "UserRegistered",
+            "UserRegistered",
+            "UserRoleChanged",
+            "TenantStatusChanged"
+        ]
+    );
+    assert!(
+        events
+            .windows(2)
+            .all(|pair| pair[0].position < pair[1].position)
+    );
+    let role_changed = &events[2];
+    assert_eq!(role_changed.aggregate_type, "user");
+    assert_eq!(role_changed.aggregate_id, user.id);
+    assert_eq!(role_changed.tenant_id, tenant.id);
+    assert_eq!(role_changed.payload["data"]["user"]["role"], "Admin");
+    assert_eq!(role_changed.payload["data"]["previous_role"], "Regular");
+    assert!(events.iter().all(|e| e.dispatched_at.is_none()));
+
+    let recorder = Recorder::default();
+    assert_eq!(relay_all(&relay(&app, &recorder)).await, 4);
+    let handled = recorder.handled.lock().unwrap().clone();
+    // Only the events of one aggregate keep their order
+    let mut ids: Vec<_> = handled.iter().map(|e| e.id).collect();
+    ids.sort();
+    let mut stored_ids: Vec<_> = events.iter().map(|e| e.id).collect();
+    stored_ids.sort();
+    assert_eq!(ids, stored_ids);
+    let of_user: Vec<_> = handled
+        .iter()
+        .filter(|e| e.event.aggregate() == ("user", user.id))
+        .map(|e| e.event.name())
+        .collect();
+    assert_eq!(of_user, ["UserRegistered", "UserRoleChanged"]);
+    let tenant_event = handled
+        .iter()
+        .find(|e| e.event.aggregate() == ("tenant", tenant.id))
+        .unwrap();
+    assert_eq!(
+        tenant_event.event,
+        DomainEvent::TenantStatusChanged {
+            tenant_id: tenant.id,
+            status: TenantStatus::Inactive,
+            previous_status: TenantStatus::Active,
+        }
+    );
+    let events = stored(&app).await;
+    assert!(events.iter().all(|e| e.dispatched_at.is_some()));
+    assert!(events.iter().all(|e| e.attempts == 1));
+}
+
+#[tokio::test]
+async fn test_failed_change_records_no_event() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+
+    // The membership of a tenant that does not exist cannot be written
+    let result = app
+        .state
+        .users
+        .create(
+            Uuid::now_v7(),
+            "ghost@example.com".to_string(),
+            TEST_PASSWORD,
+            UserRole::Regular,
+        )
+        .await;
+    assert!(result.is_err());
+
+    assert!(stored(&app).await.is_empty());
+    let ghost = users::Entity::find()
+        .filter(users::Column::Email.eq("ghost@example.com"))
+        .one(app.db.as_ref())
+        .await
+        .unwrap();
+    assert!(ghost.is_none());
+}
+
+#[tokio::test]
+async fn test_failing_event_holds_back_only_its_aggregate() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    let jane = app
+        .create_user(tenant.id, "jane@example.com", UserRole::Regular)
+        .await;
+    let path = format!("/api/tenants/{}/users/{}/change-role", tenant.id, jane.id);
+    for _ in 0..2 {
+        app.server
+            .put(&path)
+            .authorization_bearer(app.token_for(&admin))
+            .await
+            .assert_status_ok();
+    }
+    let john = app
+        .create_user(tenant.id, "john@example.com", UserRole::Regular)
+        .await;
+
+    let recorder = Recorder::default();
+    *recorder.failing.lock().unwrap() = Some(jane.id);
+    let relay = relay(&app, &recorder);
+    // Jane's registration fails and is not retried yet; her role changes
+    // wait behind it
+    assert_eq!(relay_all(&relay).await, 3);
+    let handled: HashSet<_> = recorder
+        .handled
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|e| e.event.aggregate().1)
+        .collect();
+    assert_eq!(handled, HashSet::from([admin.id, john.id]));
+    let failed = stored(&app)
+        .await
+        .into_iter()
+        .find(|e| e.aggregate_id == jane.id)
+        .unwrap();
+    assert_eq!(failed.event_type, "UserRegistered");
+    assert_eq!(failed.attempts, 1);
+    assert!(failed.next_attempt_at.unwrap().to_utc() > Utc::now());
+    assert!(failed.last_error.unwrap().starts_with("recorder: "));
+
+    // Once retried, her events are relayed in the order they happened
+    *recorder.failing.lock().unwrap() = None;
+    outbox_events::Entity::update_many()
+        .col_expr(
+            outbox_events::Column::NextAttemptAt,
+            Expr::value(Utc::now().fixed_offset()),
+        )
+        .filter(outbox_events::Column::DispatchedAt.is_null())
+        .exec(app.db.as_ref())
+        .await
+        .unwrap();
+    recorder.handled.lock().unwrap().clear();
+    assert_eq!(relay_all(&relay).await, 3);
+    let handled: Vec<_> = recorder
+        .handled
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|e| e.event.clone())
+        .collect();
+    let roles: Vec<_> = handled
+        .iter()
+        .map(|event| match event {
+            DomainEvent::UserRegistered { user } => (event.name(), user.role),
+            DomainEvent::UserRoleChanged { user, .. } => (event.name(), user.role),
+            other => panic!("unexpected event {:?}", other),
+        })
+        .collect();
+    assert_eq!(
+        roles,
+        [
+            ("UserRegistered", UserRole::Regular),
+            ("UserRoleChanged", UserRole::Admin),
+            ("UserRoleChanged", UserRole::Regular),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_dead_event_stops_holding_back_its_aggregate() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    let jane = app
+        .create_user(tenant.id, "jane@example.com", UserRole::Regular)
+        .await;
+    app.server
+        .put(&format!(
+            "/api/tenants/{}/users/{}/change-role",
+            tenant.id, jane.id
+        ))
+        .authorization_bearer(app.token_for(&admin))
+        .await
+        .assert_status_ok();
+    // Jane's registration fails its last attempt
+    outbox_events::Entity::update_many()
+        .col_expr(
+            outbox_events::Column::Attempts,
+            Expr::value(app.config.outbox.max_attempts - 1),
+        )
+        .filter(outbox_events::Column::AggregateId.eq(jane.id))
+        .filter(outbox_events::Column::EventType.eq("UserRegistered"))
+        .exec(app.db.as_ref())
+        .await
+        .unwrap();
+
+    let recorder = Recorder::default();
+    *recorder.failing.lock().unwrap() = Some(jane.id);
+    let relay = relay(&app, &recorder);
+    relay_all(&relay).await;
+    let of_jane: Vec<_> = stored(&app)
+        .await
+        .into_iter()
+        .filter(|e| e.aggregate_id == jane.id)
+        .collect();
+    assert!(of_jane[0].dead_at.is_some());
+    assert_eq!(of_jane[0].attempts, app.config.outbox.max_attempts);
+    assert_eq!(of_jane[0].next_attempt_at, None);
+    // Her role change is tried next, and once it succeeds the dead event
+    // is not handed over again
+    assert_eq!(of_jane[1].event_type, "UserRoleChanged");
+    assert_eq!(of_jane[1].attempts, 1);
+    assert!(of_jane[1].dead_at.is_none());
+
+    *recorder.failing.lock().unwrap() = None;
+    outbox_events::Entity::update_many()
+        .col_expr(
+            outbox_events::Column::NextAttemptAt,
+            Expr::value(Utc::now().fixed_offset()),
+        )
+        .filter(outbox_events::Column::DispatchedAt.is_null())
+        .exec(app.db.as_ref())
+        .await
+        .unwrap();
+    assert_eq!(relay_all(&relay).await, 1);
+    let handled = recorder.handled.lock().unwrap();
+    let last = handled.last().unwrap();
+    assert_eq!(last.id, of_jane[1].id);
+    assert!(handled.iter().all(|e| e.id != of_jane[0].id));
+}
+
+#[tokio::test]
+async fn test_events_are_relayed_by_position_not_id() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    let jane = app
+        .create_user(tenant.id, "jane@example.com", UserRole::Regular)
+        .await;
+    app.server
+        .put(&format!(
+            "/api/tenants/{}/users/{}/change-role",
+            tenant.id, jane.id
+        ))
+        .authorization_bearer(app.token_for(&admin))
+        .await
+        .assert_status_ok();
+    // As if written by a replica whose clock is an hour behind
+    let role_changed = stored(&app).await.pop().unwrap();
+    assert_eq!(role_changed.event_type, "UserRoleChanged");
+    let behind = Utc::now() - Duration::hours(1);
+    let earlier_id = Uuid::new_v7(uuid::Timestamp::from_unix(
+        uuid::NoContext,
+        behind.timestamp() as u64,
+        0,
+    ));
+    outbox_events::Entity::update_many()
+        .col_expr(outbox_events::Column::Id, Expr::value(earlier_id))
+        .filter(outbox_events::Column::Id.eq(role_changed.id))
+        .exec(app.db.as_ref())
+        .await
+        .unwrap();
+
+    let recorder = Recorder::default();
+    relay_all(&relay(&app, &recorder)).await;
+    let of_jane: Vec<_> = recorder
+        .handled
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|e| e.event.aggregate() == ("user", jane.id))
+        .map(|e| e.event.name())
+        .collect();
+    assert_eq!(of_jane, ["UserRegistered", "UserRoleChanged"]);
+}
+
+#[tokio::test]
+async fn test_relayed_events_are_purged_after_retention() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, _) = app.create_tenant_with_admin().await;
+    app.create_user(tenant.id, "jane@example.com", UserRole::Regular)
+        .await;
+    app.relay_events().await;
+    // Pending events are kept however old
+    app.create_user(tenant.id, "john@example.com", UserRole::Regular)
+        .await;
+    let long_ago = (Utc::now() - Duration::days(30)).fixed_offset();
+    outbox_events::Entity::update_many()
+        .col_expr(outbox_events::Column::CreatedAt, Expr::value(long_ago))
+        .exec(app.db.as_ref())
+        .await
+        .unwrap();
+    let first = stored(&app).await[0].id;
+    outbox_events::Entity::update_many()
+        .col_expr(outbox_events::Column::DispatchedAt, Expr::value(long_ago))
+        .filter(outbox_events::Column::Id.eq(first))
+        .exec(app.db.as_ref())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        app.state
+            .maintenance
+            .purge_relayed_events(Duration::days(7))
+            .await
+            .unwrap(),
+        1
+    );
+    let remaining = stored(&app).await;
+    assert_eq!(remaining.len(), 2);
+    assert!(remaining.iter().all(|e| e.id != first));
+    assert_eq!(
+        remaining
+            .iter()
+            .filter(|e| e.dispatched_at.is_none())
+            .count(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_one_relay_works_at_a_time() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    // SQLite has a single relay and no advisory locks
+    if app.db.get_database_backend() != DatabaseBackend::Postgres {
+        return;
+    }
+    let (tenant, _) = app.create_tenant_with_admin().await;
+    let outbox = repository(&app);
+
+    let lock = outbox.try_lock().await.unwrap().unwrap();
+    assert!(outbox.try_lock().await.unwrap().is_none());
+    let recorder = Recorder::default();
+    let relay = relay(&app, &recorder);
+    assert_eq!(relay.run_once().await.unwrap(), 0);
+    assert!(recorder.handled.lock().unwrap().is_empty());
+
+    lock.release().await.unwrap();
+    assert_eq!(relay.run_once().await.unwrap(), 1);
+    assert_eq!(
+        recorder.handled.lock().unwrap()[0].event.tenant_id(),
+        tenant.id
+    );
+}
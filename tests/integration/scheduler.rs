@@ -0,0 +1,337 @@
+use crate::common::mocks::{api_key_model, oidc_provider_model, service_account_principal};
+use crate::common::*;
+use axum::http::StatusCode;
+use chrono::{Duration, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseBackend, EntityTrait, IntoActiveModel, QueryFilter, Set,
+};
+use std::sync::Arc;
+use template_rust_backend::enums::{TaskRunStatus, UserRole, UserStatus};
+use template_rust_backend::models::tenant_settings::TenantSettings;
+use template_rust_backend::models::{memberships, oidc_login_states, outbox_events};
+use template_rust_backend::repositories::{
+    ApiKeyRepository, MembershipRepository, OidcRepository, SeaOrmApiKeyRepository,
+    SeaOrmMembershipRepository, SeaOrmOidcRepository, SeaOrmTaskRunRepository, TaskRunRepository,
+};
+use template_rust_backend::scheduler::{self, Scheduler};
+use template_rust_backend::services::tenant_settings_service::UpdateTenantSettingsRequest;
+
+fn scheduler(app: &TestApp) -> Scheduler {
+    scheduler::scheduler(
+        &app.state,
+        Arc::new(SeaOrmTaskRunRepository::new(app.db.clone())),
+    )
+}
+
+#[tokio::test]
+async fn test_scheduled_task_runs_once_per_tick() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (_, admin) = app.create_tenant_with_admin().await;
+    let member = app
+        .create_user(admin.tenant_id, "member@example.com", UserRole::Regular)
+        .await;
+    let tick = Utc::now() - Duration::minutes(1);
+
+    let run = scheduler(&app)
+        .run_due("purge_auth_artifacts", tick)
+        .await
+        .unwrap()
+        .expect("the first replica runs the tick");
+    assert_eq!(run.status, TaskRunStatus::Succeeded);
+    assert_eq!(run.affected, Some(0));
+
+    // Another replica reaching the same tick leaves it alone
+    let again = scheduler(&app)
+        .run_due("purge_auth_artifacts", tick)
+        .await
+        .unwrap();
+    assert!(again.is_none());
+
+    let response = app
+        .server
+        .get("/api/tasks/runs")
+        .add_query_param("task", "purge_auth_artifacts")
+        .authorization_bearer(get_test_bearer_token())
+        .await;
+    response.assert_status_ok();
+    let runs: serde_json::Value = response.json();
+    assert_eq!(runs.as_array().unwrap().len(), 1);
+    assert_eq!(runs[0]["id"], run.id.to_string());
+    assert_eq!(runs[0]["status"], "succeeded");
+    assert_eq!(runs[0]["runner"], run.runner);
+
+    // Runs span every tenant, so tenant admins cannot see them
+    for token in [app.token_for(&admin), app.token_for(&member)] {
+        app.server
+            .get("/api/tasks/runs")
+            .authorization_bearer(token)
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+    }
+    app.server
+        .get("/api/tasks/runs")
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_task_lock_excludes_other_replicas() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    // SQLite has a single scheduler and no advisory locks
+    if app.db.get_database_backend() != DatabaseBackend::Postgres {
+        return;
+    }
+    let runs = SeaOrmTaskRunRepository::new(app.db.clone());
+
+    let lock = runs
+        .try_lock("purge_auth_artifacts")
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(
+        runs.try_lock("purge_auth_artifacts")
+            .await
+            .unwrap()
+            .is_none()
+    );
+    let other = runs.try_lock("erase_deleted_users").await.unwrap().unwrap();
+    other.release().await.unwrap();
+
+    lock.release().await.unwrap();
+    let lock = runs
+        .try_lock("purge_auth_artifacts")
+        .await
+        .unwrap()
+        .unwrap();
+    lock.release().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_purge_deletes_expired_login_states_and_old_keys() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, _) = app.create_tenant_with_admin().await;
+
+    let oidc = SeaOrmOidcRepository::new(app.db.clone());
+    let provider = oidc
+        .insert_provider(
+            oidc_provider_model(tenant.id, "https://idp.example.com")
+                .into_active_model()
+                .reset_all(),
+        )
+        .await
+        .unwrap();
+    for (state, expires_in) in [("expired", -1), ("pending", 10)] {
+        oidc.insert_login_state(oidc_login_states::ActiveModel {
+            state: Set(state.to_string()),
+            provider_id: Set(provider.id),
+            nonce: Set("nonce".to_string()),
+            code_verifier: Set("verifier".to_string()),
+            expires_at: Set((Utc::now() + Duration::minutes(expires_in)).fixed_offset()),
+        })
+        .await
+        .unwrap();
+    }
+
+    let account = app
+        .state
+        .api_keys
+        .create_service_account(
+            &service_account_principal(tenant.id, UserRole::Admin, vec![]),
+            tenant.id,
+            "ci".to_string(),
+            UserRole::Regular,
+        )
+        .await
+        .unwrap();
+    let keys = SeaOrmApiKeyRepository::new(app.db.clone());
+    let long_ago = (Utc::now() - Duration::days(45)).fixed_offset();
+    let recently = (Utc::now() - Duration::days(1)).fixed_offset();
+    let mut kept = Vec::new();
+    for (prefix, revoked_at, expires_at) in [
+        ("sk_revoked_long_ago", Some(long_ago), None),
+        ("sk_expired_long_ago", None, Some(long_ago)),
+        ("sk_revoked_recently", Some(recently), None),
+        ("sk_expired_recently", None, Some(recently)),
+        ("sk_live", None, None),
+    ] {
+        let mut key = api_key_model(&account, prefix, "hash");
+        key.revoked_at = revoked_at;
+        key.expires_at = expires_at;
+        let key = keys
+            .insert_key(key.into_active_model().reset_all())
+            .await
+            .unwrap();
+        if !prefix.ends_with("long_ago") {
+            kept.push(key.id);
+        }
+    }
+
+    assert_eq!(
+        app.state
+            .maintenance
+            .purge_expired_auth_artifacts()
+            .await
+            .unwrap(),
+        3
+    );
+
+    assert!(oidc.take_login_state("expired").await.unwrap().is_none());
+    assert!(oidc.take_login_state("pending").await.unwrap().is_some());
+    let remaining: Vec<_> = keys
+        .list_keys(tenant.id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|key| key.id)
+        .collect();
+    assert_eq!(remaining.len(), kept.len());
+    assert!(kept.iter().all(|id| remaining.contains(id)));
+}
+
+#[tokio::test]
+async fn test_idle_members_are_deactivated() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    let idle = app
+        .create_user(tenant.id, "idle@example.com", UserRole::Regular)
+        .await;
+    let returning = app
+        .create_user(tenant.id, "returning@example.com", UserRole::Regular)
+        .await;
+    let newcomer = app
+        .create_user(tenant.id, "newcomer@example.com", UserRole::Regular)
+        .await;
+    let deleted = app
+        .create_user(tenant.id, "deleted@example.com", UserRole::Regular)
+        .await;
+
+    // Everyone but the newcomer joined long ago; only one of them came back
+    // and one was deleted since
+    let memberships = SeaOrmMembershipRepository::new(app.db.clone());
+    let long_ago = (Utc::now() - Duration::days(60)).fixed_offset();
+    for user in [&admin, &idle, &returning, &deleted] {
+        let membership = memberships.find(user.id, tenant.id).await.unwrap().unwrap();
+        let mut membership: memberships::ActiveModel = membership.into();
+        membership.created_at = Set(long_ago);
+        memberships.update(membership, Vec::new()).await.unwrap();
+    }
+    let response = app
+        .server
+        .post("/api/auth/login")
+        .authorization_bearer(get_test_bearer_token())
+        .json(&serde_json::json!({
+            "email": returning.email,
+            "password": TEST_PASSWORD
+        }))
+        .await;
+    response.assert_status_ok();
+    app.server
+        .delete(&format!("/api/tenants/{}/users/{}", tenant.id, deleted.id))
+        .authorization_bearer(app.token_for(&admin))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    let recorded = memberships
+        .find(returning.id, tenant.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(recorded.last_login_at.is_some());
+
+    // Nothing happens until the tenant opts in
+    assert_eq!(
+        app.state
+            .maintenance
+            .deactivate_idle_members()
+            .await
+            .unwrap(),
+        0
+    );
+    app.state
+        .tenant_settings
+        .update(
+            tenant.id,
+            UpdateTenantSettingsRequest {
+                version: 0,
+                settings: TenantSettings {
+                    idle_deactivation_days: Some(30),
+                    ..Default::default()
+                },
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        app.state
+            .maintenance
+            .deactivate_idle_members()
+            .await
+            .unwrap(),
+        1
+    );
+    for (user, status) in [
+        (&admin, UserStatus::Active),
+        (&idle, UserStatus::Inactive),
+        (&returning, UserStatus::Active),
+        (&newcomer, UserStatus::Active),
+    ] {
+        let membership = memberships.find(user.id, tenant.id).await.unwrap().unwrap();
+        assert_eq!(membership.status, status, "{}", user.email);
+    }
+    // Left as it was, should the user be restored
+    let membership = memberships::Entity::find_by_id((deleted.id, tenant.id))
+        .one(app.db.as_ref())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(membership.status, UserStatus::Active);
+    let deactivated = outbox_events::Entity::find()
+        .filter(outbox_events::Column::EventType.eq("UserStatusChanged"))
+        .all(app.db.as_ref())
+        .await
+        .unwrap();
+    assert_eq!(deactivated.len(), 1);
+    assert_eq!(deactivated[0].aggregate_id, idle.id);
+    assert_eq!(deactivated[0].payload["data"]["user"]["status"], "Inactive");
+    assert_eq!(deactivated[0].payload["data"]["previous_status"], "Active");
+}
+
+#[tokio::test]
+async fn test_tenant_settings_validate_idle_deactivation_days() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    let path = format!("/api/tenants/{}/settings", tenant.id);
+
+    app.server
+        .put(&path)
+        .authorization_bearer(app.token_for(&admin))
+        .json(&serde_json::json!({
+            "version": 0,
+            "settings": { "idle_deactivation_days": 0 }
+        }))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    let response = app
+        .server
+        .put(&path)
+        .authorization_bearer(app.token_for(&admin))
+        .json(&serde_json::json!({
+            "version": 0,
+            "settings": { "idle_deactivation_days": 90 }
+        }))
+        .await;
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["settings"]["idle_deactivation_days"], 90);
+}
@@ -0,0 +1,263 @@
+use crate::common::*;
+use axum::http::StatusCode;
+use serde_json::{Value, json};
+use template_rust_backend::enums::UserRole;
+use uuid::Uuid;
+
+/// Issues an API key for a new service account of the tenant, the way an
+/// admin would hand one to their directory.
+async fn scim_token(app: &TestApp, admin_token: &str, tenant_id: Uuid, scopes: Value) -> String {
+    let response = app
+        .server
+        .post(&format!("/api/tenants/{}/service-accounts", tenant_id))
+        .authorization_bearer(admin_token)
+        .json(&json!({ "name": "directory", "role": "Admin" }))
+        .await;
+    let account: Value = response.json();
+    let response = app
+        .server
+        .post(&format!(
+            "/api/tenants/{}/service-accounts/{}/api-keys",
+            tenant_id,
+            account["id"].as_str().unwrap()
+        ))
+        .authorization_bearer(admin_token)
+        .json(&json!({ "name": "scim", "scopes": scopes }))
+        .await;
+    response.assert_status(StatusCode::CREATED);
+    let issued: Value = response.json();
+    issued["secret"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn test_scim_user_lifecycle() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    let token = scim_token(
+        &app,
+        &app.token_for(&admin),
+        tenant.id,
+        json!(["scim:provision"]),
+    )
+    .await;
+
+    let response = app
+        .server
+        .post("/scim/v2/Users")
+        .authorization_bearer(&token)
+        .content_type("application/scim+json")
+        .bytes(
+            json!({
+                "schemas": ["urn:ietf:params:scim:schemas:core:2.0:User"],
+                "userName": "Jane@Example.com",
+                "name": { "givenName": "Jane", "familyName": "Doe" },
+                "active": true,
+            })
+            .to_string()
+            .into(),
+        )
+        .await;
+    response.assert_status(StatusCode::CREATED);
+    assert_eq!(
+        response.header("content-type").to_str().unwrap(),
+        "application/scim+json"
+    );
+    let created: Value = response.json();
+    let id = created["id"].as_str().unwrap().to_string();
+    assert_eq!(created["userName"], "jane@example.com");
+    assert_eq!(created["roles"][0]["value"], "Regular");
+
+    // Directories look users up by userName before creating them
+    let response = app
+        .server
+        .get("/scim/v2/Users")
+        .add_query_param("filter", r#"userName eq "JANE@example.com""#)
+        .authorization_bearer(&token)
+        .await;
+    response.assert_status_ok();
+    let list: Value = response.json();
+    assert_eq!(list["totalResults"], 1);
+    assert_eq!(list["Resources"][0]["id"], id.as_str());
+
+    let response = app
+        .server
+        .get("/scim/v2/Users")
+        .add_query_param("filter", "userName eq")
+        .authorization_bearer(&token)
+        .await;
+    response.assert_status_bad_request();
+    response.assert_json_contains(&json!({ "scimType": "invalidFilter" }));
+
+    let response = app
+        .server
+        .post("/scim/v2/Users")
+        .authorization_bearer(&token)
+        .json(&json!({ "userName": "jane@example.com" }))
+        .await;
+    response.assert_status(StatusCode::CONFLICT);
+    response.assert_json_contains(&json!({ "status": "409", "scimType": "uniqueness" }));
+
+    // Deactivation blocks sign-in
+    let response = app
+        .server
+        .patch(&format!("/scim/v2/Users/{}", id))
+        .authorization_bearer(&token)
+        .json(&json!({
+            "schemas": ["urn:ietf:params:scim:api:messages:2.0:PatchOp"],
+            "Operations": [{ "op": "Replace", "path": "active", "value": "False" }],
+        }))
+        .await;
+    response.assert_status_ok();
+    response.assert_json_contains(&json!({ "active": false }));
+    let response = app
+        .server
+        .get("/scim/v2/Users")
+        .add_query_param("filter", "active eq false")
+        .authorization_bearer(&token)
+        .await;
+    let list: Value = response.json();
+    assert_eq!(list["totalResults"], 1);
+
+    let response = app
+        .server
+        .put(&format!("/scim/v2/Users/{}", id))
+        .authorization_bearer(&token)
+        .json(&json!({
+            "userName": "jane.doe@example.com",
+            "active": true,
+            "roles": [{ "value": "admin", "primary": true }],
+        }))
+        .await;
+    response.assert_status_ok();
+    response.assert_json_contains(&json!({
+        "userName": "jane.doe@example.com",
+        "active": true,
+        "roles": [{ "value": "Admin", "primary": true }],
+    }));
+
+    app.server
+        .delete(&format!("/scim/v2/Users/{}", id))
+        .authorization_bearer(&token)
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    let response = app
+        .server
+        .get(&format!("/scim/v2/Users/{}", id))
+        .authorization_bearer(&token)
+        .await;
+    response.assert_status_not_found();
+    response.assert_json_contains(&json!({
+        "schemas": ["urn:ietf:params:scim:api:messages:2.0:Error"],
+        "status": "404",
+    }));
+}
+
+#[tokio::test]
+async fn test_scim_is_scoped_to_the_token_tenant() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    let token = scim_token(
+        &app,
+        &app.token_for(&admin),
+        tenant.id,
+        json!(["scim:provision"]),
+    )
+    .await;
+    let other = app.create_tenant("Globex").await;
+    let outsider = app
+        .create_user(other.id, "outsider@globex.com", UserRole::Regular)
+        .await;
+
+    let response = app
+        .server
+        .get("/scim/v2/Users")
+        .authorization_bearer(&token)
+        .await;
+    let list: Value = response.json();
+    // Only the tenant's own admin
+    assert_eq!(list["totalResults"], 1);
+    assert_eq!(list["Resources"][0]["userName"], admin.email.as_str());
+
+    let response = app
+        .server
+        .delete(&format!("/scim/v2/Users/{}", outsider.id))
+        .authorization_bearer(&token)
+        .await;
+    response.assert_status_not_found();
+}
+
+#[tokio::test]
+async fn test_scim_adds_user_of_another_tenant_as_member() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    let token = scim_token(
+        &app,
+        &app.token_for(&admin),
+        tenant.id,
+        json!(["scim:provision"]),
+    )
+    .await;
+    let other = app.create_tenant("Globex").await;
+    let existing = app
+        .create_user(other.id, "jane@example.com", UserRole::Regular)
+        .await;
+
+    let response = app
+        .server
+        .post("/scim/v2/Users")
+        .authorization_bearer(&token)
+        .json(&json!({ "userName": "Jane@example.com", "roles": [{ "value": "admin" }] }))
+        .await;
+    response.assert_status(StatusCode::CREATED);
+    response.assert_json_contains(&json!({
+        "id": existing.id,
+        "userName": "jane@example.com",
+        "roles": [{ "value": "Admin" }],
+    }));
+
+    // Already a member now
+    let response = app
+        .server
+        .post("/scim/v2/Users")
+        .authorization_bearer(&token)
+        .json(&json!({ "userName": "jane@example.com" }))
+        .await;
+    response.assert_status(StatusCode::CONFLICT);
+    response.assert_json_contains(&json!({ "status": "409", "scimType": "uniqueness" }));
+}
+
+#[tokio::test]
+async fn test_scim_requires_provisioning_scope() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    let token = scim_token(
+        &app,
+        &app.token_for(&admin),
+        tenant.id,
+        json!(["users:read"]),
+    )
+    .await;
+
+    let response = app
+        .server
+        .get("/scim/v2/Users")
+        .authorization_bearer(&token)
+        .await;
+    response.assert_status_forbidden();
+    assert_eq!(
+        response.header("content-type").to_str().unwrap(),
+        "application/scim+json"
+    );
+    response.assert_json_contains(&json!({ "status": "403" }));
+
+    let response = app.server.get("/scim/v2/Users").await;
+    response.assert_status_unauthorized();
+}
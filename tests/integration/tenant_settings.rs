@@ -0,0 +1,187 @@
+use crate::common::*;
+use template_rust_backend::enums::UserRole;
+use template_rust_backend::services::auth_service::AuthService;
+
+async fn register(
+    app: &TestApp,
+    tenant_id: uuid::Uuid,
+    email: &str,
+    password: &str,
+) -> axum_test::TestResponse {
+    app.server
+        .post("/api/auth/register")
+        .authorization_bearer(get_test_bearer_token())
+        .json(&serde_json::json!({
+            "tenant_id": tenant_id,
+            "email": email,
+            "password": password
+        }))
+        .await
+}
+
+#[tokio::test]
+async fn test_admin_reads_and_updates_settings() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    let path = format!("/api/tenants/{}/settings", tenant.id);
+
+    let response = app
+        .server
+        .get(&path)
+        .authorization_bearer(app.token_for(&admin))
+        .await;
+    response.assert_status_ok();
+    response.assert_json(&serde_json::json!({
+        "tenant_id": tenant.id,
+        "version": 0,
+        "settings": {
+            "registration_open": true,
+            "jwt_expiration_minutes": null,
+            "password_min_length": null,
+            "password_min_character_classes": null,
+            "password_min_strength": null,
+            "password_reject_personal_info": null,
+            "password_history": null,
+            "idle_deactivation_days": null
+        }
+    }));
+
+    let response = app
+        .server
+        .put(&path)
+        .authorization_bearer(app.token_for(&admin))
+        .json(&serde_json::json!({
+            "version": 0,
+            "settings": { "jwt_expiration_minutes": 120 }
+        }))
+        .await;
+    response.assert_status_ok();
+    response.assert_json_contains(&serde_json::json!({
+        "version": 1,
+        "settings": { "registration_open": true, "jwt_expiration_minutes": 120 }
+    }));
+
+    // Updating from a version that is no longer current
+    let response = app
+        .server
+        .put(&path)
+        .authorization_bearer(app.token_for(&admin))
+        .json(&serde_json::json!({ "version": 0, "settings": {} }))
+        .await;
+    response.assert_status(axum::http::StatusCode::CONFLICT);
+    response.assert_json_contains(&serde_json::json!({ "error": "SETTINGS_VERSION_CONFLICT" }));
+
+    // Tokens issued for the tenant use its lifetime
+    let response = app
+        .server
+        .post("/api/auth/login")
+        .authorization_bearer(get_test_bearer_token())
+        .json(&serde_json::json!({
+            "email": "admin@example.com",
+            "password": TEST_PASSWORD
+        }))
+        .await;
+    let body: serde_json::Value = response.json();
+    let claims =
+        AuthService::verify_token(body["token"].as_str().unwrap(), &app.config.jwt_secret).unwrap();
+    assert!(claims.exp - chrono::Utc::now().timestamp() > 110 * 60);
+}
+
+#[tokio::test]
+async fn test_settings_are_validated_and_admin_only() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    let regular = app
+        .create_user(tenant.id, "user@example.com", UserRole::Regular)
+        .await;
+    let path = format!("/api/tenants/{}/settings", tenant.id);
+
+    let response = app
+        .server
+        .put(&path)
+        .authorization_bearer(app.token_for(&admin))
+        .json(&serde_json::json!({
+            "version": 0,
+            "settings": {
+                "password_min_length": 4,
+                "password_history": 99
+            }
+        }))
+        .await;
+    response.assert_status_bad_request();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["error"], "VALIDATION_ERROR");
+    let message = body["message"].as_str().unwrap();
+    assert!(message.contains("settings.password_history"));
+    assert!(message.contains("settings.password_min_length"));
+
+    let response = app
+        .server
+        .put(&path)
+        .authorization_bearer(app.token_for(&admin))
+        .json(&serde_json::json!({
+            "version": 0,
+            "settings": { "registration_opne": false }
+        }))
+        .await;
+    response.assert_status_bad_request();
+    response.assert_json_contains(&serde_json::json!({ "error": "INVALID_REQUEST_BODY" }));
+
+    let response = app
+        .server
+        .get(&path)
+        .authorization_bearer(app.token_for(&regular))
+        .await;
+    response.assert_status_forbidden();
+    response.assert_json_contains(&serde_json::json!({ "error": "ADMIN_ROLE_REQUIRED" }));
+}
+
+#[tokio::test]
+async fn test_registration_follows_tenant_settings() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    let path = format!("/api/tenants/{}/settings", tenant.id);
+
+    app.server
+        .put(&path)
+        .authorization_bearer(app.token_for(&admin))
+        .json(&serde_json::json!({
+            "version": 0,
+            "settings": { "password_min_length": 12 }
+        }))
+        .await
+        .assert_status_ok();
+
+    let response = register(&app, tenant.id, "short@example.com", "password123").await;
+    response.assert_status_bad_request();
+    response.assert_json_contains(&serde_json::json!({ "error": "VALIDATION_ERROR" }));
+    register(&app, tenant.id, "long@example.com", "lengthy-secret123")
+        .await
+        .assert_status_ok();
+
+    app.server
+        .put(&path)
+        .authorization_bearer(app.token_for(&admin))
+        .json(&serde_json::json!({
+            "version": 1,
+            "settings": { "registration_open": false }
+        }))
+        .await
+        .assert_status_ok();
+
+    let response = register(&app, tenant.id, "closed@example.com", "lengthy-secret123").await;
+    response.assert_status_forbidden();
+    response.assert_json_contains(&serde_json::json!({ "error": "REGISTRATION_CLOSED" }));
+
+    // Other tenants keep the defaults
+    let other = app.create_tenant("Globex").await;
+    register(&app, other.id, "closed@example.com", "password123")
+        .await
+        .assert_status_ok();
+}
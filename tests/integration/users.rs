@@ -1,46 +1,445 @@
-use axum_test::TestServer;
-use template_rust_backend::routes;
 use crate::common::*;
+use axum::http::StatusCode;
+use chrono::{Duration, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use template_rust_backend::enums::{Locale, UserRole};
+use template_rust_backend::models::users;
+use template_rust_backend::services::auth_service::AuthService;
+use uuid::Uuid;
 
 #[tokio::test]
-#[ignore] // Ignore until test DB is set up
 async fn test_get_current_user() {
-    // This would require:
-    // 1. Register/login to get a token
-    // 2. Use that token to call /api/me
-    let app = setup_test_app().await;
-    let server = TestServer::new(app).unwrap();
-    
-    // Placeholder - would need actual JWT token
-    // let response = server
-    //     .get("/api/me")
-    //     .add_header("Authorization", "Bearer <token>")
-    //     .await;
-    // 
-    // response.assert_status_ok();
-}
-
-#[tokio::test]
-#[ignore]
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+
+    let response = app
+        .server
+        .get("/api/me")
+        .authorization_bearer(app.token_for(&admin))
+        .await;
+
+    response.assert_status_ok();
+    response.assert_json_contains(&serde_json::json!({
+        "id": admin.id,
+        "email": admin.email,
+        "tenant_id": tenant.id,
+        "role": "Admin",
+        "status": "Active"
+    }));
+}
+
+#[tokio::test]
+async fn test_get_current_user_requires_auth() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+
+    let response = app.server.get("/api/me").await;
+    response.assert_status_unauthorized();
+    response.assert_json_contains(&serde_json::json!({ "error": "MISSING_TOKEN" }));
+
+    let response = app
+        .server
+        .get("/api/me")
+        .authorization_bearer("not-a-jwt")
+        .await;
+    response.assert_status_unauthorized();
+    response.assert_json_contains(&serde_json::json!({ "error": "INVALID_TOKEN" }));
+}
+
+#[tokio::test]
 async fn test_get_user_by_id() {
-    // Test GET /api/tenants/{tenant_id}/users/{user_id}
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    let user = app
+        .create_user(tenant.id, "user@example.com", UserRole::Regular)
+        .await;
+
+    let response = app
+        .server
+        .get(&format!("/api/tenants/{}/users/{}", tenant.id, admin.id))
+        .authorization_bearer(app.token_for(&user))
+        .await;
+    response.assert_status_ok();
+    response.assert_json_contains(&serde_json::json!({
+        "id": admin.id,
+        "email": admin.email
+    }));
+
+    let response = app
+        .server
+        .get(&format!(
+            "/api/tenants/{}/users/{}",
+            tenant.id,
+            Uuid::now_v7()
+        ))
+        .authorization_bearer(app.token_for(&user))
+        .await;
+    response.assert_status_not_found();
+    response.assert_json_contains(&serde_json::json!({ "error": "USER_NOT_FOUND" }));
 }
 
 #[tokio::test]
-#[ignore]
 async fn test_get_users_list() {
-    // Test GET /api/tenants/{tenant_id}/users (admin only)
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    let user = app
+        .create_user(tenant.id, "user@example.com", UserRole::Regular)
+        .await;
+    // Users from other tenants must not leak into the list
+    let other = app.create_tenant("Globex").await;
+    app.create_user(other.id, "other@example.com", UserRole::Admin)
+        .await;
+
+    let response = app
+        .server
+        .get(&format!("/api/tenants/{}/users", tenant.id))
+        .authorization_bearer(app.token_for(&admin))
+        .await;
+    response.assert_status_ok();
+    let users: Vec<serde_json::Value> = response.json();
+    assert_eq!(users.len(), 2);
+    assert!(users.iter().all(|user| user.get("password_hash").is_none()));
+
+    // Admin only
+    let response = app
+        .server
+        .get(&format!("/api/tenants/{}/users", tenant.id))
+        .authorization_bearer(app.token_for(&user))
+        .await;
+    response.assert_status_forbidden();
+    response.assert_json_contains(&serde_json::json!({ "error": "ADMIN_ROLE_REQUIRED" }));
+
+    // Own tenant only
+    let response = app
+        .server
+        .get(&format!("/api/tenants/{}/users", other.id))
+        .authorization_bearer(app.token_for(&admin))
+        .await;
+    response.assert_status_forbidden();
+    response.assert_json_contains(&serde_json::json!({ "error": "TENANT_ACCESS_DENIED" }));
 }
 
 #[tokio::test]
-#[ignore]
 async fn test_change_user_status() {
-    // Test PUT /api/tenants/{tenant_id}/users/{user_id}/change-status
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    let user = app
+        .create_user(tenant.id, "user@example.com", UserRole::Regular)
+        .await;
+    let path = format!("/api/tenants/{}/users/{}/change-status", tenant.id, user.id);
+
+    let response = app
+        .server
+        .put(&path)
+        .authorization_bearer(app.token_for(&admin))
+        .await;
+    response.assert_status_ok();
+    response.assert_json_contains(&serde_json::json!({
+        "id": user.id,
+        "status": "Inactive"
+    }));
+
+    let response = app
+        .server
+        .put(&path)
+        .authorization_bearer(app.token_for(&admin))
+        .await;
+    response.assert_json_contains(&serde_json::json!({ "status": "Active" }));
 }
 
 #[tokio::test]
-#[ignore]
 async fn test_change_user_role() {
-    // Test PUT /api/tenants/{tenant_id}/users/{user_id}/change-role
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    let user = app
+        .create_user(tenant.id, "user@example.com", UserRole::Regular)
+        .await;
+
+    let response = app
+        .server
+        .put(&format!(
+            "/api/tenants/{}/users/{}/change-role",
+            tenant.id, user.id
+        ))
+        .authorization_bearer(app.token_for(&admin))
+        .await;
+    response.assert_status_ok();
+    response.assert_json_contains(&serde_json::json!({
+        "id": user.id,
+        "role": "Admin"
+    }));
+
+    // The role comes from the membership, so the promoted user's token
+    // already carries admin rights
+    let promoted_by_user = app
+        .create_user(tenant.id, "other@example.com", UserRole::Regular)
+        .await;
+    let response = app
+        .server
+        .put(&format!(
+            "/api/tenants/{}/users/{}/change-role",
+            tenant.id, promoted_by_user.id
+        ))
+        .authorization_bearer(app.token_for(&user))
+        .await;
+    response.assert_status_ok();
+    let regular = app
+        .create_user(tenant.id, "regular@example.com", UserRole::Regular)
+        .await;
+
+    // Regular users cannot change roles
+    let response = app
+        .server
+        .put(&format!(
+            "/api/tenants/{}/users/{}/change-role",
+            tenant.id, admin.id
+        ))
+        .authorization_bearer(app.token_for(&regular))
+        .await;
+    response.assert_status_forbidden();
+}
+
+#[tokio::test]
+async fn test_set_locale_applies_to_next_token() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    app.state
+        .tenants
+        .set_default_locale(tenant.id, Some(Locale::Pt))
+        .await
+        .unwrap();
+
+    let response = app
+        .server
+        .put("/api/me/locale")
+        .authorization_bearer(app.token_for(&admin))
+        .json(&serde_json::json!({ "locale": "es" }))
+        .await;
+    response.assert_status_ok();
+    response.assert_json(&serde_json::json!({ "id": admin.id, "locale": "es" }));
+
+    let login = |app: &TestApp| {
+        app.server
+            .post("/api/auth/login")
+            .authorization_bearer(get_test_bearer_token())
+            .json(&serde_json::json!({
+                "email": admin.email,
+                "password": TEST_PASSWORD
+            }))
+    };
+    let body: serde_json::Value = login(&app).await.json();
+    let claims =
+        AuthService::verify_token(body["token"].as_str().unwrap(), &app.config.jwt_secret).unwrap();
+    assert_eq!(claims.locale, Some(Locale::Es));
+
+    // Clearing the preference falls back to the tenant default
+    app.server
+        .put("/api/me/locale")
+        .authorization_bearer(app.token_for(&admin))
+        .json(&serde_json::json!({ "locale": null }))
+        .await
+        .assert_status_ok();
+    let body: serde_json::Value = login(&app).await.json();
+    let claims =
+        AuthService::verify_token(body["token"].as_str().unwrap(), &app.config.jwt_secret).unwrap();
+    assert_eq!(claims.locale, Some(Locale::Pt));
+}
+
+#[tokio::test]
+async fn test_change_password_follows_tenant_policy() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    app.server
+        .put(&format!("/api/tenants/{}/settings", tenant.id))
+        .authorization_bearer(app.token_for(&admin))
+        .json(&serde_json::json!({
+            "version": 0,
+            "settings": { "password_min_character_classes": 3, "password_history": 2 }
+        }))
+        .await
+        .assert_status_ok();
+
+    let change = |current: &str, new: &str| {
+        app.server
+            .put("/api/me/password")
+            .authorization_bearer(app.token_for(&admin))
+            .add_header("accept", "application/problem+json")
+            .json(&serde_json::json!({
+                "current_password": current,
+                "new_password": new
+            }))
+    };
+
+    let response = change("wrong-password", "Blue-Kettle-Sings").await;
+    response.assert_status_bad_request();
+    response.assert_json_contains(&serde_json::json!({
+        "errors": [{ "field": "current_password", "code": "invalid" }]
+    }));
+
+    let response = change(TEST_PASSWORD, "acme-kettle-sings").await;
+    response.assert_status_bad_request();
+    response.assert_json_contains(&serde_json::json!({
+        "errors": [
+            { "field": "new_password", "code": "character_classes", "params": { "min": 3 } },
+            { "field": "new_password", "code": "personal_info" }
+        ]
+    }));
+
+    change(TEST_PASSWORD, "Blue-Kettle-Sings")
+        .await
+        .assert_status(axum::http::StatusCode::NO_CONTENT);
+    let login = |password: &str| {
+        app.server
+            .post("/api/auth/login")
+            .authorization_bearer(get_test_bearer_token())
+            .json(&serde_json::json!({ "email": admin.email, "password": password }))
+    };
+    login("Blue-Kettle-Sings").await.assert_status_ok();
+    login(TEST_PASSWORD).await.assert_status_unauthorized();
+
+    // The previous password is still within the history of two
+    change("Blue-Kettle-Sings", "Green-Kettle-Hums")
+        .await
+        .assert_status(axum::http::StatusCode::NO_CONTENT);
+    let response = change("Green-Kettle-Hums", "Blue-Kettle-Sings").await;
+    response.assert_status_bad_request();
+    response.assert_json_contains(&serde_json::json!({
+        "errors": [{ "field": "new_password", "code": "reused", "params": { "count": 2 } }]
+    }));
 }
 
+#[tokio::test]
+async fn test_delete_restore_and_erase_user() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    let user = app
+        .create_user(tenant.id, "user@example.com", UserRole::Regular)
+        .await;
+    let token = app.token_for(&admin);
+    let login = || async {
+        app.server
+            .post("/api/auth/login")
+            .authorization_bearer(get_test_bearer_token())
+            .json(&serde_json::json!({
+                "email": "user@example.com",
+                "password": TEST_PASSWORD
+            }))
+            .await
+    };
+    let delete = || async {
+        app.server
+            .delete(&format!("/api/tenants/{}/users/{}", tenant.id, user.id))
+            .authorization_bearer(&token)
+            .await
+    };
+    let restore = || async {
+        app.server
+            .post(&format!(
+                "/api/tenants/{}/users/{}/restore",
+                tenant.id, user.id
+            ))
+            .authorization_bearer(&token)
+            .await
+    };
+
+    delete().await.assert_status(StatusCode::NO_CONTENT);
+    app.server
+        .get(&format!("/api/tenants/{}/users/{}", tenant.id, user.id))
+        .authorization_bearer(&token)
+        .await
+        .assert_status_not_found();
+    login().await.assert_status_unauthorized();
+    delete().await.assert_status_not_found();
+
+    let response = restore().await;
+    response.assert_status_ok();
+    response.assert_json_contains(&serde_json::json!({
+        "id": user.id,
+        "email": "user@example.com",
+        "deleted_at": null
+    }));
+    login().await.assert_status_ok();
+    restore().await.assert_status_not_found();
+
+    // Once the grace period is over the user can only be erased
+    delete().await.assert_status(StatusCode::NO_CONTENT);
+    users::Entity::update_many()
+        .col_expr(
+            users::Column::DeletedAt,
+            Expr::value(Some((Utc::now() - Duration::days(31)).fixed_offset())),
+        )
+        .filter(users::Column::Id.eq(user.id))
+        .exec(app.db.as_ref())
+        .await
+        .unwrap();
+    restore().await.assert_status_not_found();
+
+    assert_eq!(app.state.users.erase_deleted().await.unwrap(), 1);
+    assert_eq!(app.state.users.erase_deleted().await.unwrap(), 0);
+    let erased = users::Entity::find_by_id(user.id)
+        .one(app.db.as_ref())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(erased.email, format!("erased-{}@erased.invalid", user.id));
+    assert!(erased.password_hash.is_empty());
+    assert!(erased.erased_at.is_some());
+
+    // The address is free again
+    app.create_user(tenant.id, "user@example.com", UserRole::Regular)
+        .await;
+}
+
+#[tokio::test]
+async fn test_deleted_user_loses_access() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    let other = app
+        .create_user(tenant.id, "other-admin@example.com", UserRole::Admin)
+        .await;
+    // Issued before the deletion and still unexpired
+    let token = app.token_for(&other);
+    let users_path = format!("/api/tenants/{}/users", tenant.id);
+    app.server
+        .get(&users_path)
+        .authorization_bearer(&token)
+        .await
+        .assert_status_ok();
+
+    app.server
+        .delete(&format!("/api/tenants/{}/users/{}", tenant.id, other.id))
+        .authorization_bearer(app.token_for(&admin))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    app.server
+        .get(&users_path)
+        .authorization_bearer(&token)
+        .await
+        .assert_status_forbidden();
+    app.server
+        .get("/scim/v2/Users")
+        .authorization_bearer(&token)
+        .await
+        .assert_status_forbidden();
+}
@@ -0,0 +1,439 @@
+use crate::common::webhooks::{ReceivedWebhook, WebhookReceiver};
+use crate::common::*;
+use axum::http::StatusCode;
+use chrono::Utc;
+use sea_orm::sea_query::Expr;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use serde_json::{Value, json};
+use template_rust_backend::enums::{JobStatus, UserRole, WebhookEvent};
+use template_rust_backend::jobs::Job;
+use template_rust_backend::jobs::webhook::DeliverWebhook;
+use template_rust_backend::models::{jobs, outbox_events, users, webhook_deliveries};
+use template_rust_backend::services::webhooks_service::{SIGNATURE_HEADER, signature_header};
+
+/// Registers the receiver for `events` and returns the webhook with its
+/// secret.
+async fn create_webhook(
+    app: &TestApp,
+    admin: &users::Model,
+    receiver: &WebhookReceiver,
+    events: &[&str],
+) -> Value {
+    let response = app
+        .server
+        .post(&format!("/api/tenants/{}/webhooks", admin.tenant_id))
+        .authorization_bearer(app.token_for(admin))
+        .json(&json!({ "url": receiver.url, "events": events }))
+        .await;
+    response.assert_status(StatusCode::CREATED);
+    response.json()
+}
+
+async fn deliveries(app: &TestApp, admin: &users::Model, webhook_id: &str) -> Vec<Value> {
+    let response = app
+        .server
+        .get(&format!(
+            "/api/tenants/{}/webhooks/{}/deliveries",
+            admin.tenant_id, webhook_id
+        ))
+        .authorization_bearer(app.token_for(admin))
+        .await;
+    response.assert_status_ok();
+    response.json()
+}
+
+/// Makes the jobs waiting for a retry due now.
+async fn make_jobs_due(app: &TestApp) {
+    jobs::Entity::update_many()
+        .col_expr(jobs::Column::RunAt, Expr::value(Utc::now().fixed_offset()))
+        .filter(jobs::Column::Status.eq(JobStatus::Pending))
+        .exec(app.db.as_ref())
+        .await
+        .unwrap();
+}
+
+fn assert_signed(request: &ReceivedWebhook, secret: &str) {
+    let signature = request.header(SIGNATURE_HEADER);
+    let timestamp: i64 = signature
+        .strip_prefix("t=")
+        .and_then(|rest| rest.split(',').next())
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((Utc::now().timestamp() - timestamp).abs() < 60);
+    assert_eq!(
+        signature,
+        signature_header(secret, timestamp, &request.body)
+    );
+}
+
+#[tokio::test]
+async fn test_user_events_are_delivered_signed() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    // Before the webhook exists, so the admin's registration is not sent
+    app.relay_events().await;
+    let receiver = WebhookReceiver::start().await;
+    let created = create_webhook(&app, &admin, &receiver, &["user.created"]).await;
+    let secret = created["secret"].as_str().unwrap();
+    assert!(secret.starts_with("whsec_"));
+    let webhook_id = created["endpoint"]["id"].as_str().unwrap();
+    assert_eq!(created["endpoint"]["events"], "user.created");
+    assert!(created["endpoint"].get("secret").is_none());
+
+    let user = app
+        .create_user(tenant.id, "jane@example.com", UserRole::Regular)
+        .await;
+    // Not subscribed
+    app.server
+        .put(&format!(
+            "/api/tenants/{}/users/{}/change-role",
+            tenant.id, user.id
+        ))
+        .authorization_bearer(app.token_for(&admin))
+        .await
+        .assert_status_ok();
+    app.run_jobs().await;
+    assert!(receiver.received().is_empty());
+    app.relay_events().await;
+    app.run_jobs().await;
+
+    let received = receiver.received();
+    assert_eq!(received.len(), 1);
+    let request = &received[0];
+    assert_signed(request, secret);
+    assert_eq!(request.header("X-Webhook-Event"), "user.created");
+    let payload = request.json();
+    assert_eq!(payload["type"], "user.created");
+    assert_eq!(payload["id"], request.header("X-Webhook-Id"));
+    // The webhook event is the domain event, under the same id
+    let event = outbox_events::Entity::find()
+        .filter(outbox_events::Column::AggregateId.eq(user.id))
+        .one(app.db.as_ref())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(event.event_type, "UserRegistered");
+    assert_eq!(payload["id"], event.id.to_string());
+    assert_eq!(payload["tenant_id"], tenant.id.to_string());
+    assert_eq!(payload["data"]["user"]["id"], user.id.to_string());
+    assert_eq!(payload["data"]["user"]["email"], "jane@example.com");
+    assert_eq!(payload["data"]["user"]["role"], "Regular");
+
+    let log = deliveries(&app, &admin, webhook_id).await;
+    assert_eq!(log.len(), 1);
+    assert_eq!(log[0]["id"], request.header("X-Webhook-Delivery"));
+    assert_eq!(log[0]["status"], "succeeded");
+    assert_eq!(log[0]["attempts"], 1);
+    assert_eq!(log[0]["response_status"], 200);
+    assert_eq!(log[0]["response_body"], "received");
+    assert_eq!(log[0]["payload"], payload);
+}
+
+#[tokio::test]
+async fn test_failed_deliveries_are_retried() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    let user = app
+        .create_user(tenant.id, "jane@example.com", UserRole::Regular)
+        .await;
+    let receiver = WebhookReceiver::start().await;
+    receiver.respond_with(&[StatusCode::SERVICE_UNAVAILABLE]);
+    let created = create_webhook(
+        &app,
+        &admin,
+        &receiver,
+        &["user.role_changed", "user.deactivated"],
+    )
+    .await;
+    let webhook_id = created["endpoint"]["id"].as_str().unwrap();
+
+    app.server
+        .put(&format!(
+            "/api/tenants/{}/users/{}/change-role",
+            tenant.id, user.id
+        ))
+        .authorization_bearer(app.token_for(&admin))
+        .await
+        .assert_status_ok();
+    app.relay_events().await;
+    app.run_jobs().await;
+
+    let log = deliveries(&app, &admin, webhook_id).await;
+    assert_eq!(log.len(), 1);
+    assert_eq!(log[0]["event"], "user.role_changed");
+    assert_eq!(log[0]["status"], "pending");
+    assert_eq!(log[0]["attempts"], 1);
+    assert_eq!(log[0]["response_status"], 503);
+    assert!(log[0]["response_body"].is_null());
+    assert!(log[0]["next_attempt_at"].is_string());
+    assert!(log[0]["error"].as_str().unwrap().contains("503"));
+
+    make_jobs_due(&app).await;
+    app.run_jobs().await;
+
+    let log = deliveries(&app, &admin, webhook_id).await;
+    assert_eq!(log[0]["status"], "succeeded");
+    assert_eq!(log[0]["attempts"], 2);
+    assert_eq!(log[0]["response_status"], 200);
+    assert!(log[0]["next_attempt_at"].is_null());
+    assert!(log[0]["error"].is_null());
+
+    // Both attempts carried the same event
+    let received = receiver.received();
+    assert_eq!(received.len(), 2);
+    assert_eq!(
+        received[0].header("X-Webhook-Id"),
+        received[1].header("X-Webhook-Id")
+    );
+    let payload = received[1].json();
+    assert_eq!(payload["data"]["user"]["role"], "Admin");
+    assert_eq!(payload["data"]["previous_role"], "Regular");
+}
+
+#[tokio::test]
+async fn test_events_published_again_are_delivered_once() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    let receiver = WebhookReceiver::start().await;
+    let created = create_webhook(&app, &admin, &receiver, &["user.created"]).await;
+    let webhook_id = created["endpoint"]["id"].as_str().unwrap();
+
+    let event_id = uuid::Uuid::now_v7();
+    for _ in 0..2 {
+        app.state
+            .webhooks
+            .publish(
+                tenant.id,
+                event_id,
+                WebhookEvent::UserCreated,
+                json!({ "user": { "email": "jane@example.com" } }),
+            )
+            .await
+            .unwrap();
+    }
+    let jobs = jobs::Entity::find()
+        .filter(jobs::Column::Kind.eq(DeliverWebhook::KIND))
+        .all(app.db.as_ref())
+        .await
+        .unwrap();
+    assert_eq!(jobs.len(), 1);
+    app.run_jobs().await;
+
+    assert_eq!(receiver.received().len(), 1);
+    let log = deliveries(&app, &admin, webhook_id).await;
+    assert_eq!(log.len(), 1);
+    assert_eq!(log[0]["event_id"], event_id.to_string());
+    assert_eq!(log[0]["redelivery_of"], Value::Null);
+}
+
+#[tokio::test]
+async fn test_ping_and_redeliver() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    let receiver = WebhookReceiver::start().await;
+    receiver.respond_with(&[StatusCode::NOT_FOUND]);
+    let created = create_webhook(&app, &admin, &receiver, &["user.created"]).await;
+    let secret = created["secret"].as_str().unwrap();
+    let webhook_id = created["endpoint"]["id"].as_str().unwrap();
+    let path = format!("/api/tenants/{}/webhooks/{}", tenant.id, webhook_id);
+
+    // A ping is sent once, right away, and not retried
+    let response = app
+        .server
+        .post(&format!("{}/ping", path))
+        .authorization_bearer(app.token_for(&admin))
+        .await;
+    response.assert_status_ok();
+    let ping: Value = response.json();
+    assert_eq!(ping["event"], "ping");
+    assert_eq!(ping["status"], "failed");
+    assert_eq!(ping["response_status"], 404);
+    assert_eq!(app.run_jobs().await, 0);
+    let received = receiver.received();
+    assert_eq!(received.len(), 1);
+    assert_signed(&received[0], secret);
+    assert_eq!(received[0].json()["data"]["webhook_id"], webhook_id);
+
+    let response = app
+        .server
+        .post(&format!(
+            "{}/deliveries/{}/redeliver",
+            path,
+            ping["id"].as_str().unwrap()
+        ))
+        .authorization_bearer(app.token_for(&admin))
+        .await;
+    response.assert_status(StatusCode::ACCEPTED);
+    let redelivery: Value = response.json();
+    assert_ne!(redelivery["id"], ping["id"]);
+    assert_eq!(redelivery["event_id"], ping["event_id"]);
+    assert_eq!(redelivery["redelivery_of"], ping["id"]);
+    assert_eq!(redelivery["status"], "pending");
+    app.run_jobs().await;
+
+    let received = receiver.received();
+    assert_eq!(received.len(), 2);
+    assert_eq!(received[1].header("X-Webhook-Id"), ping["event_id"]);
+    assert_eq!(received[1].body, received[0].body);
+    let log = deliveries(&app, &admin, webhook_id).await;
+    assert_eq!(log.len(), 2);
+    assert_eq!(log[0]["id"], redelivery["id"]);
+    assert_eq!(log[0]["status"], "succeeded");
+    assert_eq!(log[1]["status"], "failed");
+
+    let response = app
+        .server
+        .post(&format!(
+            "{}/deliveries/{}/redeliver",
+            path,
+            uuid::Uuid::now_v7()
+        ))
+        .authorization_bearer(app.token_for(&admin))
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+    response.assert_json_contains(&json!({ "error": "WEBHOOK_DELIVERY_NOT_FOUND" }));
+}
+
+#[tokio::test]
+async fn test_webhooks_are_managed_by_tenant_admins() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    let member = app
+        .create_user(tenant.id, "member@example.com", UserRole::Regular)
+        .await;
+    let receiver = WebhookReceiver::start().await;
+    let created = create_webhook(&app, &admin, &receiver, &["user.created"]).await;
+    let webhook_id = created["endpoint"]["id"].as_str().unwrap();
+    let list_path = format!("/api/tenants/{}/webhooks", tenant.id);
+
+    app.server
+        .get(&list_path)
+        .authorization_bearer(app.token_for(&member))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    app.server
+        .post(&list_path)
+        .authorization_bearer(app.token_for(&admin))
+        .json(&json!({ "url": "ftp://hooks.example.com", "events": [] }))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    // Another tenant's admin cannot see or touch the webhook
+    let other = app.create_tenant("Globex").await;
+    let outsider = app
+        .create_user(other.id, "outsider@example.com", UserRole::Admin)
+        .await;
+    let response = app
+        .server
+        .get(&format!(
+            "/api/tenants/{}/webhooks/{}/deliveries",
+            other.id, webhook_id
+        ))
+        .authorization_bearer(app.token_for(&outsider))
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+    response.assert_json_contains(&json!({ "error": "WEBHOOK_NOT_FOUND" }));
+
+    let response = app
+        .server
+        .get(&list_path)
+        .authorization_bearer(app.token_for(&admin))
+        .await;
+    response.assert_status_ok();
+    let listed: Vec<Value> = response.json();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0]["url"], receiver.url);
+    assert!(listed[0].get("secret").is_none());
+
+    app.server
+        .delete(&format!("{}/{}", list_path, webhook_id))
+        .authorization_bearer(app.token_for(&admin))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    app.server
+        .delete(&format!("{}/{}", list_path, webhook_id))
+        .authorization_bearer(app.token_for(&admin))
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    // Nothing is sent to a deleted webhook
+    app.create_user(tenant.id, "late@example.com", UserRole::Regular)
+        .await;
+    app.relay_events().await;
+    app.run_jobs().await;
+    assert!(receiver.received().is_empty());
+}
+
+#[tokio::test]
+async fn test_erasure_scrubs_events_and_deliveries() {
+    let Some(app) = TestApp::spawn().await else {
+        return;
+    };
+    let (tenant, admin) = app.create_tenant_with_admin().await;
+    app.relay_events().await;
+    let receiver = WebhookReceiver::start().await;
+    create_webhook(&app, &admin, &receiver, &["user.created"]).await;
+    let jane = app
+        .create_user(tenant.id, "jane@example.com", UserRole::Regular)
+        .await;
+    app.relay_events().await;
+    app.run_jobs().await;
+    assert_eq!(receiver.received().len(), 1);
+    // An endpoint that echoed what it was sent
+    webhook_deliveries::Entity::update_many()
+        .col_expr(
+            webhook_deliveries::Column::ResponseBody,
+            Expr::value(Some("jane@example.com".to_string())),
+        )
+        .exec(app.db.as_ref())
+        .await
+        .unwrap();
+
+    app.server
+        .delete(&format!("/api/tenants/{}/users/{}", tenant.id, jane.id))
+        .authorization_bearer(app.token_for(&admin))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    users::Entity::update_many()
+        .col_expr(
+            users::Column::DeletedAt,
+            Expr::value(Some(
+                (Utc::now() - chrono::Duration::days(31)).fixed_offset(),
+            )),
+        )
+        .filter(users::Column::Id.eq(jane.id))
+        .exec(app.db.as_ref())
+        .await
+        .unwrap();
+    assert_eq!(app.state.users.erase_deleted().await.unwrap(), 1);
+
+    let erased = format!("erased-{}@erased.invalid", jane.id);
+    let events = outbox_events::Entity::find()
+        .filter(outbox_events::Column::AggregateId.eq(jane.id))
+        .all(app.db.as_ref())
+        .await
+        .unwrap();
+    assert!(!events.is_empty());
+    for event in &events {
+        assert_eq!(event.payload["data"]["user"]["email"], erased.as_str());
+    }
+    let delivery = webhook_deliveries::Entity::find()
+        .one(app.db.as_ref())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!delivery.payload.to_string().contains("jane@example.com"));
+    assert_eq!(delivery.payload["data"]["user"]["email"], erased.as_str());
+    assert!(delivery.response_body.is_none());
+}
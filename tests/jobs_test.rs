@@ -0,0 +1,229 @@
+// Job queue and worker tests against mocked repositories; no database required.
+
+pub mod common;
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use common::mocks::*;
+use sea_orm::{ActiveValue, TryIntoModel};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use template_rust_backend::config::JobsConfig;
+use template_rust_backend::enums::JobStatus;
+use template_rust_backend::jobs::worker::retry_delay;
+use template_rust_backend::jobs::{Job, JobHandler, JobRegistry, Worker};
+use template_rust_backend::models::jobs;
+use template_rust_backend::services::jobs_service::{JobQueue, JobsService, NewJob};
+use template_rust_backend::utils::error::AppError;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Greet {
+    name: String,
+}
+
+impl Job for Greet {
+    const KIND: &'static str = "greet";
+}
+
+/// Fails for the name "fail", panics for "panic", succeeds otherwise.
+#[derive(Default)]
+struct GreetHandler {
+    greeted: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl JobHandler for GreetHandler {
+    type Job = Greet;
+
+    async fn handle(&self, job: Greet) -> Result<(), AppError> {
+        match job.name.as_str() {
+            "fail" => Err(AppError::ServiceUnavailable),
+            "panic" => panic!("handler panicked"),
+            _ => {
+                self.greeted.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A job as claimed: running, on its `attempts`th attempt out of 3.
+fn claimed(payload: serde_json::Value, attempts: i32) -> jobs::Model {
+    let mut job = job_model(Greet::KIND, payload);
+    job.status = JobStatus::Running;
+    job.attempts = attempts;
+    job.max_attempts = 3;
+    job.locked_by = Some("worker".to_string());
+    job.locked_at = Some(Utc::now().fixed_offset());
+    job
+}
+
+/// Runs one batch holding `job`, and returns the job as recorded afterwards.
+async fn run_batch(job: jobs::Model, handler: GreetHandler) -> jobs::Model {
+    let recorded = Arc::new(std::sync::Mutex::new(None));
+    let mut repo = MockJobRepository::new();
+    repo.expect_claim()
+        .times(1)
+        .returning(move |_, _, _| Ok(vec![job.clone()]));
+    let sink = recorded.clone();
+    repo.expect_finish()
+        .withf(|_, worker| worker.starts_with("worker-"))
+        .times(1)
+        .returning(move |job, _| {
+            let job = job.try_into_model().unwrap();
+            *sink.lock().unwrap() = Some(job.clone());
+            Ok(Some(job))
+        });
+
+    let worker = Worker::new(
+        Arc::new(repo),
+        JobRegistry::new().register(handler),
+        JobsConfig::default(),
+    );
+    assert_eq!(worker.run_once().await.unwrap(), 1);
+    recorded.lock().unwrap().take().unwrap()
+}
+
+#[test]
+fn test_retry_delay_doubles_up_to_an_hour() {
+    assert_eq!(retry_delay(1), Duration::seconds(10));
+    assert_eq!(retry_delay(2), Duration::seconds(20));
+    assert_eq!(retry_delay(3), Duration::seconds(40));
+    assert_eq!(retry_delay(10), Duration::hours(1));
+    assert_eq!(retry_delay(i32::MAX), Duration::hours(1));
+}
+
+#[test]
+#[should_panic(expected = "registered twice")]
+fn test_registering_a_kind_twice_panics() {
+    let _ = JobRegistry::new()
+        .register(GreetHandler::default())
+        .register(GreetHandler::default());
+}
+
+#[tokio::test]
+async fn test_worker_completes_successful_job() {
+    let handler = GreetHandler::default();
+    let greeted = handler.greeted.clone();
+
+    let job = run_batch(claimed(serde_json::json!({ "name": "Ada" }), 1), handler).await;
+    assert_eq!(job.status, JobStatus::Completed);
+    assert!(job.completed_at.is_some());
+    assert_eq!(job.locked_by, None);
+    assert_eq!(greeted.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_worker_retries_failed_job_with_backoff() {
+    let before = Utc::now();
+    let job = run_batch(
+        claimed(serde_json::json!({ "name": "fail" }), 2),
+        GreetHandler::default(),
+    )
+    .await;
+    assert_eq!(job.status, JobStatus::Pending);
+    assert!(job.run_at >= before + retry_delay(2));
+    assert_eq!(job.last_error.as_deref(), Some("Service unavailable"));
+    assert_eq!(job.locked_at, None);
+}
+
+#[tokio::test]
+async fn test_worker_kills_job_out_of_attempts() {
+    let job = run_batch(
+        claimed(serde_json::json!({ "name": "fail" }), 3),
+        GreetHandler::default(),
+    )
+    .await;
+    assert_eq!(job.status, JobStatus::Dead);
+    assert_eq!(job.completed_at, None);
+}
+
+#[tokio::test]
+async fn test_worker_kills_job_with_invalid_payload_at_once() {
+    let job = run_batch(
+        claimed(serde_json::json!({ "unexpected": true }), 1),
+        GreetHandler::default(),
+    )
+    .await;
+    assert_eq!(job.status, JobStatus::Dead);
+    assert!(job.last_error.unwrap().starts_with("Invalid payload"));
+}
+
+#[tokio::test]
+async fn test_worker_survives_panicking_handler() {
+    let job = run_batch(
+        claimed(serde_json::json!({ "name": "panic" }), 1),
+        GreetHandler::default(),
+    )
+    .await;
+    assert_eq!(job.status, JobStatus::Pending);
+    assert!(job.last_error.unwrap().starts_with("Handler panicked"));
+}
+
+#[tokio::test]
+async fn test_worker_retries_job_without_handler() {
+    let mut job = claimed(serde_json::json!({}), 1);
+    job.kind = "unknown".to_string();
+
+    let job = run_batch(job, GreetHandler::default()).await;
+    assert_eq!(job.status, JobStatus::Pending);
+    assert_eq!(
+        job.last_error.as_deref(),
+        Some("No handler for job kind 'unknown'")
+    );
+}
+
+#[tokio::test]
+async fn test_enqueue_applies_defaults_and_schedule() {
+    let mut repo = MockJobRepository::new();
+    repo.expect_insert()
+        .returning(|job| Ok(job.try_into_model().unwrap()));
+    let queue = JobsService::new(Arc::new(repo), 7);
+
+    let job = NewJob::of(&Greet {
+        name: "Ada".to_string(),
+    })
+    .unwrap();
+    let now = queue.enqueue(job.clone()).await.unwrap();
+    assert_eq!(now.kind, "greet");
+    assert_eq!(now.payload, serde_json::json!({ "name": "Ada" }));
+    assert_eq!(now.status, JobStatus::Pending);
+    assert_eq!(now.max_attempts, 7);
+    assert!(now.run_at <= Utc::now());
+
+    let later = queue
+        .enqueue(job.after(Duration::minutes(5)).max_attempts(1))
+        .await
+        .unwrap();
+    assert!(later.run_at > Utc::now() + Duration::minutes(4));
+    assert_eq!(later.max_attempts, 1);
+}
+
+#[tokio::test]
+async fn test_retry_only_requeues_dead_jobs() {
+    let mut dead = claimed(serde_json::json!({ "name": "fail" }), 3);
+    dead.status = JobStatus::Dead;
+    let dead_id = dead.id;
+    let pending = job_model(Greet::KIND, serde_json::json!({ "name": "Ada" }));
+    let pending_id = pending.id;
+
+    let mut repo = MockJobRepository::new();
+    repo.expect_find_by_id().returning(move |job_id| {
+        Ok([dead.clone(), pending.clone()]
+            .into_iter()
+            .find(|job| job.id == job_id))
+    });
+    repo.expect_update()
+        .withf(|job| {
+            job.status == ActiveValue::Set(JobStatus::Pending)
+                && job.attempts == ActiveValue::Set(0)
+        })
+        .times(1)
+        .returning(|job| Ok(job.try_into_model().unwrap()));
+    let queue = JobsService::new(Arc::new(repo), 5);
+
+    let retried = queue.retry(dead_id).await.unwrap().unwrap();
+    assert_eq!(retried.status, JobStatus::Pending);
+    assert!(queue.retry(pending_id).await.unwrap().is_none());
+}
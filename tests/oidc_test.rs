@@ -0,0 +1,359 @@
+// OIDC login tests against the local mock provider and mocked repositories;
+// no database required.
+
+pub mod common;
+
+use common::mocks::*;
+use common::oidc::{self, MockOidcServer};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use template_rust_backend::enums::UserRole;
+use template_rust_backend::models::{oidc_login_states, oidc_providers};
+use template_rust_backend::services::auth_service::AuthResponse;
+use template_rust_backend::services::oidc_service::{
+    CreateOidcProviderRequest, OidcAuthService, OidcService,
+};
+use template_rust_backend::utils::error::AppError;
+use template_rust_backend::utils::http_client::HttpClient;
+use uuid::Uuid;
+
+fn keys() -> jsonwebtoken::jwk::JwkSet {
+    serde_json::from_value(oidc::jwks()).unwrap()
+}
+
+fn id_token(provider: &oidc_providers::Model, extra: Value) -> String {
+    let mut claims = json!({
+        "iss": provider.issuer,
+        "aud": provider.client_id,
+        "sub": "user-123",
+        "email": "Jane@Example.com",
+        "email_verified": true,
+        "nonce": "nonce-1",
+        "exp": chrono::Utc::now().timestamp() + 60,
+    });
+    if let Value::Object(extra) = extra {
+        claims.as_object_mut().unwrap().extend(extra);
+    }
+    oidc::sign_id_token(&claims)
+}
+
+#[test]
+fn test_pkce_challenge_is_s256() {
+    // Computed independently: base64url(sha256(verifier)) without padding
+    assert_eq!(
+        OidcAuthService::pkce_challenge("dBjftJeZ4CVP-mJ92K9qDVtiJ0jEjn1r9TDpZmHNCdI"),
+        "8xxzIINJqkF-jUKRyoMxoXEMnbi2b34oMOedeco_iZ4"
+    );
+
+    let verifier = OidcAuthService::random_token();
+    assert_eq!(verifier.len(), 43);
+    assert_ne!(verifier, OidcAuthService::random_token());
+}
+
+#[test]
+fn test_verify_id_token_accepts_valid_token() {
+    let provider = oidc_provider_model(Uuid::now_v7(), "https://idp.example.com");
+    let token = id_token(&provider, json!({}));
+
+    let identity = OidcAuthService::verify_id_token(&token, &keys(), &provider, "nonce-1").unwrap();
+
+    assert_eq!(identity.subject, "user-123");
+    assert_eq!(identity.email, "jane@example.com");
+    assert!(identity.email_verified);
+    assert!(!identity.is_admin);
+}
+
+#[test]
+fn test_verify_id_token_rejects_tampered_claims() {
+    let provider = oidc_provider_model(Uuid::now_v7(), "https://idp.example.com");
+    let cases = [
+        ("wrong nonce", id_token(&provider, json!({})), "nonce-2"),
+        (
+            "wrong audience",
+            id_token(&provider, json!({ "aud": "another-client" })),
+            "nonce-1",
+        ),
+        (
+            "wrong issuer",
+            id_token(&provider, json!({ "iss": "https://evil.example.com" })),
+            "nonce-1",
+        ),
+        (
+            "expired",
+            id_token(
+                &provider,
+                json!({ "exp": chrono::Utc::now().timestamp() - 3600 }),
+            ),
+            "nonce-1",
+        ),
+        (
+            "missing email",
+            id_token(&provider, json!({ "email": null })),
+            "nonce-1",
+        ),
+    ];
+
+    for (case, token, nonce) in cases {
+        let result = OidcAuthService::verify_id_token(&token, &keys(), &provider, nonce);
+        assert!(
+            matches!(result, Err(AppError::OidcLoginFailed)),
+            "{} was accepted",
+            case
+        );
+    }
+}
+
+#[test]
+fn test_verify_id_token_rejects_symmetric_signature() {
+    let provider = oidc_provider_model(Uuid::now_v7(), "https://idp.example.com");
+    // Signed with the client secret, which a provider's JWKS never vouches for
+    let token = jsonwebtoken::encode(
+        &jsonwebtoken::Header::default(),
+        &json!({
+            "iss": provider.issuer,
+            "aud": provider.client_id,
+            "sub": "user-123",
+            "email": "jane@example.com",
+            "nonce": "nonce-1",
+            "exp": chrono::Utc::now().timestamp() + 60,
+        }),
+        &jsonwebtoken::EncodingKey::from_secret(provider.client_secret.as_bytes()),
+    )
+    .unwrap();
+
+    let result = OidcAuthService::verify_id_token(&token, &keys(), &provider, "nonce-1");
+    assert!(matches!(result, Err(AppError::OidcLoginFailed)));
+}
+
+#[test]
+fn test_verify_id_token_maps_role_claim() {
+    let mut provider = oidc_provider_model(Uuid::now_v7(), "https://idp.example.com");
+    provider.role_claim = Some("groups".to_string());
+    provider.admin_role_value = Some("app-admins".to_string());
+
+    let token = id_token(&provider, json!({ "groups": ["staff", "app-admins"] }));
+    let identity = OidcAuthService::verify_id_token(&token, &keys(), &provider, "nonce-1").unwrap();
+    assert!(identity.is_admin);
+
+    let token = id_token(&provider, json!({ "groups": "staff" }));
+    let identity = OidcAuthService::verify_id_token(&token, &keys(), &provider, "nonce-1").unwrap();
+    assert!(!identity.is_admin);
+}
+
+/// A service over the given mocks whose login states live in memory, the way
+/// the database would keep them between authorize and callback.
+fn service(
+    provider: oidc_providers::Model,
+    mut repo: MockOidcRepository,
+    users: MockUserRepository,
+    user_service: MockUserService,
+    auth: MockAuthenticationService,
+) -> OidcAuthService {
+    let states: Arc<Mutex<HashMap<String, oidc_login_states::Model>>> = Arc::default();
+    let inserted = states.clone();
+    repo.expect_insert_login_state().returning(move |state| {
+        let state = oidc_login_states::Model {
+            state: state.state.unwrap(),
+            provider_id: state.provider_id.unwrap(),
+            nonce: state.nonce.unwrap(),
+            code_verifier: state.code_verifier.unwrap(),
+            expires_at: state.expires_at.unwrap(),
+        };
+        inserted
+            .lock()
+            .unwrap()
+            .insert(state.state.clone(), state.clone());
+        Ok(state)
+    });
+    repo.expect_take_login_state()
+        .returning(move |state| Ok(states.lock().unwrap().remove(state)));
+    repo.expect_find_provider()
+        .returning(move |_| Ok(Some(provider.clone())));
+
+    OidcAuthService::new(
+        Arc::new(repo),
+        Arc::new(users),
+        Arc::new(user_service),
+        Arc::new(auth),
+        HttpClient::new(Duration::from_secs(10), true),
+    )
+}
+
+fn signing_in(user_id: Uuid) -> MockAuthenticationService {
+    let mut auth = MockAuthenticationService::new();
+    auth.expect_sign_in()
+        .withf(move |user| user.id == user_id)
+        .times(1)
+        .returning(|user| {
+            Ok(AuthResponse {
+                token: "jwt".to_string(),
+                user,
+                memberships: vec![],
+            })
+        });
+    auth
+}
+
+#[tokio::test]
+async fn test_login_provisions_new_user_with_default_role() {
+    let idp = MockOidcServer::start().await;
+    let mut provider = oidc_provider_model(Uuid::now_v7(), &idp.issuer);
+    provider.default_role = UserRole::Admin;
+    let tenant_id = provider.tenant_id;
+
+    let mut repo = MockOidcRepository::new();
+    repo.expect_find_identity().returning(|_, _| Ok(None));
+    repo.expect_insert_identity()
+        .withf(|identity| identity.subject.as_ref() == "user-123")
+        .times(1)
+        .returning(|identity| {
+            Ok(template_rust_backend::models::user_identities::Model {
+                id: identity.id.unwrap(),
+                user_id: identity.user_id.unwrap(),
+                provider_id: identity.provider_id.unwrap(),
+                subject: identity.subject.unwrap(),
+                created_at: identity.created_at.unwrap(),
+            })
+        });
+    let mut users = MockUserRepository::new();
+    users
+        .expect_find_by_email_in_tenant()
+        .returning(|_, _| Ok(None));
+    users.expect_find_by_email().returning(|_| Ok(None));
+
+    let created = user_model(tenant_id, "jane@example.com", UserRole::Admin);
+    let mut user_service = MockUserService::new();
+    let returned = created.clone();
+    user_service
+        .expect_create()
+        .withf(move |t, email, _, role| {
+            *t == tenant_id && email == "jane@example.com" && *role == UserRole::Admin
+        })
+        .times(1)
+        .returning(move |_, _, _, _| Ok(returned.clone()));
+
+    let service = service(provider, repo, users, user_service, signing_in(created.id));
+    let request = service.authorize(Uuid::now_v7()).await.unwrap();
+    assert!(
+        request
+            .authorization_url
+            .starts_with(&format!("{}/authorize?", idp.issuer))
+    );
+
+    let code = idp.approve(
+        &request.authorization_url,
+        json!({ "sub": "user-123", "email": "jane@example.com" }),
+    );
+    let response = service.callback(&request.state, &code).await.unwrap();
+    assert_eq!(response.user.id, created.id);
+
+    // The state was consumed
+    let result = service.callback(&request.state, &code).await;
+    assert!(matches!(result, Err(AppError::OidcStateInvalid)));
+}
+
+#[tokio::test]
+async fn test_login_refuses_to_link_unverified_email() {
+    let idp = MockOidcServer::start().await;
+    let provider = oidc_provider_model(Uuid::now_v7(), &idp.issuer);
+    let existing = user_model(provider.tenant_id, "jane@example.com", UserRole::Admin);
+
+    let mut repo = MockOidcRepository::new();
+    repo.expect_find_identity().returning(|_, _| Ok(None));
+    repo.expect_insert_identity().never();
+    let mut users = MockUserRepository::new();
+    users
+        .expect_find_by_email_in_tenant()
+        .returning(move |_, _| Ok(Some(existing.clone())));
+
+    let service = service(
+        provider,
+        repo,
+        users,
+        MockUserService::new(),
+        MockAuthenticationService::new(),
+    );
+    let request = service.authorize(Uuid::now_v7()).await.unwrap();
+    let code = idp.approve(
+        &request.authorization_url,
+        json!({ "sub": "attacker", "email": "jane@example.com", "email_verified": false }),
+    );
+
+    let result = service.callback(&request.state, &code).await;
+    assert!(matches!(result, Err(AppError::OidcAccountConflict)));
+}
+
+#[tokio::test]
+async fn test_login_with_unknown_code_fails() {
+    let idp = MockOidcServer::start().await;
+    let provider = oidc_provider_model(Uuid::now_v7(), &idp.issuer);
+
+    let service = service(
+        provider,
+        MockOidcRepository::new(),
+        MockUserRepository::new(),
+        MockUserService::new(),
+        MockAuthenticationService::new(),
+    );
+    let request = service.authorize(Uuid::now_v7()).await.unwrap();
+
+    let result = service.callback(&request.state, "forged-code").await;
+    assert!(matches!(result, Err(AppError::OidcLoginFailed)));
+}
+
+#[tokio::test]
+async fn test_unreachable_provider_is_unavailable() {
+    // Nothing listens on the discard port
+    let provider = oidc_provider_model(Uuid::now_v7(), "http://127.0.0.1:9");
+
+    let service = service(
+        provider,
+        MockOidcRepository::new(),
+        MockUserRepository::new(),
+        MockUserService::new(),
+        MockAuthenticationService::new(),
+    );
+    let result = service.authorize(Uuid::now_v7()).await;
+
+    assert!(matches!(result, Err(AppError::OidcProviderUnavailable)));
+}
+
+#[tokio::test]
+async fn test_provider_must_be_at_a_public_address() {
+    let idp = MockOidcServer::start().await;
+    let mut repo = MockOidcRepository::new();
+    repo.expect_insert_provider().never();
+    let service = OidcAuthService::new(
+        Arc::new(repo),
+        Arc::new(MockUserRepository::new()),
+        Arc::new(MockUserService::new()),
+        Arc::new(MockAuthenticationService::new()),
+        HttpClient::new(Duration::from_secs(10), false),
+    );
+
+    let result = service
+        .create_provider(
+            Uuid::now_v7(),
+            CreateOidcProviderRequest {
+                name: "Corporate SSO".to_string(),
+                issuer: idp.issuer.clone(),
+                client_id: "app".to_string(),
+                client_secret: "secret".to_string(),
+                scopes: None,
+                redirect_uri: "https://app.example.com/auth/callback".to_string(),
+                email_claim: None,
+                role_claim: None,
+                admin_role_value: None,
+                default_role: None,
+            },
+        )
+        .await;
+
+    let Err(AppError::Validation(errors)) = result else {
+        panic!("expected a validation error, got {:?}", result);
+    };
+    assert_eq!(errors[0].field, "issuer");
+    assert_eq!(errors[0].code, "url_not_public");
+}
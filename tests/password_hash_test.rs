@@ -0,0 +1,89 @@
+use argon2::Params;
+use template_rust_backend::utils::password_hash::PasswordHashing;
+
+const PEPPER: &str = "a-server-side-pepper-of-32-chars!";
+const OTHER_PEPPER: &str = "another-server-side-pepper-32chars";
+
+fn cheap() -> Params {
+    Params::new(8, 1, 1, None).unwrap()
+}
+
+#[tokio::test]
+async fn test_hash_password() {
+    let password = "test_password_123";
+    let hash = PasswordHashing::default().hash(password).await.unwrap();
+    assert!(hash.starts_with("$argon2id$v=19$"));
+    assert_ne!(hash, password);
+}
+
+#[tokio::test]
+async fn test_verify_password() {
+    let hashing = PasswordHashing::new(cheap(), None);
+    let hash = hashing.hash("test_password_123").await.unwrap();
+
+    assert!(hashing.verify("test_password_123", &hash).await.unwrap());
+    assert!(!hashing.verify("wrong_password", &hash).await.unwrap());
+    assert!(!hashing.needs_rehash(&hash));
+}
+
+#[tokio::test]
+async fn test_hash_with_other_params_verifies_and_needs_rehash() {
+    let old = PasswordHashing::new(cheap(), None);
+    let hash = old.hash("test_password_123").await.unwrap();
+
+    let current = PasswordHashing::default();
+    assert!(current.verify("test_password_123", &hash).await.unwrap());
+    assert!(current.needs_rehash(&hash));
+
+    let rehashed = current.hash("test_password_123").await.unwrap();
+    assert!(!current.needs_rehash(&rehashed));
+}
+
+#[tokio::test]
+async fn test_other_algorithms_need_rehash() {
+    let hashing = PasswordHashing::default();
+    let argon2i =
+        "$argon2i$v=19$m=19456,t=2,p=1$c29tZXNhbHQ$ZGVyaXZlZGtleWRlcml2ZWRrZXlkZXJpdmVka2V5";
+    assert!(hashing.needs_rehash(argon2i));
+    assert!(hashing.needs_rehash("not a hash"));
+}
+
+#[tokio::test]
+async fn test_peppered_hash_needs_the_pepper() {
+    let peppered = PasswordHashing::new(cheap(), Some(PEPPER));
+    let hash = peppered.hash("test_password_123").await.unwrap();
+
+    assert!(hash.contains("keyid="));
+    assert!(!hash.contains(PEPPER));
+    assert!(peppered.verify("test_password_123", &hash).await.unwrap());
+    assert!(!peppered.verify("wrong_password", &hash).await.unwrap());
+    assert!(!peppered.needs_rehash(&hash));
+
+    let unpeppered = PasswordHashing::new(cheap(), None);
+    assert!(!unpeppered.verify("test_password_123", &hash).await.unwrap());
+    let other = PasswordHashing::new(cheap(), Some(OTHER_PEPPER));
+    assert!(!other.verify("test_password_123", &hash).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_hash_made_before_pepper_verifies_and_needs_rehash() {
+    let hash = PasswordHashing::new(cheap(), None)
+        .hash("test_password_123")
+        .await
+        .unwrap();
+
+    let peppered = PasswordHashing::new(cheap(), Some(PEPPER));
+    assert!(peppered.verify("test_password_123", &hash).await.unwrap());
+    assert!(peppered.needs_rehash(&hash));
+}
+
+#[tokio::test]
+async fn test_malformed_hash_is_an_error() {
+    let hashing = PasswordHashing::default();
+    assert!(
+        hashing
+            .verify("test_password_123", "not a hash")
+            .await
+            .is_err()
+    );
+}
@@ -0,0 +1,146 @@
+// Password policy rules, strength estimates and the breached password list.
+
+use sha1::{Digest, Sha1};
+use template_rust_backend::models::tenant_settings::TenantSettings;
+use template_rust_backend::utils::password_policy::{
+    BreachedPasswords, PasswordPolicy, PasswordViolation, strength,
+};
+
+#[test]
+fn test_default_policy_only_requires_length() {
+    let policy = PasswordPolicy::default();
+    assert!(policy.violations("abcdefgh", &[]).is_empty());
+    assert_eq!(
+        policy.violations("abcdefg", &[]),
+        vec![PasswordViolation::TooShort { min: 8 }]
+    );
+}
+
+#[test]
+fn test_policy_counts_character_classes() {
+    let policy = PasswordPolicy {
+        min_character_classes: 3,
+        ..Default::default()
+    };
+    assert_eq!(
+        policy.violations("lowercase123", &[]),
+        vec![PasswordViolation::CharacterClasses { min: 3 }]
+    );
+    assert!(policy.violations("Lowercase123", &[]).is_empty());
+    assert!(policy.violations("lowercase-123", &[]).is_empty());
+}
+
+#[test]
+fn test_strength_scores_guessability() {
+    for weak in [
+        "password",
+        "Password1!",
+        "12345678",
+        "qwertyuiop",
+        "aaaaaaaaaaaa",
+    ] {
+        assert!(strength(weak) <= 1, "{} scored {}", weak, strength(weak));
+    }
+    assert!(strength("Tr0ub4dor&3") >= 3);
+    assert_eq!(strength("correct horse battery staple"), 4);
+    assert_eq!(strength(""), 0);
+
+    let policy = PasswordPolicy {
+        min_strength: 3,
+        ..Default::default()
+    };
+    assert_eq!(
+        policy.violations("P@ssw0rd2024", &[]),
+        vec![PasswordViolation::TooWeak { min: 3 }]
+    );
+}
+
+#[test]
+fn test_policy_rejects_email_and_tenant_name() {
+    let policy = PasswordPolicy::default();
+    let personal = ["jane.doe@example.com", "Acme Corp"];
+    for password in ["jane.doe@example.com!", "JaneLovesCats", "acme-rocks-2024"] {
+        assert_eq!(
+            policy.violations(password, &personal),
+            vec![PasswordViolation::PersonalInfo],
+            "{}",
+            password
+        );
+    }
+    // Words shorter than four characters are too common to reject on
+    assert!(policy.violations("doe-and-co-42", &personal).is_empty());
+
+    let lenient = PasswordPolicy {
+        reject_personal_info: false,
+        ..Default::default()
+    };
+    assert!(lenient.violations("JaneLovesCats", &personal).is_empty());
+}
+
+#[test]
+fn test_tenant_settings_override_and_strictest_combines() {
+    let global = PasswordPolicy {
+        min_length: 10,
+        history: 3,
+        ..Default::default()
+    };
+    let settings = TenantSettings {
+        password_min_character_classes: Some(3),
+        password_reject_personal_info: Some(false),
+        ..Default::default()
+    };
+    let tenant = global.for_tenant(&settings);
+    assert_eq!(tenant.min_length, 10);
+    assert_eq!(tenant.min_character_classes, 3);
+    assert!(!tenant.reject_personal_info);
+
+    let other = global.for_tenant(&TenantSettings {
+        password_min_length: Some(14),
+        password_history: Some(1),
+        ..Default::default()
+    });
+    let combined = tenant.strictest(&other);
+    assert_eq!(combined.min_length, 14);
+    assert_eq!(combined.min_character_classes, 3);
+    assert!(combined.reject_personal_info);
+    assert_eq!(combined.history, 3);
+}
+
+#[tokio::test]
+async fn test_breached_passwords_read_the_range_of_the_prefix() {
+    let dir = std::env::temp_dir().join(format!("pwned-{}", uuid::Uuid::now_v7()));
+    std::fs::create_dir_all(&dir).unwrap();
+    // SHA-1 of "correct horse battery staple" and "Tr0ub4dor&3"
+    std::fs::write(
+        dir.join("ABF7A.txt"),
+        "0000000000000000000000000000000000A:0\r\n\
+         AD6438836DBE526AA231ABDE2D0EEF74D42:3\r\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("87457.txt"),
+        "2e7a5ae6a49466a6ac578b98adba78c6aa6:1\n",
+    )
+    .unwrap();
+    // Padding entries are not breached
+    let padding = hex::encode_upper(Sha1::digest("Tr0ub4dor&4"));
+    std::fs::write(
+        dir.join(format!("{}.txt", &padding[..5])),
+        format!("{}:0\n", &padding[5..]),
+    )
+    .unwrap();
+    let breached = BreachedPasswords::new(&dir);
+
+    assert!(
+        breached
+            .contains("correct horse battery staple")
+            .await
+            .unwrap()
+    );
+    assert!(breached.contains("Tr0ub4dor&3").await.unwrap());
+    assert!(!breached.contains("Tr0ub4dor&4").await.unwrap());
+    // No range file, nothing listed
+    assert!(!breached.contains("Blue-Kettle-Sings").await.unwrap());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,75 @@
+use axum::{Router, routing::get};
+use axum_test::TestServer;
+use template_rust_backend::middleware::{RequestId, tracing_middleware};
+use template_rust_backend::utils::error::AppError;
+
+fn test_router() -> Router {
+    Router::new()
+        .route(
+            "/echo",
+            get(|request_id: RequestId| async move { request_id.0 }),
+        )
+        .route(
+            "/fail",
+            get(|| async { Err::<(), AppError>(AppError::UserNotFound) }),
+        )
+        .layer(axum::middleware::from_fn(tracing_middleware))
+}
+
+#[test]
+fn test_parse_request_id() {
+    assert!(RequestId::parse("abc-123_DEF.4:5").is_some());
+    assert!(RequestId::parse("").is_none());
+    assert!(RequestId::parse("has space").is_none());
+    assert!(RequestId::parse("<script>").is_none());
+    assert!(RequestId::parse(&"a".repeat(129)).is_none());
+}
+
+#[tokio::test]
+async fn test_generates_request_id_header() {
+    let server = TestServer::new(test_router()).unwrap();
+
+    let response = server.get("/echo").await;
+    response.assert_status_ok();
+    let header = response.header("x-request-id");
+    assert_eq!(header.to_str().unwrap(), response.text());
+}
+
+#[tokio::test]
+async fn test_accepts_inbound_request_id() {
+    let server = TestServer::new(test_router()).unwrap();
+
+    let response = server
+        .get("/echo")
+        .add_header("X-Request-Id", "gateway-42")
+        .await;
+    response.assert_header("x-request-id", "gateway-42");
+    response.assert_text("gateway-42");
+}
+
+#[tokio::test]
+async fn test_rejects_invalid_inbound_request_id() {
+    let server = TestServer::new(test_router()).unwrap();
+
+    let response = server
+        .get("/echo")
+        .add_header("X-Request-Id", "not valid!")
+        .await;
+    assert_ne!(response.text(), "not valid!");
+}
+
+#[tokio::test]
+async fn test_error_body_includes_request_id() {
+    let server = TestServer::new(test_router()).unwrap();
+
+    let response = server
+        .get("/fail")
+        .add_header("X-Request-Id", "trace-me")
+        .await;
+    response.assert_status_not_found();
+    response.assert_json(&serde_json::json!({
+        "error": "USER_NOT_FOUND",
+        "message": "User not found",
+        "request_id": "trace-me"
+    }));
+}
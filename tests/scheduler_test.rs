@@ -0,0 +1,437 @@
+// Cron, scheduler and maintenance service tests against mocked repositories;
+// no database required.
+
+pub mod common;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use common::mocks::*;
+use sea_orm::TryIntoModel;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use template_rust_backend::enums::TaskRunStatus;
+use template_rust_backend::models::task_runs;
+use template_rust_backend::models::tenant_settings::TenantSettings;
+use template_rust_backend::repositories::TaskLock;
+use template_rust_backend::scheduler::{ScheduledTask, Scheduler};
+use template_rust_backend::services::maintenance_service::{
+    ENDED_API_KEY_RETENTION, Maintenance, MaintenanceService,
+};
+use template_rust_backend::services::tenant_settings_service::TenantSettingsResponse;
+use template_rust_backend::utils::cron::Schedule;
+use template_rust_backend::utils::error::AppError;
+
+fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(year, month, day, hour, minute, 0)
+        .unwrap()
+}
+
+fn schedule(expression: &str) -> Schedule {
+    expression.parse().unwrap()
+}
+
+#[test]
+fn test_cron_next_after_steps_and_ranges() {
+    let every_quarter = schedule("*/15 * * * *");
+    assert_eq!(
+        every_quarter.next_after(at(2026, 3, 1, 10, 0)),
+        Some(at(2026, 3, 1, 10, 15))
+    );
+    assert_eq!(
+        every_quarter.next_after(at(2026, 3, 1, 10, 50)),
+        Some(at(2026, 3, 1, 11, 0))
+    );
+
+    let office_hours = schedule("30 9-17/4 * * mon-fri");
+    // Saturday the 7th rolls over to Monday the 9th
+    assert_eq!(
+        office_hours.next_after(at(2026, 3, 6, 17, 30)),
+        Some(at(2026, 3, 9, 9, 30))
+    );
+    assert_eq!(
+        office_hours.next_after(at(2026, 3, 9, 9, 30)),
+        Some(at(2026, 3, 9, 13, 30))
+    );
+}
+
+#[test]
+fn test_cron_next_after_is_strictly_later() {
+    let daily = schedule("@daily");
+    assert_eq!(
+        daily.next_after(at(2026, 12, 31, 0, 0)),
+        Some(at(2027, 1, 1, 0, 0))
+    );
+    // Seconds past the minute still move on to the next match
+    let tick = at(2026, 1, 1, 0, 0) + Duration::seconds(30);
+    assert_eq!(daily.next_after(tick), Some(at(2026, 1, 2, 0, 0)));
+}
+
+#[test]
+fn test_cron_day_fields_match_either_when_both_restricted() {
+    // The 13th, or any Friday
+    let unlucky = schedule("0 0 13 * fri");
+    assert_eq!(
+        unlucky.next_after(at(2026, 2, 1, 0, 0)),
+        Some(at(2026, 2, 6, 0, 0))
+    );
+    assert_eq!(
+        unlucky.next_after(at(2026, 2, 11, 0, 0)),
+        Some(at(2026, 2, 13, 0, 0))
+    );
+}
+
+#[test]
+fn test_cron_names_and_sunday_as_seven() {
+    // 2026-03-07 is a Saturday
+    for sunday in ["0 0 * * 7", "0 0 * * 0", "0 0 * * SUN"] {
+        assert_eq!(
+            schedule(sunday).next_after(at(2026, 3, 7, 12, 0)),
+            Some(at(2026, 3, 8, 0, 0))
+        );
+    }
+    assert_eq!(
+        schedule("0 12 1 jan,JUL *").next_after(at(2026, 2, 1, 0, 0)),
+        Some(at(2026, 7, 1, 12, 0))
+    );
+    assert_eq!(
+        schedule("0 0 29 feb *").next_after(at(2026, 3, 1, 0, 0)),
+        Some(at(2028, 2, 29, 0, 0))
+    );
+    assert_eq!(
+        schedule("0 0 30 2 *").next_after(at(2026, 1, 1, 0, 0)),
+        None
+    );
+    assert_eq!(schedule(" @hourly ").to_string(), "@hourly");
+}
+
+#[test]
+fn test_cron_last_day_of_month() {
+    let month_end = schedule("0 23 L * *");
+    assert_eq!(
+        month_end.next_after(at(2026, 2, 1, 0, 0)),
+        Some(at(2026, 2, 28, 23, 0))
+    );
+    assert_eq!(
+        month_end.next_after(at(2028, 2, 28, 23, 0)),
+        Some(at(2028, 2, 29, 23, 0))
+    );
+}
+
+#[test]
+fn test_cron_rejects_invalid_expressions() {
+    for expression in [
+        "",
+        "* * * *",
+        "* * * * * *",
+        "60 * * * *",
+        "* 24 * * *",
+        "* * 0 * *",
+        "* * * 13 *",
+        "* * * * 8",
+        "*/0 * * * *",
+        "5-1 * * * *",
+        "x * * * *",
+        "@often",
+    ] {
+        assert!(
+            expression.parse::<Schedule>().is_err(),
+            "{:?} should be rejected",
+            expression
+        );
+    }
+}
+
+/// Counts its runs; fails when `fail` is set and panics when `panic` is.
+#[derive(Default)]
+struct Count {
+    runs: Arc<AtomicUsize>,
+    fail: bool,
+    panic: bool,
+}
+
+#[async_trait]
+impl ScheduledTask for Count {
+    fn name(&self) -> &'static str {
+        "count"
+    }
+
+    async fn run(&self) -> Result<u64, AppError> {
+        self.runs.fetch_add(1, Ordering::SeqCst);
+        if self.panic {
+            panic!("task panicked");
+        }
+        if self.fail {
+            return Err(AppError::ServiceUnavailable);
+        }
+        Ok(3)
+    }
+}
+
+/// Repository that grants the lock, has no earlier run and echoes writes.
+fn free_runs() -> MockTaskRunRepository {
+    let mut runs = MockTaskRunRepository::new();
+    runs.expect_try_lock()
+        .times(1)
+        .returning(|_| Ok(Some(TaskLock::local())));
+    runs.expect_find().times(1).returning(|_, _| Ok(None));
+    runs.expect_insert()
+        .times(1)
+        .returning(|run| Ok(run.try_into_model().unwrap()));
+    runs.expect_update()
+        .times(1)
+        .returning(|run| Ok(run.try_into_model().unwrap()));
+    runs
+}
+
+async fn run_count(task: Count) -> task_runs::Model {
+    let scheduler = Scheduler::new(Arc::new(free_runs())).add(schedule("@hourly"), task);
+    let tick = at(2026, 1, 1, 0, 0);
+    let run = scheduler.run_due("count", tick).await.unwrap().unwrap();
+    assert_eq!(run.task, "count");
+    assert_eq!(run.scheduled_for, tick.fixed_offset());
+    assert_eq!(run.runner, scheduler.id());
+    assert!(run.finished_at.is_some());
+    run
+}
+
+#[tokio::test]
+async fn test_scheduler_records_successful_run() {
+    let task = Count::default();
+    let runs = task.runs.clone();
+
+    let run = run_count(task).await;
+
+    assert_eq!(run.status, TaskRunStatus::Succeeded);
+    assert_eq!(run.affected, Some(3));
+    assert_eq!(run.error, None);
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_scheduler_records_failed_and_panicked_runs() {
+    let run = run_count(Count {
+        fail: true,
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(run.status, TaskRunStatus::Failed);
+    assert_eq!(run.error.as_deref(), Some("Service unavailable"));
+
+    let run = run_count(Count {
+        panic: true,
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(run.status, TaskRunStatus::Failed);
+    assert_eq!(run.affected, None);
+}
+
+#[tokio::test]
+async fn test_scheduler_skips_task_locked_elsewhere() {
+    let mut runs = MockTaskRunRepository::new();
+    runs.expect_try_lock().times(1).returning(|_| Ok(None));
+    runs.expect_insert().never();
+    let task = Count::default();
+    let count = task.runs.clone();
+    let scheduler = Scheduler::new(Arc::new(runs)).add(schedule("@hourly"), task);
+
+    let run = scheduler
+        .run_due("count", at(2026, 1, 1, 0, 0))
+        .await
+        .unwrap();
+
+    assert!(run.is_none());
+    assert_eq!(count.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn test_scheduler_skips_tick_already_run() {
+    let mut runs = MockTaskRunRepository::new();
+    runs.expect_try_lock()
+        .times(1)
+        .returning(|_| Ok(Some(TaskLock::local())));
+    runs.expect_find()
+        .withf(|task, _| task == "count")
+        .times(1)
+        .returning(|task, _| Ok(Some(task_run_model(task, 0))));
+    runs.expect_insert().never();
+    let task = Count::default();
+    let count = task.runs.clone();
+    let scheduler = Scheduler::new(Arc::new(runs)).add(schedule("@hourly"), task);
+
+    let run = scheduler
+        .run_due("count", at(2026, 1, 1, 0, 0))
+        .await
+        .unwrap();
+
+    assert!(run.is_none());
+    assert_eq!(count.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn test_scheduler_rejects_unknown_task() {
+    let scheduler = Scheduler::new(Arc::new(MockTaskRunRepository::new()));
+    let result = scheduler.run_due("count", Utc::now()).await;
+    assert!(matches!(result, Err(AppError::Internal)));
+}
+
+#[test]
+#[should_panic(expected = "task 'count' added twice")]
+fn test_scheduler_rejects_task_added_twice() {
+    let _ = Scheduler::new(Arc::new(MockTaskRunRepository::new()))
+        .add(schedule("@hourly"), Count::default())
+        .add(schedule("@daily"), Count::default());
+}
+
+fn maintenance(
+    oidc: MockOidcRepository,
+    api_keys: MockApiKeyRepository,
+    memberships: MockMembershipRepository,
+    tenants: MockTenantRepository,
+    settings: MockTenantSettingsService,
+    runs: MockTaskRunRepository,
+    outbox: MockOutboxRepository,
+) -> Maintenance {
+    Maintenance::new(
+        Arc::new(oidc),
+        Arc::new(api_keys),
+        Arc::new(memberships),
+        Arc::new(tenants),
+        Arc::new(settings),
+        Arc::new(runs),
+        Arc::new(outbox),
+    )
+}
+
+#[tokio::test]
+async fn test_purge_deletes_login_states_and_long_ended_keys() {
+    let mut oidc = MockOidcRepository::new();
+    oidc.expect_delete_expired_login_states()
+        .withf(|before| (Utc::now() - before.to_utc()).num_seconds().abs() < 60)
+        .times(1)
+        .returning(|_| Ok(2));
+    let mut api_keys = MockApiKeyRepository::new();
+    api_keys
+        .expect_delete_keys_ended_before()
+        .withf(|before| {
+            let age = Utc::now() - before.to_utc();
+            (age - ENDED_API_KEY_RETENTION).num_seconds().abs() < 60
+        })
+        .times(1)
+        .returning(|_| Ok(1));
+    let service = maintenance(
+        oidc,
+        api_keys,
+        MockMembershipRepository::new(),
+        MockTenantRepository::new(),
+        default_settings(),
+        MockTaskRunRepository::new(),
+        MockOutboxRepository::new(),
+    );
+
+    assert_eq!(service.purge_expired_auth_artifacts().await.unwrap(), 3);
+}
+
+#[tokio::test]
+async fn test_idle_members_are_deactivated_only_where_configured() {
+    let strict = tenant_model("Strict");
+    let lenient = tenant_model("Lenient");
+    let strict_id = strict.id;
+    let mut tenants = MockTenantRepository::new();
+    tenants
+        .expect_list_all()
+        .returning(move || Ok(vec![strict.clone(), lenient.clone()]));
+    let mut settings = MockTenantSettingsService::new();
+    settings.expect_get().returning(move |tenant_id| {
+        let mut settings = TenantSettings::default();
+        if tenant_id == strict_id {
+            settings.idle_deactivation_days = Some(90);
+        }
+        Ok(TenantSettingsResponse {
+            tenant_id,
+            version: 1,
+            settings,
+        })
+    });
+    let mut memberships = MockMembershipRepository::new();
+    memberships
+        .expect_deactivate_idle()
+        .withf(move |tenant_id, idle_since| {
+            let idle = Utc::now() - idle_since.to_utc();
+            *tenant_id == strict_id && (idle - Duration::days(90)).num_seconds().abs() < 60
+        })
+        .times(1)
+        .returning(|_, _| Ok(4));
+    let service = maintenance(
+        MockOidcRepository::new(),
+        MockApiKeyRepository::new(),
+        memberships,
+        tenants,
+        settings,
+        MockTaskRunRepository::new(),
+        MockOutboxRepository::new(),
+    );
+
+    assert_eq!(service.deactivate_idle_members().await.unwrap(), 4);
+}
+
+#[tokio::test]
+async fn test_purge_relayed_events_keeps_the_retention() {
+    let mut outbox = MockOutboxRepository::new();
+    outbox
+        .expect_purge_dispatched()
+        .withf(|before| {
+            let age = Utc::now() - before.to_utc();
+            (age - Duration::days(7)).num_seconds().abs() < 60
+        })
+        .times(1)
+        .returning(|_| Ok(5));
+    let service = maintenance(
+        MockOidcRepository::new(),
+        MockApiKeyRepository::new(),
+        MockMembershipRepository::new(),
+        MockTenantRepository::new(),
+        default_settings(),
+        MockTaskRunRepository::new(),
+        outbox,
+    );
+
+    assert_eq!(
+        service
+            .purge_relayed_events(Duration::days(7))
+            .await
+            .unwrap(),
+        5
+    );
+}
+
+#[tokio::test]
+async fn test_list_runs_clamps_limit() {
+    let mut runs = MockTaskRunRepository::new();
+    runs.expect_list()
+        .withf(|task, limit| task.is_none() && *limit == 50)
+        .times(1)
+        .returning(|_, _| Ok(vec![task_run_model("purge_auth_artifacts", 1)]));
+    runs.expect_list()
+        .withf(|task, limit| task.as_deref() == Some("count") && *limit == 200)
+        .times(1)
+        .returning(|_, _| Ok(vec![]));
+    let service = maintenance(
+        MockOidcRepository::new(),
+        MockApiKeyRepository::new(),
+        MockMembershipRepository::new(),
+        MockTenantRepository::new(),
+        default_settings(),
+        runs,
+        MockOutboxRepository::new(),
+    );
+
+    assert_eq!(service.list_runs(None, None).await.unwrap().len(), 1);
+    assert!(
+        service
+            .list_runs(Some("count".to_string()), Some(10_000))
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
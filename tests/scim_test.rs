@@ -0,0 +1,266 @@
+// SCIM filter, patch and provisioning tests against mocked services; no
+// database required.
+
+pub mod common;
+
+use common::mocks::*;
+use serde_json::json;
+use std::sync::Arc;
+use template_rust_backend::enums::{UserRole, UserStatus};
+use template_rust_backend::services::scim_service::{
+    ScimListQuery, ScimPatchOperation, ScimService, ScimUser, ScimUserRequest, ScimUsersService,
+};
+use template_rust_backend::services::users_service::UserChanges;
+use template_rust_backend::utils::error::AppError;
+use template_rust_backend::utils::scim_filter::Filter;
+use uuid::Uuid;
+
+fn resource(email: &str, active: bool) -> serde_json::Value {
+    let mut user = user_model(Uuid::now_v7(), email, UserRole::Regular);
+    if !active {
+        user.status = UserStatus::Inactive;
+    }
+    serde_json::to_value(ScimUser::from(user)).unwrap()
+}
+
+#[test]
+fn test_filter_matches_case_insensitively() {
+    let jane = resource("jane@example.com", true);
+
+    let cases = [
+        (r#"userName eq "Jane@Example.com""#, true),
+        (r#"USERNAME EQ "jane@example.com""#, true),
+        (r#"userName ne "jane@example.com""#, false),
+        (r#"emails.value co "@example""#, true),
+        (r#"userName sw "john""#, false),
+        (r#"userName ew ".com""#, true),
+        (
+            r#"urn:ietf:params:scim:schemas:core:2.0:User:userName eq "jane@example.com""#,
+            true,
+        ),
+        ("active eq true", true),
+        ("active eq false", false),
+        ("roles.value eq \"regular\"", true),
+        ("meta.created gt \"2000-01-01T00:00:00Z\"", true),
+        ("meta.created lt \"2000-01-01T00:00:00+00:00\"", false),
+        ("title pr", false),
+        ("userName pr", true),
+    ];
+    for (filter, expected) in cases {
+        let parsed = Filter::parse(filter).unwrap();
+        assert_eq!(parsed.matches(&jane), expected, "{}", filter);
+    }
+}
+
+#[test]
+fn test_filter_combines_with_precedence() {
+    let active = resource("jane@example.com", true);
+    let inactive = resource("john@example.com", false);
+
+    // `and` binds tighter than `or`
+    let filter =
+        Filter::parse(r#"userName eq "nobody@example.com" or active eq true and userName sw "j""#)
+            .unwrap();
+    assert!(filter.matches(&active));
+    assert!(!filter.matches(&inactive));
+
+    let filter = Filter::parse(r#"not (active eq true) and (userName sw "john")"#).unwrap();
+    assert!(!filter.matches(&active));
+    assert!(filter.matches(&inactive));
+}
+
+#[test]
+fn test_filter_rejects_malformed_expressions() {
+    for filter in [
+        "",
+        "userName",
+        "userName eq",
+        r#"userName like "jane""#,
+        r#"userName eq "jane"#,
+        r#"(userName eq "jane""#,
+        r#"emails[type eq "work"].value eq "jane@example.com""#,
+        r#"userName eq "jane" and"#,
+        "userName eq jane",
+    ] {
+        assert!(
+            matches!(Filter::parse(filter), Err(AppError::ScimFilterInvalid(_))),
+            "{} was accepted",
+            filter
+        );
+    }
+}
+
+fn operation(op: &str, path: Option<&str>, value: serde_json::Value) -> ScimPatchOperation {
+    ScimPatchOperation {
+        op: op.to_string(),
+        path: path.map(str::to_string),
+        value: Some(value),
+    }
+}
+
+#[test]
+fn test_patch_maps_active_username_and_roles() {
+    let changes = ScimUsersService::patch_changes(&[
+        // Azure AD style: capitalized op, boolean as a string
+        operation("Replace", Some("active"), json!("False")),
+        operation(
+            "add",
+            None,
+            json!({ "userName": "Jane.Doe@Example.com", "displayName": "Jane" }),
+        ),
+        operation("replace", Some("roles"), json!([{ "value": "admin" }])),
+        operation("replace", Some("name.givenName"), json!("Jane")),
+    ])
+    .unwrap();
+
+    assert_eq!(
+        changes,
+        UserChanges {
+            email: Some("jane.doe@example.com".to_string()),
+            role: Some(UserRole::Admin),
+            status: Some(UserStatus::Inactive),
+        }
+    );
+
+    let changes = ScimUsersService::patch_changes(&[ScimPatchOperation {
+        op: "remove".to_string(),
+        path: Some("roles".to_string()),
+        value: None,
+    }])
+    .unwrap();
+    assert_eq!(changes.role, Some(UserRole::Regular));
+}
+
+#[test]
+fn test_patch_rejects_unsupported_operations() {
+    let cases = [
+        operation("move", Some("active"), json!(true)),
+        operation("replace", Some("active"), json!("maybe")),
+        operation("replace", None, json!("not an object")),
+        ScimPatchOperation {
+            op: "replace".to_string(),
+            path: Some("active".to_string()),
+            value: None,
+        },
+        ScimPatchOperation {
+            op: "remove".to_string(),
+            path: Some("userName".to_string()),
+            value: None,
+        },
+    ];
+    for case in cases {
+        let result = ScimUsersService::patch_changes(std::slice::from_ref(&case));
+        assert!(
+            matches!(result, Err(AppError::ScimPatchInvalid(_))),
+            "{:?} was accepted",
+            case
+        );
+    }
+
+    let result = ScimUsersService::patch_changes(&[operation(
+        "replace",
+        Some("roles"),
+        json!([{ "value": "owner" }]),
+    )]);
+    assert!(matches!(result, Err(AppError::Validation(_))));
+}
+
+#[tokio::test]
+async fn test_list_users_filters_and_pages_oldest_first() {
+    let tenant_id = Uuid::now_v7();
+    // Newest first, as the service returns them
+    let users: Vec<_> = (0..5)
+        .rev()
+        .map(|i| {
+            user_model(
+                tenant_id,
+                &format!("user{}@example.com", i),
+                UserRole::Regular,
+            )
+        })
+        .collect();
+    let mut user_service = MockUserService::new();
+    user_service
+        .expect_list_by_tenant()
+        .returning(move |_| Ok(users.clone()));
+    let service = ScimUsersService::new(Arc::new(user_service));
+
+    let page = service
+        .list_users(
+            tenant_id,
+            ScimListQuery {
+                filter: None,
+                start_index: Some(2),
+                count: Some(2),
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(page.total_results, 5);
+    assert_eq!(page.start_index, 2);
+    assert_eq!(page.items_per_page, 2);
+    let names: Vec<_> = page
+        .resources
+        .iter()
+        .map(|u| u.user_name.as_str())
+        .collect();
+    assert_eq!(names, ["user1@example.com", "user2@example.com"]);
+
+    let page = service
+        .list_users(
+            tenant_id,
+            ScimListQuery {
+                filter: Some(r#"userName eq "USER3@example.com""#.to_string()),
+                start_index: Some(0),
+                count: None,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(page.total_results, 1);
+    assert_eq!(page.start_index, 1);
+    assert_eq!(page.resources[0].user_name, "user3@example.com");
+}
+
+#[tokio::test]
+async fn test_create_user_provisions_inactive_user() {
+    let tenant_id = Uuid::now_v7();
+    let created = user_model(tenant_id, "jane@example.com", UserRole::Admin);
+    let user_id = created.id;
+
+    let mut user_service = MockUserService::new();
+    let returned = created.clone();
+    user_service
+        .expect_create()
+        .withf(move |t, email, password, role| {
+            *t == tenant_id
+                && email == "jane@example.com"
+                && password.len() >= 32
+                && *role == UserRole::Admin
+        })
+        .times(1)
+        .returning(move |_, _, _, _| Ok(returned.clone()));
+    user_service
+        .expect_update()
+        .withf(move |_, id, changes| *id == user_id && changes.status == Some(UserStatus::Inactive))
+        .times(1)
+        .returning(move |_, _, _| {
+            let mut user = created.clone();
+            user.status = UserStatus::Inactive;
+            Ok(user)
+        });
+
+    let service = ScimUsersService::new(Arc::new(user_service));
+    let request: ScimUserRequest = serde_json::from_value(json!({
+        "schemas": ["urn:ietf:params:scim:schemas:core:2.0:User"],
+        "userName": "Jane@Example.com",
+        "name": { "givenName": "Jane", "familyName": "Doe" },
+        "active": false,
+        "roles": [{ "value": "regular" }, { "value": "Admin", "primary": true }],
+    }))
+    .unwrap();
+    let user = service.create_user(tenant_id, request).await.unwrap();
+
+    assert!(!user.active);
+    assert_eq!(user.meta.location, format!("/scim/v2/Users/{}", user_id));
+}
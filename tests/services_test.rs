@@ -0,0 +1,1615 @@
+// Service tests against mocked repositories; no database required.
+
+pub mod common;
+
+use common::mocks::*;
+use mockall::predicate::eq;
+use sea_orm::{ActiveValue, TryIntoModel};
+use std::path::PathBuf;
+use std::sync::Arc;
+use template_rust_backend::enums::{
+    ApiScope, ExportFormat, ExportStatus, Locale, UserRole, UserStatus,
+};
+use template_rust_backend::events::DomainEvent;
+use template_rust_backend::jobs::Job;
+use template_rust_backend::jobs::export::RunExport;
+use template_rust_backend::middleware::auth::{Claims, PrincipalKind};
+use template_rust_backend::models::tenant_settings::{self, TenantSettings};
+use template_rust_backend::models::{
+    memberships, password_history, tenant_exports, tenants, users,
+};
+use template_rust_backend::services::api_keys_service::{ApiKeyService, ApiKeysService};
+use template_rust_backend::services::auth_service::{
+    AuthService, AuthenticationService, LoginRequest, RegisterRequest,
+};
+use template_rust_backend::services::exports_service::{
+    ARCHIVE_FORMAT_VERSION, ExportService, ExportSources, TenantExporter,
+};
+use template_rust_backend::services::password_policy_service::{
+    PasswordPolicyService, PasswordsService,
+};
+use template_rust_backend::services::tenant_settings_service::{
+    TenantSettingsResponse, TenantSettingsService, TenantSettingsStore, UpdateTenantSettingsRequest,
+};
+use template_rust_backend::services::tenants_service::{TenantService, TenantsService};
+use template_rust_backend::services::users_service::{UserChanges, UserService, UsersService};
+use template_rust_backend::utils::error::{AppError, AuthError};
+use template_rust_backend::utils::password_hash::PasswordHashing;
+use template_rust_backend::utils::password_policy::{BreachedPasswords, PasswordPolicy};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_create_user_rejects_email_taken_in_any_tenant() {
+    let existing = user_model(Uuid::now_v7(), "taken@example.com", UserRole::Regular);
+    let mut repo = MockUserRepository::new();
+    repo.expect_find_by_email()
+        .withf(|email| email == "taken@example.com")
+        .returning(move |_| Ok(Some(existing.clone())));
+    repo.expect_insert_member().never();
+    let mut memberships = MockMembershipRepository::new();
+    memberships.expect_insert().never();
+
+    let service = UsersService::new(
+        Arc::new(repo),
+        Arc::new(memberships),
+        Arc::new(any_password()),
+        PasswordHashing::default(),
+        chrono::Duration::days(30),
+    );
+    let result = service
+        .create(
+            Uuid::now_v7(),
+            "taken@example.com".to_string(),
+            "password123",
+            UserRole::Regular,
+        )
+        .await;
+
+    assert!(matches!(result, Err(AppError::UserAlreadyExists)));
+}
+
+#[tokio::test]
+async fn test_create_user_stores_password_hash_and_membership() {
+    let tenant_id = Uuid::now_v7();
+    let mut repo = MockUserRepository::new();
+    repo.expect_find_by_email().returning(|_| Ok(None));
+    repo.expect_insert_member()
+        .withf(move |user, membership, events| {
+            let hashed = match &user.password_hash {
+                ActiveValue::Set(hash) => hash.starts_with("$argon2id$"),
+                _ => false,
+            };
+            hashed
+                && membership.user_id == user.id
+                && membership.tenant_id == ActiveValue::Set(tenant_id)
+                && membership.role == ActiveValue::Set(UserRole::Admin)
+                && matches!(
+                    events.as_slice(),
+                    [DomainEvent::UserRegistered { user }]
+                        if user.tenant_id == tenant_id && user.role == UserRole::Admin
+                )
+        })
+        .times(1)
+        .returning(|user, membership, _| {
+            Ok(users::Model {
+                id: user.id.clone().unwrap(),
+                password_hash: user.password_hash.clone().unwrap(),
+                ..user_model(
+                    membership.tenant_id.clone().unwrap(),
+                    &user.email.clone().unwrap(),
+                    membership.role.clone().unwrap(),
+                )
+            })
+        });
+    let memberships = MockMembershipRepository::new();
+
+    let service = UsersService::new(
+        Arc::new(repo),
+        Arc::new(memberships),
+        Arc::new(any_password()),
+        PasswordHashing::default(),
+        chrono::Duration::days(30),
+    );
+    let user = service
+        .create(
+            tenant_id,
+            "new@example.com".to_string(),
+            "password123",
+            UserRole::Admin,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(user.email, "new@example.com");
+    assert!(
+        PasswordHashing::default()
+            .verify("password123", &user.password_hash)
+            .await
+            .unwrap()
+    );
+    assert_eq!(user.tenant_id, tenant_id);
+    assert_eq!(user.role, UserRole::Admin);
+}
+
+#[tokio::test]
+async fn test_update_user_rejects_email_of_another_user() {
+    let tenant_id = Uuid::now_v7();
+    let user = user_model(tenant_id, "jane@example.com", UserRole::Regular);
+    let other = user_model(tenant_id, "john@example.com", UserRole::Regular);
+    let mut repo = MockUserRepository::new();
+    let found = user.clone();
+    repo.expect_find_in_tenant()
+        .returning(move |_, _| Ok(Some(found.clone())));
+    repo.expect_find_by_email()
+        .withf(|email| email == "john@example.com")
+        .returning(move |_| Ok(Some(other.clone())));
+    repo.expect_update().never();
+
+    let service = UsersService::new(
+        Arc::new(repo),
+        Arc::new(MockMembershipRepository::new()),
+        Arc::new(any_password()),
+        PasswordHashing::default(),
+        chrono::Duration::days(30),
+    );
+    let changes = UserChanges {
+        email: Some("john@example.com".to_string()),
+        ..Default::default()
+    };
+    let result = service.update(tenant_id, user.id, changes).await;
+
+    assert!(matches!(result, Err(AppError::UserAlreadyExists)));
+}
+
+#[tokio::test]
+async fn test_update_user_writes_only_real_changes() {
+    let tenant_id = Uuid::now_v7();
+    let user = user_model(tenant_id, "jane@example.com", UserRole::Regular);
+    let mut repo = MockUserRepository::new();
+    let found = user.clone();
+    repo.expect_find_in_tenant()
+        .returning(move |_, _| Ok(Some(found.clone())));
+    repo.expect_update().never();
+    let mut memberships = MockMembershipRepository::new();
+    let membership = membership_model(&user);
+    memberships
+        .expect_find()
+        .returning(move |_, _| Ok(Some(membership.clone())));
+    let membership = membership_model(&user);
+    memberships
+        .expect_update()
+        .withf(|membership, events| {
+            membership.status == ActiveValue::Set(UserStatus::Inactive)
+                && !membership.role.is_set()
+                && matches!(
+                    events.as_slice(),
+                    [DomainEvent::UserStatusChanged { user, previous_status: UserStatus::Active }]
+                        if user.status == UserStatus::Inactive
+                )
+        })
+        .times(1)
+        .returning(move |update, _| {
+            Ok(memberships::Model {
+                status: update.status.clone().unwrap(),
+                ..membership.clone()
+            })
+        });
+
+    let service = UsersService::new(
+        Arc::new(repo),
+        Arc::new(memberships),
+        Arc::new(any_password()),
+        PasswordHashing::default(),
+        chrono::Duration::days(30),
+    );
+
+    // Same email and role, new status
+    let changes = UserChanges {
+        email: Some("jane@example.com".to_string()),
+        role: Some(UserRole::Regular),
+        status: Some(UserStatus::Inactive),
+    };
+    let updated = service.update(tenant_id, user.id, changes).await.unwrap();
+    assert_eq!(updated.status, UserStatus::Inactive);
+
+    // Nothing to change: no write at all
+    let changes = UserChanges {
+        role: Some(UserRole::Regular),
+        ..Default::default()
+    };
+    let unchanged = service.update(tenant_id, user.id, changes).await.unwrap();
+    assert_eq!(unchanged, user);
+}
+
+#[tokio::test]
+async fn test_delete_user_not_in_tenant() {
+    let user = user_model(Uuid::now_v7(), "jane@example.com", UserRole::Regular);
+    let mut repo = MockUserRepository::new();
+    let found = user.clone();
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(found.clone())));
+    let mut memberships = MockMembershipRepository::new();
+    let membership = membership_model(&user);
+    memberships
+        .expect_list_by_user()
+        .returning(move |_| Ok(vec![membership.clone()]));
+    memberships.expect_delete().never();
+
+    let service = UsersService::new(
+        Arc::new(repo),
+        Arc::new(memberships),
+        Arc::new(any_password()),
+        PasswordHashing::default(),
+        chrono::Duration::days(30),
+    );
+    let result = service.delete(Uuid::now_v7(), user.id).await;
+
+    assert!(matches!(result, Err(AppError::UserNotFound)));
+}
+
+#[tokio::test]
+async fn test_delete_user_from_home_tenant_keeps_identity_for_other_tenants() {
+    let home = Uuid::now_v7();
+    let user = user_model(home, "jane@example.com", UserRole::Regular);
+    let user_id = user.id;
+    let other = membership_model(&user_model(
+        Uuid::now_v7(),
+        "jane@example.com",
+        UserRole::Admin,
+    ));
+    let other_tenant = other.tenant_id;
+
+    let mut repo = MockUserRepository::new();
+    let found = user.clone();
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(found.clone())));
+    repo.expect_update().never();
+    let mut memberships = MockMembershipRepository::new();
+    memberships
+        .expect_delete()
+        .withf(move |_, tenant_id, home_tenant_id| {
+            *tenant_id == home && *home_tenant_id == Some(other_tenant)
+        })
+        .times(1)
+        .returning(|_, _, _| Ok(true));
+    let memberships_of_user = vec![membership_model(&user), other];
+    memberships
+        .expect_list_by_user()
+        .returning(move |_| Ok(memberships_of_user.clone()));
+
+    let service = UsersService::new(
+        Arc::new(repo),
+        Arc::new(memberships),
+        Arc::new(any_password()),
+        PasswordHashing::default(),
+        chrono::Duration::days(30),
+    );
+    service.delete(home, user_id).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_delete_user_of_a_single_tenant_soft_deletes_it() {
+    let tenant_id = Uuid::now_v7();
+    let user = user_model(tenant_id, "jane@example.com", UserRole::Regular);
+    let user_id = user.id;
+
+    let mut repo = MockUserRepository::new();
+    let found = user.clone();
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(found.clone())));
+    let updated = user.clone();
+    repo.expect_update()
+        .withf(|identity| matches!(identity.deleted_at, ActiveValue::Set(Some(_))))
+        .times(1)
+        .returning(move |_| Ok(updated.clone()));
+    let mut memberships = MockMembershipRepository::new();
+    let membership = membership_model(&user);
+    memberships
+        .expect_list_by_user()
+        .returning(move |_| Ok(vec![membership.clone()]));
+    memberships.expect_delete().never();
+
+    let service = UsersService::new(
+        Arc::new(repo),
+        Arc::new(memberships),
+        Arc::new(any_password()),
+        PasswordHashing::default(),
+        chrono::Duration::days(30),
+    );
+    service.delete(tenant_id, user_id).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_restore_user_only_within_grace_period() {
+    let tenant_id = Uuid::now_v7();
+    let mut recent = user_model(tenant_id, "jane@example.com", UserRole::Regular);
+    recent.deleted_at = Some((chrono::Utc::now() - chrono::Duration::days(29)).fixed_offset());
+    let mut expired = user_model(tenant_id, "john@example.com", UserRole::Regular);
+    expired.deleted_at = Some((chrono::Utc::now() - chrono::Duration::days(31)).fixed_offset());
+    let (recent_id, expired_id) = (recent.id, expired.id);
+
+    let mut repo = MockUserRepository::new();
+    repo.expect_find_deleted_in_tenant()
+        .returning(move |_, user_id| {
+            Ok([recent.clone(), expired.clone()]
+                .into_iter()
+                .find(|user| user.id == user_id))
+        });
+    repo.expect_update()
+        .withf(|identity| identity.deleted_at == ActiveValue::Set(None))
+        .times(1)
+        .returning(|identity| Ok(sea_orm::TryIntoModel::try_into_model(identity).unwrap()));
+
+    let service = UsersService::new(
+        Arc::new(repo),
+        Arc::new(MockMembershipRepository::new()),
+        Arc::new(any_password()),
+        PasswordHashing::default(),
+        chrono::Duration::days(30),
+    );
+
+    let restored = service.restore(tenant_id, recent_id).await.unwrap();
+    assert_eq!(restored.deleted_at, None);
+    assert!(matches!(
+        service.restore(tenant_id, expired_id).await,
+        Err(AppError::UserNotFound)
+    ));
+    assert!(matches!(
+        service.restore(tenant_id, Uuid::now_v7()).await,
+        Err(AppError::UserNotFound)
+    ));
+}
+
+#[tokio::test]
+async fn test_erase_deleted_anonymizes_users_past_grace_period() {
+    let mut user = user_model(Uuid::now_v7(), "jane@example.com", UserRole::Regular);
+    user.password_hash = "$argon2id$v=19$m=19456,t=2,p=1$c2FsdA$aGFzaA".to_string();
+    user.locale = Some(Locale::Es);
+    user.deleted_at = Some((chrono::Utc::now() - chrono::Duration::days(31)).fixed_offset());
+    let user_id = user.id;
+
+    let mut repo = MockUserRepository::new();
+    repo.expect_list_erasable()
+        .withf(|deleted_before| {
+            let expected = (chrono::Utc::now() - chrono::Duration::days(30)).fixed_offset();
+            (*deleted_before - expected).num_seconds().abs() < 5
+        })
+        .returning(move |_| Ok(vec![user.clone()]));
+    repo.expect_erase()
+        .withf(move |identity| {
+            identity.id == ActiveValue::Unchanged(user_id)
+                && identity.email == ActiveValue::Set(format!("erased-{}@erased.invalid", user_id))
+                && identity.password_hash == ActiveValue::Set(String::new())
+                && identity.locale == ActiveValue::Set(None)
+                && matches!(identity.erased_at, ActiveValue::Set(Some(_)))
+                && matches!(identity.deleted_at, ActiveValue::Unchanged(Some(_)))
+        })
+        .times(1)
+        .returning(|_| Ok(()));
+
+    let service = UsersService::new(
+        Arc::new(repo),
+        Arc::new(MockMembershipRepository::new()),
+        Arc::new(any_password()),
+        PasswordHashing::default(),
+        chrono::Duration::days(30),
+    );
+    assert_eq!(service.erase_deleted().await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_get_tenant_not_found() {
+    let mut repo = MockTenantRepository::new();
+    repo.expect_find_by_id().returning(|_| Ok(None));
+
+    let service = TenantsService::new(Arc::new(repo));
+    let result = service.get_by_id(Uuid::now_v7()).await;
+
+    assert!(matches!(result, Err(AppError::TenantNotFound)));
+}
+
+#[tokio::test]
+async fn test_register_first_user_becomes_admin() {
+    let tenant_id = Uuid::now_v7();
+    let mut repo = MockUserRepository::new();
+    repo.expect_tenant_has_users()
+        .with(eq(tenant_id))
+        .returning(|_| Ok(false));
+
+    let mut users = MockUserService::new();
+    users
+        .expect_create()
+        .withf(move |t, _, _, role| *t == tenant_id && *role == UserRole::Admin)
+        .times(1)
+        .returning(|tenant_id, email, _, role| Ok(user_model(tenant_id, &email, role)));
+    users.expect_record_login().returning(|_, _| Ok(()));
+    users.expect_list_memberships().returning(|_| Ok(vec![]));
+
+    let mut tenants = MockTenantRepository::new();
+    tenants.expect_find_by_id().returning(|tenant_id| {
+        let mut tenant = tenant_model("Acme");
+        tenant.id = tenant_id;
+        tenant.default_locale = Some(Locale::Pt);
+        Ok(Some(tenant))
+    });
+
+    let service = AuthService::new(
+        Arc::new(repo),
+        Arc::new(tenants),
+        Arc::new(users),
+        Arc::new(default_settings()),
+        Arc::new(any_password()),
+        "secret".to_string(),
+        10,
+    );
+    let response = service
+        .register(RegisterRequest {
+            tenant_id,
+            email: "first@example.com".to_string(),
+            password: "password123".to_string(),
+        })
+        .await
+        .unwrap();
+
+    let claims = AuthService::verify_token(&response.token, "secret").unwrap();
+    assert_eq!(claims.role, UserRole::Admin);
+    // No profile locale, so the tenant default is carried in the token
+    assert_eq!(claims.locale, Some(Locale::Pt));
+}
+
+#[tokio::test]
+async fn test_login_prefers_user_locale_over_tenant_default() {
+    let mut user = user_model(Uuid::now_v7(), "es@example.com", UserRole::Regular);
+    user.locale = Some(Locale::Es);
+
+    let mut repo = MockUserRepository::new();
+    repo.expect_find_by_email()
+        .returning(move |_| Ok(Some(user.clone())));
+    let mut users = MockUserService::new();
+    users
+        .expect_verify_password()
+        .withf(|_, password| password == "password123")
+        .returning(|_, _| Ok(true));
+    users.expect_record_login().returning(|_, _| Ok(()));
+    users.expect_list_memberships().returning(|_| Ok(vec![]));
+
+    // The tenant is not consulted when the user has a preference
+    let service = AuthService::new(
+        Arc::new(repo),
+        Arc::new(MockTenantRepository::new()),
+        Arc::new(users),
+        Arc::new(default_settings()),
+        Arc::new(any_password()),
+        "secret".to_string(),
+        10,
+    );
+    let response = service
+        .login(LoginRequest {
+            email: "es@example.com".to_string(),
+            password: "password123".to_string(),
+        })
+        .await
+        .unwrap();
+
+    let claims = AuthService::verify_token(&response.token, "secret").unwrap();
+    assert_eq!(claims.locale, Some(Locale::Es));
+}
+
+#[tokio::test]
+async fn test_login_rejects_inactive_user() {
+    let mut user = user_model(Uuid::now_v7(), "inactive@example.com", UserRole::Regular);
+    user.status = UserStatus::Inactive;
+    let membership = membership_model(&user);
+
+    let mut repo = MockUserRepository::new();
+    repo.expect_find_by_email()
+        .returning(move |_| Ok(Some(user.clone())));
+    let mut users = MockUserService::new();
+    users
+        .expect_verify_password()
+        .withf(|_, password| password == "password123")
+        .returning(|_, _| Ok(true));
+    users.expect_record_login().returning(|_, _| Ok(()));
+    users
+        .expect_list_memberships()
+        .returning(move |_| Ok(vec![membership.clone()]));
+
+    let service = AuthService::new(
+        Arc::new(repo),
+        Arc::new(MockTenantRepository::new()),
+        Arc::new(users),
+        Arc::new(default_settings()),
+        Arc::new(any_password()),
+        "secret".to_string(),
+        10,
+    );
+    let result = service
+        .login(LoginRequest {
+            email: "inactive@example.com".to_string(),
+            password: "password123".to_string(),
+        })
+        .await;
+
+    assert!(matches!(result, Err(AppError::UserNotValidated)));
+}
+
+#[tokio::test]
+async fn test_login_starts_in_another_tenant_when_home_membership_is_inactive() {
+    let mut user = user_model(Uuid::now_v7(), "jane@example.com", UserRole::Regular);
+    user.status = UserStatus::Inactive;
+    let home = membership_model(&user);
+    let mut other = membership_model(&user);
+    other.tenant_id = Uuid::now_v7();
+    other.role = UserRole::Admin;
+    other.status = UserStatus::Active;
+    let other_tenant = other.tenant_id;
+
+    let mut repo = MockUserRepository::new();
+    repo.expect_find_by_email()
+        .returning(move |_| Ok(Some(user.clone())));
+    let mut users = MockUserService::new();
+    users
+        .expect_verify_password()
+        .withf(|_, password| password == "password123")
+        .returning(|_, _| Ok(true));
+    users.expect_record_login().returning(|_, _| Ok(()));
+    users
+        .expect_list_memberships()
+        .returning(move |_| Ok(vec![home.clone(), other.clone()]));
+    let mut tenants = MockTenantRepository::new();
+    tenants.expect_find_by_id().returning(|_| Ok(None));
+
+    let service = AuthService::new(
+        Arc::new(repo),
+        Arc::new(tenants),
+        Arc::new(users),
+        Arc::new(default_settings()),
+        Arc::new(any_password()),
+        "secret".to_string(),
+        10,
+    );
+    let response = service
+        .login(LoginRequest {
+            email: "jane@example.com".to_string(),
+            password: "password123".to_string(),
+        })
+        .await
+        .unwrap();
+
+    let claims = AuthService::verify_token(&response.token, "secret").unwrap();
+    assert_eq!(claims.tenant_id, other_tenant);
+    assert_eq!(claims.role, UserRole::Admin);
+    assert_eq!(response.memberships.len(), 2);
+}
+
+fn claims_for(user: &users::Model, tenant_bound: bool) -> Claims {
+    Claims {
+        user_id: user.id,
+        tenant_id: user.tenant_id,
+        email: user.email.clone(),
+        role: user.role,
+        exp: 0,
+        locale: None,
+        tenant_bound,
+    }
+}
+
+#[tokio::test]
+async fn test_switch_tenant_requires_active_membership() {
+    let user = user_model(Uuid::now_v7(), "jane@example.com", UserRole::Regular);
+    let target = Uuid::now_v7();
+    let inactive = Uuid::now_v7();
+
+    let mut repo = MockUserRepository::new();
+    let email = user.email.clone();
+    repo.expect_find_in_tenant().returning(move |tenant_id, _| {
+        Ok(if tenant_id == target {
+            Some(user_model(tenant_id, &email, UserRole::Admin))
+        } else if tenant_id == inactive {
+            let mut member = user_model(tenant_id, &email, UserRole::Regular);
+            member.status = UserStatus::Inactive;
+            Some(member)
+        } else {
+            None
+        })
+    });
+    let mut users = MockUserService::new();
+    users.expect_record_login().returning(|_, _| Ok(()));
+    users.expect_list_memberships().returning(|_| Ok(vec![]));
+    let mut tenants = MockTenantRepository::new();
+    tenants.expect_find_by_id().returning(|_| Ok(None));
+
+    let service = AuthService::new(
+        Arc::new(repo),
+        Arc::new(tenants),
+        Arc::new(users),
+        Arc::new(default_settings()),
+        Arc::new(any_password()),
+        "secret".to_string(),
+        10,
+    );
+
+    let response = service
+        .switch_tenant(claims_for(&user, false), target)
+        .await
+        .unwrap();
+    let claims = AuthService::verify_token(&response.token, "secret").unwrap();
+    assert_eq!(claims.tenant_id, target);
+    assert_eq!(claims.role, UserRole::Admin);
+
+    for tenant_id in [inactive, Uuid::now_v7()] {
+        let result = service
+            .switch_tenant(claims_for(&user, false), tenant_id)
+            .await;
+        assert!(matches!(result, Err(AppError::TenantAccessDenied)));
+    }
+
+    // A token from a tenant's identity provider stays in that tenant
+    let result = service.switch_tenant(claims_for(&user, true), target).await;
+    assert!(matches!(result, Err(AppError::TenantAccessDenied)));
+}
+
+/// Settings service where every tenant has the defaults changed by `edit`.
+fn settings_with(edit: impl Fn(&mut TenantSettings) + Send + 'static) -> MockTenantSettingsService {
+    let mut settings = MockTenantSettingsService::new();
+    settings.expect_get().returning(move |tenant_id| {
+        let mut settings = TenantSettings::default();
+        edit(&mut settings);
+        Ok(TenantSettingsResponse {
+            tenant_id,
+            version: 1,
+            settings,
+        })
+    });
+    settings
+}
+
+fn register_request(password: &str) -> RegisterRequest {
+    RegisterRequest {
+        tenant_id: Uuid::now_v7(),
+        email: "new@example.com".to_string(),
+        password: password.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_register_follows_tenant_settings() {
+    let mut users = MockUserService::new();
+    users.expect_create().never();
+
+    let service = AuthService::new(
+        Arc::new(MockUserRepository::new()),
+        Arc::new(MockTenantRepository::new()),
+        Arc::new(users),
+        Arc::new(settings_with(|s| s.registration_open = false)),
+        Arc::new(any_password()),
+        "secret".to_string(),
+        10,
+    );
+    let result = service.register(register_request("password123")).await;
+    assert!(matches!(result, Err(AppError::RegistrationClosed)));
+
+    let mut users = MockUserService::new();
+    users.expect_create().never();
+    let service = AuthService::new(
+        Arc::new(MockUserRepository::new()),
+        Arc::new(MockTenantRepository::new()),
+        Arc::new(users),
+        Arc::new(default_settings()),
+        Arc::new(password_policy(
+            settings_with(|s| s.password_min_length = Some(12)),
+            MockPasswordHistoryRepository::new(),
+        )),
+        "secret".to_string(),
+        10,
+    );
+    let Err(AppError::Validation(errors)) = service.register(register_request("password123")).await
+    else {
+        panic!("expected a validation error");
+    };
+    assert_eq!(errors[0].field, "password");
+    assert_eq!(errors[0].params["min"], 12);
+}
+
+/// The default global policy over the given tenant settings and password
+/// history, for tenants named "Acme".
+fn password_policy(
+    settings: MockTenantSettingsService,
+    history: MockPasswordHistoryRepository,
+) -> PasswordsService {
+    let mut tenants = MockTenantRepository::new();
+    tenants
+        .expect_find_by_id()
+        .returning(|_| Ok(Some(tenant_model("Acme"))));
+    PasswordsService::new(
+        PasswordPolicy::default(),
+        None,
+        Arc::new(settings),
+        Arc::new(tenants),
+        Arc::new(history),
+        PasswordHashing::default(),
+    )
+}
+
+fn error_codes(result: Result<(), AppError>) -> Vec<String> {
+    match result {
+        Err(AppError::Validation(errors)) => errors.into_iter().map(|e| e.code).collect(),
+        other => panic!("expected a validation error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_password_policy_applies_strictest_tenant_and_breached_list() {
+    let strict = Uuid::now_v7();
+    let mut settings = MockTenantSettingsService::new();
+    settings.expect_get().returning(move |tenant_id| {
+        let mut settings = TenantSettings::default();
+        if tenant_id == strict {
+            settings.password_min_character_classes = Some(3);
+        }
+        Ok(TenantSettingsResponse {
+            tenant_id,
+            version: 1,
+            settings,
+        })
+    });
+    let mut tenants = MockTenantRepository::new();
+    tenants
+        .expect_find_by_id()
+        .returning(|_| Ok(Some(tenant_model("Acme"))));
+    let dir = std::env::temp_dir().join(format!("pwned-{}", Uuid::now_v7()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("ABF7A.txt"),
+        "AD6438836DBE526AA231ABDE2D0EEF74D42:12\n",
+    )
+    .unwrap();
+    let breached = BreachedPasswords::new(&dir);
+    let service = PasswordsService::new(
+        PasswordPolicy::default(),
+        Some(Arc::new(breached)),
+        Arc::new(settings),
+        Arc::new(tenants),
+        Arc::new(MockPasswordHistoryRepository::new()),
+        PasswordHashing::default(),
+    );
+
+    let tenant_ids = [Uuid::now_v7(), strict];
+    let result = service
+        .check(&tenant_ids, "jane@example.com", "acme rocks forever")
+        .await;
+    assert_eq!(error_codes(result), ["character_classes", "personal_info"]);
+
+    let result = service
+        .check(
+            &tenant_ids[..1],
+            "jane@example.com",
+            "correct horse battery staple",
+        )
+        .await;
+    assert_eq!(error_codes(result), ["breached"]);
+
+    service
+        .check(&tenant_ids, "jane@example.com", "Blue-Kettle-Sings")
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_reset_password_rejects_recent_passwords_and_keeps_history() {
+    let mut user = user_model(Uuid::now_v7(), "jane@example.com", UserRole::Regular);
+    user.password_hash = PasswordHashing::default()
+        .hash("Current-Secret-1")
+        .await
+        .unwrap();
+    let older = PasswordHashing::default()
+        .hash("Older-Secret-2")
+        .await
+        .unwrap();
+
+    let mut repo = MockUserRepository::new();
+    let found = user.clone();
+    repo.expect_find_by_email_in_tenant()
+        .returning(move |_, _| Ok(Some(found.clone())));
+    repo.expect_update().times(1).returning(|identity| {
+        let ActiveValue::Set(hash) = identity.password_hash.clone() else {
+            panic!("password hash not set");
+        };
+        Ok(users::Model {
+            password_hash: hash,
+            ..user_model(Uuid::now_v7(), "jane@example.com", UserRole::Regular)
+        })
+    });
+    let mut memberships = MockMembershipRepository::new();
+    let membership = membership_model(&user);
+    memberships
+        .expect_list_by_user()
+        .returning(move |_| Ok(vec![membership.clone()]));
+
+    let mut history = MockPasswordHistoryRepository::new();
+    let user_id = user.id;
+    history
+        .expect_list_recent()
+        .with(eq(user_id), eq(2))
+        .returning(move |user_id, _| {
+            Ok(vec![password_history::Model {
+                id: Uuid::now_v7(),
+                user_id,
+                password_hash: older.clone(),
+                created_at: chrono::Utc::now().fixed_offset(),
+            }])
+        });
+    let replaced = user.password_hash.clone();
+    history
+        .expect_insert()
+        .withf(move |entry| entry.password_hash == ActiveValue::Set(replaced.clone()))
+        .times(1)
+        .returning(|entry| Ok(sea_orm::TryIntoModel::try_into_model(entry).unwrap()));
+    history
+        .expect_prune()
+        .with(eq(user_id), eq(24))
+        .times(1)
+        .returning(|_, _| Ok(()));
+
+    let passwords = password_policy(settings_with(|s| s.password_history = Some(3)), history);
+    let service = UsersService::new(
+        Arc::new(repo),
+        Arc::new(memberships),
+        Arc::new(passwords),
+        PasswordHashing::default(),
+        chrono::Duration::days(30),
+    );
+
+    for reused in ["Current-Secret-1", "Older-Secret-2"] {
+        let Err(AppError::Validation(errors)) = service
+            .reset_password(user.tenant_id, "jane@example.com", reused)
+            .await
+        else {
+            panic!("expected {} to be rejected", reused);
+        };
+        assert_eq!(errors[0].code, "reused");
+        assert_eq!(errors[0].params["count"], 3);
+    }
+
+    let updated = service
+        .reset_password(user.tenant_id, "jane@example.com", "Brand-New-Secret-3")
+        .await
+        .unwrap();
+    assert!(
+        PasswordHashing::default()
+            .verify("Brand-New-Secret-3", &updated.password_hash)
+            .await
+            .unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_change_password_checks_current_password_first() {
+    let mut user = user_model(Uuid::now_v7(), "jane@example.com", UserRole::Regular);
+    user.password_hash = PasswordHashing::default()
+        .hash("Current-Secret-1")
+        .await
+        .unwrap();
+
+    let mut repo = MockUserRepository::new();
+    let found = user.clone();
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(found.clone())));
+    repo.expect_update().never();
+    let mut memberships = MockMembershipRepository::new();
+    let membership = membership_model(&user);
+    memberships
+        .expect_list_by_user()
+        .returning(move |_| Ok(vec![membership.clone()]));
+
+    let passwords = password_policy(default_settings(), MockPasswordHistoryRepository::new());
+    let service = UsersService::new(
+        Arc::new(repo),
+        Arc::new(memberships),
+        Arc::new(passwords),
+        PasswordHashing::default(),
+        chrono::Duration::days(30),
+    );
+
+    let Err(AppError::Validation(errors)) = service
+        .change_password(user.id, "wrong", "Brand-New-Secret-3")
+        .await
+    else {
+        panic!("expected a validation error");
+    };
+    assert_eq!(errors[0].field, "current_password");
+    assert_eq!(errors[0].code, "invalid");
+
+    // Policy errors name the field of the request
+    let Err(AppError::Validation(errors)) = service
+        .change_password(user.id, "Current-Secret-1", "jane-secret-99")
+        .await
+    else {
+        panic!("expected a validation error");
+    };
+    assert_eq!(errors[0].field, "new_password");
+    assert_eq!(errors[0].code, "personal_info");
+}
+
+#[tokio::test]
+async fn test_tenant_token_lifetime_overrides_default() {
+    let user = user_model(Uuid::now_v7(), "jane@example.com", UserRole::Regular);
+    let mut repo = MockUserRepository::new();
+    let found = user.clone();
+    repo.expect_find_in_tenant()
+        .returning(move |_, _| Ok(Some(found.clone())));
+    let mut users = MockUserService::new();
+    users.expect_record_login().returning(|_, _| Ok(()));
+    users.expect_list_memberships().returning(|_| Ok(vec![]));
+    let mut tenants = MockTenantRepository::new();
+    tenants.expect_find_by_id().returning(|_| Ok(None));
+
+    let service = AuthService::new(
+        Arc::new(repo),
+        Arc::new(tenants),
+        Arc::new(users),
+        Arc::new(settings_with(|s| s.jwt_expiration_minutes = Some(120))),
+        Arc::new(any_password()),
+        "secret".to_string(),
+        10,
+    );
+    let response = service
+        .refresh_token(claims_for(&user, false))
+        .await
+        .unwrap();
+
+    let claims = AuthService::verify_token(&response.token, "secret").unwrap();
+    let lifetime = claims.exp - chrono::Utc::now().timestamp();
+    assert!((119 * 60..=120 * 60).contains(&lifetime));
+}
+
+fn settings_row(
+    tenant_id: Uuid,
+    version: i32,
+    settings: serde_json::Value,
+) -> tenant_settings::Model {
+    tenant_settings::Model {
+        tenant_id,
+        version,
+        settings,
+        updated_at: chrono::Utc::now().fixed_offset(),
+    }
+}
+
+#[tokio::test]
+async fn test_tenant_settings_are_cached_until_updated() {
+    let tenant_id = Uuid::now_v7();
+    let mut repo = MockTenantSettingsRepository::new();
+    repo.expect_find()
+        .with(eq(tenant_id))
+        .times(1)
+        .returning(|_| Ok(None));
+    repo.expect_save()
+        .withf(move |t, version, settings| {
+            *t == tenant_id && *version == 0 && settings["registration_open"] == false
+        })
+        .times(1)
+        .returning(|tenant_id, _, settings| Ok(Some(settings_row(tenant_id, 1, settings))));
+    let mut tenants = MockTenantRepository::new();
+    tenants.expect_find_by_id().times(1).returning(|tenant_id| {
+        Ok(Some(tenants::Model {
+            id: tenant_id,
+            ..tenant_model("Acme")
+        }))
+    });
+
+    let store = TenantSettingsStore::new(Arc::new(repo), Arc::new(tenants));
+    for _ in 0..2 {
+        let current = store.get(tenant_id).await.unwrap();
+        assert_eq!(current.version, 0);
+        assert_eq!(current.settings, TenantSettings::default());
+    }
+
+    let settings = TenantSettings {
+        registration_open: false,
+        ..Default::default()
+    };
+    let updated = store
+        .update(
+            tenant_id,
+            UpdateTenantSettingsRequest {
+                version: 0,
+                settings: settings.clone(),
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(updated.version, 1);
+
+    // Served from the cache, without another read
+    assert_eq!(store.get(tenant_id).await.unwrap().settings, settings);
+}
+
+#[tokio::test]
+async fn test_unknown_tenant_settings_are_not_cached() {
+    let tenant_id = Uuid::now_v7();
+    let mut repo = MockTenantSettingsRepository::new();
+    repo.expect_find().times(2).returning(|_| Ok(None));
+    let mut tenants = MockTenantRepository::new();
+    tenants.expect_find_by_id().times(2).returning(|_| Ok(None));
+
+    let store = TenantSettingsStore::new(Arc::new(repo), Arc::new(tenants));
+    for _ in 0..2 {
+        let current = store.get(tenant_id).await.unwrap();
+        assert_eq!(current.settings, TenantSettings::default());
+    }
+}
+
+#[tokio::test]
+async fn test_stale_tenant_settings_update_conflicts_and_drops_cache() {
+    let tenant_id = Uuid::now_v7();
+    let mut repo = MockTenantSettingsRepository::new();
+    let mut reads = 0;
+    repo.expect_find().times(2).returning(move |tenant_id| {
+        reads += 1;
+        Ok(Some(settings_row(
+            tenant_id,
+            reads,
+            serde_json::json!({ "password_min_length": 8 + reads }),
+        )))
+    });
+    repo.expect_save().returning(|_, _, _| Ok(None));
+
+    let store = TenantSettingsStore::new(Arc::new(repo), Arc::new(MockTenantRepository::new()));
+    assert_eq!(store.get(tenant_id).await.unwrap().version, 1);
+
+    let result = store
+        .update(
+            tenant_id,
+            UpdateTenantSettingsRequest {
+                version: 1,
+                settings: TenantSettings::default(),
+            },
+        )
+        .await;
+    assert!(matches!(result, Err(AppError::SettingsVersionConflict)));
+
+    // The next read sees what the other writer stored
+    let current = store.get(tenant_id).await.unwrap();
+    assert_eq!(current.version, 2);
+    assert_eq!(current.settings.password_min_length, Some(10));
+}
+
+/// A repository holding one key for `secret`, as issued by the service.
+fn key_repo(
+    secret: &str,
+    edit: impl Fn(&mut template_rust_backend::models::api_keys::Model),
+) -> MockApiKeyRepository {
+    let account = service_account_model(Uuid::now_v7(), UserRole::Admin);
+    let prefix = secret[..15].to_string();
+    let mut key = api_key_model(&account, &prefix, &ApiKeysService::hash_secret(secret));
+    edit(&mut key);
+
+    let mut repo = MockApiKeyRepository::new();
+    repo.expect_find_key_by_prefix()
+        .withf(move |p| p == prefix)
+        .returning(move |_| Ok(Some((key.clone(), account.clone()))));
+    repo
+}
+
+#[test]
+fn test_generated_api_key_shape() {
+    let (prefix, secret) = ApiKeysService::generate_secret();
+
+    assert!(secret.starts_with(&format!("{}_", prefix)));
+    assert_eq!(prefix.len(), 15);
+    assert_eq!(secret.len(), 15 + 1 + 64);
+    assert_ne!(ApiKeysService::hash_secret(&secret), secret);
+}
+
+#[tokio::test]
+async fn test_authenticate_api_key_yields_service_account_principal() {
+    let (_, secret) = ApiKeysService::generate_secret();
+    let mut repo = key_repo(&secret, |_| {});
+    repo.expect_touch_key().times(1).returning(|_, _| Ok(()));
+
+    let service = ApiKeysService::new(Arc::new(repo));
+    let principal = service.authenticate(&secret).await.unwrap();
+
+    assert_eq!(principal.role, UserRole::Admin);
+    assert!(principal.has_scope(ApiScope::UsersRead));
+    assert!(!principal.has_scope(ApiScope::UsersWrite));
+    assert!(matches!(
+        principal.kind,
+        PrincipalKind::ServiceAccount { .. }
+    ));
+}
+
+#[tokio::test]
+async fn test_authenticate_skips_recent_last_used_write() {
+    let (_, secret) = ApiKeysService::generate_secret();
+    let mut repo = key_repo(&secret, |key| {
+        key.last_used_at = Some(chrono::Utc::now().fixed_offset())
+    });
+    repo.expect_touch_key().never();
+
+    let service = ApiKeysService::new(Arc::new(repo));
+    assert!(service.authenticate(&secret).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_authenticate_rejects_wrong_revoked_and_expired_keys() {
+    let (_, secret) = ApiKeysService::generate_secret();
+
+    // Same prefix, different secret part
+    let forged = format!("{}{}", &secret[..16], "0".repeat(64));
+    let service = ApiKeysService::new(Arc::new(key_repo(&secret, |_| {})));
+    assert!(matches!(
+        service.authenticate(&forged).await,
+        Err(AppError::Auth(AuthError::InvalidToken))
+    ));
+
+    let service = ApiKeysService::new(Arc::new(key_repo(&secret, |key| {
+        key.revoked_at = Some(chrono::Utc::now().fixed_offset())
+    })));
+    assert!(matches!(
+        service.authenticate(&secret).await,
+        Err(AppError::Auth(AuthError::InvalidToken))
+    ));
+
+    let service = ApiKeysService::new(Arc::new(key_repo(&secret, |key| {
+        key.expires_at = Some((chrono::Utc::now() - chrono::Duration::hours(1)).fixed_offset())
+    })));
+    assert!(matches!(
+        service.authenticate(&secret).await,
+        Err(AppError::Auth(AuthError::ExpiredToken))
+    ));
+
+    // Not an API key at all: no lookup
+    let service = ApiKeysService::new(Arc::new(MockApiKeyRepository::new()));
+    assert!(matches!(
+        service.authenticate("not-a-key").await,
+        Err(AppError::Auth(AuthError::InvalidToken))
+    ));
+}
+
+/// A repository holding `account` and the key `edit` makes of it.
+fn rotation_repo(
+    account: &template_rust_backend::models::service_accounts::Model,
+    edit: impl Fn(&mut template_rust_backend::models::api_keys::Model),
+) -> (
+    MockApiKeyRepository,
+    template_rust_backend::models::api_keys::Model,
+) {
+    let mut key = api_key_model(account, "sk_000000000000", "hash");
+    edit(&mut key);
+
+    let mut repo = MockApiKeyRepository::new();
+    let found = key.clone();
+    repo.expect_find_key()
+        .with(eq(key.tenant_id), eq(key.id))
+        .returning(move |_, _| Ok(Some(found.clone())));
+    let found = account.clone();
+    repo.expect_find_service_account()
+        .with(eq(account.tenant_id), eq(account.id))
+        .returning(move |_, _| Ok(Some(found.clone())));
+    (repo, key)
+}
+
+#[tokio::test]
+async fn test_rotate_key_revokes_old_and_keeps_settings() {
+    let account = service_account_model(Uuid::now_v7(), UserRole::Regular);
+    let now = chrono::Utc::now();
+    let (mut repo, old) = rotation_repo(&account, |key| {
+        key.scopes = "users:read tenants:read".to_string();
+        key.created_at = (now - chrono::Duration::days(10)).fixed_offset();
+        key.expires_at = Some((now + chrono::Duration::days(20)).fixed_offset());
+    });
+    let (tenant_id, key_id) = (old.tenant_id, old.id);
+    // The old key is revoked with the new one inserted, in one transaction
+    repo.expect_update_key().never();
+    repo.expect_insert_key().never();
+    repo.expect_rotate_key()
+        .withf(move |revoked, key| {
+            // The 30 day lifetime starts again
+            let expires_at = key.expires_at.clone().unwrap().unwrap().to_utc();
+            revoked.id == ActiveValue::Unchanged(key_id)
+                && matches!(revoked.revoked_at, ActiveValue::Set(Some(_)))
+                && key.scopes == ActiveValue::Set("users:read tenants:read".to_string())
+                && key.id != ActiveValue::Set(key_id)
+                && (expires_at - (now + chrono::Duration::days(30)))
+                    .num_seconds()
+                    .abs()
+                    < 60
+        })
+        .times(1)
+        .returning(|_, key| {
+            let account = service_account_model(key.tenant_id.clone().unwrap(), UserRole::Regular);
+            Ok(api_key_model(
+                &account,
+                &key.prefix.clone().unwrap(),
+                &key.key_hash.clone().unwrap(),
+            ))
+        });
+
+    let service = ApiKeysService::new(Arc::new(repo));
+    let caller = service_account_principal(tenant_id, UserRole::Admin, ApiScope::ALL.to_vec());
+    let issued = service
+        .rotate_key(&caller, tenant_id, key_id)
+        .await
+        .unwrap();
+
+    assert!(issued.secret.starts_with(&issued.key.prefix));
+    assert_eq!(
+        issued.key.key_hash,
+        ApiKeysService::hash_secret(&issued.secret)
+    );
+}
+
+#[tokio::test]
+async fn test_rotate_key_rejects_revoked_and_expired_keys() {
+    let account = service_account_model(Uuid::now_v7(), UserRole::Regular);
+    let caller =
+        service_account_principal(account.tenant_id, UserRole::Admin, ApiScope::ALL.to_vec());
+    let edits: [fn(&mut template_rust_backend::models::api_keys::Model); 2] = [
+        |key| key.revoked_at = Some(chrono::Utc::now().fixed_offset()),
+        |key| {
+            key.expires_at = Some((chrono::Utc::now() - chrono::Duration::hours(1)).fixed_offset())
+        },
+    ];
+
+    for edit in edits {
+        let (mut repo, key) = rotation_repo(&account, edit);
+        repo.expect_rotate_key().never();
+
+        let service = ApiKeysService::new(Arc::new(repo));
+        let result = service.rotate_key(&caller, key.tenant_id, key.id).await;
+
+        assert!(matches!(result, Err(AppError::ApiKeyInactive)));
+    }
+}
+
+#[tokio::test]
+async fn test_keys_cannot_exceed_the_caller() {
+    let account = service_account_model(Uuid::now_v7(), UserRole::Admin);
+    let tenant_id = account.tenant_id;
+    let (mut repo, key) = rotation_repo(&account, |_| {});
+    repo.expect_insert_service_account().never();
+    repo.expect_rotate_key().never();
+    repo.expect_insert_key().never();
+    let service = ApiKeysService::new(Arc::new(repo));
+
+    // A key that may manage keys but not read users cannot mint one that can
+    let caller =
+        service_account_principal(tenant_id, UserRole::Admin, vec![ApiScope::ApiKeysManage]);
+    let result = service
+        .create_key(
+            &caller,
+            tenant_id,
+            account.id,
+            "deploy".to_string(),
+            vec![ApiScope::UsersRead],
+            None,
+        )
+        .await;
+    assert!(matches!(
+        result,
+        Err(AppError::InsufficientScope(ApiScope::UsersRead))
+    ));
+    let result = service.rotate_key(&caller, tenant_id, key.id).await;
+    assert!(matches!(
+        result,
+        Err(AppError::InsufficientScope(ApiScope::UsersRead))
+    ));
+
+    // Nor can a regular principal hand out the admin role
+    let caller = service_account_principal(tenant_id, UserRole::Regular, ApiScope::ALL.to_vec());
+    let result = service
+        .create_service_account(&caller, tenant_id, "ci".to_string(), UserRole::Admin)
+        .await;
+    assert!(matches!(result, Err(AppError::Forbidden(_))));
+    let result = service
+        .create_key(
+            &caller,
+            tenant_id,
+            account.id,
+            "deploy".to_string(),
+            vec![ApiScope::UsersRead],
+            None,
+        )
+        .await;
+    assert!(matches!(result, Err(AppError::Forbidden(_))));
+}
+
+#[tokio::test]
+async fn test_revoke_unknown_key() {
+    let mut repo = MockApiKeyRepository::new();
+    repo.expect_find_key().returning(|_, _| Ok(None));
+
+    let service = ApiKeysService::new(Arc::new(repo));
+    let result = service.revoke_key(Uuid::now_v7(), Uuid::now_v7()).await;
+
+    assert!(matches!(result, Err(AppError::ApiKeyNotFound)));
+}
+
+#[tokio::test]
+async fn test_verify_password_rehashes_outdated_hash() {
+    let outdated = PasswordHashing::new(argon2::Params::new(8, 1, 1, None).unwrap(), None);
+    let mut user = user_model(Uuid::now_v7(), "jane@example.com", UserRole::Regular);
+    user.password_hash = outdated.hash("password123").await.unwrap();
+
+    let mut repo = MockUserRepository::new();
+    let user_id = user.id;
+    repo.expect_update()
+        .withf(move |identity| {
+            identity.id == ActiveValue::Unchanged(user_id)
+                && matches!(
+                    &identity.password_hash,
+                    ActiveValue::Set(hash) if hash.starts_with("$argon2id$v=19$m=19456,t=2,p=1$")
+                )
+        })
+        .times(1)
+        .returning(|identity| Ok(sea_orm::TryIntoModel::try_into_model(identity).unwrap()));
+
+    let service = UsersService::new(
+        Arc::new(repo),
+        Arc::new(MockMembershipRepository::new()),
+        Arc::new(any_password()),
+        PasswordHashing::default(),
+        chrono::Duration::days(30),
+    );
+
+    assert!(!service.verify_password(&user, "wrong").await.unwrap());
+    assert!(service.verify_password(&user, "password123").await.unwrap());
+}
+
+fn export_model(
+    tenant_id: Uuid,
+    format: ExportFormat,
+    status: ExportStatus,
+) -> tenant_exports::Model {
+    tenant_exports::Model {
+        id: Uuid::now_v7(),
+        tenant_id,
+        requested_by: Uuid::now_v7(),
+        format,
+        status,
+        size_bytes: None,
+        error: None,
+        created_at: chrono::Utc::now().fixed_offset(),
+        completed_at: None,
+    }
+}
+
+fn exporter(
+    exports: MockTenantExportRepository,
+    sources: ExportSources,
+    queue: MockJobQueue,
+    dir: PathBuf,
+) -> TenantExporter {
+    TenantExporter::new(
+        Arc::new(exports),
+        sources,
+        Arc::new(queue),
+        dir,
+        "export-test-secret".to_string(),
+        chrono::Duration::minutes(15),
+    )
+}
+
+fn no_sources() -> ExportSources {
+    ExportSources {
+        tenants: Arc::new(MockTenantRepository::new()),
+        settings: Arc::new(MockTenantSettingsRepository::new()),
+        users: Arc::new(MockUserRepository::new()),
+        memberships: Arc::new(MockMembershipRepository::new()),
+        api_keys: Arc::new(MockApiKeyRepository::new()),
+        oidc: Arc::new(MockOidcRepository::new()),
+    }
+}
+
+#[tokio::test]
+async fn test_export_writes_members_without_credentials() {
+    let tenant = tenant_model("Acme");
+    let tenant_id = tenant.id;
+    let mut user = user_model(tenant_id, "jane@example.com", UserRole::Admin);
+    user.password_hash = "$argon2id$secret".to_string();
+    let membership = membership_model(&user);
+    // A deleted user's membership is still stored but must not be exported
+    let deleted = user_model(tenant_id, "gone@example.com", UserRole::Regular);
+    let deleted_membership = membership_model(&deleted);
+    let export = export_model(tenant_id, ExportFormat::Ndjson, ExportStatus::Pending);
+
+    let mut tenants = MockTenantRepository::new();
+    tenants
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(tenant.clone())));
+    let mut settings = MockTenantSettingsRepository::new();
+    settings.expect_find().returning(|_| Ok(None));
+    let mut users = MockUserRepository::new();
+    users
+        .expect_list_by_tenant()
+        .returning(move |_| Ok(vec![user.clone()]));
+    let mut memberships = MockMembershipRepository::new();
+    memberships
+        .expect_list_by_tenant()
+        .returning(move |_| Ok(vec![membership.clone(), deleted_membership.clone()]));
+    let mut api_keys = MockApiKeyRepository::new();
+    api_keys
+        .expect_list_service_accounts()
+        .returning(|_| Ok(vec![]));
+    api_keys.expect_list_keys().returning(|_| Ok(vec![]));
+    let mut oidc = MockOidcRepository::new();
+    oidc.expect_list_providers().returning(|_| Ok(vec![]));
+
+    let mut exports = MockTenantExportRepository::new();
+    let pending = export.clone();
+    exports
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(pending.clone())));
+    exports
+        .expect_update()
+        .withf(|export| export.status == ActiveValue::Set(ExportStatus::Running))
+        .times(1)
+        .returning(|export| Ok(export.try_into_model().unwrap()));
+    exports
+        .expect_update()
+        .withf(|export| {
+            export.status == ActiveValue::Set(ExportStatus::Completed)
+                && matches!(export.size_bytes, ActiveValue::Set(Some(size)) if size > 0)
+        })
+        .times(1)
+        .returning(|export| Ok(export.try_into_model().unwrap()));
+
+    let dir = std::env::temp_dir().join(format!("exports-{}", Uuid::now_v7()));
+    let service = exporter(
+        exports,
+        ExportSources {
+            tenants: Arc::new(tenants),
+            settings: Arc::new(settings),
+            users: Arc::new(users),
+            memberships: Arc::new(memberships),
+            api_keys: Arc::new(api_keys),
+            oidc: Arc::new(oidc),
+        },
+        MockJobQueue::new(),
+        dir.clone(),
+    );
+    service.run(export.id).await.unwrap();
+
+    let content = std::fs::read_to_string(dir.join(format!("{}.ndjson", export.id))).unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+    let lines: Vec<serde_json::Value> = content
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines[0]["format_version"], ARCHIVE_FORMAT_VERSION);
+    assert_eq!(lines[0]["export_id"], export.id.to_string());
+    let types: Vec<&str> = lines[1..]
+        .iter()
+        .map(|line| line["type"].as_str().unwrap())
+        .collect();
+    assert_eq!(types, ["tenant", "user", "membership"]);
+    assert_eq!(lines[2]["data"]["email"], "jane@example.com");
+    assert!(!content.contains("password_hash"));
+    assert!(!content.contains("$argon2id$secret"));
+}
+
+#[tokio::test]
+async fn test_export_download_link_is_bound_to_its_export() {
+    let tenant_id = Uuid::now_v7();
+    let completed = export_model(tenant_id, ExportFormat::Json, ExportStatus::Completed);
+    let pending = export_model(tenant_id, ExportFormat::Json, ExportStatus::Pending);
+    let (completed_id, pending_id) = (completed.id, pending.id);
+
+    let mut exports = MockTenantExportRepository::new();
+    exports.expect_find().returning(move |_, export_id| {
+        Ok([completed.clone(), pending.clone()]
+            .into_iter()
+            .find(|export| export.id == export_id))
+    });
+    exports.expect_find_by_id().never();
+    let service = exporter(
+        exports,
+        no_sources(),
+        MockJobQueue::new(),
+        std::env::temp_dir(),
+    );
+
+    let pending = service.get(tenant_id, pending_id).await.unwrap();
+    assert_eq!(pending.download_url, None);
+
+    let completed = service.get(tenant_id, completed_id).await.unwrap();
+    let url = completed.download_url.unwrap();
+    assert!(url.starts_with(&format!("/api/exports/{}/download?token=", completed_id)));
+    let token = url.split("token=").nth(1).unwrap();
+
+    // A link opens only the export it was issued for
+    let result = service.download(pending_id, token).await;
+    assert!(matches!(result, Err(AppError::DownloadLinkInvalid)));
+    let result = service.download(completed_id, "not-a-token").await;
+    assert!(matches!(result, Err(AppError::DownloadLinkInvalid)));
+
+    // Access tokens are signed with the same secret but are not links
+    let access_token = AuthService::generate_token(
+        Uuid::now_v7(),
+        tenant_id,
+        "jane@example.com".to_string(),
+        UserRole::Admin,
+        "export-test-secret",
+        10,
+    )
+    .unwrap();
+    let result = service.download(completed_id, &access_token).await;
+    assert!(matches!(result, Err(AppError::DownloadLinkInvalid)));
+
+    let result = service.get(tenant_id, Uuid::now_v7()).await;
+    assert!(matches!(result, Err(AppError::ExportNotFound)));
+}
+
+#[tokio::test]
+async fn test_export_request_queues_its_job() {
+    let tenant_id = Uuid::now_v7();
+    let requested_by = Uuid::now_v7();
+
+    let mut exports = MockTenantExportRepository::new();
+    exports
+        .expect_insert()
+        .times(1)
+        .returning(|export| Ok(export.try_into_model().unwrap()));
+    let mut queue = MockJobQueue::new();
+    queue
+        .expect_enqueue()
+        .withf(|job| job.kind == RunExport::KIND && job.payload["export_id"].is_string())
+        .times(1)
+        .returning(|job| Ok(job_model(&job.kind, job.payload)));
+    let service = exporter(exports, no_sources(), queue, std::env::temp_dir());
+
+    let response = service
+        .request(tenant_id, requested_by, ExportFormat::Ndjson)
+        .await
+        .unwrap();
+    assert_eq!(response.export.status, ExportStatus::Pending);
+    assert_eq!(response.export.requested_by, requested_by);
+    assert_eq!(response.download_url, None);
+}
+
+#[tokio::test]
+async fn test_export_that_cannot_be_queued_is_failed() {
+    let mut exports = MockTenantExportRepository::new();
+    exports
+        .expect_insert()
+        .returning(|export| Ok(export.try_into_model().unwrap()));
+    exports
+        .expect_update()
+        .withf(|export| export.status == ActiveValue::Set(ExportStatus::Failed))
+        .times(1)
+        .returning(|export| Ok(export.try_into_model().unwrap()));
+    let mut queue = MockJobQueue::new();
+    queue
+        .expect_enqueue()
+        .returning(|_| Err(AppError::Internal));
+    let service = exporter(exports, no_sources(), queue, std::env::temp_dir());
+
+    let result = service
+        .request(Uuid::now_v7(), Uuid::now_v7(), ExportFormat::Json)
+        .await;
+    assert!(matches!(result, Err(AppError::Internal)));
+}
+
+#[tokio::test]
+async fn test_export_job_skips_finished_exports() {
+    let finished = export_model(Uuid::now_v7(), ExportFormat::Json, ExportStatus::Completed);
+    let finished_id = finished.id;
+
+    let mut exports = MockTenantExportRepository::new();
+    exports
+        .expect_find_by_id()
+        .returning(move |export_id| Ok((export_id == finished.id).then(|| finished.clone())));
+    exports.expect_update().never();
+    let service = exporter(
+        exports,
+        no_sources(),
+        MockJobQueue::new(),
+        std::env::temp_dir(),
+    );
+
+    service.run(finished_id).await.unwrap();
+    // Deleted along with its tenant
+    service.run(Uuid::now_v7()).await.unwrap();
+}
@@ -0,0 +1,57 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use template_rust_backend::utils::shutdown::Shutdown;
+
+#[tokio::test]
+async fn test_wait_resolves_after_trigger() {
+    let shutdown = Shutdown::new();
+    assert!(!shutdown.is_triggered());
+
+    let waiter = {
+        let shutdown = shutdown.clone();
+        tokio::spawn(async move { shutdown.wait().await })
+    };
+
+    shutdown.trigger();
+    tokio::time::timeout(Duration::from_secs(1), waiter)
+        .await
+        .expect("wait should resolve after trigger")
+        .unwrap();
+    assert!(shutdown.is_triggered());
+}
+
+#[tokio::test]
+async fn test_join_tasks_waits_for_cooperative_tasks() {
+    let shutdown = Shutdown::new();
+    let stopped = Arc::new(AtomicBool::new(false));
+
+    {
+        let signal = shutdown.clone();
+        let stopped = stopped.clone();
+        shutdown.spawn("cooperative", async move {
+            signal.wait().await;
+            stopped.store(true, Ordering::SeqCst);
+        });
+    }
+
+    shutdown.trigger();
+    shutdown.join_tasks(Duration::from_secs(1)).await;
+    assert!(stopped.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn test_join_tasks_aborts_stragglers() {
+    let shutdown = Shutdown::new();
+    shutdown.spawn("stuck", async {
+        tokio::time::sleep(Duration::from_secs(60)).await;
+    });
+
+    shutdown.trigger();
+    tokio::time::timeout(
+        Duration::from_secs(2),
+        shutdown.join_tasks(Duration::from_millis(50)),
+    )
+    .await
+    .expect("join_tasks should give up after its timeout");
+}